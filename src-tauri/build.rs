@@ -1,3 +1,5 @@
 fn main() {
+    // 内置浏览页面通过 include_dir 嵌入, 修改后需要重新编译
+    println!("cargo:rerun-if-changed=web-viewer");
     tauri_build::build()
 }
@@ -0,0 +1,144 @@
+use crate::error::AppError;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// 应用数据目录(缓存、日志等), 启动时由 init 设置
+static DATA_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// 保存在配置目录中的应用设置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct AppConfig {
+    /// 自定义数据目录, 为空时使用系统的应用数据目录
+    data_directory: Option<String>,
+}
+
+/// 旧版本写在可执行文件旁边的目录
+const LEGACY_DIRS: [&str; 2] = ["temp", "logs"];
+
+fn config_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join("config.json"))
+}
+
+fn load_config(app: &AppHandle) -> AppConfig {
+    config_path(app)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_config(app: &AppHandle, config: &AppConfig) -> Result<(), String> {
+    let path = config_path(app).ok_or("无法获取配置目录")?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("创建配置目录失败: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("序列化配置失败: {}", e))?;
+    fs::write(&path, json)
+        .map_err(|e| format!("写入配置失败: {}", e))
+}
+
+/// 系统默认的应用数据目录
+fn default_data_dir(app: &AppHandle) -> PathBuf {
+    app.path()
+        .app_data_dir()
+        .unwrap_or_else(|_| std::env::temp_dir().join("minecraft_pack_editor"))
+}
+
+/// 确认目录可写
+fn ensure_writable(dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(dir)
+        .map_err(|e| format!("无法创建目录 {}: {}", dir.display(), e))?;
+    let probe = dir.join(".write_test");
+    fs::write(&probe, b"")
+        .map_err(|e| format!("目录不可写 {}: {}", dir.display(), e))?;
+    let _ = fs::remove_file(probe);
+    Ok(())
+}
+
+/// 将旧版本写在可执行文件旁边的数据移动到数据目录, 已存在的目标不覆盖
+fn migrate_legacy_data(data_dir: &Path) {
+    let Some(exe_dir) = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+    else {
+        return;
+    };
+    if exe_dir == data_dir {
+        return;
+    }
+
+    for name in LEGACY_DIRS {
+        let legacy = exe_dir.join(name);
+        let target = data_dir.join(name);
+        if legacy.is_dir() && !target.exists() {
+            if let Err(e) = crate::fs_utils::move_path(&legacy, &target) {
+                eprintln!("迁移 {} 失败: {}", legacy.display(), e);
+            }
+        }
+    }
+}
+
+/// 读取配置并设置数据目录, 应在其他模块写入文件前调用
+pub fn init(app: &AppHandle) {
+    let config = load_config(app);
+    let dir = config
+        .data_directory
+        .map(PathBuf::from)
+        .filter(|dir| ensure_writable(dir).is_ok())
+        .unwrap_or_else(|| default_data_dir(app));
+
+    let _ = fs::create_dir_all(&dir);
+    migrate_legacy_data(&dir);
+    *DATA_DIR.write() = Some(dir);
+}
+
+/// 应用数据目录
+pub fn data_dir() -> PathBuf {
+    DATA_DIR
+        .read()
+        .clone()
+        .unwrap_or_else(|| std::env::temp_dir().join("minecraft_pack_editor"))
+}
+
+/// 模板 jar、版本清单等缓存目录
+pub fn cache_dir() -> PathBuf {
+    data_dir().join("temp")
+}
+
+/// 日志目录
+pub fn logs_dir() -> PathBuf {
+    data_dir().join("logs")
+}
+
+/// 获取当前数据目录
+#[tauri::command]
+pub async fn get_data_directory() -> Result<String, AppError> {
+    Ok(data_dir().to_string_lossy().to_string())
+}
+
+/// 设置数据目录, 传入空值恢复为系统默认目录; 已有的缓存不会自动移动
+#[tauri::command]
+pub async fn set_data_directory(path: Option<String>, app: AppHandle) -> Result<String, AppError> {
+    let path = path.filter(|p| !p.trim().is_empty());
+    let dir = match &path {
+        Some(p) => {
+            let dir = PathBuf::from(p);
+            if !dir.is_absolute() {
+                return Err(AppError::InvalidInput("数据目录必须是绝对路径".to_string()));
+            }
+            dir
+        }
+        None => default_data_dir(&app),
+    };
+    ensure_writable(&dir).map_err(AppError::PermissionDenied)?;
+
+    save_config(&app, &AppConfig { data_directory: path })?;
+    *DATA_DIR.write() = Some(dir.clone());
+
+    Ok(dir.to_string_lossy().to_string())
+}
@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use crate::error::AppError;
+
+/// 音频元数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioMetadata {
+    pub codec: String,
+    pub channels: u8,
+    pub sample_rate: u32,
+    pub duration_secs: f64,
+    pub size_bytes: u64,
+}
+
+/// 从文件末尾读取的字节数, 足够覆盖最后一个Ogg页
+const TAIL_READ_SIZE: u64 = 64 * 1024;
+
+/// 读取Ogg音频的元数据(支持Vorbis和Opus)
+pub fn get_ogg_metadata(path: &Path) -> Result<AudioMetadata, String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open audio file: {}", e))?;
+    let size_bytes = file
+        .metadata()
+        .map_err(|e| format!("Failed to read audio metadata: {}", e))?
+        .len();
+
+    // 读取第一页中的标识头
+    let mut head = vec![0u8; 512.min(size_bytes as usize)];
+    file.read_exact(&mut head)
+        .map_err(|e| format!("Failed to read audio header: {}", e))?;
+
+    if !head.starts_with(b"OggS") {
+        return Err("Not an Ogg file".to_string());
+    }
+
+    let first_packet = first_page_packet(&head).ok_or("Invalid Ogg page header")?;
+    let (codec, channels, sample_rate) = if first_packet.len() >= 16
+        && first_packet[0] == 1
+        && &first_packet[1..7] == b"vorbis"
+    {
+        let channels = first_packet[11];
+        let sample_rate = u32::from_le_bytes([
+            first_packet[12],
+            first_packet[13],
+            first_packet[14],
+            first_packet[15],
+        ]);
+        ("vorbis", channels, sample_rate)
+    } else if first_packet.len() >= 10 && &first_packet[0..8] == b"OpusHead" {
+        // Opus的granule position始终以48kHz计
+        ("opus", first_packet[9], 48000)
+    } else {
+        return Err("Unsupported Ogg codec".to_string());
+    };
+
+    if sample_rate == 0 {
+        return Err("Invalid sample rate".to_string());
+    }
+
+    // 最后一页的granule position即为总采样数
+    let tail_start = size_bytes.saturating_sub(TAIL_READ_SIZE);
+    file.seek(SeekFrom::Start(tail_start))
+        .map_err(|e| format!("Failed to seek audio file: {}", e))?;
+    let mut tail = Vec::new();
+    file.read_to_end(&mut tail)
+        .map_err(|e| format!("Failed to read audio file: {}", e))?;
+
+    let last_page = tail
+        .windows(4)
+        .rposition(|w| w == b"OggS")
+        .ok_or("No Ogg page found at end of file")?;
+
+    if tail.len() < last_page + 14 {
+        return Err("Truncated Ogg page".to_string());
+    }
+
+    let mut granule_bytes = [0u8; 8];
+    granule_bytes.copy_from_slice(&tail[last_page + 6..last_page + 14]);
+    let granule = i64::from_le_bytes(granule_bytes).max(0) as f64;
+
+    Ok(AudioMetadata {
+        codec: codec.to_string(),
+        channels,
+        sample_rate,
+        duration_secs: granule / sample_rate as f64,
+        size_bytes,
+    })
+}
+
+/// 取出第一页的第一个数据包
+fn first_page_packet(page: &[u8]) -> Option<&[u8]> {
+    let segment_count = *page.get(26)? as usize;
+    let segments = page.get(27..27 + segment_count)?;
+
+    let mut packet_len = 0usize;
+    for &lacing in segments {
+        packet_len += lacing as usize;
+        if lacing < 255 {
+            break;
+        }
+    }
+
+    let data_start = 27 + segment_count;
+    page.get(data_start..data_start + packet_len)
+}
+
+/// 获取音频文件元数据
+#[tauri::command]
+pub async fn get_audio_metadata(
+    file_path: String,
+    allow_external: Option<bool>,
+    pack_id: Option<String>,
+    state: tauri::State<'_, crate::commands::AppState>,
+) -> Result<AudioMetadata, AppError> {
+    let full_path = crate::commands::resolve_read_path_in(&state, pack_id.as_deref(), &file_path, allow_external)?;
+
+    Ok(get_ogg_metadata(&full_path)?)
+}
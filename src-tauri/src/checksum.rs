@@ -0,0 +1,212 @@
+use crate::error::AppError;
+use crate::pack_parser::is_hidden_relative_path;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use sha2::{Sha256, Sha512};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+use tauri::State;
+use walkdir::WalkDir;
+
+/// 哈希算法
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+impl HashAlgorithm {
+    pub fn name(self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha1 => "sha1",
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Sha512 => "sha512",
+        }
+    }
+}
+
+/// 清单格式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ManifestFormat {
+    #[default]
+    Json,
+    /// 与 sha1sum/sha256sum 输出兼容的 "<hash>  <path>" 文本
+    Text,
+}
+
+/// 生成校验清单的选项
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChecksumManifestOptions {
+    pub algorithm: HashAlgorithm,
+    #[serde(default)]
+    pub format: ManifestFormat,
+    /// 外部输出路径, 为空时写入材质包根目录
+    #[serde(default)]
+    pub output_path: Option<String>,
+}
+
+impl ChecksumManifestOptions {
+    /// 写入材质包时使用的默认文件名
+    pub fn default_file_name(&self) -> String {
+        match self.format {
+            ManifestFormat::Json => "checksums.json".to_string(),
+            ManifestFormat::Text => format!("checksums.{}", self.algorithm.name()),
+        }
+    }
+}
+
+/// 清单中的单个文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChecksumEntry {
+    pub path: String,
+    pub size: u64,
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ChecksumManifest {
+    algorithm: String,
+    files: Vec<ChecksumEntry>,
+}
+
+/// 生成结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChecksumManifestResult {
+    pub output_path: String,
+    pub file_count: usize,
+    /// 清单文件本身的哈希
+    pub manifest_hash: String,
+}
+
+fn digest_reader<D: Digest>(mut reader: impl Read) -> std::io::Result<String> {
+    let mut hasher = D::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// 计算数据流的哈希值(十六进制小写)
+pub fn hash_reader(algorithm: HashAlgorithm, reader: impl Read) -> std::io::Result<String> {
+    match algorithm {
+        HashAlgorithm::Sha1 => digest_reader::<Sha1>(reader),
+        HashAlgorithm::Sha256 => digest_reader::<Sha256>(reader),
+        HashAlgorithm::Sha512 => digest_reader::<Sha512>(reader),
+    }
+}
+
+/// 计算文件的哈希值
+pub fn hash_file(algorithm: HashAlgorithm, path: &Path) -> Result<String, String> {
+    let file = File::open(path)
+        .map_err(|e| format!("打开文件失败 {}: {}", path.display(), e))?;
+    hash_reader(algorithm, BufReader::new(file))
+        .map_err(|e| format!("读取文件失败 {}: {}", path.display(), e))
+}
+
+/// 并行计算材质包中所有文件的哈希, include 根据相对路径决定是否计入, 返回 false 的目录整体跳过
+pub fn collect_checksums(
+    pack_path: &Path,
+    algorithm: HashAlgorithm,
+    include: impl Fn(&Path) -> bool,
+) -> Result<Vec<ChecksumEntry>, String> {
+    let files: Vec<PathBuf> = WalkDir::new(pack_path)
+        .into_iter()
+        .filter_entry(|e| include(e.path().strip_prefix(pack_path).unwrap_or(e.path())))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+        .collect();
+
+    let mut entries = files
+        .par_iter()
+        .map(|path| {
+            let size = path
+                .metadata()
+                .map_err(|e| format!("获取文件大小失败: {}", e))?
+                .len();
+            let hash = hash_file(algorithm, path)?;
+            let relative = path
+                .strip_prefix(pack_path)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            Ok(ChecksumEntry { path: relative, size, hash })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(entries)
+}
+
+/// 生成清单文本
+pub fn render_manifest(
+    entries: Vec<ChecksumEntry>,
+    algorithm: HashAlgorithm,
+    format: ManifestFormat,
+) -> Result<String, String> {
+    match format {
+        ManifestFormat::Json => serde_json::to_string_pretty(&ChecksumManifest {
+            algorithm: algorithm.name().to_string(),
+            files: entries,
+        })
+        .map_err(|e| format!("序列化清单失败: {}", e)),
+        ManifestFormat::Text => Ok(entries
+            .iter()
+            .map(|entry| format!("{}  {}\n", entry.hash, entry.path))
+            .collect()),
+    }
+}
+
+/// 为当前材质包生成文件校验清单
+#[tauri::command]
+pub async fn generate_checksum_manifest(
+    options: ChecksumManifestOptions,
+    state: State<'_, crate::commands::AppState>,
+) -> Result<ChecksumManifestResult, AppError> {
+    let base_path = state.pack_path()?;
+
+    let output_path = match options.output_path.as_ref() {
+        Some(path) => PathBuf::from(path),
+        None => base_path.join(options.default_file_name()),
+    };
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let (pack_path, exclude) = (base_path.clone(), output_path.clone());
+    rayon::spawn(move || {
+        let include = |relative: &Path| {
+            !is_hidden_relative_path(relative) && pack_path.join(relative) != exclude
+        };
+        let _ = tx.send(collect_checksums(&pack_path, options.algorithm, include));
+    });
+    let entries = rx
+        .await
+        .map_err(|e| AppError::Other(format!("Channel error: {}", e)))??;
+
+    let file_count = entries.len();
+    let content = render_manifest(entries, options.algorithm, options.format)?;
+    let manifest_hash = hash_reader(options.algorithm, content.as_bytes())
+        .map_err(|e| AppError::Other(format!("计算清单哈希失败: {}", e)))?;
+
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| AppError::io("创建输出目录失败", e))?;
+    }
+    std::fs::write(&output_path, content)
+        .map_err(|e| AppError::io("写入校验清单失败", e))?;
+
+    Ok(ChecksumManifestResult {
+        output_path: output_path.to_string_lossy().to_string(),
+        file_count,
+        manifest_hash,
+    })
+}
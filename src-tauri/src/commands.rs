@@ -1,8 +1,9 @@
+use crate::error::AppError;
 use crate::image_handler::{get_image_info, ImageInfo};
-use crate::pack_parser::{scan_pack_directory, PackInfo};
-use crate::preloader::ImagePreloader;
+use crate::pack_parser::{scan_pack_directory, MinecraftVersion, PackInfo, ResourceType};
+use crate::preloader::{ImagePreloader, PreloaderStats};
 use crate::zip_handler::{
-    cleanup_temp_files, create_zip, extract_zip, get_temp_extract_dir, validate_pack_zip,
+    cleanup_temp_files, extract_zip, get_temp_extract_dir, validate_pack_zip,
 };
 use font_kit::source::SystemSource;
 use serde::{Deserialize, Serialize};
@@ -12,34 +13,173 @@ use tauri::State;
 use regex::Regex;
 use rayon::prelude::*;
 
+/// 每个材质包的预加载缓存容量(缩略图 base64 的总字节数)
+const PRELOADER_CACHE_BYTES: usize = 64 * 1024 * 1024;
+
+/// 一个已打开的材质包
+pub struct OpenPack {
+    pub path: PathBuf,
+    pub info: Option<PackInfo>,
+    /// 预加载缓存以相对路径为键, 每个材质包单独一份, 避免不同材质包的缩略图混用
+    pub preloader: Arc<ImagePreloader>,
+    /// 磁盘上的文件变化监听, 关闭材质包时随之停止
+    pub watcher: Option<crate::pack_watcher::PackWatcher>,
+}
+
 /// 应用状态
 pub struct AppState {
-    pub current_pack_path: Mutex<Option<PathBuf>>,
-    pub current_pack_info: Mutex<Option<PackInfo>>,
-    pub preloader: Arc<ImagePreloader>,
+    /// 已打开的材质包, 键为 pack_id
+    pub packs: Mutex<std::collections::HashMap<String, OpenPack>>,
+    /// 未指定 pack_id 的命令使用的材质包
+    pub active_pack: Mutex<Option<String>>,
+    /// 进行中的流式搜索, 值为取消标记
+    pub searches: Mutex<std::collections::HashMap<String, Arc<std::sync::atomic::AtomicBool>>>,
+}
+
+impl AppState {
+    /// 当前加载的材质包路径
+    pub fn pack_path(&self) -> Result<PathBuf, AppError> {
+        self.pack_path_for(None)
+    }
+
+    /// pack_id 对应的材质包路径, 为 None 时使用当前材质包
+    pub fn pack_path_for(&self, pack_id: Option<&str>) -> Result<PathBuf, AppError> {
+        let active = self.active_pack.lock().unwrap().clone();
+        let id = match pack_id {
+            Some(id) => id.to_string(),
+            None => active.ok_or(AppError::NoPackLoaded)?,
+        };
+        self.packs
+            .lock()
+            .unwrap()
+            .get(&id)
+            .map(|pack| pack.path.clone())
+            .ok_or_else(|| AppError::NotFound(format!("材质包未打开: {}", id)))
+    }
+
+    /// pack_id 对应材质包的版本, 为 None 时使用当前材质包; 未解析出信息时按旧版处理
+    pub fn pack_version_for(&self, pack_id: Option<&str>) -> MinecraftVersion {
+        self.pack_info_for(pack_id)
+            .map(|info| info.version)
+            .unwrap_or(MinecraftVersion::Legacy)
+    }
+
+    /// 当前材质包的信息
+    pub fn pack_info(&self) -> Option<PackInfo> {
+        self.pack_info_for(None)
+    }
+
+    /// pack_id 对应材质包的信息, 为 None 时使用当前材质包
+    pub fn pack_info_for(&self, pack_id: Option<&str>) -> Option<PackInfo> {
+        let id = match pack_id {
+            Some(id) => id.to_string(),
+            None => self.active_pack.lock().unwrap().clone()?,
+        };
+        self.packs.lock().unwrap().get(&id)?.info.clone()
+    }
+
+    /// 替换 base_path 对应材质包的信息
+    pub fn set_pack_info(&self, base_path: &Path, info: PackInfo) {
+        if let Some(pack) = self.packs.lock().unwrap().values_mut().find(|p| p.path == base_path) {
+            pack.info = Some(info);
+        }
+    }
+
+    /// 当前材质包的预加载器, 没有打开的材质包时返回空的预加载器
+    pub fn preloader(&self) -> Arc<ImagePreloader> {
+        let active = self.active_pack.lock().unwrap().clone();
+        active
+            .and_then(|id| self.packs.lock().unwrap().get(&id).map(|p| p.preloader.clone()))
+            .unwrap_or_else(|| Arc::new(ImagePreloader::with_byte_budget(PRELOADER_CACHE_BYTES)))
+    }
+
+    /// base_path 对应材质包的预加载器
+    pub fn preloader_for_path(&self, base_path: &Path) -> Arc<ImagePreloader> {
+        self.packs
+            .lock()
+            .unwrap()
+            .values()
+            .find(|p| p.path == base_path)
+            .map(|p| p.preloader.clone())
+            .unwrap_or_else(|| Arc::new(ImagePreloader::with_byte_budget(PRELOADER_CACHE_BYTES)))
+    }
+
+    /// 打开材质包并返回 pack_id, 同一路径已打开时沿用原 pack_id 并重置其缓存
+    ///
+    /// activate 为 true 时设为当前材质包
+    pub fn open_pack(&self, path: PathBuf, info: PackInfo, activate: bool) -> (String, Arc<ImagePreloader>) {
+        let preloader = Arc::new(ImagePreloader::with_byte_budget(PRELOADER_CACHE_BYTES));
+        let mut packs = self.packs.lock().unwrap();
+        let id = packs
+            .iter()
+            .find(|(_, pack)| pack.path == path)
+            .map(|(id, _)| id.clone())
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        packs.insert(
+            id.clone(),
+            OpenPack {
+                path,
+                info: Some(info),
+                preloader: preloader.clone(),
+                watcher: None,
+            },
+        );
+        drop(packs);
+
+        let mut active = self.active_pack.lock().unwrap();
+        if activate || active.is_none() {
+            *active = Some(id.clone());
+        }
+        (id, preloader)
+    }
+
+    /// 以新材质包替换当前材质包(导入、新建材质包时使用), 其他已打开的材质包不受影响
+    pub fn replace_active_pack(&self, path: PathBuf, info: PackInfo) -> (String, Arc<ImagePreloader>) {
+        if let Some(active) = self.active_pack.lock().unwrap().take() {
+            let mut packs = self.packs.lock().unwrap();
+            if packs.get(&active).is_some_and(|pack| pack.path != path) {
+                packs.remove(&active);
+            }
+        }
+        self.open_pack(path, info, true)
+    }
+
+    /// 修改文件后刷新缓存的材质包信息
+    ///
+    /// changed 为变更的相对路径(文件或目录), 为 None 或尚无缓存时完整重新扫描
+    pub fn refresh_pack_info(&self, base_path: &Path, changed: Option<&[PathBuf]>) -> Result<(), String> {
+        let mut packs = self.packs.lock().unwrap();
+        let Some(pack) = packs.values_mut().find(|p| p.path == base_path) else {
+            return Ok(());
+        };
+        match (pack.info.as_mut(), changed) {
+            (Some(info), Some(changed)) => {
+                crate::pack_parser::update_pack_info(info, base_path, changed)
+            }
+            _ => {
+                pack.info = Some(scan_pack_directory(base_path)?);
+                Ok(())
+            }
+        }
+    }
 }
 
 impl Default for AppState {
     fn default() -> Self {
         Self {
-            current_pack_path: Mutex::new(None),
-            current_pack_info: Mutex::new(None),
-            preloader: Arc::new(ImagePreloader::new(200)),
+            packs: Mutex::new(std::collections::HashMap::new()),
+            active_pack: Mutex::new(None),
+            searches: Mutex::new(std::collections::HashMap::new()),
         }
     }
 }
 
-/// 导入材质包
-#[tauri::command]
-pub async fn import_pack_zip(
-    zip_path: String,
-    state: State<'_, AppState>,
-) -> Result<PackInfo, String> {
-    let zip_path = Path::new(&zip_path);
-
+/// 解压材质包ZIP到临时目录, 返回材质包根目录
+fn extract_pack_zip(zip_path: &Path) -> Result<PathBuf, AppError> {
     // 验证ZIP文件
+    crate::zip_handler::check_archive(zip_path)?;
     if !validate_pack_zip(zip_path)? {
-        return Err("Invalid resource pack: pack.mcmeta not found".to_string());
+        return Err(AppError::InvalidInput("Invalid resource pack: pack.mcmeta not found".to_string()));
     }
 
     // 解压到临时目录
@@ -54,23 +194,115 @@ pub async fn import_pack_zip(
 
     extract_zip(zip_path, &extract_path)?;
 
+    // 压缩时多套了一层文件夹的材质包, 以内层文件夹为根目录
+    Ok(match crate::zip_handler::nested_pack_root(zip_path)? {
+        Some(root) => extract_path.join(root),
+        None => extract_path,
+    })
+}
+
+/// 导入材质包
+#[tauri::command]
+pub async fn import_pack_zip(
+    zip_path: String,
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<PackInfo, AppError> {
+    let extract_path = extract_pack_zip(Path::new(&zip_path))?;
+
     // 扫描材质包
     let pack_info = scan_pack_directory(&extract_path)?;
 
-    // 保存状态
-    *state.current_pack_path.lock().unwrap() = Some(extract_path);
-    *state.current_pack_info.lock().unwrap() = Some(pack_info.clone());
+    // 替换当前材质包, 预加载缓存随之重建
+    let (id, preloader) = state.replace_active_pack(extract_path.clone(), pack_info.clone());
+    start_pack_watcher(&app, &state, &id, &extract_path, preloader.clone());
+    start_preview_warmup(app, preloader, extract_path);
 
     Ok(pack_info)
 }
 
+/// 为已打开的材质包启动文件监听, 失败时只记录日志
+fn start_pack_watcher(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    pack_id: &str,
+    pack_path: &Path,
+    preloader: Arc<ImagePreloader>,
+) {
+    match crate::pack_watcher::watch_pack(app.clone(), pack_id.to_string(), pack_path.to_path_buf(), preloader) {
+        Ok(watcher) => {
+            if let Some(pack) = state.packs.lock().unwrap().get_mut(pack_id) {
+                pack.watcher = Some(watcher);
+            }
+        }
+        Err(e) => eprintln!("[文件监听] {}", e),
+    }
+}
+
+/// 预热进度事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreloadProgress {
+    pub done: usize,
+    pub total: usize,
+}
+
+/// 导入后预热的纹理文件夹
+const WARMUP_FOLDERS: [&str; 2] = ["textures/item", "textures/block"];
+
+/// 收集各命名空间常用纹理文件夹中的前 max_per_folder 个 PNG
+fn warmup_files(pack_path: &Path, max_per_folder: usize) -> Vec<PathBuf> {
+    let Ok(namespaces) = std::fs::read_dir(pack_path.join("assets")) else {
+        return Vec::new();
+    };
+    namespaces
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+        .flat_map(|ns| WARMUP_FOLDERS.map(|folder| ns.path().join(folder)))
+        .flat_map(|folder| {
+            walkdir::WalkDir::new(folder)
+                .sort_by_file_name()
+                .into_iter()
+                .filter_map(|e| e.ok())
+                .filter(|e| {
+                    e.file_type().is_file()
+                        && e.path().extension().is_some_and(|ext| ext.eq_ignore_ascii_case("png"))
+                })
+                .take(max_per_folder)
+                .map(|e| e.into_path())
+        })
+        .collect()
+}
+
+/// 导入材质包后在后台预热常用纹理文件夹的缩略图, 通过 preload-progress 事件报告进度
+fn start_preview_warmup(app: tauri::AppHandle, preloader: Arc<ImagePreloader>, pack_path: PathBuf) {
+    let settings = crate::pack_settings::load_pack_settings(&pack_path)
+        .map(|s| s.preview_warmup)
+        .unwrap_or_default();
+    if !settings.enabled || settings.max_files_per_folder == 0 {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        use tauri::Emitter;
+        let files = warmup_files(&pack_path, settings.max_files_per_folder);
+        if files.is_empty() {
+            return;
+        }
+        preloader
+            .preload_files_low_priority(files, &pack_path, 512, |done, total| {
+                let _ = app.emit("preload-progress", PreloadProgress { done, total });
+            })
+            .await;
+    });
+}
+
 /// 检查文件夹是否有pack.mcmeta
 #[tauri::command]
-pub async fn check_pack_mcmeta(folder_path: String) -> Result<bool, String> {
+pub async fn check_pack_mcmeta(folder_path: String) -> Result<bool, AppError> {
     let folder_path = Path::new(&folder_path);
 
     if !folder_path.exists() {
-        return Err("Folder does not exist".to_string());
+        return Err(AppError::NotFound("Folder does not exist".to_string()));
     }
 
     Ok(folder_path.join("pack.mcmeta").exists())
@@ -80,39 +312,185 @@ pub async fn check_pack_mcmeta(folder_path: String) -> Result<bool, String> {
 #[tauri::command]
 pub async fn import_pack_folder(
     folder_path: String,
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
-) -> Result<PackInfo, String> {
+) -> Result<PackInfo, AppError> {
     let folder_path = Path::new(&folder_path);
 
     if !folder_path.exists() {
-        return Err("Folder does not exist".to_string());
+        return Err(AppError::NotFound("Folder does not exist".to_string()));
     }
 
     // 扫描材质包(即使没有pack.mcmeta也允许导入)
     let pack_info = scan_pack_directory(folder_path)?;
 
-    // 保存状态
-    *state.current_pack_path.lock().unwrap() = Some(folder_path.to_path_buf());
-    *state.current_pack_info.lock().unwrap() = Some(pack_info.clone());
+    // 替换当前材质包, 预加载缓存随之重建
+    let (id, preloader) = state.replace_active_pack(folder_path.to_path_buf(), pack_info.clone());
+    start_pack_watcher(&app, &state, &id, folder_path, preloader.clone());
+    start_preview_warmup(app, preloader, folder_path.to_path_buf());
 
     Ok(pack_info)
 }
 
+/// 已打开的材质包
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenPackSummary {
+    pub id: String,
+    pub name: String,
+    pub path: String,
+    /// 是否为未指定 pack_id 时使用的当前材质包
+    pub active: bool,
+}
+
+/// 在当前材质包之外再打开一个材质包(文件夹或ZIP), 返回 pack_id
+///
+/// activate 为 true 时设为当前材质包; 没有当前材质包时总是设为当前材质包
+#[tauri::command]
+pub async fn open_pack(
+    path: String,
+    activate: Option<bool>,
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    let source = Path::new(&path);
+    let pack_path = if source.is_file() {
+        extract_pack_zip(source)?
+    } else if source.is_dir() {
+        source.to_path_buf()
+    } else {
+        return Err(AppError::NotFound(format!("材质包不存在: {}", path)));
+    };
+
+    let pack_info = scan_pack_directory(&pack_path)?;
+    let (id, preloader) = state.open_pack(pack_path.clone(), pack_info, activate.unwrap_or(false));
+    start_pack_watcher(&app, &state, &id, &pack_path, preloader.clone());
+    start_preview_warmup(app, preloader, pack_path);
+
+    Ok(id)
+}
+
+/// 关闭已打开的材质包并停止其文件监听, 关闭当前材质包后没有当前材质包
+#[tauri::command]
+pub async fn close_pack(pack_id: String, state: State<'_, AppState>) -> Result<(), AppError> {
+    let removed = state.packs.lock().unwrap().remove(&pack_id);
+    let pack = removed.ok_or_else(|| AppError::NotFound(format!("材质包未打开: {}", pack_id)))?;
+    pack.preloader.clear_cache().await;
+
+    let mut active = state.active_pack.lock().unwrap();
+    if active.as_deref() == Some(pack_id.as_str()) {
+        *active = None;
+    }
+    Ok(())
+}
+
+/// 设置未指定 pack_id 的命令使用的材质包
+#[tauri::command]
+pub async fn set_active_pack(pack_id: String, state: State<'_, AppState>) -> Result<Option<PackInfo>, AppError> {
+    if !state.packs.lock().unwrap().contains_key(&pack_id) {
+        return Err(AppError::NotFound(format!("材质包未打开: {}", pack_id)));
+    }
+    *state.active_pack.lock().unwrap() = Some(pack_id);
+    Ok(state.pack_info())
+}
+
+/// 列出已打开的材质包
+#[tauri::command]
+pub async fn list_open_packs(state: State<'_, AppState>) -> Result<Vec<OpenPackSummary>, AppError> {
+    let active = state.active_pack.lock().unwrap().clone();
+    let mut packs: Vec<OpenPackSummary> = state
+        .packs
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(id, pack)| OpenPackSummary {
+            id: id.clone(),
+            name: pack
+                .info
+                .as_ref()
+                .map(|info| info.name.clone())
+                .unwrap_or_else(|| pack.path.file_name().unwrap_or_default().to_string_lossy().to_string()),
+            path: pack.path.to_string_lossy().to_string(),
+            active: active.as_deref() == Some(id.as_str()),
+        })
+        .collect();
+    packs.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
+    Ok(packs)
+}
+
+/// 在两个已打开的材质包之间复制文件或文件夹, 返回目标的相对路径
+///
+/// 目标已存在时返回 AlreadyExists, overwrite 为 true 时覆盖(覆盖文件前保存历史记录, 文件夹合并)
+#[tauri::command]
+pub async fn copy_between_packs(
+    src_pack_id: String,
+    src_path: String,
+    dst_pack_id: String,
+    dst_path: String,
+    overwrite: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    let src_base = state.pack_path_for(Some(&src_pack_id))?;
+    let dst_base = state.pack_path_for(Some(&dst_pack_id))?;
+    let full_src = resolve_pack_path(&src_base, &src_path)?;
+    let full_dest = resolve_pack_path(&dst_base, &dst_path)?;
+
+    if !full_src.exists() {
+        return Err(AppError::NotFound(format!("文件不存在: {}", src_path)));
+    }
+    if full_dest.starts_with(&full_src) {
+        return Err(AppError::InvalidInput("不能复制到自身或其子目录中".to_string()));
+    }
+    if full_dest == dst_base {
+        return Err(AppError::InvalidInput("目标不能是材质包根目录".to_string()));
+    }
+    if full_dest.exists() {
+        if !overwrite.unwrap_or(false) {
+            return Err(AppError::AlreadyExists(format!("目标已存在: {}", dst_path)));
+        }
+        if full_dest.is_dir() != full_src.is_dir() {
+            return Err(AppError::AlreadyExists(format!("目标已存在且类型不同: {}", dst_path)));
+        }
+    }
+
+    let relative = full_dest
+        .strip_prefix(&dst_base)
+        .map(Path::to_path_buf)
+        .map_err(|_| AppError::InvalidInput(format!("路径不在材质包内: {}", dst_path)))?;
+    let relative_str = relative.to_string_lossy().replace('\\', "/");
+
+    if let Some(parent) = full_dest.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| AppError::io("Failed to create directory", e))?;
+    }
+
+    let preloader = state.preloader_for_path(&dst_base);
+    if full_src.is_dir() {
+        crate::fs_utils::copy_dir_all(&full_src, &full_dest)?;
+        crate::image_handler::clear_caches();
+        preloader.clear_cache().await;
+    } else {
+        crate::history_manager::snapshot_file(&dst_base, &relative_str)?;
+        std::fs::copy(&full_src, &full_dest)
+            .map_err(|e| AppError::io("Failed to copy file", e))?;
+        crate::image_handler::invalidate_image_caches(&full_dest);
+        preloader.invalidate(&relative_str);
+    }
+
+    state.refresh_pack_info(&dst_base, Some(&[relative]))?;
+
+    Ok(relative_str)
+}
+
 /// 获取当前材质包信息
 #[tauri::command]
-pub async fn get_current_pack_info(state: State<'_, AppState>) -> Result<Option<PackInfo>, String> {
-    let pack_info = state.current_pack_info.lock().unwrap();
-    Ok(pack_info.clone())
+pub async fn get_current_pack_info(state: State<'_, AppState>) -> Result<Option<PackInfo>, AppError> {
+    Ok(state.pack_info())
 }
 
 /// 获取当前材质包路径
 #[tauri::command]
-pub async fn get_current_pack_path(state: State<'_, AppState>) -> Result<String, String> {
-    let pack_path = state.current_pack_path.lock().unwrap();
-    match pack_path.as_ref() {
-        Some(path) => Ok(path.to_string_lossy().to_string()),
-        None => Err("No pack loaded".to_string()),
-    }
+pub async fn get_current_pack_path(state: State<'_, AppState>) -> Result<String, AppError> {
+    Ok(state.pack_path()?.to_string_lossy().to_string())
 }
 
 /// 获取图片缩略图
@@ -120,48 +498,24 @@ pub async fn get_current_pack_path(state: State<'_, AppState>) -> Result<String,
 pub async fn get_image_thumbnail(
     image_path: String,
     max_size: u32,
+    allow_external: Option<bool>,
+    pack_id: Option<String>,
     state: State<'_, AppState>,
-) -> Result<String, String> {
-    let full_path = {
-        let pack_path = state.current_pack_path.lock().unwrap();
-
-        match pack_path.as_ref() {
-            Some(base_path) => {
-                let path = Path::new(&image_path);
-                if path.is_absolute() {
-                    path.to_path_buf()
-                } else {
-                    base_path.join(path)
-                }
-            }
-            None => PathBuf::from(&image_path),
-        }
-    };
+) -> Result<String, AppError> {
+    let full_path = resolve_read_path_in(&state, pack_id.as_deref(), &image_path, allow_external)?;
 
-    crate::image_handler::create_thumbnail_async(full_path, max_size).await
+    Ok(crate::image_handler::create_thumbnail_async(full_path, max_size).await?)
 }
 
 #[tauri::command]
 pub async fn get_image_preview(
     image_path: String,
     size: String,
+    allow_external: Option<bool>,
+    pack_id: Option<String>,
     state: State<'_, AppState>,
-) -> Result<String, String> {
-    let full_path = {
-        let pack_path = state.current_pack_path.lock().unwrap();
-
-        match pack_path.as_ref() {
-            Some(base_path) => {
-                let path = Path::new(&image_path);
-                if path.is_absolute() {
-                    path.to_path_buf()
-                } else {
-                    base_path.join(path)
-                }
-            }
-            None => PathBuf::from(&image_path),
-        }
-    };
+) -> Result<String, AppError> {
+    let full_path = resolve_read_path_in(&state, pack_id.as_deref(), &image_path, allow_external)?;
 
     let max_size = match size.as_str() {
         "thumbnail" => 128, // 缩略
@@ -171,359 +525,1310 @@ pub async fn get_image_preview(
     };
 
     // 使用异步
-    crate::image_handler::create_thumbnail_async(full_path, max_size).await
+    Ok(crate::image_handler::create_thumbnail_async(full_path, max_size).await?)
+}
+
+/// 获取叠加像素网格的放大纹理预览
+#[tauri::command]
+pub async fn get_texture_with_grid(
+    texture_path: String,
+    grid_size: u32,
+    allow_external: Option<bool>,
+    pack_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    let full_path = resolve_read_path_in(&state, pack_id.as_deref(), &texture_path, allow_external)?;
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    rayon::spawn(move || {
+        let _ = tx.send(crate::image_handler::create_grid_overlay(&full_path, grid_size));
+    });
+
+    Ok(rx.await
+        .map_err(|e| format!("Channel error: {}", e))??)
+}
+
+/// 获取动画纹理的各帧及每帧时长(游戏刻), 用于在编辑器中播放
+#[tauri::command]
+pub async fn get_animation_preview(
+    texture_path: String,
+    pack_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<crate::image_handler::AnimationPreview, AppError> {
+    let base_path = state.pack_path_for(pack_id.as_deref())?;
+    let full_path = resolve_pack_path(&base_path, &texture_path)?;
+    if !full_path.is_file() {
+        return Err(AppError::NotFound(format!("文件不存在: {}", texture_path)));
+    }
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    rayon::spawn(move || {
+        let _ = tx.send(crate::image_handler::animation_preview(&full_path));
+    });
+
+    Ok(rx.await
+        .map_err(|e| format!("Channel error: {}", e))??)
 }
 
 /// 获取图片信息
 #[tauri::command]
 pub async fn get_image_details(
     image_path: String,
+    allow_external: Option<bool>,
+    pack_id: Option<String>,
     state: State<'_, AppState>,
-) -> Result<ImageInfo, String> {
-    let pack_path = state.current_pack_path.lock().unwrap();
-
-    let full_path = match pack_path.as_ref() {
-        Some(base_path) => {
-            let path = Path::new(&image_path);
-            if path.is_absolute() {
-                path.to_path_buf()
-            } else {
-                base_path.join(path)
+) -> Result<ImageInfo, AppError> {
+    let full_path = resolve_read_path_in(&state, pack_id.as_deref(), &image_path, allow_external)?;
+
+    Ok(get_image_info(&full_path)?)
+}
+
+/// 单个文件或目录的元数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileMetadata {
+    pub size: u64,
+    /// RFC3339 时间, 文件系统不支持时为空
+    pub created: Option<String>,
+    pub modified: Option<String>,
+    pub is_dir: bool,
+    pub extension: Option<String>,
+    /// 仅当图片信息已在缓存中时返回
+    pub image_info: Option<ImageInfo>,
+    /// 目录内(递归)的文件数, 文件为空
+    pub file_count: Option<u64>,
+}
+
+/// 获取文件或目录的大小、时间等元数据, 目录的大小为递归统计结果
+#[tauri::command]
+pub async fn get_file_metadata(
+    path: String,
+    pack_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<FileMetadata, AppError> {
+    let base_path = state.pack_path_for(pack_id.as_deref())?;
+    let full_path = resolve_pack_path(&base_path, &path)?;
+
+    let metadata = std::fs::metadata(&full_path)
+        .map_err(|e| AppError::io(&format!("读取元数据失败 {}", path), e))?;
+    let to_rfc3339 = |time: std::io::Result<std::time::SystemTime>| {
+        time.ok()
+            .map(|t| chrono::DateTime::<chrono::Local>::from(t).to_rfc3339())
+    };
+    let created = to_rfc3339(metadata.created());
+    let modified = to_rfc3339(metadata.modified());
+
+    if metadata.is_dir() {
+        let stats = tokio::task::spawn_blocking(move || {
+            crate::fs_utils::dir_stats(&full_path, true)
+        })
+        .await
+        .map_err(|e| AppError::Other(format!("Task error: {}", e)))??;
+
+        return Ok(FileMetadata {
+            size: stats.total_size,
+            created,
+            modified,
+            is_dir: true,
+            extension: None,
+            image_info: None,
+            file_count: Some(stats.file_count),
+        });
+    }
+
+    Ok(FileMetadata {
+        size: metadata.len(),
+        created,
+        modified,
+        is_dir: false,
+        extension: full_path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase()),
+        image_info: crate::image_handler::cached_image_info(&full_path),
+        file_count: None,
+    })
+}
+
+/// 导出材质包, 作为 "export" 任务在后台执行, 立即返回任务 ID
+///
+/// 进度通过 download-progress 事件报告(current/total 为文件数), 可用 cancel_download_task 取消;
+/// compression 为 store/fast/default/best 预设, options 可指定排除规则和压缩方式/级别并覆盖预设
+#[tauri::command]
+pub async fn export_pack(
+    output_path: String,
+    checksum_manifest: Option<crate::checksum::ChecksumManifestOptions>,
+    compression: Option<crate::zip_handler::ZipCompression>,
+    options: Option<crate::zip_handler::ExportOptions>,
+    state: State<'_, AppState>,
+    manager: State<'_, std::sync::Arc<crate::download_manager::DownloadManager>>,
+) -> Result<String, AppError> {
+    let path = state.pack_path()?;
+    let output = PathBuf::from(&output_path);
+    let options = options.unwrap_or_default();
+    let excludes = options.exclude_patterns().map_err(AppError::InvalidInput)?;
+    let file_options = options
+        .file_options(compression.unwrap_or_default())
+        .map_err(AppError::InvalidInput)?;
+
+    let file_name = output
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| output_path.clone());
+    let task_id = manager.create_task(
+        format!("导出材质包: {}", file_name),
+        "export".to_string(),
+        output.parent().map(Path::to_path_buf).unwrap_or_default(),
+        None,
+    ).await;
+
+    spawn_pack_export(
+        std::sync::Arc::clone(&manager),
+        task_id.clone(),
+        PackExportJob { path, output, checksum_manifest, excludes, file_options },
+    );
+
+    Ok(task_id)
+}
+
+/// 后台导出任务的参数
+struct PackExportJob {
+    path: PathBuf,
+    output: PathBuf,
+    checksum_manifest: Option<crate::checksum::ChecksumManifestOptions>,
+    excludes: crate::zip_handler::ExcludePatterns,
+    file_options: crate::zip_handler::ZipFileOptions,
+}
+
+impl PackExportJob {
+    /// 在阻塞线程中执行打包, on_progress 返回 false 时停止
+    fn run(self, on_progress: &mut dyn FnMut(crate::zip_handler::ZipProgress) -> bool) -> Result<(), String> {
+        // 需要时将校验清单一并写入ZIP, 清单只覆盖实际打包的文件;
+        // 材质包根目录中已有的同名清单由新生成的替代, 避免ZIP中出现重复条目
+        let manifest_name = self.checksum_manifest.as_ref().map(|options| options.default_file_name());
+        let excludes = &self.excludes;
+        let include = |relative: &Path| {
+            !excludes.is_excluded(relative)
+                && manifest_name.as_deref().is_none_or(|name| relative != Path::new(name))
+        };
+
+        let mut extra_files = Vec::new();
+        if let (Some(options), Some(name)) = (&self.checksum_manifest, &manifest_name) {
+            let entries = crate::checksum::collect_checksums(&self.path, options.algorithm, include)?;
+            let content = crate::checksum::render_manifest(entries, options.algorithm, options.format)?;
+            extra_files.push((name.clone(), content.into_bytes()));
+        }
+
+        crate::zip_handler::create_zip_filtered(
+            &self.path,
+            &self.output,
+            &extra_files,
+            include,
+            self.file_options,
+            Some(on_progress),
+        )
+    }
+}
+
+/// 在后台执行导出任务, 打包进度同步到下载管理器
+fn spawn_pack_export(
+    manager: std::sync::Arc<crate::download_manager::DownloadManager>,
+    task_id: String,
+    job: PackExportJob,
+) {
+    use crate::download_manager::{DownloadProgress, DownloadStatus};
+
+    tokio::spawn(async move {
+        let cancel_token = tokio_util::sync::CancellationToken::new();
+        manager.register_cancel_token(task_id.clone(), cancel_token.clone()).await;
+
+        // 超出并发上限时排队等待
+        let _slot = match manager.acquire_slot(&task_id).await {
+            Some(slot) => slot,
+            None => {
+                manager.remove_cancel_token(&task_id).await;
+                return;
             }
+        };
+
+        let output = job.output.clone();
+        let (progress_tx, mut progress_rx) = tokio::sync::mpsc::unbounded_channel();
+        let worker_token = cancel_token.clone();
+        let worker = tokio::task::spawn_blocking(move || {
+            job.run(&mut |progress| {
+                let _ = progress_tx.send(progress);
+                !worker_token.is_cancelled()
+            })
+        });
+
+        let started = std::time::Instant::now();
+        let mut last = crate::zip_handler::ZipProgress { files_done: 0, total_files: 0, bytes_written: 0 };
+        while let Some(progress) = progress_rx.recv().await {
+            last = progress;
+            if cancel_token.is_cancelled() {
+                continue;
+            }
+            let elapsed = started.elapsed().as_secs_f64().max(0.001);
+            let remaining = progress.total_files.saturating_sub(progress.files_done);
+            let eta = (progress.files_done > 0)
+                .then(|| (elapsed * remaining as f64 / progress.files_done as f64) as u64);
+            manager.update_progress(&task_id, DownloadProgress {
+                task_id: task_id.clone(),
+                status: DownloadStatus::Downloading,
+                current: progress.files_done,
+                total: progress.total_files,
+                current_file: None,
+                speed: progress.bytes_written as f64 / elapsed,
+                eta,
+                error: None,
+            }).await;
+        }
+
+        let result = worker
+            .await
+            .unwrap_or_else(|e| Err(format!("导出线程异常: {}", e)));
+
+        // 取消时任务状态已由 cancel_task 更新; 打包中途取消时ZIP已被删除,
+        // 取消恰好发生在写完之后时也不保留结果
+        if cancel_token.is_cancelled() {
+            if result.is_ok() {
+                let _ = std::fs::remove_file(&output);
+            }
+        } else {
+            let (status, error) = match &result {
+                Ok(()) => {
+                    manager.record_transfer(&task_id, last.files_done as u64, last.bytes_written).await;
+                    (DownloadStatus::Completed, None)
+                }
+                Err(e) => (DownloadStatus::Failed, Some(e.clone())),
+            };
+            manager.update_progress(&task_id, DownloadProgress {
+                task_id: task_id.clone(),
+                status,
+                current: last.files_done,
+                total: last.total_files,
+                current_file: None,
+                speed: 0.0,
+                eta: None,
+                error,
+            }).await;
         }
-        None => PathBuf::from(&image_path),
-    };
 
-    get_image_info(&full_path)
+        manager.remove_cancel_token(&task_id).await;
+    });
 }
 
-/// 导出材质包
+/// 只导出材质包中的部分路径(如 assets/minecraft/textures/item), pack.mcmeta 和 pack.png 始终包含
 #[tauri::command]
-pub async fn export_pack(output_path: String, state: State<'_, AppState>) -> Result<(), String> {
-    let pack_path = state.current_pack_path.lock().unwrap();
+pub async fn export_pack_subset(
+    output_path: String,
+    include_paths: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let base_path = state.pack_path()?;
+    if include_paths.is_empty() {
+        return Err(AppError::InvalidInput("至少需要选择一个导出路径".to_string()));
+    }
 
-    match pack_path.as_ref() {
-        Some(path) => {
-            let output = Path::new(&output_path);
-            create_zip(path, output)?;
-            Ok(())
+    // 所有路径必须存在且位于材质包内
+    let canonical_base = base_path
+        .canonicalize()
+        .map_err(|e| AppError::io("Failed to resolve pack path", e))?;
+    let mut includes = Vec::new();
+    for path in &include_paths {
+        let full_path = resolve_pack_path(&base_path, path)?;
+        let canonical = full_path
+            .canonicalize()
+            .map_err(|_| AppError::NotFound(format!("路径不存在: {}", path)))?;
+        let relative = canonical
+            .strip_prefix(&canonical_base)
+            .map_err(|_| AppError::InvalidInput(format!("路径不在材质包内: {}", path)))?;
+        includes.push(relative.to_path_buf());
+    }
+    for name in ["pack.mcmeta", "pack.png"] {
+        includes.push(PathBuf::from(name));
+    }
+
+    let output = PathBuf::from(&output_path);
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    rayon::spawn(move || {
+        let excludes = crate::zip_handler::ExcludePatterns::export_defaults();
+        let result = crate::zip_handler::create_zip_filtered(
+            &base_path,
+            &output,
+            &[],
+            |relative| {
+                // 选中路径本身、其子项, 以及通往选中路径的上级目录
+                !excludes.is_excluded(relative)
+                    && includes
+                        .iter()
+                        .any(|include| relative.starts_with(include) || include.starts_with(relative))
+            },
+            crate::zip_handler::ZipCompression::Default.file_options(),
+            None,
+        )
+        .and_then(|_| validate_pack_zip(&output))
+        .and_then(|valid| {
+            if valid {
+                Ok(())
+            } else {
+                let _ = std::fs::remove_file(&output);
+                Err("导出的压缩包不是有效的材质包: 缺少 pack.mcmeta".to_string())
+            }
+        });
+        let _ = tx.send(result);
+    });
+
+    Ok(rx.await
+        .map_err(|e| format!("Channel error: {}", e))??)
+}
+
+/// 将材质包导出为文件夹, 排除规则与 ZIP 导出相同; 目标已存在时需要 overwrite 才会替换
+#[tauri::command]
+pub async fn export_pack_folder(
+    output_path: String,
+    overwrite: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let path = state.pack_path()?;
+    let output = PathBuf::from(&output_path);
+
+    if output.exists() {
+        if !output.is_dir() {
+            return Err(AppError::AlreadyExists(format!("目标已存在且不是文件夹: {}", output_path)));
+        }
+        if !overwrite.unwrap_or(false) {
+            return Err(AppError::AlreadyExists(format!("目标文件夹已存在: {}", output_path)));
         }
-        None => Err("No pack loaded".to_string()),
     }
+    // 导出到材质包自身内部会无限递归复制, 替换包含材质包的文件夹会删除材质包本身
+    let source = path.canonicalize().map_err(|e| AppError::io("Failed to resolve pack path", e))?;
+    let resolved_output = output.canonicalize().unwrap_or_else(|_| output.clone());
+    if resolved_output.starts_with(&source) || source.starts_with(&resolved_output) {
+        return Err(AppError::InvalidInput("不能导出到材质包自身或其上级文件夹".to_string()));
+    }
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    rayon::spawn(move || {
+        let excludes = crate::zip_handler::ExcludePatterns::export_defaults();
+        let _ = tx.send(export_folder_replacing(&path, &output, &excludes));
+    });
+
+    Ok(rx.await
+        .map_err(|e| format!("Channel error: {}", e))??)
+}
+
+/// 先复制到 output 旁的临时文件夹, 完成后再替换 output, 使导出结果不残留材质包中已删除的文件
+fn export_folder_replacing(
+    source: &Path,
+    output: &Path,
+    excludes: &crate::zip_handler::ExcludePatterns,
+) -> Result<(), String> {
+    let mut staging_name = output.file_name().unwrap_or_default().to_os_string();
+    staging_name.push(format!(".export-{}", std::process::id()));
+    let staging = output.with_file_name(staging_name);
+    if staging.exists() {
+        std::fs::remove_dir_all(&staging).map_err(|e| format!("清理临时文件夹失败: {}", e))?;
+    }
+
+    let copied = crate::fs_utils::copy_dir_filtered(source, &staging, |relative| {
+        !excludes.is_excluded(relative)
+    });
+    if let Err(e) = copied {
+        let _ = std::fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+
+    if output.exists() {
+        std::fs::remove_dir_all(output).map_err(|e| {
+            let _ = std::fs::remove_dir_all(&staging);
+            format!("删除原有导出文件夹失败: {}", e)
+        })?;
+    }
+    std::fs::rename(&staging, output).map_err(|e| format!("替换导出文件夹失败: {}", e))
 }
 
 /// 清理临时文件
 #[tauri::command]
-pub async fn cleanup_temp() -> Result<(), String> {
-    cleanup_temp_files()
+pub async fn cleanup_temp() -> Result<(), AppError> {
+    Ok(cleanup_temp_files()?)
 }
 
 /// 读取文件内容 
 #[tauri::command]
 pub async fn read_file_content(
     file_path: String,
+    allow_external: Option<bool>,
+    pack_id: Option<String>,
     state: State<'_, AppState>,
-) -> Result<String, String> {
-    let full_path = {
-        let pack_path = state.current_pack_path.lock().unwrap();
-
-        match pack_path.as_ref() {
-            Some(base_path) => {
-                let path = Path::new(&file_path);
-                if path.is_absolute() {
-                    path.to_path_buf()
-                } else {
-                    base_path.join(path)
-                }
-            }
-            None => PathBuf::from(&file_path),
+) -> Result<crate::text_encoding::DecodedText, AppError> {
+    let full_path = resolve_read_path_in(&state, pack_id.as_deref(), &file_path, allow_external)?;
+
+    let bytes = tokio::fs::read(&full_path)
+        .await
+        .map_err(|e| AppError::io("Failed to read file", e))?;
+    crate::text_encoding::decode_text(&bytes)
+        .map_err(|_| AppError::InvalidInput(format!("Binary file cannot be opened as text: {}", file_path)))
+}
+
+/// 读取文件二进制内容
+#[tauri::command]
+pub async fn read_file_binary(
+    file_path: String,
+    allow_external: Option<bool>,
+    pack_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<u8>, AppError> {
+    let full_path = resolve_read_path_in(&state, pack_id.as_deref(), &file_path, allow_external)?;
+
+    tokio::fs::read(&full_path)
+        .await
+        .map_err(|e| AppError::io("Failed to read file", e))
+}
+
+#[tauri::command]
+pub async fn write_file_content(
+    file_path: String,
+    content: String,
+    encoding: Option<crate::text_encoding::TextEncoding>,
+    bom: Option<bool>,
+    pack_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let full_path = resolve_pack_path(&state.pack_path_for(pack_id.as_deref())?, &file_path)?;
+
+    // 默认统一保存为不带 BOM 的 UTF-8, 传入读取时检测到的编码可按原编码写回
+    let bytes = crate::text_encoding::encode_text(
+        &content,
+        encoding.unwrap_or(crate::text_encoding::TextEncoding::Utf8),
+        bom.unwrap_or(false),
+    )
+    .map_err(AppError::InvalidInput)?;
+
+    // 创建父目录
+    if let Some(parent) = full_path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    tokio::fs::write(&full_path, bytes)
+        .await
+        .map_err(|e| AppError::io("Failed to write file", e))
+}
+
+/// 创建新文件, 文件已存在时返回 AlreadyExists, 除非 overwrite 为 true(覆盖前保存历史记录)
+#[tauri::command]
+pub async fn create_new_file(
+    file_path: String,
+    content: String,
+    overwrite: Option<bool>,
+    pack_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let base_path = state.pack_path_for(pack_id.as_deref())?;
+    let full_path = resolve_pack_path(&base_path, &file_path)?;
+
+    if full_path.is_dir() {
+        return Err(AppError::AlreadyExists(format!("同名文件夹已存在: {}", file_path)));
+    }
+    if full_path.exists() {
+        if !overwrite.unwrap_or(false) {
+            return Err(AppError::AlreadyExists(format!("File already exists: {}", file_path)));
         }
-    };
+        if let Ok(relative) = full_path.strip_prefix(&base_path) {
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            crate::history_manager::snapshot_file(&base_path, &relative)?;
+            state.preloader_for_path(&base_path).invalidate(&relative);
+        }
+        crate::image_handler::invalidate_image_caches(&full_path);
+    }
+
+    // 创建父目录
+    if let Some(parent) = full_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    // 写入文件
+    std::fs::write(&full_path, content).map_err(|e| format!("Failed to create file: {}", e))?;
+
+    Ok(())
+}
+
+/// 创建新文件夹, 路径已被文件占用时返回 AlreadyExists
+#[tauri::command]
+pub async fn create_new_folder(
+    folder_path: String,
+    pack_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let full_path = resolve_pack_path(&state.pack_path_for(pack_id.as_deref())?, &folder_path)?;
+
+    if full_path.exists() && !full_path.is_dir() {
+        return Err(AppError::AlreadyExists(format!("同名文件已存在: {}", folder_path)));
+    }
+
+    // 创建文件夹
+    std::fs::create_dir_all(&full_path).map_err(|e| format!("Failed to create folder: {}", e))?;
+
+    Ok(())
+}
+
+/// 解析相对材质包的路径, 结果不能超出材质包目录
+pub(crate) fn resolve_pack_path(base_path: &Path, file_path: &str) -> Result<PathBuf, AppError> {
+    crate::fs_utils::resolve_pack_path(base_path, file_path).map_err(AppError::PermissionDenied)
+}
+
+/// 只读命令解析路径, 相对 pack_id 指定的材质包(为 None 时为当前材质包):
+/// allow_external 时允许材质包外的路径, 未打开材质包时只接受 allow_external
+pub(crate) fn resolve_read_path_in(
+    state: &AppState,
+    pack_id: Option<&str>,
+    file_path: &str,
+    allow_external: Option<bool>,
+) -> Result<PathBuf, AppError> {
+    let allow_external = allow_external.unwrap_or(false);
+    match state.pack_path_for(pack_id) {
+        Ok(base_path) => crate::fs_utils::resolve_pack_path_with(&base_path, file_path, allow_external)
+            .map_err(AppError::PermissionDenied),
+        Err(AppError::NoPackLoaded) if allow_external => Ok(PathBuf::from(file_path)),
+        Err(e) => Err(e),
+    }
+}
+
+/// 获取删除某个文件或文件夹会移除的内容, 用于删除前确认
+#[tauri::command]
+pub async fn get_delete_impact(
+    file_path: String,
+    pack_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<crate::pack_trash::DeleteImpact, AppError> {
+    let full_path = resolve_pack_path(&state.pack_path_for(pack_id.as_deref())?, &file_path)?;
+    if !full_path.exists() {
+        return Err(AppError::NotFound(format!("路径不存在: {}", file_path)));
+    }
+    Ok(crate::pack_trash::delete_impact(&full_path))
+}
+
+/// 删除文件或文件夹
+///
+/// 默认移动到材质包内的 .trash, 可用 restore_from_trash 恢复; to_recycle_bin 为 true 时移动到系统回收站;
+/// permanent 为 true 时直接删除, 永久删除超过设置阈值的文件夹需要 force
+#[tauri::command]
+pub async fn delete_file(
+    file_path: String,
+    to_recycle_bin: Option<bool>,
+    permanent: Option<bool>,
+    force: Option<bool>,
+    pack_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let base_path = state.pack_path_for(pack_id.as_deref())?;
+    let full_path = resolve_pack_path(&base_path, &file_path)?;
+    let guard = crate::pack_settings::load_pack_settings(&base_path)
+        .map(|settings| settings.delete_guard)
+        .unwrap_or_default();
+
+    let metadata = remove_entry(
+        &base_path,
+        &full_path,
+        to_recycle_bin.unwrap_or(false),
+        permanent.unwrap_or(false),
+        force.unwrap_or(false),
+        &guard,
+    )?;
+
+    // 清理缓存并刷新材质包信息
+    if metadata.is_dir() {
+        crate::image_handler::clear_caches();
+        state.preloader_for_path(&base_path).clear_cache().await;
+    } else {
+        crate::image_handler::invalidate_image_caches(&full_path);
+    }
+    if let Ok(relative) = full_path.strip_prefix(&base_path) {
+        if metadata.is_file() {
+            state.preloader_for_path(&base_path).invalidate(&relative.to_string_lossy());
+        }
+        state.refresh_pack_info(&base_path, Some(&[relative.to_path_buf()]))?;
+    }
 
-    tokio::fs::read_to_string(&full_path)
-        .await
-        .map_err(|e| format!("Failed to read file: {}", e))
+    Ok(())
 }
 
-/// 写入文件内容
-#[tauri::command]
-pub async fn read_file_binary(
-    file_path: String,
-    state: State<'_, AppState>,
-) -> Result<Vec<u8>, String> {
-    let full_path = {
-        let pack_path = state.current_pack_path.lock().unwrap();
-
-        match pack_path.as_ref() {
-            Some(base_path) => {
-                let path = Path::new(&file_path);
-                if path.is_absolute() {
-                    path.to_path_buf()
-                } else {
-                    base_path.join(path)
-                }
-            }
-            None => PathBuf::from(&file_path),
+/// 删除单个文件或文件夹, 返回删除前的元数据; 永久删除超过阈值的文件夹需要 force
+///
+/// 非永久删除时移动到 .trash, .trash 中的内容本身总是永久删除
+fn remove_entry(
+    base_path: &Path,
+    full_path: &Path,
+    to_recycle_bin: bool,
+    permanent: bool,
+    force: bool,
+    guard: &crate::pack_settings::DeleteGuardSettings,
+) -> Result<std::fs::Metadata, AppError> {
+    if full_path == base_path {
+        return Err(AppError::InvalidInput("不能删除材质包根目录".to_string()));
+    }
+    // 判断是文件还是目录
+    let metadata = std::fs::metadata(full_path)
+        .map_err(|e| AppError::io("Failed to get file metadata", e))?;
+    let relative = full_path.strip_prefix(base_path).ok();
+    let in_trash = relative.is_some_and(|r| r.starts_with(crate::pack_trash::TRASH_DIR_NAME));
+
+    if to_recycle_bin {
+        crate::pack_trash::move_to_recycle_bin(full_path)?;
+    } else if !permanent && !in_trash {
+        let relative = relative
+            .ok_or_else(|| AppError::InvalidInput(format!("路径不在材质包内: {}", full_path.display())))?;
+        crate::pack_trash::move_to_trash(base_path, &[relative.to_path_buf()])?;
+    } else if metadata.is_dir() {
+        let impact = crate::pack_trash::delete_impact(full_path);
+        if !force && (impact.files > guard.max_files || impact.total_bytes > guard.max_bytes) {
+            return Err(AppError::PermissionDenied(format!(
+                "将永久删除 {} 个文件({} 字节), 超过保护阈值, 需要确认后强制删除",
+                impact.files, impact.total_bytes
+            )));
         }
-    };
+        std::fs::remove_dir_all(full_path)
+            .map_err(|e| AppError::io("Failed to delete folder", e))?;
+    } else {
+        std::fs::remove_file(full_path).map_err(|e| AppError::io("Failed to delete file", e))?;
+    }
 
-    tokio::fs::read(&full_path)
-        .await
-        .map_err(|e| format!("Failed to read file: {}", e))
+    Ok(metadata)
+}
+
+/// 批量删除中失败的条目
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DeleteFailure {
+    pub path: String,
+    pub code: String,
+    pub message: String,
+}
+
+/// 批量删除结果
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct DeleteFilesResult {
+    pub deleted: Vec<String>,
+    pub failed: Vec<DeleteFailure>,
 }
 
+/// 批量删除文件/文件夹, 单个失败不会中止其余条目, 参数含义同 delete_file
 #[tauri::command]
-pub async fn write_file_content(
-    file_path: String,
-    content: String,
+pub async fn delete_files(
+    paths: Vec<String>,
+    to_recycle_bin: Option<bool>,
+    permanent: Option<bool>,
+    force: Option<bool>,
+    pack_id: Option<String>,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let full_path = {
-        let pack_path = state.current_pack_path.lock().unwrap();
-
-        match pack_path.as_ref() {
-            Some(base_path) => {
-                let path = Path::new(&file_path);
-                if path.is_absolute() {
-                    path.to_path_buf()
+) -> Result<DeleteFilesResult, AppError> {
+    let mut result = DeleteFilesResult::default();
+    if paths.is_empty() {
+        return Ok(result);
+    }
+
+    let base_path = state.pack_path_for(pack_id.as_deref())?;
+    let guard = crate::pack_settings::load_pack_settings(&base_path)
+        .map(|settings| settings.delete_guard)
+        .unwrap_or_default();
+
+    let mut changed = Vec::new();
+    let mut removed_dir = false;
+    for path in paths {
+        let removed = resolve_pack_path(&base_path, &path).and_then(|full_path| {
+            remove_entry(
+                &base_path,
+                &full_path,
+                to_recycle_bin.unwrap_or(false),
+                permanent.unwrap_or(false),
+                force.unwrap_or(false),
+                &guard,
+            )
+                .map(|metadata| (full_path, metadata))
+        });
+        match removed {
+            Ok((full_path, metadata)) => {
+                if metadata.is_dir() {
+                    removed_dir = true;
                 } else {
-                    base_path.join(path)
+                    crate::image_handler::invalidate_image_caches(&full_path);
                 }
+                if let Ok(relative) = full_path.strip_prefix(&base_path) {
+                    if metadata.is_file() {
+                        state.preloader_for_path(&base_path).invalidate(&relative.to_string_lossy());
+                    }
+                    changed.push(relative.to_path_buf());
+                }
+                result.deleted.push(path);
             }
-            None => {
-                // 如果没有加载材质包，尝试直接使用路径
-                PathBuf::from(&file_path)
-            }
+            Err(e) => result.failed.push(DeleteFailure {
+                path,
+                code: e.code().to_string(),
+                message: e.message().to_string(),
+            }),
         }
-    };
+    }
 
-    // 创建父目录
-    if let Some(parent) = full_path.parent() {
-        tokio::fs::create_dir_all(parent)
-            .await
-            .map_err(|e| format!("Failed to create directory: {}", e))?;
+    // 删除了文件夹时无法逐个定位其中的缓存, 整体清空
+    if removed_dir {
+        crate::image_handler::clear_caches();
+        state.preloader_for_path(&base_path).clear_cache().await;
+    }
+    if !changed.is_empty() {
+        state.refresh_pack_info(&base_path, Some(&changed))?;
     }
 
-    tokio::fs::write(&full_path, content)
-        .await
-        .map_err(|e| format!("Failed to write file: {}", e))
+    Ok(result)
 }
 
-/// 创建新文件
-#[tauri::command]
-pub async fn create_new_file(
-    file_path: String,
-    content: String,
-    state: State<'_, AppState>,
-) -> Result<(), String> {
-    let pack_path = state.current_pack_path.lock().unwrap();
+/// 移动或重命名文件/文件夹, 历史记录和缓存随之迁移, 返回新的相对路径
+async fn move_entry(
+    state: &AppState,
+    pack_id: Option<&str>,
+    old_path: &str,
+    new_path: &str,
+    overwrite: bool,
+) -> Result<String, AppError> {
+    let base_path = state.pack_path_for(pack_id)?;
+    let full_old_path = resolve_pack_path(&base_path, old_path)?;
+    let full_new_path = resolve_pack_path(&base_path, new_path)?;
+    let relative_of = |p: &Path| {
+        p.strip_prefix(&base_path)
+            .ok()
+            .map(|r| r.to_string_lossy().replace('\\', "/"))
+    };
+    let normalized = relative_of(&full_new_path)
+        .unwrap_or_else(|| full_new_path.to_string_lossy().to_string());
 
-    let full_path = match pack_path.as_ref() {
-        Some(base_path) => {
-            let path = Path::new(&file_path);
-            if path.is_absolute() {
-                path.to_path_buf()
-            } else {
-                base_path.join(path)
-            }
+    if !full_old_path.exists() {
+        return Err(AppError::NotFound(format!("源文件不存在: {}", old_path)));
+    }
+    if full_old_path == full_new_path {
+        return Ok(normalized);
+    }
+    if full_old_path.is_dir() && full_new_path.starts_with(&full_old_path) {
+        return Err(AppError::InvalidInput("不能将文件夹移动到其自身内部".to_string()));
+    }
+
+    // 目标存在且不是源文件本身(大小写不敏感时仅大小写不同的路径指向同一文件)
+    let is_case_only = crate::fs_utils::is_same_entry(&full_old_path, &full_new_path);
+    let replaces_existing = full_new_path.exists() && !is_case_only;
+    if replaces_existing {
+        if !overwrite {
+            return Err(AppError::AlreadyExists(format!("目标已存在: {}", new_path)));
         }
-        None => {
-            return Err("No pack loaded".to_string());
+        if full_new_path.is_dir() {
+            return Err(AppError::InvalidInput(format!("不能覆盖文件夹: {}", new_path)));
         }
-    };
+    }
 
-    // 创建父目录
-    if let Some(parent) = full_path.parent() {
+    // 覆盖前保存目标文件的历史记录, 迁移时与源文件的历史记录合并
+    if replaces_existing {
+        if let Some(relative) = relative_of(&full_new_path) {
+            crate::history_manager::snapshot_file(&base_path, &relative)?;
+        }
+    }
+
+    if let Some(parent) = full_new_path.parent() {
         std::fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create directory: {}", e))?;
+            .map_err(|e| AppError::io("Failed to create directory", e))?;
+    }
+    crate::fs_utils::move_path(&full_old_path, &full_new_path)
+        .map_err(|e| AppError::Io(format!("Failed to move file: {}", e)))?;
+
+    // 历史记录和缓存随文件一起迁移
+    let old_relative = relative_of(&full_old_path);
+    let new_relative = relative_of(&full_new_path);
+    if let (Some(old_relative), Some(new_relative)) = (&old_relative, &new_relative) {
+        crate::history_manager::move_file_history(&base_path, old_relative, new_relative)?;
+    }
+    if full_new_path.is_dir() {
+        crate::image_handler::clear_caches();
+        state.preloader_for_path(&base_path).clear_cache().await;
+    } else {
+        for path in [&full_old_path, &full_new_path] {
+            crate::image_handler::invalidate_image_caches(path);
+            if let Ok(relative) = path.strip_prefix(&base_path) {
+                state.preloader_for_path(&base_path).invalidate(&relative.to_string_lossy());
+            }
+        }
     }
+    let changed: Vec<PathBuf> = [old_relative, new_relative]
+        .into_iter()
+        .flatten()
+        .map(PathBuf::from)
+        .collect();
+    state.refresh_pack_info(&base_path, Some(&changed))?;
 
-    // 写入文件
-    std::fs::write(&full_path, content).map_err(|e| format!("Failed to create file: {}", e))?;
+    Ok(normalized)
+}
 
-    Ok(())
+/// 重命名文件或文件夹, 返回新的相对路径
+///
+/// 目标已存在时需要 overwrite, 仅大小写不同的重命名在大小写不敏感的文件系统上同样可用
+#[tauri::command]
+pub async fn rename_file(
+    old_path: String,
+    new_path: String,
+    overwrite: Option<bool>,
+    pack_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    move_entry(&state, pack_id.as_deref(), &old_path, &new_path, overwrite.unwrap_or(false)).await
 }
 
-/// 创建新文件夹
+/// 将文件或文件夹移动到其他目录, 跨磁盘时退回复制后删除, 返回新的相对路径
+///
+/// 源不存在返回 not_found, 目标已存在返回 already_exists, 其余读写失败返回 io_error
 #[tauri::command]
-pub async fn create_new_folder(
+pub async fn move_file(
+    old_path: String,
+    new_path: String,
+    pack_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    move_entry(&state, pack_id.as_deref(), &old_path, &new_path, false).await
+}
+
+/// 批量重命名中的一项, 均为相对材质包根目录的路径
+#[derive(Debug, Clone, Serialize)]
+pub struct RenameMapping {
+    pub old_path: String,
+    pub new_path: String,
+}
+
+/// 用正则替换文件夹内(含子目录)所有文件的文件名
+///
+/// dry_run 时只返回将要执行的重命名; 重名或目标已存在时在改名前整体报错,
+/// 执行中途失败会撤销已完成的部分, 返回实际执行的重命名
+#[tauri::command]
+pub async fn batch_rename(
     folder_path: String,
+    pattern: String,
+    replacement: String,
+    dry_run: bool,
+    pack_id: Option<String>,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let pack_path = state.current_pack_path.lock().unwrap();
+) -> Result<Vec<RenameMapping>, AppError> {
+    let base_path = state.pack_path_for(pack_id.as_deref())?;
+    let folder = resolve_pack_path(&base_path, &folder_path)?;
+    if !folder.is_dir() {
+        return Err(AppError::NotFound(format!("文件夹不存在: {}", folder_path)));
+    }
+    let regex = Regex::new(&pattern)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid regex pattern: {}", e)))?;
 
-    let full_path = match pack_path.as_ref() {
-        Some(base_path) => {
-            let path = Path::new(&folder_path);
-            if path.is_absolute() {
-                path.to_path_buf()
-            } else {
-                base_path.join(path)
-            }
+    let mut plan: Vec<(PathBuf, PathBuf)> = Vec::new();
+    let walker = walkdir::WalkDir::new(&folder)
+        .into_iter()
+        .filter_entry(|e| {
+            // 跳过编辑器数据目录, 回收站中的文件改名后无法还原
+            !(e.file_type().is_dir()
+                && e.file_name().to_str().is_some_and(crate::fs_utils::is_editor_data_dir))
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file());
+    for entry in walker {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !regex.is_match(&name) {
+            continue;
+        }
+        let new_name = regex.replace_all(&name, replacement.as_str()).to_string();
+        if new_name == name {
+            continue;
+        }
+        if new_name.is_empty() || new_name == "." || new_name == ".." || new_name.contains(['/', '\\']) {
+            return Err(AppError::InvalidInput(format!("无效的新文件名: {} -> {}", name, new_name)));
+        }
+        let old = entry.into_path();
+        let new = old.with_file_name(&new_name);
+        plan.push((old, new));
+    }
+
+    // 先检查冲突, 保证要么全部执行要么都不执行; 按小写比较以兼容大小写不敏感的文件系统
+    let mut targets = std::collections::HashSet::new();
+    for (old, new) in &plan {
+        if !targets.insert(new.to_string_lossy().to_lowercase()) {
+            return Err(AppError::AlreadyExists(format!("多个文件将重命名为同一名称: {}", new.display())));
         }
-        None => {
-            return Err("No pack loaded".to_string());
+        if new.exists() && !crate::fs_utils::is_same_entry(old, new) {
+            return Err(AppError::AlreadyExists(format!("目标已存在: {}", new.display())));
         }
+    }
+
+    let relative_of = |p: &Path| {
+        p.strip_prefix(&base_path)
+            .map(|r| r.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_else(|_| p.to_string_lossy().to_string())
     };
+    let mappings: Vec<RenameMapping> = plan
+        .iter()
+        .map(|(old, new)| RenameMapping {
+            old_path: relative_of(old),
+            new_path: relative_of(new),
+        })
+        .collect();
+    if dry_run || plan.is_empty() {
+        return Ok(mappings);
+    }
 
-    // 创建文件夹
-    std::fs::create_dir_all(&full_path).map_err(|e| format!("Failed to create folder: {}", e))?;
+    for (index, (old, new)) in plan.iter().enumerate() {
+        if let Err(e) = crate::fs_utils::rename_path(old, new) {
+            // 撤销已完成的重命名
+            for (done_old, done_new) in plan[..index].iter().rev() {
+                let _ = crate::fs_utils::rename_path(done_new, done_old);
+            }
+            return Err(AppError::io(&format!("Failed to rename {}", old.display()), e));
+        }
+    }
 
-    Ok(())
+    let preloader = state.preloader_for_path(&base_path);
+    let mut changed = Vec::new();
+    for (mapping, (old, new)) in mappings.iter().zip(&plan) {
+        crate::history_manager::move_file_history(&base_path, &mapping.old_path, &mapping.new_path)?;
+        for path in [old, new] {
+            crate::image_handler::invalidate_image_caches(path);
+        }
+        for relative in [&mapping.old_path, &mapping.new_path] {
+            preloader.invalidate(relative);
+            changed.push(PathBuf::from(relative));
+        }
+    }
+    state.refresh_pack_info(&base_path, Some(&changed))?;
+
+    Ok(mappings)
 }
 
-/// 删除文件
+/// 复制文件或文件夹, 返回目标的相对路径
+///
+/// 目标已存在时需要 overwrite, 文件夹会与已有的同名文件夹合并
 #[tauri::command]
-pub async fn delete_file(file_path: String, state: State<'_, AppState>) -> Result<(), String> {
-    let pack_path = state.current_pack_path.lock().unwrap();
-
-    let full_path = match pack_path.as_ref() {
-        Some(base_path) => {
-            let path = Path::new(&file_path);
-            if path.is_absolute() {
-                path.to_path_buf()
-            } else {
-                base_path.join(path)
-            }
+pub async fn copy_file(
+    src_path: String,
+    dest_path: String,
+    overwrite: bool,
+    pack_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    let base_path = state.pack_path_for(pack_id.as_deref())?;
+    let full_src = resolve_pack_path(&base_path, &src_path)?;
+    let full_dest = resolve_pack_path(&base_path, &dest_path)?;
+    let dest_relative = full_dest
+        .strip_prefix(&base_path)
+        .ok()
+        .map(Path::to_path_buf);
+
+    if !full_src.exists() {
+        return Err(AppError::NotFound(format!("文件不存在: {}", src_path)));
+    }
+    if full_src == full_dest || crate::fs_utils::is_same_entry(&full_src, &full_dest) {
+        return Err(AppError::InvalidInput("源路径和目标路径相同".to_string()));
+    }
+    if full_src.is_dir() && full_dest.starts_with(&full_src) {
+        return Err(AppError::InvalidInput("不能将文件夹复制到其自身内部".to_string()));
+    }
+    if full_dest.exists() {
+        if !overwrite {
+            return Err(AppError::AlreadyExists(format!("目标已存在: {}", dest_path)));
         }
-        None => {
-            return Err("No pack loaded".to_string());
+        if full_src.is_dir() != full_dest.is_dir() {
+            return Err(AppError::InvalidInput(format!("不能用文件覆盖文件夹或反之: {}", dest_path)));
         }
-    };
+    }
 
-    // 判断是文件还是目录
-    let metadata =
-        std::fs::metadata(&full_path).map_err(|e| format!("Failed to get file metadata: {}", e))?;
+    if let Some(parent) = full_dest.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| AppError::io("Failed to create directory", e))?;
+    }
 
-    if metadata.is_dir() {
-        std::fs::remove_dir_all(&full_path)
-            .map_err(|e| format!("Failed to delete folder: {}", e))?;
+    if full_src.is_dir() {
+        crate::fs_utils::copy_dir_all(&full_src, &full_dest)?;
+        crate::image_handler::clear_caches();
+        state.preloader_for_path(&base_path).clear_cache().await;
     } else {
-        std::fs::remove_file(&full_path).map_err(|e| format!("Failed to delete file: {}", e))?;
+        // 覆盖前保存目标文件的历史记录
+        if let Some(relative) = &dest_relative {
+            crate::history_manager::snapshot_file(&base_path, &relative.to_string_lossy().replace('\\', "/"))?;
+            state.preloader_for_path(&base_path).invalidate(&relative.to_string_lossy());
+        }
+        std::fs::copy(&full_src, &full_dest)
+            .map_err(|e| AppError::io("Failed to copy file", e))?;
+        crate::image_handler::invalidate_image_caches(&full_dest);
     }
 
-    Ok(())
+    match dest_relative {
+        Some(relative) => {
+            state.refresh_pack_info(&base_path, Some(std::slice::from_ref(&relative)))?;
+            Ok(relative.to_string_lossy().replace('\\', "/"))
+        }
+        None => Ok(full_dest.to_string_lossy().to_string()),
+    }
+}
+
+/// 导入外部文件时目标已存在的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportConflict {
+    /// 覆盖文件, 文件夹与已有的同名文件夹合并
+    Overwrite,
+    Skip,
+    /// 自动命名为 name_copy、name_copy2 ...
+    Rename,
+}
+
+/// 导入的单个文件或文件夹
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedFile {
+    pub source: String,
+    /// 相对材质包根目录的路径
+    pub path: String,
+    /// PNG 是否为有效纹理, 其他文件为 None
+    pub is_valid_texture: Option<bool>,
+}
+
+/// 导入结果, renamed 中为因重名而改名导入的条目
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportFilesResult {
+    pub imported: Vec<ImportedFile>,
+    pub skipped: Vec<String>,
+    pub renamed: Vec<ImportedFile>,
 }
 
-/// 重命名文件
+/// 将材质包外部的文件或文件夹(绝对路径)复制到材质包内的 dest_folder, 按需创建目录
 #[tauri::command]
-pub async fn rename_file(
-    old_path: String,
-    new_path: String,
+pub async fn import_files(
+    sources: Vec<String>,
+    dest_folder: String,
+    conflict: ImportConflict,
+    pack_id: Option<String>,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let pack_path = state.current_pack_path.lock().unwrap();
-
-    let full_old_path = match pack_path.as_ref() {
-        Some(base_path) => {
-            let path = Path::new(&old_path);
-            if path.is_absolute() {
-                path.to_path_buf()
-            } else {
-                base_path.join(path)
-            }
+) -> Result<ImportFilesResult, AppError> {
+    let base_path = state.pack_path_for(pack_id.as_deref())?;
+    let dest_dir = resolve_pack_path(&base_path, &dest_folder)?;
+    let preloader = state.preloader_for_path(&base_path);
+
+    // 先检查全部来源, 避免导入一半才发现路径错误
+    for source in &sources {
+        let path = Path::new(source);
+        if !path.is_absolute() {
+            return Err(AppError::InvalidInput(format!("来源必须是绝对路径: {}", source)));
         }
-        None => {
-            return Err("No pack loaded".to_string());
+        if !path.exists() {
+            return Err(AppError::NotFound(format!("文件不存在: {}", source)));
+        }
+        if path.is_dir() && dest_dir.starts_with(path) {
+            return Err(AppError::InvalidInput(format!("不能将文件夹复制到其自身内部: {}", source)));
+        }
+    }
+    std::fs::create_dir_all(&dest_dir)
+        .map_err(|e| AppError::io("Failed to create directory", e))?;
+
+    let mut result = ImportFilesResult::default();
+    let mut changed = Vec::new();
+    let mut copied_dir = false;
+    for source in sources {
+        let src = PathBuf::from(&source);
+        let Some(file_name) = src.file_name() else {
+            return Err(AppError::InvalidInput(format!("无效的来源路径: {}", source)));
+        };
+        let mut target = dest_dir.join(file_name);
+        let mut renamed = false;
+        if target.exists() {
+            match conflict {
+                ImportConflict::Skip => {
+                    result.skipped.push(source);
+                    continue;
+                }
+                ImportConflict::Rename => {
+                    target = crate::fs_utils::next_copy_path(&target)
+                        .ok_or_else(|| AppError::Other(format!("无法生成副本名称: {}", source)))?;
+                    renamed = true;
+                }
+                ImportConflict::Overwrite => {
+                    if src.is_dir() != target.is_dir() {
+                        return Err(AppError::InvalidInput(format!(
+                            "不能用文件覆盖文件夹或反之: {}",
+                            target.display()
+                        )));
+                    }
+                }
+            }
         }
-    };
 
-    let full_new_path = match pack_path.as_ref() {
-        Some(base_path) => {
-            let path = Path::new(&new_path);
-            if path.is_absolute() {
-                path.to_path_buf()
-            } else {
-                base_path.join(path)
+        let relative = target
+            .strip_prefix(&base_path)
+            .map(|r| r.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_else(|_| target.to_string_lossy().to_string());
+        let mut is_valid_texture = None;
+        if src.is_dir() {
+            crate::fs_utils::copy_dir_all(&src, &target)?;
+            copied_dir = true;
+        } else {
+            if target.exists() {
+                crate::history_manager::snapshot_file(&base_path, &relative)?;
+            }
+            std::fs::copy(&src, &target)
+                .map_err(|e| AppError::io("Failed to copy file", e))?;
+            crate::image_handler::invalidate_image_caches(&target);
+            preloader.invalidate(&relative);
+            if target.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("png")) {
+                is_valid_texture = Some(get_image_info(&target).is_ok_and(|info| info.is_valid_texture));
             }
         }
-        None => {
-            return Err("No pack loaded".to_string());
+
+        changed.push(PathBuf::from(&relative));
+        let entry = ImportedFile { source, path: relative, is_valid_texture };
+        if renamed {
+            result.renamed.push(entry);
+        } else {
+            result.imported.push(entry);
         }
-    };
+    }
 
-    std::fs::rename(&full_old_path, &full_new_path)
-        .map_err(|e| format!("Failed to rename file: {}", e))?;
+    if copied_dir {
+        crate::image_handler::clear_caches();
+        preloader.clear_cache().await;
+    }
+    if !changed.is_empty() {
+        state.refresh_pack_info(&base_path, Some(&changed))?;
+    }
 
-    Ok(())
+    Ok(result)
 }
 
-/// 获取pack.mcmeta内容
+/// 在原位置创建文件或文件夹的副本(自动命名为 name_copy、name_copy2 ...), 返回副本的相对路径
 #[tauri::command]
-pub async fn get_pack_mcmeta(state: State<'_, AppState>) -> Result<String, String> {
-    let pack_path = state.current_pack_path.lock().unwrap();
+pub async fn duplicate_file(
+    file_path: String,
+    pack_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    let base_path = state.pack_path_for(pack_id.as_deref())?;
+    let full_path = resolve_pack_path(&base_path, &file_path)?;
+    if !full_path.exists() {
+        return Err(AppError::NotFound(format!("文件不存在: {}", file_path)));
+    }
+    let target = crate::fs_utils::next_copy_path(&full_path)
+        .ok_or_else(|| AppError::InvalidInput(format!("无法复制: {}", file_path)))?;
+
+    if full_path.is_dir() {
+        crate::fs_utils::copy_dir_skipping(&full_path, &target, &[".history"])?;
+    } else {
+        std::fs::copy(&full_path, &target)
+            .map_err(|e| AppError::io("Failed to copy file", e))?;
+    }
 
-    match pack_path.as_ref() {
-        Some(path) => {
-            let mcmeta_path = path.join("pack.mcmeta");
-            std::fs::read_to_string(mcmeta_path)
-                .map_err(|e| format!("Failed to read pack.mcmeta: {}", e))
+    match target.strip_prefix(&base_path) {
+        Ok(relative) => {
+            state.refresh_pack_info(&base_path, Some(&[relative.to_path_buf()]))?;
+            Ok(relative.to_string_lossy().replace('\\', "/"))
         }
-        None => Err("No pack loaded".to_string()),
+        Err(_) => Ok(target.to_string_lossy().replace('\\', "/")),
     }
 }
 
-/// 更新pack.mcmeta
+/// 获取pack.mcmeta内容
 #[tauri::command]
-pub async fn update_pack_mcmeta(content: String, state: State<'_, AppState>) -> Result<(), String> {
-    let pack_path = state.current_pack_path.lock().unwrap();
+pub async fn get_pack_mcmeta(state: State<'_, AppState>) -> Result<String, AppError> {
+    let mcmeta_path = state.pack_path()?.join("pack.mcmeta");
+    std::fs::read_to_string(mcmeta_path)
+        .map_err(|e| AppError::io("Failed to read pack.mcmeta", e))
+}
 
-    match pack_path.as_ref() {
-        Some(path) => {
-            let mcmeta_path = path.join("pack.mcmeta");
-            std::fs::write(mcmeta_path, content)
-                .map_err(|e| format!("Failed to write pack.mcmeta: {}", e))?;
+/// 更新pack.mcmeta
+#[tauri::command]
+pub async fn update_pack_mcmeta(content: String, state: State<'_, AppState>) -> Result<(), AppError> {
+    let path = state.pack_path()?;
+    std::fs::write(path.join("pack.mcmeta"), content)
+        .map_err(|e| format!("Failed to write pack.mcmeta: {}", e))?;
 
-            // 重新扫描材质包
-            let pack_info = scan_pack_directory(path)?;
-            drop(pack_path);
-            *state.current_pack_info.lock().unwrap() = Some(pack_info);
+    // 刷新材质包信息
+    state.refresh_pack_info(&path, Some(&[PathBuf::from("pack.mcmeta")]))?;
 
-            Ok(())
-        }
-        None => Err("No pack loaded".to_string()),
-    }
+    Ok(())
 }
 
 /// 创建新材质包
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn create_new_pack(
     output_path: String,
     pack_name: String,
     pack_format: i32,
     description: String,
+    overlays: Option<Vec<crate::pack_creator::OverlaySpec>>,
+    allow_existing: Option<bool>,
+    overwrite_existing: Option<bool>,
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
-) -> Result<(), String> {
+) -> Result<String, AppError> {
+    crate::pack_creator::validate_pack_name(&pack_name).map_err(AppError::InvalidInput)?;
+
     let path = std::path::Path::new(&output_path);
-    crate::pack_creator::create_new_pack(path, &pack_name, pack_format, &description)?;
+    // 默认不写入已有内容的目录, 避免和无关文件混在一起
+    if !allow_existing.unwrap_or(false) && crate::pack_creator::is_non_empty_dir(path) {
+        return Err(AppError::AlreadyExists(format!("目标目录已存在且不为空: {}", output_path)));
+    }
+
+    crate::pack_creator::create_new_pack(
+        path,
+        &pack_name,
+        pack_format,
+        &description,
+        &overlays.unwrap_or_default(),
+        overwrite_existing.unwrap_or(false),
+    )?;
 
     // 自动加载新创建的材质包
     let pack_info = crate::pack_parser::scan_pack_directory(path)?;
-    *state.current_pack_path.lock().unwrap() = Some(path.to_path_buf());
-    *state.current_pack_info.lock().unwrap() = Some(pack_info);
+    let (id, preloader) = state.replace_active_pack(path.to_path_buf(), pack_info);
+    start_pack_watcher(&app, &state, &id, path, preloader);
+
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// 为当前材质包添加 overlay, formats 为 [min, max]
+#[tauri::command]
+pub async fn add_overlay(
+    directory: String,
+    formats: (u32, u32),
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let base_path = state.pack_path()?;
+    let overlay = crate::pack_creator::OverlaySpec {
+        directory,
+        min_format: formats.0,
+        max_format: formats.1,
+    };
+    crate::pack_creator::add_overlay(&base_path, &overlay)?;
+
+    state.refresh_pack_info(&base_path, Some(&[PathBuf::from("pack.mcmeta")]))?;
 
     Ok(())
 }
 
-/// 为物品创建模型
+/// 移除当前材质包的 overlay
 #[tauri::command]
-pub async fn create_item_model(item_id: String, state: State<'_, AppState>) -> Result<(), String> {
-    let pack_path_guard = state.current_pack_path.lock().unwrap();
-    let pack_info_guard = state.current_pack_info.lock().unwrap();
+pub async fn remove_overlay(directory: String, state: State<'_, AppState>) -> Result<(), AppError> {
+    let base_path = state.pack_path()?;
+    crate::pack_creator::remove_overlay(&base_path, &directory)?;
+
+    state.refresh_pack_info(&base_path, Some(&[PathBuf::from("pack.mcmeta")]))?;
 
-    let path = pack_path_guard.as_ref().ok_or("No pack loaded")?;
-    let info = pack_info_guard.as_ref().ok_or("No pack loaded")?;
-    let pack_format = info.pack_format;
-    let path_clone = path.clone();
+    Ok(())
+}
 
-    drop(pack_path_guard);
-    drop(pack_info_guard);
+/// 为物品创建模型
+#[tauri::command]
+pub async fn create_item_model(item_id: String, state: State<'_, AppState>) -> Result<(), AppError> {
+    let path_clone = state.pack_path()?;
+    let pack_format = state.pack_info().ok_or(AppError::NoPackLoaded)?.pack_format;
 
     crate::pack_creator::create_item_model(&path_clone, &item_id, pack_format)?;
 
-    // 重新扫描材质包
-    let new_pack_info = crate::pack_parser::scan_pack_directory(&path_clone)?;
-    *state.current_pack_info.lock().unwrap() = Some(new_pack_info);
+    // 刷新材质包信息
+    let changed = crate::pack_creator::item_model_paths(std::slice::from_ref(&item_id));
+    state.refresh_pack_info(&path_clone, Some(&changed))?;
 
     Ok(())
 }
@@ -533,16 +1838,14 @@ pub async fn create_item_model(item_id: String, state: State<'_, AppState>) -> R
 pub async fn create_block_model(
     block_id: String,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let pack_path_guard = state.current_pack_path.lock().unwrap();
-    let path = pack_path_guard.as_ref().ok_or("No pack loaded")?.clone();
-    drop(pack_path_guard);
+) -> Result<(), AppError> {
+    let path = state.pack_path()?;
 
     crate::pack_creator::create_block_model(&path, &block_id)?;
 
-    // 重新扫描材质包
-    let pack_info = crate::pack_parser::scan_pack_directory(&path)?;
-    *state.current_pack_info.lock().unwrap() = Some(pack_info);
+    // 刷新材质包信息
+    let changed = crate::pack_creator::block_model_paths(std::slice::from_ref(&block_id));
+    state.refresh_pack_info(&path, Some(&changed))?;
 
     Ok(())
 }
@@ -552,24 +1855,16 @@ pub async fn create_block_model(
 pub async fn create_multiple_item_models(
     item_ids: Vec<String>,
     state: State<'_, AppState>,
-) -> Result<Vec<String>, String> {
-    let pack_path_guard = state.current_pack_path.lock().unwrap();
-    let pack_info_guard = state.current_pack_info.lock().unwrap();
-
-    let path = pack_path_guard.as_ref().ok_or("No pack loaded")?;
-    let info = pack_info_guard.as_ref().ok_or("No pack loaded")?;
-    let pack_format = info.pack_format;
-    let path_clone = path.clone();
-
-    drop(pack_path_guard);
-    drop(pack_info_guard);
+) -> Result<Vec<String>, AppError> {
+    let path_clone = state.pack_path()?;
+    let pack_format = state.pack_info().ok_or(AppError::NoPackLoaded)?.pack_format;
 
     let created =
         crate::pack_creator::create_multiple_item_models(&path_clone, &item_ids, pack_format)?;
 
-    // 重新扫描材质包
-    let new_pack_info = crate::pack_parser::scan_pack_directory(&path_clone)?;
-    *state.current_pack_info.lock().unwrap() = Some(new_pack_info);
+    // 刷新材质包信息
+    let changed = crate::pack_creator::item_model_paths(&created);
+    state.refresh_pack_info(&path_clone, Some(&changed))?;
 
     Ok(created)
 }
@@ -578,23 +1873,21 @@ pub async fn create_multiple_item_models(
 pub async fn create_multiple_block_models(
     block_ids: Vec<String>,
     state: State<'_, AppState>,
-) -> Result<Vec<String>, String> {
-    let pack_path_guard = state.current_pack_path.lock().unwrap();
-    let path = pack_path_guard.as_ref().ok_or("No pack loaded")?.clone();
-    drop(pack_path_guard);
+) -> Result<Vec<String>, AppError> {
+    let path = state.pack_path()?;
 
     let created = crate::pack_creator::create_multiple_block_models(&path, &block_ids)?;
 
-    // 重新扫描材质包
-    let pack_info = crate::pack_parser::scan_pack_directory(&path)?;
-    *state.current_pack_info.lock().unwrap() = Some(pack_info);
+    // 刷新材质包信息
+    let changed = crate::pack_creator::block_model_paths(&created);
+    state.refresh_pack_info(&path, Some(&changed))?;
 
     Ok(created)
 }
 
 /// 获取系统已安装的字体列表
 #[tauri::command]
-pub async fn get_system_fonts() -> Result<Vec<String>, String> {
+pub async fn get_system_fonts() -> Result<Vec<String>, AppError> {
     let source = SystemSource::new();
     let mut font_names = std::collections::HashSet::new();
 
@@ -623,6 +1916,76 @@ pub struct FileTreeNode {
     pub children: Option<Vec<FileTreeNode>>,
     pub file_count: Option<usize>,
     pub loaded: bool,
+    /// 文件大小, 文件夹为 None
+    #[serde(default)]
+    pub size: Option<u64>,
+    /// 修改时间(Unix 毫秒)
+    #[serde(default)]
+    pub modified_ms: Option<u64>,
+    /// 已加载子节点的文件夹的内容指纹, 可传给 load_folder_children 的 if_none_match
+    #[serde(default)]
+    pub fingerprint: Option<String>,
+}
+
+/// 文件夹子节点及其内容指纹, not_modified 时不返回子节点
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct FolderChildren {
+    pub fingerprint: String,
+    pub not_modified: bool,
+    pub children: Option<Vec<FileTreeNode>>,
+}
+
+fn modified_ms(metadata: &std::fs::Metadata) -> Option<u64> {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+}
+
+/// 根据按名称排序的子项(名称、文件大小、修改时间、子文件夹指纹)计算指纹
+fn fingerprint_of<'a>(
+    items: impl Iterator<Item = (&'a str, Option<u64>, Option<u64>, Option<&'a str>)>,
+) -> String {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    for item in items {
+        item.hash(&mut hasher);
+    }
+    format!("{:016x}", hasher.finish())
+}
+
+/// 包含已加载子文件夹的指纹, 深层文件变化时上层指纹也会变化
+fn fingerprint_nodes(nodes: &[FileTreeNode]) -> String {
+    fingerprint_of(
+        nodes
+            .iter()
+            .map(|n| (n.name.as_str(), n.size, n.modified_ms, n.fingerprint.as_deref())),
+    )
+}
+
+/// 不构建节点直接计算文件夹指纹, 与 read_directory_tree_lazy 以相同 depth/max_depth 读取的结果一致
+fn directory_fingerprint(path: &Path, depth: usize, max_depth: usize) -> Result<String, String> {
+    let mut items: Vec<_> = std::fs::read_dir(path)
+        .map_err(|e| format!("Failed to read directory: {}", e))?
+        .filter_map(|e| e.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if crate::fs_utils::is_editor_data_dir(&name) {
+                return None;
+            }
+            let metadata = entry.metadata().ok()?;
+            let size = (!metadata.is_dir()).then_some(metadata.len());
+            let children = (metadata.is_dir() && depth < max_depth)
+                .then(|| directory_fingerprint(&entry.path(), depth + 1, max_depth).ok())
+                .flatten();
+            Some((name, size, modified_ms(&metadata), children))
+        })
+        .collect();
+    items.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+    Ok(fingerprint_of(items.iter().map(|(name, size, modified, children)| {
+        (name.as_str(), *size, *modified, children.as_deref())
+    })))
 }
 
 fn read_directory_tree_lazy(
@@ -658,11 +2021,14 @@ fn read_directory_tree_lazy(
 
             let name = entry.file_name().to_string_lossy().to_string();
             
-            // 跳过 .little100 目录
-            if name == ".little100" {
+            // 跳过 .history、.little100 缓存和 .trash 回收站
+            if crate::fs_utils::is_editor_data_dir(&name) {
                 return None;
             }
 
+            // 复用遍历时取得的元数据, 不再单独 stat
+            let modified_ms = modified_ms(&metadata);
+
             let node = if metadata.is_dir() {
                 let file_count = std::fs::read_dir(&entry_path)
                     .map(|entries| entries.count())
@@ -683,9 +2049,12 @@ fn read_directory_tree_lazy(
                     name,
                     path: relative_path,
                     is_dir: true,
+                    fingerprint: children.as_deref().map(fingerprint_nodes),
                     children,
                     file_count: Some(file_count),
                     loaded: depth < max_depth,
+                    size: None,
+                    modified_ms,
                 }
             } else {
                 FileTreeNode {
@@ -695,6 +2064,9 @@ fn read_directory_tree_lazy(
                     children: None,
                     file_count: None,
                     loaded: true,
+                    size: Some(metadata.len()),
+                    modified_ms,
+                    fingerprint: None,
                 }
             };
 
@@ -705,177 +2077,289 @@ fn read_directory_tree_lazy(
     Ok(nodes)
 }
 
-/// 获取材质包的文件树结构
-#[tauri::command]
-pub async fn get_file_tree(state: State<'_, AppState>) -> Result<FileTreeNode, String> {
-    let pack_path = state.current_pack_path.lock().unwrap();
+/// get_file_tree 预先读取的层级
+const FILE_TREE_DEPTH: usize = 2;
 
-    match pack_path.as_ref() {
-        Some(path) => {
-            let pack_name = path
-                .file_name()
-                .unwrap_or_default()
-                .to_string_lossy()
-                .to_string();
+/// load_folder_children 预先读取的层级
+const FOLDER_CHILDREN_DEPTH: usize = 1;
 
-            let children = read_directory_tree_lazy(path, path, 0, 2)?;
+/// 获取材质包的文件树结构
+///
+/// if_none_match 与根目录当前指纹相同时返回 None
+#[tauri::command]
+pub async fn get_file_tree(
+    if_none_match: Option<String>,
+    pack_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Option<FileTreeNode>, AppError> {
+    let path = state.pack_path_for(pack_id.as_deref())?;
+    // 指纹覆盖与返回结果相同的层级
+    let fingerprint = directory_fingerprint(&path, 0, FILE_TREE_DEPTH)?;
+    if if_none_match.as_deref() == Some(fingerprint.as_str()) {
+        return Ok(None);
+    }
 
-            let file_count = std::fs::read_dir(path)
-                .map(|entries| entries.count())
-                .unwrap_or(0);
+    let pack_name = path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
 
-            Ok(FileTreeNode {
-                name: pack_name,
-                path: String::new(),
-                is_dir: true,
-                children: Some(children),
-                file_count: Some(file_count),
-                loaded: true,
-            })
-        }
-        None => Err("No pack loaded".to_string()),
-    }
+    let children = read_directory_tree_lazy(&path, &path, 0, FILE_TREE_DEPTH)?;
+
+    let file_count = std::fs::read_dir(&path)
+        .map(|entries| entries.count())
+        .unwrap_or(0);
+
+    Ok(Some(FileTreeNode {
+        name: pack_name,
+        path: String::new(),
+        is_dir: true,
+        fingerprint: Some(fingerprint_nodes(&children)),
+        children: Some(children),
+        file_count: Some(file_count),
+        loaded: true,
+        size: None,
+        modified_ms: None,
+    }))
 }
 
 /// 懒加载指定文件夹的子节点
+///
+/// if_none_match 与当前指纹相同时只返回指纹, 跳过子节点的读取和序列化
 #[tauri::command]
 pub async fn load_folder_children(
     folder_path: String,
+    if_none_match: Option<String>,
+    pack_id: Option<String>,
     state: State<'_, AppState>,
-) -> Result<Vec<FileTreeNode>, String> {
-    let pack_path = state.current_pack_path.lock().unwrap();
-
-    match pack_path.as_ref() {
-        Some(base_path) => {
-            let full_path = if folder_path.is_empty() {
-                base_path.clone()
-            } else {
-                base_path.join(&folder_path)
-            };
+) -> Result<FolderChildren, AppError> {
+    let base_path = state.pack_path_for(pack_id.as_deref())?;
+    let full_path = resolve_pack_path(&base_path, &folder_path)?;
 
-            read_directory_tree_lazy(&full_path, base_path, 0, 1)
-        }
-        None => Err("No pack loaded".to_string()),
+    let fingerprint = directory_fingerprint(&full_path, 0, FOLDER_CHILDREN_DEPTH)?;
+    if if_none_match.as_deref() == Some(fingerprint.as_str()) {
+        return Ok(FolderChildren { fingerprint, not_modified: true, children: None });
     }
+
+    let children = read_directory_tree_lazy(&full_path, &base_path, 0, FOLDER_CHILDREN_DEPTH)?;
+    Ok(FolderChildren {
+        fingerprint: fingerprint_nodes(&children),
+        not_modified: false,
+        children: Some(children),
+    })
+}
+
+/// 创建透明PNG的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatePngResult {
+    pub created: bool,
+    /// 尺寸少见时的提示, 未指定 force 时不会创建文件
+    pub warnings: Vec<String>,
 }
 
 /// 创建透明PNG图片
+///
+/// 尺寸少见(如 182x5 的 GUI 切片)时返回警告而不创建, 确认后传入 force 再次调用
 #[tauri::command]
 pub async fn create_transparent_png(
     file_path: String,
     width: u32,
     height: u32,
+    force: Option<bool>,
+    pack_id: Option<String>,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let pack_path = state.current_pack_path.lock().unwrap();
+) -> Result<CreatePngResult, AppError> {
+    let warnings = crate::image_handler::check_texture_size(width, height)
+        .map_err(AppError::InvalidInput)?;
+    if !warnings.is_empty() && !force.unwrap_or(false) {
+        return Ok(CreatePngResult { created: false, warnings });
+    }
 
-    let full_path = match pack_path.as_ref() {
-        Some(base_path) => {
-            let path = Path::new(&file_path);
-            if path.is_absolute() {
-                path.to_path_buf()
-            } else {
-                base_path.join(path)
-            }
-        }
-        None => {
-            return Err("No pack loaded".to_string());
-        }
-    };
+    let full_path = resolve_pack_path(&state.pack_path_for(pack_id.as_deref())?, &file_path)?;
 
     crate::image_handler::create_transparent_png(&full_path, width, height)?;
 
-    Ok(())
+    Ok(CreatePngResult { created: true, warnings })
 }
 
 /// 保存编辑后的图片
+///
+/// 写入前校验图片数据并保存历史记录, convert 为 true 时将数据转换为目标扩展名对应的格式
 #[tauri::command]
 pub async fn save_image(
     image_path: String,
     base64_data: String,
+    convert: Option<bool>,
+    pack_id: Option<String>,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    use base64::{engine::general_purpose, Engine as _};
-
-    let pack_path = state.current_pack_path.lock().unwrap();
-
-    let full_path = match pack_path.as_ref() {
-        Some(base_path) => {
-            let path = Path::new(&image_path);
-            if path.is_absolute() {
-                path.to_path_buf()
-            } else {
-                base_path.join(path)
-            }
-        }
-        None => {
-            return Err("No pack loaded".to_string());
-        }
-    };
+) -> Result<crate::image_handler::ImageInfo, AppError> {
+    let base_path = state.pack_path_for(pack_id.as_deref())?;
+    let full_path = resolve_pack_path(&base_path, &image_path)?;
 
-    // 解码base64数据
-    let image_data = general_purpose::STANDARD
-        .decode(&base64_data)
-        .map_err(|e| format!("Failed to decode base64: {}", e))?;
+    let image_data = crate::image_handler::prepare_image_bytes(
+        &full_path,
+        &base64_data,
+        convert.unwrap_or(false),
+    )
+    .map_err(AppError::InvalidInput)?;
 
     // 确保父目录存在
     if let Some(parent) = full_path.parent() {
         std::fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create directory: {}", e))?;
+            .map_err(|e| AppError::io("Failed to create directory", e))?;
     }
 
-    // 写入文件
-    std::fs::write(&full_path, image_data).map_err(|e| format!("Failed to save image: {}", e))?;
+    // 覆盖前保存旧文件的历史记录
+    let relative_path = full_path.strip_prefix(&base_path).ok();
+    if let Some(relative) = relative_path {
+        crate::history_manager::snapshot_file(
+            &base_path,
+            &relative.to_string_lossy().replace('\\', "/"),
+        )?;
+    }
 
-    Ok(())
+    crate::image_handler::write_file_atomic(&full_path, &image_data)?;
+
+    crate::image_handler::invalidate_image_caches(&full_path);
+    if let Some(relative) = relative_path {
+        state.preloader_for_path(&base_path).invalidate(&relative.to_string_lossy());
+    }
+
+    Ok(crate::image_handler::get_image_info(&full_path)?)
 }
 
 /// 获取版本清单
 #[tauri::command]
-pub async fn get_minecraft_versions() -> Result<crate::version_downloader::VersionManifest, String>
-{
-    crate::version_downloader::fetch_version_manifest().await
+pub async fn get_minecraft_versions() -> Result<crate::version_downloader::VersionManifest, AppError> {
+    let cache_dir = template_cache_dir()?;
+    Ok(crate::version_downloader::fetch_version_manifest_cached(&cache_dir).await?)
 }
 
 /// 下载指定的版本jar文件
+///
+/// 创建下载任务并立即返回 task_id, 完成后通过 minecraft-version-downloaded 事件返回jar路径
 #[tauri::command]
-pub async fn download_minecraft_version(version_id: String) -> Result<String, String> {
-    // 获取src-tauri目录的路径
-    let exe_path = std::env::current_exe().map_err(|e| format!("Failed to get exe path: {}", e))?;
-    let exe_dir = exe_path.parent().ok_or("Failed to get exe directory")?;
-
-    // 创建temp目录
-    let temp_dir = exe_dir.join("temp");
+pub async fn download_minecraft_version(
+    version_id: String,
+    app: tauri::AppHandle,
+    manager: State<'_, std::sync::Arc<crate::download_manager::DownloadManager>>,
+) -> Result<String, AppError> {
+    let temp_dir = template_cache_dir()?;
     std::fs::create_dir_all(&temp_dir)
         .map_err(|e| format!("Failed to create temp directory: {}", e))?;
 
-    // 下载版本
-    crate::version_downloader::download_version(&version_id, &temp_dir).await
+    let task_id = manager.create_task(
+        format!("下载版本: {}", version_id),
+        "version".to_string(),
+        temp_dir.clone(),
+        Some(crate::download_manager::DownloadParams::Version { version_id: version_id.clone() }),
+    ).await;
+
+    spawn_version_download(std::sync::Arc::clone(&manager), app, task_id.clone(), version_id, temp_dir);
+
+    Ok(task_id)
+}
+
+/// jar下载完成事件
+#[derive(Debug, Clone, Serialize)]
+struct VersionDownloaded {
+    task_id: String,
+    version_id: String,
+    path: String,
+}
+
+/// 在后台执行版本jar下载任务
+fn spawn_version_download(
+    manager: std::sync::Arc<crate::download_manager::DownloadManager>,
+    app: tauri::AppHandle,
+    task_id: String,
+    version_id: String,
+    temp_dir: PathBuf,
+) {
+    use crate::download_manager::{DownloadProgress, DownloadStatus};
+    use tauri::Emitter;
+
+    tokio::spawn(async move {
+        let cancel_token = tokio_util::sync::CancellationToken::new();
+        manager.register_cancel_token(task_id.clone(), cancel_token.clone()).await;
+
+        // 超出并发上限时排队等待
+        let _slot = match manager.acquire_slot(&task_id).await {
+            Some(slot) => slot,
+            None => {
+                manager.remove_cancel_token(&task_id).await;
+                return;
+            }
+        };
+
+        let result = crate::version_downloader::download_version_with_progress(
+            &version_id,
+            &temp_dir,
+            &task_id,
+            &cancel_token,
+            &manager,
+        )
+        .await;
+
+        // 取消时任务状态已由 cancel_task 更新
+        if !cancel_token.is_cancelled() {
+            let (status, current_file, error) = match &result {
+                Ok(path) => (DownloadStatus::Completed, Some(path.clone()), None),
+                Err(e) => (DownloadStatus::Failed, None, Some(e.clone())),
+            };
+            manager.update_progress(&task_id, DownloadProgress {
+                task_id: task_id.clone(),
+                status,
+                current: 100,
+                total: 100,
+                current_file,
+                speed: 0.0,
+                eta: None,
+                error,
+            }).await;
+
+            if let Ok(path) = result {
+                let _ = app.emit("minecraft-version-downloaded", VersionDownloaded {
+                    task_id: task_id.clone(),
+                    version_id,
+                    path,
+                });
+            }
+        }
+
+        manager.remove_cancel_token(&task_id).await;
+    });
 }
 
 /// 下载最新的release版本
 #[tauri::command]
-pub async fn download_latest_minecraft_version() -> Result<String, String> {
-    // 获取src-tauri目录的路径
-    let exe_path = std::env::current_exe().map_err(|e| format!("Failed to get exe path: {}", e))?;
-    let exe_dir = exe_path.parent().ok_or("Failed to get exe directory")?;
-
+pub async fn download_latest_minecraft_version() -> Result<String, AppError> {
     // 创建temp目录
-    let temp_dir = exe_dir.join("temp");
+    let temp_dir = template_cache_dir()?;
     std::fs::create_dir_all(&temp_dir)
         .map_err(|e| format!("Failed to create temp directory: {}", e))?;
 
     // 下载最新版本
-    crate::version_downloader::download_latest_release(&temp_dir).await
+    Ok(crate::version_downloader::download_latest_release(&temp_dir).await?)
 }
 
-/// 从jar文件中提取assets到指定目录
+/// 从jar文件中提取assets到指定目录, 可只提取 include_prefixes 下的文件(如 assets/minecraft/textures/block/)
 #[tauri::command]
-pub async fn extract_assets_from_jar(jar_path: String, output_path: String) -> Result<(), String> {
+pub async fn extract_assets_from_jar(
+    jar_path: String,
+    output_path: String,
+    include_prefixes: Option<Vec<String>>,
+) -> Result<(), AppError> {
     let jar = Path::new(&jar_path);
     let output = Path::new(&output_path);
 
-    crate::version_downloader::extract_assets_from_jar(jar, output)
+    Ok(crate::version_downloader::extract_assets_from_jar(jar, output, include_prefixes.as_deref())?)
+}
+
+/// 模板jar的缓存目录
+pub(crate) fn template_cache_dir() -> Result<PathBuf, String> {
+    Ok(crate::app_dirs::cache_dir())
 }
 
 /// 下载版本并提取assets到材质包
@@ -884,41 +2368,73 @@ pub async fn download_and_extract_template(
     version_id: String,
     pack_path: String,
     keep_cache: bool,
+    folders: Option<Vec<String>>,
+    locale: Option<String>,
     manager: State<'_, std::sync::Arc<crate::download_manager::DownloadManager>>,
-) -> Result<String, String> {
-    // 获取temp目录
-    let exe_path = std::env::current_exe().map_err(|e| format!("Failed to get exe path: {}", e))?;
-    let exe_dir = exe_path.parent().ok_or("Failed to get exe directory")?;
-    let temp_dir = exe_dir.join("temp");
-
-    std::fs::create_dir_all(&temp_dir)
-        .map_err(|e| format!("Failed to create temp directory: {}", e))?;
-
+) -> Result<String, AppError> {
     let output = Path::new(&pack_path);
+    let folders = folders.unwrap_or_default();
+    let locale = crate::version_downloader::normalize_locale(locale.as_deref())
+        .map_err(AppError::InvalidInput)?;
     
     // 创建下载任务
     let task_id = manager.create_task(
         format!("下载模板: {}", version_id),
         "template".to_string(),
         output.to_path_buf(),
+        Some(crate::download_manager::DownloadParams::Template {
+            version_id: version_id.clone(),
+            keep_cache,
+            folders: folders.clone(),
+            locale: Some(locale.clone()),
+        }),
     ).await;
     
-    // 克隆管理器用于异步任务
-    let manager_clone = std::sync::Arc::clone(&manager);
-    let task_id_clone = task_id.clone();
-    let version_id_clone = version_id.clone();
-    let temp_dir_clone = temp_dir.clone();
-    let output_clone = output.to_path_buf();
-
-    // 在后台启动下载任务
+    spawn_template_download(
+        std::sync::Arc::clone(&manager),
+        task_id.clone(),
+        version_id,
+        output.to_path_buf(),
+        keep_cache,
+        folders,
+        locale,
+    )?;
+    
+    // 立即返回 task_id
+    Ok(format!("Task created|TASK_ID|{}", task_id))
+}
+
+/// 在后台执行模板下载任务
+fn spawn_template_download(
+    manager: std::sync::Arc<crate::download_manager::DownloadManager>,
+    task_id: String,
+    version_id: String,
+    output: PathBuf,
+    keep_cache: bool,
+    folders: Vec<String>,
+    locale: String,
+) -> Result<(), String> {
+    // 获取temp目录
+    let temp_dir = template_cache_dir()?;
+    std::fs::create_dir_all(&temp_dir)
+        .map_err(|e| format!("Failed to create temp directory: {}", e))?;
+
     tokio::spawn(async move {
+        // 超出并发上限时排队等待
+        let _slot = match manager.acquire_slot(&task_id).await {
+            Some(slot) => slot,
+            None => return,
+        };
+
         let result = crate::version_downloader::download_and_extract_version_with_progress(
-            &version_id_clone,
-            &temp_dir_clone,
-            &output_clone,
+            &version_id,
+            &temp_dir,
+            &output,
             keep_cache,
-            task_id_clone,
-            (*manager_clone).clone(),
+            &folders,
+            &locale,
+            task_id,
+            (*manager).clone(),
         )
         .await;
         
@@ -926,85 +2442,111 @@ pub async fn download_and_extract_template(
             println!("模板下载失败: {}", e);
         }
     });
-    
-    // 立即返回 task_id
-    Ok(format!("Task created|TASK_ID|{}", task_id))
+
+    Ok(())
+}
+
+/// 下载(或复用缓存的)jar并列出assets下的文件夹, 供选择需要提取的部分
+#[tauri::command]
+pub async fn list_jar_asset_folders(
+    version_id: String,
+) -> Result<crate::version_downloader::JarAssetFolder, AppError> {
+    let temp_dir = template_cache_dir()?;
+    std::fs::create_dir_all(&temp_dir)
+        .map_err(|e| format!("Failed to create temp directory: {}", e))?;
+
+    let jar_path = crate::version_downloader::download_version(&version_id, &temp_dir).await?;
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    rayon::spawn(move || {
+        let _ = tx.send(crate::version_downloader::list_jar_asset_folders(Path::new(&jar_path)));
+    });
+    Ok(rx
+        .await
+        .map_err(|e| AppError::Other(format!("Channel error: {}", e)))??)
+}
+
+/// 获取模板缓存中jar文件的大小
+#[tauri::command]
+pub async fn get_template_cache_info() -> Result<crate::version_downloader::TemplateCacheInfo, AppError> {
+    let temp_dir = template_cache_dir()?;
+
+    Ok(crate::version_downloader::get_template_cache_info(&temp_dir)?)
+}
+
+/// 清理指定版本的模板缓存, 返回释放的字节数
+#[tauri::command]
+pub async fn clear_template_cache_version(version_id: String) -> Result<u64, AppError> {
+    let temp_dir = template_cache_dir()?;
+
+    crate::version_downloader::clear_template_cache_version(&temp_dir, &version_id)
 }
 
 /// 清理模板缓存
 #[tauri::command]
-pub async fn clear_template_cache() -> Result<(), String> {
-    let exe_path = std::env::current_exe().map_err(|e| format!("Failed to get exe path: {}", e))?;
-    let exe_dir = exe_path.parent().ok_or("Failed to get exe directory")?;
-    let temp_dir = exe_dir.join("temp");
+pub async fn clear_template_cache() -> Result<(), AppError> {
+    let temp_dir = template_cache_dir()?;
 
-    crate::version_downloader::clear_template_cache(&temp_dir)
+    Ok(crate::version_downloader::clear_template_cache(&temp_dir)?)
 }
 
+/// 预加载文件夹中的图片, 会取消同一材质包上一次未完成的预加载
+///
+/// request_id 用于 cancel_preload, 为空时自动生成
 #[tauri::command]
 pub async fn preload_folder_images(
     folder_path: String,
+    request_id: Option<String>,
     state: State<'_, AppState>,
-) -> Result<usize, String> {
-    let (base_path, full_path) = {
-        let pack_path = state.current_pack_path.lock().unwrap();
-
-        let base_path = match pack_path.as_ref() {
-            Some(path) => path.clone(),
-            None => return Err("No pack loaded".to_string()),
-        };
-
-        let full_path = if folder_path.is_empty() {
-            base_path.clone()
-        } else {
-            base_path.join(&folder_path)
-        };
+) -> Result<usize, AppError> {
+    let base_path = state.pack_path()?;
+    let full_path = resolve_pack_path(&base_path, &folder_path)?;
+    let preloader = state.preloader();
+    let request_id = request_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let cancel = preloader.begin_request(&request_id);
+    let result = preloader.preload_folder(&full_path, &base_path, 512, cancel).await;
+    preloader.finish_request(&request_id);
+    Ok(result?)
+}
 
-        (base_path, full_path)
-    };
-    state
-        .preloader
-        .preload_folder(&full_path, &base_path, 512)
-        .await
+/// 取消文件夹预加载, request_id 为空时取消当前的预加载; 返回是否有请求被取消
+#[tauri::command]
+pub async fn cancel_preload(
+    request_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<bool, AppError> {
+    Ok(state.preloader().cancel_request(request_id.as_deref()))
 }
 
 #[tauri::command]
-pub async fn get_preloader_stats(state: State<'_, AppState>) -> Result<(usize, usize), String> {
-    Ok(state.preloader.get_stats().await)
+pub async fn get_preloader_stats(
+    state: State<'_, AppState>,
+) -> Result<PreloaderStats, AppError> {
+    Ok(state.preloader().get_stats().await)
 }
 
 #[tauri::command]
-pub async fn clear_preloader_cache(state: State<'_, AppState>) -> Result<(), String> {
-    state.preloader.clear_cache().await;
+pub async fn clear_preloader_cache(state: State<'_, AppState>) -> Result<(), AppError> {
+    state.preloader().clear_cache().await;
     Ok(())
 }
 
 #[tauri::command]
 pub async fn preload_folder_aggressive(
     folder_path: String,
+    request_id: Option<String>,
     state: State<'_, AppState>,
-) -> Result<usize, String> {
-    let (base_path, full_path) = {
-        let pack_path = state.current_pack_path.lock().unwrap();
-
-        let base_path = match pack_path.as_ref() {
-            Some(path) => path.clone(),
-            None => return Err("No pack loaded".to_string()),
-        };
-
-        let full_path = if folder_path.is_empty() {
-            base_path.clone()
-        } else {
-            base_path.join(&folder_path)
-        };
-
-        (base_path, full_path)
-    };
-
-    state
-        .preloader
-        .preload_folder_aggressive(&full_path, &base_path)
-        .await
+) -> Result<usize, AppError> {
+    let base_path = state.pack_path()?;
+    let full_path = resolve_pack_path(&base_path, &folder_path)?;
+    let preloader = state.preloader();
+    let request_id = request_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let cancel = preloader.begin_request(&request_id);
+    let result = preloader
+        .preload_folder_aggressive(&full_path, &base_path, cancel)
+        .await;
+    preloader.finish_request(&request_id);
+    Ok(result?)
 }
 
 /// Debug信息
@@ -1023,18 +2565,21 @@ pub struct DebugInfo {
 pub struct DebugLog {
     pub level: String,
     pub message: String,
+    /// 前端写入的日志为 "frontend"
+    #[serde(default)]
+    pub source: Option<String>,
 }
 
 /// 获取调试信息
 #[tauri::command]
-pub async fn get_debug_info(state: State<'_, AppState>) -> Result<DebugInfo, String> {
+pub async fn get_debug_info(state: State<'_, AppState>) -> Result<DebugInfo, AppError> {
     // 获取CPU核心数
     let cpu_cores = std::thread::available_parallelism()
         .map(|n| n.get())
         .unwrap_or(4);
 
     // 获取缓存统计
-    let (cached_files, _loading) = state.preloader.get_stats().await;
+    let cached_files = state.preloader().get_stats().await.cached_files;
 
     // 获取GPU信息
     let gpu_info = "请在前端获取".to_string();
@@ -1056,22 +2601,12 @@ pub async fn get_debug_info(state: State<'_, AppState>) -> Result<DebugInfo, Str
     })
 }
 
-/// 打开日志文件夹
-#[tauri::command]
-pub async fn open_logs_folder() -> Result<(), String> {
-    let exe_path = std::env::current_exe().map_err(|e| format!("Failed to get exe path: {}", e))?;
-    let exe_dir = exe_path.parent().ok_or("Failed to get exe directory")?;
-    let logs_dir = exe_dir.join("logs");
-
-    // 确保logs目录存在
-    std::fs::create_dir_all(&logs_dir)
-        .map_err(|e| format!("Failed to create logs directory: {}", e))?;
-
-    // 打开文件夹
+/// 在系统文件管理器中打开目录
+fn open_in_file_manager(dir: &Path) -> Result<(), String> {
     #[cfg(target_os = "windows")]
     {
         std::process::Command::new("explorer")
-            .arg(logs_dir)
+            .arg(dir)
             .spawn()
             .map_err(|e| format!("Failed to open folder: {}", e))?;
     }
@@ -1079,7 +2614,7 @@ pub async fn open_logs_folder() -> Result<(), String> {
     #[cfg(target_os = "macos")]
     {
         std::process::Command::new("open")
-            .arg(logs_dir)
+            .arg(dir)
             .spawn()
             .map_err(|e| format!("Failed to open folder: {}", e))?;
     }
@@ -1087,7 +2622,7 @@ pub async fn open_logs_folder() -> Result<(), String> {
     #[cfg(target_os = "linux")]
     {
         std::process::Command::new("xdg-open")
-            .arg(logs_dir)
+            .arg(dir)
             .spawn()
             .map_err(|e| format!("Failed to open folder: {}", e))?;
     }
@@ -1095,23 +2630,44 @@ pub async fn open_logs_folder() -> Result<(), String> {
     Ok(())
 }
 
-/// 写入日志到文件
-#[allow(dead_code)]
-pub async fn write_log(level: &str, message: &str) {
-    let exe_path = match std::env::current_exe() {
-        Ok(path) => path,
-        Err(_) => return,
-    };
+/// 打开日志文件夹
+#[tauri::command]
+pub async fn open_logs_folder() -> Result<(), AppError> {
+    let logs_dir = crate::app_dirs::logs_dir();
 
-    let exe_dir = match exe_path.parent() {
-        Some(dir) => dir,
-        None => return,
-    };
+    // 确保logs目录存在
+    std::fs::create_dir_all(&logs_dir)
+        .map_err(|e| format!("Failed to create logs directory: {}", e))?;
+
+    Ok(open_in_file_manager(&logs_dir)?)
+}
+
+/// 打开模板缓存(temp)文件夹
+#[tauri::command]
+pub async fn open_temp_folder() -> Result<(), AppError> {
+    let temp_dir = template_cache_dir()?;
+
+    // 确保temp目录存在
+    std::fs::create_dir_all(&temp_dir)
+        .map_err(|e| format!("Failed to create temp directory: {}", e))?;
 
-    let logs_dir = exe_dir.join("logs");
+    Ok(open_in_file_manager(&temp_dir)?)
+}
+
+/// 默认的 latest.log 大小上限
+pub const DEFAULT_MAX_LOG_SIZE: u64 = 5 * 1024 * 1024;
+
+static MAX_LOG_SIZE: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(DEFAULT_MAX_LOG_SIZE);
+
+/// 串行化日志写入和轮转
+static LOG_WRITE_LOCK: parking_lot::Mutex<()> = parking_lot::Mutex::new(());
+
+/// 写入日志到文件, latest.log 超过大小上限时轮转为 latest.1.log
+pub async fn write_log(level: &str, message: &str) {
+    let logs_dir = crate::app_dirs::logs_dir();
 
     // 确保logs目录存在
-    if let Err(_) = std::fs::create_dir_all(&logs_dir) {
+    if std::fs::create_dir_all(&logs_dir).is_err() {
         return;
     }
 
@@ -1121,6 +2677,13 @@ pub async fn write_log(level: &str, message: &str) {
     let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
     let log_entry = format!("[{}] [{}] {}\n", timestamp, level.to_uppercase(), message);
 
+    let _guard = LOG_WRITE_LOCK.lock();
+    let max_size = MAX_LOG_SIZE.load(std::sync::atomic::Ordering::Relaxed);
+    let current_size = std::fs::metadata(&log_file).map(|m| m.len()).unwrap_or(0);
+    if current_size > 0 && current_size + log_entry.len() as u64 > max_size {
+        let _ = std::fs::rename(&log_file, logs_dir.join("latest.1.log"));
+    }
+
     // 追加到文件
     use std::io::Write;
     if let Ok(mut file) = std::fs::OpenOptions::new()
@@ -1132,17 +2695,45 @@ pub async fn write_log(level: &str, message: &str) {
     }
 }
 
+/// 前端日志的来源标记
+const FRONTEND_LOG_TAG: &str = "[frontend]";
+
+/// 写入前端日志
+#[tauri::command]
+pub async fn log_message(level: String, message: String, source: Option<String>) -> Result<(), AppError> {
+    let level = match level.to_lowercase().as_str() {
+        "error" | "warn" | "info" | "debug" => level,
+        "warning" => "warn".to_string(),
+        _ => "info".to_string(),
+    };
+    // 多行内容(如堆栈)合并为一行, 便于按行读取
+    let message = message.lines().collect::<Vec<_>>().join(" | ");
+    let entry = match source.as_deref().filter(|s| !s.is_empty()) {
+        Some(source) => format!("{} {}: {}", FRONTEND_LOG_TAG, source, message),
+        None => format!("{} {}", FRONTEND_LOG_TAG, message),
+    };
+
+    write_log(&level, &entry).await;
+    Ok(())
+}
+
+/// 设置 latest.log 的大小上限(字节)
+#[tauri::command]
+pub async fn set_log_max_size(max_bytes: u64) -> Result<(), AppError> {
+    if max_bytes < 64 * 1024 {
+        return Err(AppError::InvalidInput("日志大小上限不能小于 64 KB".to_string()));
+    }
+    MAX_LOG_SIZE.store(max_bytes, std::sync::atomic::Ordering::Relaxed);
+    Ok(())
+}
+
 /// 读取语言映射表
 #[tauri::command]
-pub async fn load_language_map(state: State<'_, AppState>) -> Result<std::collections::HashMap<String, String>, String> {
+pub async fn load_language_map(state: State<'_, AppState>) -> Result<std::collections::HashMap<String, String>, AppError> {
     // 先获取路径，然后立即释放锁
-    let map_file = {
-        let pack_path = state.current_pack_path.lock().unwrap();
-        
-        match pack_path.as_ref() {
-            Some(path) => path.join(".little100").join("map.json"),
-            None => return Ok(std::collections::HashMap::new()),
-        }
+    let map_file = match state.pack_path() {
+        Ok(path) => path.join(".little100").join("map.json"),
+        Err(_) => return Ok(std::collections::HashMap::new()),
     };
 
     if !map_file.exists() {
@@ -1170,7 +2761,7 @@ pub struct SoundEntry {
 
 /// 获取所有音效列表
 #[tauri::command]
-pub async fn get_sound_subtitles(state: State<'_, AppState>) -> Result<Vec<SoundEntry>, String> {
+pub async fn get_sound_subtitles(state: State<'_, AppState>) -> Result<Vec<SoundEntry>, AppError> {
     // 加载语言映射表
     let language_map = load_language_map(state).await?;
     
@@ -1201,59 +2792,54 @@ pub async fn get_sound_subtitles(state: State<'_, AppState>) -> Result<Vec<Sound
     Ok(sound_entries)
 }
 
-/// 读取最新的日志
-async fn read_latest_logs() -> Vec<DebugLog> {
-    let exe_path = match std::env::current_exe() {
-        Ok(path) => path,
-        Err(_) => return Vec::new(),
-    };
+/// 显示的日志行数
+const DEBUG_LOG_LINES: usize = 50;
+
+/// 解析一行日志, 格式为 [时间] [级别] 内容
+fn parse_log_line(line: &str) -> Option<DebugLog> {
+    let level_start = line.find("] [")?;
+    let level_end = line[level_start + 3..].find(']')?;
+    let level = &line[level_start + 3..level_start + 3 + level_end];
+    let message = line.get(level_start + 3 + level_end + 2..).unwrap_or_default();
 
-    let exe_dir = match exe_path.parent() {
-        Some(dir) => dir,
-        None => return Vec::new(),
+    let (source, message) = match message.strip_prefix(FRONTEND_LOG_TAG) {
+        Some(rest) => (Some("frontend".to_string()), rest.trim_start()),
+        None => (None, message),
     };
 
-    let log_file = exe_dir.join("logs").join("latest.log");
+    Some(DebugLog {
+        level: level.to_lowercase(),
+        message: message.to_string(),
+        source,
+    })
+}
 
-    if !log_file.exists() {
-        return Vec::new();
-    }
+/// 读取最新的日志, latest.log 不足时从轮转的 latest.1.log 中补足
+async fn read_latest_logs() -> Vec<DebugLog> {
+    let logs_dir = crate::app_dirs::logs_dir();
 
-    // 读取日志文件
-    let content = match std::fs::read_to_string(&log_file) {
-        Ok(c) => c,
-        Err(_) => return Vec::new(),
+    let read_lines = |name: &str| {
+        std::fs::read_to_string(logs_dir.join(name))
+            .map(|content| content.lines().map(str::to_string).collect::<Vec<_>>())
+            .unwrap_or_default()
     };
 
-    // 只返回最后50行
-    let lines: Vec<&str> = content.lines().collect();
-    let start = if lines.len() > 50 {
-        lines.len() - 50
-    } else {
-        0
-    };
+    let mut lines = read_lines("latest.log");
+    if lines.len() < DEBUG_LOG_LINES {
+        let mut rotated = read_lines("latest.1.log");
+        let keep = DEBUG_LOG_LINES - lines.len();
+        rotated.drain(..rotated.len().saturating_sub(keep));
+        rotated.append(&mut lines);
+        lines = rotated;
+    }
 
-    lines[start..]
-        .iter()
-        .filter_map(|line| {
-            if let Some(level_start) = line.find("] [") {
-                if let Some(level_end) = line[level_start + 3..].find(']') {
-                    let level = &line[level_start + 3..level_start + 3 + level_end];
-                    let message = &line[level_start + 3 + level_end + 2..];
-
-                    return Some(DebugLog {
-                        level: level.to_lowercase(),
-                        message: message.to_string(),
-                    });
-                }
-            }
-            None
-        })
-        .collect()
+    // 只返回最后50行
+    let start = lines.len().saturating_sub(DEBUG_LOG_LINES);
+    lines[start..].iter().filter_map(|line| parse_log_line(line)).collect()
 }
 
 /// 搜索结果
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SearchResult {
     pub file_path: String,
     pub match_type: String,
@@ -1262,6 +2848,19 @@ pub struct SearchResult {
     pub match_start: Option<usize>,
     pub match_end: Option<usize>,
     pub translation: Option<String>,
+    /// 模糊搜索时文件名的匹配得分, 越高越相关
+    pub score: Option<i64>,
+}
+
+/// 文件名搜索方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchMode {
+    /// 子串或正则匹配
+    #[default]
+    Exact,
+    /// 按顺序包含查询中的字符即可, 结果按得分排序
+    Fuzzy,
 }
 
 /// 搜索响应
@@ -1272,22 +2871,24 @@ pub struct SearchResponse {
     pub total_count: usize,
 }
 
-/// 搜索文件
+/// 搜索文件, 可按 glob(相对材质包, 如 assets/minecraft/models/**)和资源类型限定范围
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn search_files(
     query: String,
     case_sensitive: bool,
     use_regex: bool,
+    mode: Option<SearchMode>,
+    include_globs: Option<Vec<String>>,
+    exclude_globs: Option<Vec<String>>,
+    resource_types: Option<Vec<ResourceType>>,
+    pack_id: Option<String>,
     state: State<'_, AppState>,
-) -> Result<SearchResponse, String> {
-    let pack_path = state.current_pack_path.lock().unwrap();
-    
-    let base_path = match pack_path.as_ref() {
-        Some(path) => path.clone(),
-        None => return Err("No pack loaded".to_string()),
-    };
-    
-    drop(pack_path);
+) -> Result<SearchResponse, AppError> {
+    let base_path = state.pack_path_for(pack_id.as_deref())?;
+    let fuzzy = mode.unwrap_or_default() == SearchMode::Fuzzy;
+    let version = state.pack_version_for(pack_id.as_deref());
+    let scope = SearchScope::new(include_globs, exclude_globs, resource_types, version)?;
     
     // 加载语言映射表用于中文搜索
     let language_map = load_language_map_sync(&base_path);
@@ -1300,7 +2901,7 @@ pub async fn search_files(
     };
     
     // 收集所有文件
-    let files = collect_searchable_files(&base_path)?;
+    let files = collect_searchable_files(&base_path, &scope)?;
     
     // 并行搜索
     let (filename_matches, content_matches): (Vec<_>, Vec<_>) = files
@@ -1313,51 +2914,347 @@ pub async fn search_files(
                 case_sensitive,
                 use_regex,
                 regex_pattern.as_ref(),
+                fuzzy,
                 &language_map,
             ).ok()
         })
-        .flatten()
-        .partition(|result| result.match_type == "filename");
-    
-    // 限制结果数量
-    let filename_matches: Vec<_> = filename_matches.into_iter().take(100).collect();
-    let content_matches: Vec<_> = content_matches.into_iter().take(200).collect();
-    
-    let total_count = filename_matches.len() + content_matches.len();
-    
-    Ok(SearchResponse {
-        filename_matches,
-        content_matches,
-        total_count,
-    })
+        .flatten()
+        .partition(|result| result.match_type == "filename");
+
+    // 模糊搜索保留得分最高的结果
+    let mut filename_matches = filename_matches;
+    if fuzzy {
+        sort_by_fuzzy_score(&mut filename_matches);
+    }
+    
+    // 限制结果数量
+    let filename_matches: Vec<_> = filename_matches.into_iter().take(100).collect();
+    let content_matches: Vec<_> = content_matches.into_iter().take(200).collect();
+    
+    let total_count = filename_matches.len() + content_matches.len();
+    
+    Ok(SearchResponse {
+        filename_matches,
+        content_matches,
+        total_count,
+    })
+}
+
+/// 按模糊匹配得分从高到低排序, 同分时路径短的在前
+fn sort_by_fuzzy_score(results: &mut [SearchResult]) {
+    results.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.file_path.len().cmp(&b.file_path.len()))
+            .then_with(|| a.file_path.cmp(&b.file_path))
+    });
+}
+
+/// 流式搜索每批发送的结果数
+const SEARCH_BATCH_SIZE: usize = 50;
+
+/// search-result-batch 事件
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResultBatch {
+    pub search_id: String,
+    pub filename_matches: Vec<SearchResult>,
+    pub content_matches: Vec<SearchResult>,
+}
+
+/// search-complete 事件
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchComplete {
+    pub search_id: String,
+    pub filename_count: usize,
+    pub content_count: usize,
+    pub total_count: usize,
+    pub cancelled: bool,
+}
+
+/// 已收到但尚未发送的结果
+#[derive(Default)]
+struct PendingSearchResults {
+    filename_matches: Vec<SearchResult>,
+    content_matches: Vec<SearchResult>,
+    filename_count: usize,
+    content_count: usize,
+}
+
+impl PendingSearchResults {
+    fn len(&self) -> usize {
+        self.filename_matches.len() + self.content_matches.len()
+    }
+
+    /// 按与 search_files 相同的上限加入结果, 两类都已满时返回 true
+    fn push(&mut self, result: SearchResult, keep_filenames: bool) -> bool {
+        if result.match_type == "filename" {
+            if keep_filenames && self.filename_count < 100 {
+                self.filename_count += 1;
+                self.filename_matches.push(result);
+            }
+        } else if self.content_count < 200 {
+            self.content_count += 1;
+            self.content_matches.push(result);
+        }
+        self.filename_count >= 100 && self.content_count >= 200
+    }
+
+    fn take_batch(&mut self, search_id: &str) -> SearchResultBatch {
+        SearchResultBatch {
+            search_id: search_id.to_string(),
+            filename_matches: std::mem::take(&mut self.filename_matches),
+            content_matches: std::mem::take(&mut self.content_matches),
+        }
+    }
+}
+
+/// 开始流式搜索, 立即返回 search_id(调用方可自行指定, 以便在命令返回前就能识别事件)
+///
+/// 结果每 batch_size 条(默认 50)以 search-result-batch 事件发送, 结束后发送 search-complete;
+/// 模糊模式下文件名结果需要整体排序, 会在结束时作为最后一批发送
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn start_search(
+    query: String,
+    case_sensitive: bool,
+    use_regex: bool,
+    mode: Option<SearchMode>,
+    include_globs: Option<Vec<String>>,
+    exclude_globs: Option<Vec<String>>,
+    resource_types: Option<Vec<ResourceType>>,
+    batch_size: Option<usize>,
+    search_id: Option<String>,
+    pack_id: Option<String>,
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use tauri::{Emitter, Manager};
+
+    let base_path = state.pack_path_for(pack_id.as_deref())?;
+    let fuzzy = mode.unwrap_or_default() == SearchMode::Fuzzy;
+    let version = state.pack_version_for(pack_id.as_deref());
+    let scope = SearchScope::new(include_globs, exclude_globs, resource_types, version)?;
+    let batch_size = batch_size.unwrap_or(SEARCH_BATCH_SIZE).max(1);
+    let regex_pattern = if use_regex {
+        Some(Regex::new(&query).map_err(|e| AppError::InvalidInput(format!("Invalid regex pattern: {}", e)))?)
+    } else {
+        None
+    };
+
+    let search_id = search_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let cancelled = Arc::new(AtomicBool::new(false));
+    state.searches.lock().unwrap().insert(search_id.clone(), cancelled.clone());
+
+    let id = search_id.clone();
+    rayon::spawn(move || {
+        let language_map = load_language_map_sync(&base_path);
+        let files = collect_searchable_files(&base_path, &scope).unwrap_or_default();
+        let pending = Mutex::new(PendingSearchResults::default());
+        let fuzzy_matches: Mutex<Vec<SearchResult>> = Mutex::new(Vec::new());
+        let full = AtomicBool::new(false);
+
+        let _ = files.par_iter().try_for_each(|file_path| {
+            if cancelled.load(Ordering::Relaxed) || full.load(Ordering::Relaxed) {
+                return Err(());
+            }
+            let Ok(results) = search_in_file(
+                file_path,
+                &base_path,
+                &query,
+                case_sensitive,
+                use_regex,
+                regex_pattern.as_ref(),
+                fuzzy,
+                &language_map,
+            ) else {
+                return Ok(());
+            };
+
+            let batch = {
+                let mut pending = pending.lock().unwrap();
+                for result in results {
+                    if fuzzy && result.match_type == "filename" {
+                        fuzzy_matches.lock().unwrap().push(result);
+                    } else if pending.push(result, !fuzzy) {
+                        full.store(true, Ordering::Relaxed);
+                    }
+                }
+                (pending.len() >= batch_size).then(|| pending.take_batch(&id))
+            };
+            if let Some(batch) = batch {
+                let _ = app.emit("search-result-batch", batch);
+            }
+            Ok(())
+        });
+
+        let was_cancelled = cancelled.load(Ordering::Relaxed);
+        let mut pending = pending.into_inner().unwrap();
+        if !was_cancelled {
+            // 模糊模式按得分保留前 100 个文件名结果
+            let mut fuzzy_matches = fuzzy_matches.into_inner().unwrap();
+            sort_by_fuzzy_score(&mut fuzzy_matches);
+            fuzzy_matches.truncate(100);
+            pending.filename_count += fuzzy_matches.len();
+            pending.filename_matches.extend(fuzzy_matches);
+            if pending.len() > 0 {
+                let _ = app.emit("search-result-batch", pending.take_batch(&id));
+            }
+        }
+
+        app.state::<AppState>().searches.lock().unwrap().remove(&id);
+        let _ = app.emit(
+            "search-complete",
+            SearchComplete {
+                search_id: id.clone(),
+                filename_count: pending.filename_count,
+                content_count: pending.content_count,
+                total_count: pending.filename_count + pending.content_count,
+                cancelled: was_cancelled,
+            },
+        );
+    });
+
+    Ok(search_id)
+}
+
+/// 取消流式搜索, 搜索已结束时忽略
+#[tauri::command]
+pub async fn cancel_search(search_id: String, state: State<'_, AppState>) -> Result<(), AppError> {
+    if let Some(cancelled) = state.searches.lock().unwrap().get(&search_id) {
+        cancelled.store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+    Ok(())
+}
+
+/// 搜索范围: 按相对路径的 glob 和资源类型筛选, 遍历时直接跳过不可能匹配的目录
+#[derive(Default)]
+pub(crate) struct SearchScope {
+    /// 包含规则及其不含通配符的前缀目录
+    include: Vec<(glob::Pattern, String)>,
+    exclude: crate::zip_handler::ExcludePatterns,
+    resource_types: Vec<ResourceType>,
+    version: Option<MinecraftVersion>,
+}
+
+impl SearchScope {
+    pub(crate) fn new(
+        include_globs: Option<Vec<String>>,
+        exclude_globs: Option<Vec<String>>,
+        resource_types: Option<Vec<ResourceType>>,
+        version: MinecraftVersion,
+    ) -> Result<Self, AppError> {
+        let include = include_globs
+            .unwrap_or_default()
+            .iter()
+            .map(|pattern| {
+                let pattern = pattern.replace('\\', "/");
+                let compiled = glob::Pattern::new(&pattern)
+                    .map_err(|e| AppError::InvalidInput(format!("无效的搜索范围 {}: {}", pattern, e)))?;
+                let prefix = pattern
+                    .split('/')
+                    .take_while(|part| !part.contains(['*', '?', '[', '{']))
+                    .collect::<Vec<_>>()
+                    .join("/");
+                Ok((compiled, prefix))
+            })
+            .collect::<Result<Vec<_>, AppError>>()?;
+        let exclude = crate::zip_handler::ExcludePatterns::new(&exclude_globs.unwrap_or_default())
+            .map_err(AppError::InvalidInput)?;
+        Ok(Self {
+            include,
+            exclude,
+            resource_types: resource_types.unwrap_or_default(),
+            version: Some(version),
+        })
+    }
+
+    fn resource_type(&self, relative: &str) -> ResourceType {
+        let version = self.version.clone().unwrap_or(MinecraftVersion::Legacy);
+        crate::pack_parser::parse_resource_type(Path::new(&format!("/{}", relative)), &version)
+    }
+
+    /// 目录下是否可能有匹配的文件
+    fn allows_dir(&self, relative: &str) -> bool {
+        if self.exclude.is_excluded(Path::new(relative)) {
+            return false;
+        }
+        let under_include = self.include.is_empty()
+            || self.include.iter().any(|(_, prefix)| {
+                prefix.is_empty()
+                    || relative == prefix
+                    || relative.starts_with(&format!("{}/", prefix))
+                    || prefix.starts_with(&format!("{}/", relative))
+            });
+        if !under_include {
+            return false;
+        }
+        // 目录已确定为其他类型时跳过; 更深的 items、optifine 目录会改变类型, 需要它们时不能跳过
+        if self.resource_types.is_empty()
+            || self.resource_types.contains(&ResourceType::ItemModel)
+            || self.resource_types.contains(&ResourceType::Optifine)
+        {
+            return true;
+        }
+        let resource_type = self.resource_type(&format!("{}/", relative));
+        resource_type == ResourceType::Other || self.resource_types.contains(&resource_type)
+    }
+
+    fn allows_file(&self, relative: &str) -> bool {
+        let options = glob::MatchOptions {
+            case_sensitive: true,
+            require_literal_separator: true,
+            require_literal_leading_dot: false,
+        };
+        !self.exclude.is_excluded(Path::new(relative))
+            && (self.include.is_empty()
+                || self.include.iter().any(|(pattern, _)| pattern.matches_with(relative, options)))
+            && (self.resource_types.is_empty()
+                || self.resource_types.contains(&self.resource_type(relative)))
+    }
 }
 
+/// 按行搜索内容的文本文件(含 OptiFine 配置和着色器)
+const SEARCH_TEXT_EXTENSIONS: [&str; 8] = ["json", "mcmeta", "txt", "lang", "properties", "fsh", "vsh", "glsl"];
+
+/// 只匹配文件名的文件
+const SEARCH_NAME_ONLY_EXTENSIONS: [&str; 3] = ["png", "tga", "ogg"];
+
 /// 收集可搜索的文件（并行优化版本）
-fn collect_searchable_files(base_path: &Path) -> Result<Vec<PathBuf>, String> {
+fn collect_searchable_files(base_path: &Path, scope: &SearchScope) -> Result<Vec<PathBuf>, String> {
     use walkdir::WalkDir;
+
+    let relative = |path: &Path| {
+        path.strip_prefix(base_path)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    };
     
     // 并行收集文件
     let files: Vec<PathBuf> = WalkDir::new(base_path)
         .follow_links(false)
         .into_iter()
         .filter_entry(|e| {
-            // 排除 .history 和 .little100
-            if let Some(name) = e.file_name().to_str() {
-                !matches!(name, ".history" | ".little100")
-            } else {
-                true
+            // 排除 .history、.little100 和 .trash
+            if e.file_name().to_str().is_some_and(crate::fs_utils::is_editor_data_dir) {
+                return false;
             }
+            // 不在搜索范围内的目录整体跳过
+            e.depth() == 0 || !e.file_type().is_dir() || scope.allows_dir(&relative(e.path()))
         })
         .filter_map(|e| e.ok())
         .filter(|e| e.file_type().is_file())
         .filter(|e| {
             if let Some(ext) = e.path().extension() {
                 let ext_str = ext.to_string_lossy().to_lowercase();
-                matches!(ext_str.as_str(), "json" | "mcmeta" | "txt" | "png" | "lang")
+                SEARCH_TEXT_EXTENSIONS.contains(&ext_str.as_str())
+                    || SEARCH_NAME_ONLY_EXTENSIONS.contains(&ext_str.as_str())
             } else {
                 false
             }
         })
+        .filter(|e| scope.allows_file(&relative(e.path())))
         .map(|e| e.path().to_path_buf())
         .collect();
     
@@ -1365,7 +3262,7 @@ fn collect_searchable_files(base_path: &Path) -> Result<Vec<PathBuf>, String> {
 }
 
 /// 同步加载语言映射表
-fn load_language_map_sync(base_path: &Path) -> std::collections::HashMap<String, String> {
+pub(crate) fn load_language_map_sync(base_path: &Path) -> std::collections::HashMap<String, String> {
     let map_file = base_path.join(".little100").join("map.json");
     
     if !map_file.exists() {
@@ -1500,7 +3397,96 @@ fn check_chinese_match(
     None
 }
 
+// 模糊匹配计分, 数值与 fzf 相同
+const FUZZY_SCORE_MATCH: i64 = 16;
+const FUZZY_GAP_START: i64 = -3;
+const FUZZY_GAP_EXTENSION: i64 = -1;
+const FUZZY_BONUS_BOUNDARY: i64 = 8;
+const FUZZY_BONUS_CAMEL: i64 = 7;
+const FUZZY_BONUS_CONSECUTIVE: i64 = 4;
+
+/// 单词开头的加分(前一个字符是分隔符, 或小写到大写的转折)
+fn fuzzy_boundary_bonus(prev: Option<char>, current: char) -> i64 {
+    match prev {
+        None => FUZZY_BONUS_BOUNDARY,
+        Some('_' | '-' | '.' | '/' | ' ') => FUZZY_BONUS_BOUNDARY,
+        Some(prev) if prev.is_lowercase() && current.is_uppercase() => FUZZY_BONUS_CAMEL,
+        _ => 0,
+    }
+}
+
+/// 模糊匹配文件名(参考 fzf v1 算法), query 中的字符须按顺序出现在 name 中
+///
+/// 先正向找到最早的完整匹配, 再从结尾反向收缩起点, 最后对该区间计分;
+/// 返回 (得分, 匹配起点, 匹配终点), 位置为 name 中的字节偏移
+fn fuzzy_match(name: &str, query: &str, case_sensitive: bool) -> Option<(i64, usize, usize)> {
+    let fold = |c: char| if case_sensitive { c } else { c.to_lowercase().next().unwrap_or(c) };
+    let chars: Vec<(usize, char)> = name.char_indices().collect();
+    let pattern: Vec<char> = query.chars().filter(|c| !c.is_whitespace()).map(fold).collect();
+    if pattern.is_empty() {
+        return None;
+    }
+
+    // 正向: 找到匹配最后一个字符的位置
+    let mut next = 0;
+    let mut end = None;
+    for (index, &(_, c)) in chars.iter().enumerate() {
+        if fold(c) == pattern[next] {
+            next += 1;
+            if next == pattern.len() {
+                end = Some(index);
+                break;
+            }
+        }
+    }
+    let end = end?;
+
+    // 反向: 从结尾往回找到最晚的起点, 使匹配区间尽量短
+    let mut remaining = pattern.len();
+    let mut start = end;
+    for index in (0..=end).rev() {
+        if fold(chars[index].1) == pattern[remaining - 1] {
+            remaining -= 1;
+            if remaining == 0 {
+                start = index;
+                break;
+            }
+        }
+    }
+
+    // 计分
+    let mut score = 0;
+    let mut next = 0;
+    let mut in_gap = false;
+    let mut consecutive = false;
+    for index in start..=end {
+        let c = chars[index].1;
+        if next < pattern.len() && fold(c) == pattern[next] {
+            let prev = index.checked_sub(1).map(|i| chars[i].1);
+            let mut bonus = fuzzy_boundary_bonus(prev, c);
+            if next == 0 {
+                bonus *= 2;
+            }
+            if consecutive {
+                bonus = bonus.max(FUZZY_BONUS_CONSECUTIVE);
+            }
+            score += FUZZY_SCORE_MATCH + bonus;
+            next += 1;
+            in_gap = false;
+            consecutive = true;
+        } else {
+            score += if in_gap { FUZZY_GAP_EXTENSION } else { FUZZY_GAP_START };
+            in_gap = true;
+            consecutive = false;
+        }
+    }
+
+    let (last_offset, last_char) = chars[end];
+    Some((score, chars[start].0, last_offset + last_char.len_utf8()))
+}
+
 /// 在单个文件中搜索
+#[allow(clippy::too_many_arguments)]
 fn search_in_file(
     file_path: &Path,
     base_path: &Path,
@@ -1508,6 +3494,7 @@ fn search_in_file(
     case_sensitive: bool,
     use_regex: bool,
     regex_pattern: Option<&Regex>,
+    fuzzy: bool,
     language_map: &std::collections::HashMap<String, String>,
 ) -> Result<Vec<SearchResult>, String> {
     let mut results = Vec::new();
@@ -1528,7 +3515,17 @@ fn search_in_file(
     let translation = get_file_translation(file_path, base_path, language_map);
     
     // 搜索文件名
-    let (filename_match, match_start, match_end) = if use_regex {
+    let mut score = None;
+    let (filename_match, match_start, match_end) = if fuzzy {
+        if let Some((fuzzy_score, start, end)) = fuzzy_match(&file_name, query, case_sensitive) {
+            score = Some(fuzzy_score);
+            (true, Some(start), Some(end))
+        } else if let Some((start, end)) = check_chinese_match(file_path, base_path, query, case_sensitive, language_map) {
+            (true, Some(start), Some(end))
+        } else {
+            (false, None, None)
+        }
+    } else if use_regex {
         if let Some(regex) = regex_pattern {
             if regex.is_match(&file_name) {
                 if let Some(mat) = regex.find(&file_name) {
@@ -1575,13 +3572,14 @@ fn search_in_file(
             match_start,
             match_end,
             translation: translation.clone(),
+            score,
         });
     }
     
     // 搜索文件内容
     if let Some(ext) = file_path.extension() {
         let ext_str = ext.to_string_lossy().to_lowercase();
-        if matches!(ext_str.as_str(), "json" | "mcmeta" | "txt" | "lang") {
+        if SEARCH_TEXT_EXTENSIONS.contains(&ext_str.as_str()) {
             // 读取文件内容限制大小为 10MB
             let metadata = std::fs::metadata(file_path).ok();
             if let Some(meta) = metadata {
@@ -1645,6 +3643,7 @@ fn search_in_file(
                             match_start,
                             match_end,
                             translation: None, // 内容匹配不需要翻译
+                            score: None,
                         });
                     }
                 }
@@ -1655,81 +3654,466 @@ fn search_in_file(
     Ok(results)
 }
 
+/// 替换预览中每个文件最多展示的行数
+const REPLACE_PREVIEW_LINES: usize = 20;
+
+/// 替换前后的一行
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplacePreviewLine {
+    pub line_number: usize,
+    pub before: String,
+    pub after: String,
+}
+
+/// 单个文件的替换结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplaceFileResult {
+    pub file_path: String,
+    pub replacements: usize,
+    /// 前 20 处发生变化的行
+    pub preview: Vec<ReplacePreviewLine>,
+}
+
+/// 批量替换中写入失败的文件
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplaceFailure {
+    pub file_path: String,
+    pub code: String,
+    pub message: String,
+}
+
+/// 批量替换结果, files 只包含实际写入(或 dry_run 时将会写入)的文件
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplaceResponse {
+    pub files: Vec<ReplaceFileResult>,
+    pub failed: Vec<ReplaceFailure>,
+    pub files_changed: usize,
+    pub total_replacements: usize,
+    pub dry_run: bool,
+}
+
+/// 逐行替换文件内容, 保留原有换行符; 没有匹配时返回 None
+fn replace_in_content(
+    content: &str,
+    regex: &Regex,
+    replacement: &str,
+    use_regex: bool,
+) -> Option<(String, usize, Vec<ReplacePreviewLine>)> {
+    let mut output = String::with_capacity(content.len());
+    let mut count = 0;
+    let mut preview = Vec::new();
+
+    for (index, raw_line) in content.split_inclusive('\n').enumerate() {
+        let line = raw_line.trim_end_matches(['\r', '\n']);
+        let ending = &raw_line[line.len()..];
+
+        let matches = regex.find_iter(line).count();
+        if matches == 0 {
+            output.push_str(raw_line);
+            continue;
+        }
+
+        // 非正则模式下替换文本中的 $ 不做展开
+        let replaced = if use_regex {
+            regex.replace_all(line, replacement)
+        } else {
+            regex.replace_all(line, regex::NoExpand(replacement))
+        };
+        count += matches;
+        if preview.len() < REPLACE_PREVIEW_LINES {
+            preview.push(ReplacePreviewLine {
+                line_number: index + 1,
+                before: line.to_string(),
+                after: replaced.to_string(),
+            });
+        }
+        output.push_str(&replaced);
+        output.push_str(ending);
+    }
+
+    (count > 0).then_some((output, count, preview))
+}
+
+/// 在材质包的文本文件中批量替换, dry_run 时只返回每个文件的匹配数和变化预览
+///
+/// paths 可限定替换的文件或文件夹(相对材质包); 实际写入前每个文件都会先保存历史记录,
+/// 写入失败的文件列在 failed 中
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn replace_in_files(
+    query: String,
+    replacement: String,
+    case_sensitive: bool,
+    use_regex: bool,
+    paths: Option<Vec<String>>,
+    dry_run: bool,
+    pack_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<ReplaceResponse, AppError> {
+    if query.is_empty() {
+        return Err(AppError::InvalidInput("Search query cannot be empty".to_string()));
+    }
+    let base_path = state.pack_path_for(pack_id.as_deref())?;
+
+    let pattern = if use_regex { query.clone() } else { regex::escape(&query) };
+    let regex = regex::RegexBuilder::new(&pattern)
+        .case_insensitive(!case_sensitive)
+        .build()
+        .map_err(|e| AppError::InvalidInput(format!("Invalid regex pattern: {}", e)))?;
+
+    let scopes = paths
+        .unwrap_or_default()
+        .iter()
+        .map(|path| resolve_pack_path(&base_path, path))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let files: Vec<PathBuf> = collect_searchable_files(&base_path, &SearchScope::default())?
+        .into_iter()
+        .filter(|file| {
+            file.extension()
+                .map(|ext| ext.to_string_lossy().to_lowercase())
+                .is_some_and(|ext| SEARCH_TEXT_EXTENSIONS.contains(&ext.as_str()))
+        })
+        .filter(|file| scopes.is_empty() || scopes.iter().any(|scope| file.starts_with(scope)))
+        .collect();
+
+    // 并行计算替换结果, 过大或非 UTF-8 的文件跳过
+    let mut changed: Vec<(String, String, ReplaceFileResult)> = files
+        .par_iter()
+        .filter_map(|file| {
+            if std::fs::metadata(file).ok()?.len() > 10 * 1024 * 1024 {
+                return None;
+            }
+            let content = std::fs::read_to_string(file).ok()?;
+            let (new_content, replacements, preview) =
+                replace_in_content(&content, &regex, &replacement, use_regex)?;
+            let relative = file
+                .strip_prefix(&base_path)
+                .ok()?
+                .to_string_lossy()
+                .replace('\\', "/");
+            Some((
+                relative.clone(),
+                new_content,
+                ReplaceFileResult {
+                    file_path: relative,
+                    replacements,
+                    preview,
+                },
+            ))
+        })
+        .collect();
+    changed.sort_by(|a, b| a.0.cmp(&b.0));
+
+    // 单个文件写入失败不会中止其余文件, 失败的文件单独返回
+    let mut files = Vec::with_capacity(changed.len());
+    let mut failed = Vec::new();
+    for (relative, new_content, result) in changed {
+        if !dry_run {
+            let written = crate::history_manager::snapshot_file(&base_path, &relative)
+                .and_then(|_| {
+                    crate::image_handler::write_file_atomic(&base_path.join(&relative), new_content.as_bytes())
+                })
+                .map_err(AppError::from);
+            if let Err(e) = written {
+                failed.push(ReplaceFailure {
+                    file_path: relative,
+                    code: e.code().to_string(),
+                    message: e.message().to_string(),
+                });
+                continue;
+            }
+        }
+        files.push(result);
+    }
+
+    Ok(ReplaceResponse {
+        files_changed: files.len(),
+        total_replacements: files.iter().map(|file| file.replacements).sum(),
+        files,
+        failed,
+        dry_run,
+    })
+}
+
+/// 模型中指向不存在文件的纹理或父模型引用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrokenModelReference {
+    /// 模型文件的相对路径
+    pub model: String,
+    /// textures 中的键, 父模型缺失时为 "parent"
+    pub key: String,
+    /// 模型中填写的原始引用
+    pub reference: String,
+    /// 期望存在的文件相对路径
+    pub expected_path: String,
+}
+
+/// 沿 #变量 引用查找最终的纹理ID, 变量在本模型中未定义(由父/子模型提供)或循环时返回 None
+fn resolve_texture_variable<'a>(
+    textures: &'a serde_json::Map<String, serde_json::Value>,
+    value: &'a str,
+) -> Option<&'a str> {
+    let mut current = value;
+    for _ in 0..=textures.len() {
+        match current.strip_prefix('#') {
+            Some(key) => current = textures.get(key)?.as_str()?,
+            None => return Some(current),
+        }
+    }
+    None
+}
+
+/// 检查单个模型文件, exists 判断相对路径是否存在(材质包或原版模板中)
+///
+/// #变量 引用按解析后的纹理报告, 同一纹理经多个键引用时每个键都会列出
+fn check_model_references(
+    model_path: &Path,
+    relative: &str,
+    exists: &dyn Fn(&str, &Path) -> bool,
+) -> Vec<BrokenModelReference> {
+    let model = match std::fs::read_to_string(model_path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+    {
+        Some(serde_json::Value::Object(model)) => model,
+        _ => return Vec::new(),
+    };
+
+    let mut broken = Vec::new();
+
+    if let Some(parent) = model.get("parent").and_then(|v| v.as_str()) {
+        if !crate::model_resolver::is_builtin_model(parent) {
+            let (namespace, _) = crate::model_resolver::split_resource_id(parent);
+            let expected = crate::model_resolver::model_id_to_relative_path(parent);
+            if !exists(namespace, &expected) {
+                broken.push(BrokenModelReference {
+                    model: relative.to_string(),
+                    key: "parent".to_string(),
+                    reference: parent.to_string(),
+                    expected_path: expected.to_string_lossy().replace('\\', "/"),
+                });
+            }
+        }
+    }
+
+    if let Some(serde_json::Value::Object(textures)) = model.get("textures") {
+        for (key, value) in textures {
+            let Some(id) = value
+                .as_str()
+                .and_then(|value| resolve_texture_variable(textures, value))
+            else {
+                continue;
+            };
+            let (namespace, _) = crate::model_resolver::split_resource_id(id);
+            let expected = crate::texture_checks::texture_id_to_relative_path(id);
+            if !exists(namespace, &expected) {
+                broken.push(BrokenModelReference {
+                    model: relative.to_string(),
+                    key: key.clone(),
+                    reference: id.to_string(),
+                    expected_path: expected.to_string_lossy().replace('\\', "/"),
+                });
+            }
+        }
+    }
+
+    broken
+}
+
+/// 并行检查所有模型的纹理和父模型引用
+///
+/// 提供原版模板目录时同时在其中查找; 未提供时无法确认原版资源, minecraft 命名空间中缺失的引用不报告
+pub fn scan_broken_model_references(
+    pack_path: &Path,
+    template_dir: Option<&Path>,
+) -> Vec<BrokenModelReference> {
+    use walkdir::WalkDir;
+
+    let model_files: Vec<PathBuf> = WalkDir::new(pack_path.join("assets"))
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")))
+        .map(|e| e.into_path())
+        .filter(|path| {
+            path.strip_prefix(pack_path).is_ok_and(|relative| {
+                relative.components().nth(2).is_some_and(|c| c.as_os_str() == "models")
+            })
+        })
+        .collect();
+
+    let exists = |namespace: &str, relative: &Path| {
+        if template_dir.is_none() && namespace == "minecraft" {
+            return true;
+        }
+        std::iter::once(pack_path)
+            .chain(template_dir)
+            .any(|root| root.join(relative).is_file())
+    };
+
+    let mut broken: Vec<BrokenModelReference> = model_files
+        .par_iter()
+        .flat_map_iter(|path| {
+            let relative = path
+                .strip_prefix(pack_path)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            check_model_references(path, &relative, &exists)
+        })
+        .collect();
+
+    broken.sort_by(|a, b| a.model.cmp(&b.model).then_with(|| a.key.cmp(&b.key)));
+    broken
+}
+
+/// 查找引用了不存在的纹理或父模型的模型
+#[tauri::command]
+pub async fn find_broken_model_references(
+    template_dir: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<BrokenModelReference>, AppError> {
+    let base_path = state.pack_path()?;
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    rayon::spawn(move || {
+        let _ = tx.send(scan_broken_model_references(
+            &base_path,
+            template_dir.as_deref().map(Path::new),
+        ));
+    });
+    rx.await
+        .map_err(|e| AppError::Other(format!("Channel error: {}", e)))
+}
+
 /// 下载声音资源
 #[tauri::command]
 pub async fn download_minecraft_sounds(
     state: State<'_, AppState>,
     manager: State<'_, std::sync::Arc<crate::download_manager::DownloadManager>>,
     concurrent_downloads: Option<usize>,
-) -> Result<String, String> {
-    use std::sync::Arc;
-    
-    let output_dir = {
-        let pack_path = state.current_pack_path.lock().unwrap();
-        match pack_path.as_ref() {
-            Some(path) => path.clone(),
-            None => return Err("没有加载材质包".to_string()),
-        }
-    };
+) -> Result<String, AppError> {
+    let output_dir = state.pack_path()?;
+    let concurrent_downloads = concurrent_downloads.unwrap_or(32);
     
     // 创建下载任务
     let task_id = manager.create_task(
         "Minecraft 声音资源".to_string(),
         "sounds".to_string(),
         output_dir.clone(),
+        Some(crate::download_manager::DownloadParams::Sounds { concurrent_downloads }),
     ).await;
     
-    let manager_clone = Arc::clone(&manager);
-    let task_id_clone = task_id.clone();
-    
-    // 在后台启动下载任务
+    spawn_sounds_download(
+        std::sync::Arc::clone(&manager),
+        task_id.clone(),
+        output_dir,
+        concurrent_downloads,
+    );
+    Ok(task_id)
+}
+
+/// 在后台执行声音资源下载任务
+fn spawn_sounds_download(
+    manager: std::sync::Arc<crate::download_manager::DownloadManager>,
+    task_id: String,
+    output_dir: PathBuf,
+    concurrent_downloads: usize,
+) {
     tokio::spawn(async move {
+        // 超出并发上限时排队等待
+        let _slot = match manager.acquire_slot(&task_id).await {
+            Some(slot) => slot,
+            None => return,
+        };
+
         let result = crate::version_downloader::download_minecraft_sounds_with_progress(
             &output_dir,
-            task_id_clone.clone(),
-            manager_clone.clone(),
-            concurrent_downloads.unwrap_or(32),
+            task_id.clone(),
+            manager.clone(),
+            concurrent_downloads,
         ).await;
         
         // 更新最终状态
-        match result {
-            Ok(_message) => {
-                let progress = crate::download_manager::DownloadProgress {
-                    task_id: task_id_clone.clone(),
-                    status: crate::download_manager::DownloadStatus::Completed,
-                    current: 100,
-                    total: 100,
-                    current_file: None,
-                    speed: 0.0,
-                    eta: None,
-                    error: None,
-                };
-                manager_clone.update_progress(&task_id_clone, progress).await;
-            }
-            Err(e) => {
-                let progress = crate::download_manager::DownloadProgress {
-                    task_id: task_id_clone.clone(),
-                    status: crate::download_manager::DownloadStatus::Failed,
-                    current: 0,
-                    total: 100,
-                    current_file: None,
-                    speed: 0.0,
-                    eta: None,
-                    error: Some(e),
-                };
-                manager_clone.update_progress(&task_id_clone, progress).await;
-            }
-        }
+        let progress = match result {
+            Ok(_message) => crate::download_manager::DownloadProgress {
+                task_id: task_id.clone(),
+                status: crate::download_manager::DownloadStatus::Completed,
+                current: 100,
+                total: 100,
+                current_file: None,
+                speed: 0.0,
+                eta: None,
+                error: None,
+            },
+            Err(e) => crate::download_manager::DownloadProgress {
+                task_id: task_id.clone(),
+                status: crate::download_manager::DownloadStatus::Failed,
+                current: 0,
+                total: 100,
+                current_file: None,
+                speed: 0.0,
+                eta: None,
+                error: Some(e),
+            },
+        };
+        manager.update_progress(&task_id, progress).await;
         
         // 移除取消令牌
-        manager_clone.remove_cancel_token(&task_id_clone).await;
+        manager.remove_cancel_token(&task_id).await;
     });
-    Ok(task_id)
+}
+
+/// 重试失败的下载任务
+#[tauri::command]
+pub async fn retry_download_task(
+    task_id: String,
+    app: tauri::AppHandle,
+    manager: State<'_, std::sync::Arc<crate::download_manager::DownloadManager>>,
+) -> Result<(), AppError> {
+    let task = manager.reset_for_retry(&task_id).await?;
+
+    match task.params {
+        Some(crate::download_manager::DownloadParams::Template { version_id, keep_cache, folders, locale }) => {
+            let locale = crate::version_downloader::normalize_locale(locale.as_deref())
+                .map_err(AppError::InvalidInput)?;
+            spawn_template_download(
+                std::sync::Arc::clone(&manager),
+                task_id,
+                version_id,
+                task.output_dir,
+                keep_cache,
+                folders,
+                locale,
+            )?;
+        }
+        Some(crate::download_manager::DownloadParams::Sounds { concurrent_downloads }) => {
+            spawn_sounds_download(
+                std::sync::Arc::clone(&manager),
+                task_id,
+                task.output_dir,
+                concurrent_downloads,
+            );
+        }
+        Some(crate::download_manager::DownloadParams::Version { version_id }) => {
+            spawn_version_download(
+                std::sync::Arc::clone(&manager),
+                app,
+                task_id,
+                version_id,
+                task.output_dir,
+            );
+        }
+        None => return Err(AppError::InvalidInput("该任务不支持重试".to_string())),
+    }
+
+    Ok(())
 }
 
 /// 读取pack.mcmeta文件内容
 #[tauri::command]
-pub async fn read_pack_mcmeta(path: String, is_zip: bool) -> Result<serde_json::Value, String> {
+pub async fn read_pack_mcmeta(path: String, is_zip: bool) -> Result<serde_json::Value, AppError> {
     use std::fs::File;
     use std::io::Read;
     use zip::ZipArchive;
@@ -1758,18 +4142,18 @@ pub async fn read_pack_mcmeta(path: String, is_zip: bool) -> Result<serde_json::
                     .map_err(|e| format!("无法解析pack.mcmeta JSON: {}", e))?;
                 
                 return json.get("pack")
-                    .ok_or_else(|| "pack.mcmeta中缺少pack字段".to_string())
+                    .ok_or_else(|| AppError::Parse("pack.mcmeta中缺少pack字段".to_string()))
                     .map(|v| v.clone());
             }
         }
         
-        Err("ZIP文件中未找到pack.mcmeta".to_string())
+        Err(AppError::NotFound("ZIP文件中未找到pack.mcmeta".to_string()))
     } else {
         // 从文件夹中读取pack.mcmeta
         let mcmeta_path = Path::new(&path).join("pack.mcmeta");
         
         if !mcmeta_path.exists() {
-            return Err("文件夹中未找到pack.mcmeta".to_string());
+            return Err(AppError::NotFound("文件夹中未找到pack.mcmeta".to_string()));
         }
         
         let mut file = File::open(&mcmeta_path)
@@ -1783,50 +4167,79 @@ pub async fn read_pack_mcmeta(path: String, is_zip: bool) -> Result<serde_json::
             .map_err(|e| format!("无法解析pack.mcmeta JSON: {}", e))?;
         
         json.get("pack")
-            .ok_or_else(|| "pack.mcmeta中缺少pack字段".to_string())
+            .ok_or_else(|| AppError::Parse("pack.mcmeta中缺少pack字段".to_string()))
             .map(|v| v.clone())
     }
 }
 
 /// 获取支持的版本列表
 #[tauri::command]
-pub async fn get_supported_versions() -> Result<Vec<(u32, String)>, String> {
+pub async fn get_supported_versions() -> Result<Vec<(u32, String)>, AppError> {
     Ok(crate::version_converter::get_supported_versions())
 }
 
+/// 获取当前材质包适用的游戏版本范围
+#[tauri::command]
+pub async fn get_pack_version_range(
+    state: State<'_, AppState>,
+) -> Result<crate::version_converter::PackVersionRange, AppError> {
+    let base_path = state.pack_path()?;
+    let content = std::fs::read_to_string(base_path.join("pack.mcmeta"))
+        .map_err(|e| AppError::io("读取pack.mcmeta失败", e))?;
+    let mcmeta: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| AppError::Parse(format!("无法解析pack.mcmeta JSON: {}", e)))?;
+
+    let (min_format, max_format) = mcmeta
+        .get("pack")
+        .and_then(crate::pack_parser::read_pack_format_range)
+        .ok_or_else(|| AppError::Parse("pack.mcmeta中缺少pack_format".to_string()))?;
+
+    crate::version_converter::get_version_range_for_formats(min_format, max_format)
+        .ok_or_else(|| AppError::NotFound(format!("未知的 pack_format: {} – {}", min_format, max_format)))
+}
+
 /// 转换材质包版本
 #[tauri::command]
 pub async fn convert_pack_version(
     input_path: String,
     output_path: String,
     target_version: String,
-) -> Result<String, String> {
+) -> Result<String, AppError> {
     let input = Path::new(&input_path);
     let output = Path::new(&output_path);
     
-    crate::version_converter::convert_pack_version(input, output, &target_version)
+    Ok(crate::version_converter::convert_pack_version(input, output, &target_version)?)
+}
+
+/// 预览版本转换会改写的文件
+#[tauri::command]
+pub async fn preview_conversion(
+    input_path: String,
+    target_version: String,
+) -> Result<Vec<crate::version_converter::ConversionChange>, AppError> {
+    Ok(crate::version_converter::preview_conversion(Path::new(&input_path), &target_version)?)
 }
 
 /// 获取URL内容
 #[tauri::command]
-pub async fn fetch_url(url: String) -> Result<String, String> {
+pub async fn fetch_url(url: String) -> Result<String, AppError> {
     let response = reqwest::get(&url)
         .await
-        .map_err(|e| format!("Failed to fetch URL: {}", e))?;
+        .map_err(|e| AppError::Network(format!("Failed to fetch URL: {}", e)))?;
     
     if !response.status().is_success() {
-        return Err(format!("HTTP error! status: {}", response.status()));
+        return Err(AppError::Network(format!("HTTP error! status: {}", response.status())));
     }
     
     response
         .text()
         .await
-        .map_err(|e| format!("Failed to read response: {}", e))
+        .map_err(|e| AppError::Network(format!("Failed to read response: {}", e)))
 }
 
 /// 检查文件是否存在
 #[tauri::command]
-pub fn check_file_exists(file_path: String) -> Result<bool, String> {
+pub fn check_file_exists(file_path: String) -> Result<bool, AppError> {
     let path = Path::new(&file_path);
     Ok(path.exists())
 }
@@ -1836,15 +4249,9 @@ pub fn check_file_exists(file_path: String) -> Result<bool, String> {
 pub async fn copy_sound_file(
     sound_name: String,
     state: State<'_, AppState>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     // 获取路径
-    let base_path = {
-        let pack_path = state.current_pack_path.lock().unwrap();
-        match pack_path.as_ref() {
-            Some(path) => path.clone(),
-            None => return Err("No pack loaded".to_string()),
-        }
-    };
+    let base_path = state.pack_path()?;
     
     let source_ogg = base_path.join(".little100").join("sounds").join(format!("{}.ogg", sound_name));
     let source_wav = base_path.join(".little100").join("sounds").join(format!("{}.wav", sound_name));
@@ -1857,7 +4264,7 @@ pub async fn copy_sound_file(
     } else if source_wav.exists() {
         source_wav
     } else {
-        return Err(format!("音频文件不存在: {}", sound_name));
+        return Err(AppError::NotFound(format!("音频文件不存在: {}", sound_name)));
     };
     
     // 创建目标目录
@@ -1877,15 +4284,8 @@ pub async fn copy_sound_file(
 
 /// 检查临时文件夹中的音频文件
 #[tauri::command]
-pub async fn check_temp_audio_files(state: State<'_, AppState>) -> Result<Vec<String>, String> {
-    let pack_path = state.current_pack_path.lock().unwrap();
-    
-    let base_path = match pack_path.as_ref() {
-        Some(path) => path.clone(),
-        None => return Err("No pack loaded".to_string()),
-    };
-    
-    drop(pack_path);
+pub async fn check_temp_audio_files(state: State<'_, AppState>) -> Result<Vec<String>, AppError> {
+    let base_path = state.pack_path()?;
     
     let sounds_dir = base_path.join(".little100").join("sounds");
     
@@ -1918,7 +4318,7 @@ pub async fn check_temp_audio_files(state: State<'_, AppState>) -> Result<Vec<St
 
 /// 读取文件内容并转换为 base64
 #[tauri::command]
-pub async fn read_file_as_base64(file_path: String) -> Result<String, String> {
+pub async fn read_file_as_base64(file_path: String) -> Result<String, AppError> {
     use base64::{Engine as _, engine::general_purpose};
     
     let file_content = tokio::fs::read(&file_path)
@@ -1933,26 +4333,12 @@ pub async fn read_file_as_base64(file_path: String) -> Result<String, String> {
 pub async fn open_in_explorer(
     file_path: String,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let full_path = {
-        let pack_path = state.current_pack_path.lock().unwrap();
-        
-        match pack_path.as_ref() {
-            Some(base_path) => {
-                let path = Path::new(&file_path);
-                if path.is_absolute() {
-                    path.to_path_buf()
-                } else {
-                    base_path.join(path)
-                }
-            }
-            None => PathBuf::from(&file_path),
-        }
-    };
+) -> Result<(), AppError> {
+    let full_path = resolve_pack_path(&state.pack_path()?, &file_path)?;
     
     // 检查路径是否存在
     if !full_path.exists() {
-        return Err(format!("路径不存在: {}", full_path.display()));
+        return Err(AppError::NotFound(format!("路径不存在: {}", full_path.display())));
     }
     
     // 根据操作系统打开资源管理器
@@ -2005,4 +4391,206 @@ pub async fn open_in_explorer(
     }
     
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checksum::{ChecksumManifestOptions, HashAlgorithm, ManifestFormat};
+    use crate::zip_handler::{ExcludePatterns, ZipCompression, DEFAULT_EXPORT_EXCLUDES};
+    use std::io::Read;
+
+    fn write(root: &Path, relative: &str, content: &str) {
+        let path = root.join(relative);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn export_with_manifest(pack: &Path, output: &Path, excludes: ExcludePatterns) -> Result<(), String> {
+        PackExportJob {
+            path: pack.to_path_buf(),
+            output: output.to_path_buf(),
+            checksum_manifest: Some(ChecksumManifestOptions {
+                algorithm: HashAlgorithm::Sha1,
+                format: ManifestFormat::Json,
+                output_path: None,
+            }),
+            excludes,
+            file_options: ZipCompression::Default.file_options(),
+        }
+        .run(&mut |_| true)
+    }
+
+    fn zip_names(path: &Path) -> Vec<String> {
+        let archive = zip::ZipArchive::new(std::fs::File::open(path).unwrap()).unwrap();
+        let mut names: Vec<String> = archive.file_names().map(str::to_string).collect();
+        names.sort();
+        names
+    }
+
+    fn manifest_paths(path: &Path) -> Vec<String> {
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(path).unwrap()).unwrap();
+        let mut content = String::new();
+        archive.by_name("checksums.json").unwrap().read_to_string(&mut content).unwrap();
+        let manifest: serde_json::Value = serde_json::from_str(&content).unwrap();
+        manifest["files"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| entry["path"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn export_manifest_lists_only_zipped_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let pack = dir.path().join("pack");
+        write(&pack, "pack.mcmeta", "{}");
+        write(&pack, "assets/minecraft/models/item/a.json", "{}");
+        write(&pack, "assets/minecraft/textures/work.psd", "psd");
+        write(&pack, "logs/latest.log", "log");
+        write(&pack, "assets/Thumbs.db", "db");
+
+        let mut patterns: Vec<&str> = DEFAULT_EXPORT_EXCLUDES.to_vec();
+        patterns.push("**/*.psd");
+        let output = dir.path().join("out.zip");
+        export_with_manifest(&pack, &output, ExcludePatterns::new(&patterns).unwrap()).unwrap();
+
+        let expected = vec!["assets/minecraft/models/item/a.json".to_string(), "pack.mcmeta".to_string()];
+        assert_eq!(manifest_paths(&output), expected);
+        let mut zipped = expected;
+        zipped.push("checksums.json".to_string());
+        zipped.sort();
+        assert_eq!(zip_names(&output), zipped);
+    }
+
+    #[test]
+    fn export_replaces_existing_manifest_in_pack_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let pack = dir.path().join("pack");
+        write(&pack, "pack.mcmeta", "{}");
+        write(&pack, "checksums.json", "{\"stale\": true}");
+
+        let output = dir.path().join("out.zip");
+        export_with_manifest(&pack, &output, ExcludePatterns::export_defaults()).unwrap();
+
+        assert_eq!(zip_names(&output), vec!["checksums.json", "pack.mcmeta"]);
+        assert_eq!(manifest_paths(&output), vec!["pack.mcmeta"]);
+    }
+
+    #[test]
+    fn export_folder_overwrite_drops_stale_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let pack = dir.path().join("pack");
+        write(&pack, "pack.mcmeta", "{}");
+        write(&pack, "assets/minecraft/textures/item/a.png", "a");
+        write(&pack, ".history/pack.mcmeta/001.json", "{}");
+
+        let output = dir.path().join("export");
+        write(&output, "assets/minecraft/textures/item/deleted.png", "old");
+        write(&output, "pack.mcmeta", "old");
+
+        export_folder_replacing(&pack, &output, &ExcludePatterns::export_defaults()).unwrap();
+
+        assert_eq!(std::fs::read_to_string(output.join("pack.mcmeta")).unwrap(), "{}");
+        assert!(output.join("assets/minecraft/textures/item/a.png").is_file());
+        assert!(!output.join("assets/minecraft/textures/item/deleted.png").exists());
+        assert!(!output.join(".history").exists());
+        let leftovers: Vec<_> = std::fs::read_dir(dir.path()).unwrap().filter_map(|e| e.ok()).collect();
+        assert_eq!(leftovers.len(), 2);
+    }
+
+    #[test]
+    fn model_with_missing_texture_is_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "assets/mypack/textures/item/ruby.png", "");
+        write(
+            dir.path(),
+            "assets/mypack/models/item/ruby.json",
+            r#"{ "parent": "item/generated", "textures": { "layer0": "mypack:item/ruby" } }"#,
+        );
+        write(
+            dir.path(),
+            "assets/mypack/models/block/ore.json",
+            r##"{ "parent": "mypack:block/base", "textures": { "all": "mypack:block/ore", "particle": "#all" } }"##,
+        );
+
+        let broken = scan_broken_model_references(dir.path(), None);
+        let found: Vec<(&str, &str, &str)> = broken
+            .iter()
+            .map(|r| (r.key.as_str(), r.reference.as_str(), r.expected_path.as_str()))
+            .collect();
+        // #all 解析为实际纹理后报告; 未提供模板时 minecraft 命名空间的父模型不报告
+        assert_eq!(
+            found,
+            [
+                ("all", "mypack:block/ore", "assets/mypack/textures/block/ore.png"),
+                ("parent", "mypack:block/base", "assets/mypack/models/block/base.json"),
+                ("particle", "mypack:block/ore", "assets/mypack/textures/block/ore.png"),
+            ]
+        );
+        assert!(broken.iter().all(|r| r.model == "assets/mypack/models/block/ore.json"));
+    }
+
+    #[test]
+    fn vanilla_references_are_checked_against_template() {
+        let pack = tempfile::tempdir().unwrap();
+        let template = tempfile::tempdir().unwrap();
+        write(template.path(), "assets/minecraft/textures/block/stone.png", "");
+        write(
+            pack.path(),
+            "assets/minecraft/models/block/stone.json",
+            r#"{ "textures": { "all": "block/stone", "side": "minecraft:block/missing" } }"#,
+        );
+
+        let broken = scan_broken_model_references(pack.path(), Some(template.path()));
+        assert_eq!(broken.len(), 1);
+        assert_eq!(broken[0].key, "side");
+    }
+
+    #[test]
+    fn tree_fingerprint_covers_nested_levels() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(root, "pack.mcmeta", "{}");
+        write(root, "assets/minecraft/sounds.json", "{}");
+        write(root, "assets/minecraft/textures/block/stone.png", "png");
+        write(root, ".history/pack.mcmeta/001.json.gz", "");
+
+        let fingerprint = || directory_fingerprint(root, 0, FILE_TREE_DEPTH).unwrap();
+        let tree = read_directory_tree_lazy(root, root, 0, FILE_TREE_DEPTH).unwrap();
+        assert_eq!(fingerprint(), fingerprint_nodes(&tree));
+
+        // 两层以下的文件变化也会改变根目录指纹
+        let before = fingerprint();
+        write(root, "assets/minecraft/sounds.json", "{\"a\":{}}");
+        let edited = fingerprint();
+        assert_ne!(before, edited);
+        write(root, "assets/minecraft/lang.json", "{}");
+        assert_ne!(edited, fingerprint());
+
+        let folder = root.join("assets");
+        let children = read_directory_tree_lazy(&folder, root, 0, FOLDER_CHILDREN_DEPTH).unwrap();
+        assert_eq!(directory_fingerprint(&folder, 0, FOLDER_CHILDREN_DEPTH).unwrap(), fingerprint_nodes(&children));
+    }
+
+    #[test]
+    fn literal_replacement_keeps_dollar_signs() {
+        let regex = Regex::new(&regex::escape("stone")).unwrap();
+        let (output, count, preview) =
+            replace_in_content("stone\r\nstone stone\r\ndirt", &regex, "$1 gem", false).unwrap();
+        assert_eq!(output, "$1 gem\r\n$1 gem $1 gem\r\ndirt");
+        assert_eq!(count, 3);
+        assert_eq!(preview.len(), 2);
+        assert_eq!((preview[1].line_number, preview[1].after.as_str()), (2, "$1 gem $1 gem"));
+    }
+
+    #[test]
+    fn regex_replacement_expands_groups() {
+        let regex = Regex::new(r"block/(\w+)").unwrap();
+        let (output, count, _) = replace_in_content("block/stone\nitem/stick\n", &regex, "block/new_$1", true).unwrap();
+        assert_eq!(output, "block/new_stone\nitem/stick\n");
+        assert_eq!(count, 1);
+        assert!(replace_in_content("item/stick\n", &regex, "x", true).is_none());
+    }
+}
@@ -0,0 +1,176 @@
+use crate::commands::AppState;
+use crate::error::AppError;
+use crate::pack_parser::is_hidden_relative_path;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+use walkdir::WalkDir;
+
+/// 当前平台默认的 .minecraft 目录
+fn default_minecraft_dir() -> Option<PathBuf> {
+    if cfg!(target_os = "windows") {
+        std::env::var_os("APPDATA").map(|appdata| PathBuf::from(appdata).join(".minecraft"))
+    } else if cfg!(target_os = "macos") {
+        std::env::var_os("HOME")
+            .map(|home| PathBuf::from(home).join("Library/Application Support/minecraft"))
+    } else {
+        std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".minecraft"))
+    }
+}
+
+/// 解析 resourcepacks 目录, 传入的目录本身就是 resourcepacks 时直接使用
+pub fn resolve_resourcepacks_dir(minecraft_dir: Option<&str>) -> Result<PathBuf, String> {
+    let dir = match minecraft_dir {
+        Some(dir) => PathBuf::from(dir),
+        None => default_minecraft_dir().ok_or("无法确定 .minecraft 目录, 请手动指定")?,
+    };
+
+    if dir.file_name().is_some_and(|name| name == "resourcepacks") {
+        return Ok(dir);
+    }
+    if !dir.is_dir() {
+        return Err(format!("Minecraft 目录不存在: {}", dir.display()));
+    }
+    Ok(dir.join("resourcepacks"))
+}
+
+/// 复制材质包文件夹, 跳过隐藏的内部目录
+fn copy_pack_dir(pack_path: &Path, target: &Path) -> Result<(), String> {
+    let walker = WalkDir::new(pack_path)
+        .into_iter()
+        .filter_entry(|e| !is_hidden_relative_path(e.path().strip_prefix(pack_path).unwrap_or(e.path())));
+
+    for entry in walker {
+        let entry = entry.map_err(|e| format!("遍历目录失败: {}", e))?;
+        let relative = entry.path().strip_prefix(pack_path).unwrap_or(entry.path());
+        let dest = target.join(relative);
+
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&dest)
+                .map_err(|e| format!("创建目录失败 {}: {}", dest.display(), e))?;
+        } else if entry.file_type().is_file() {
+            fs::copy(entry.path(), &dest)
+                .map_err(|e| format!("复制 {} 失败: {}", relative.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+/// 将材质包部署到 resourcepacks 目录, 已存在的同名包会被替换
+pub fn deploy_pack(pack_path: &Path, resourcepacks_dir: &Path, as_zip: bool) -> Result<PathBuf, String> {
+    let pack_name = pack_path
+        .file_name()
+        .ok_or("无法获取材质包名称")?
+        .to_string_lossy()
+        .to_string();
+
+    fs::create_dir_all(resourcepacks_dir)
+        .map_err(|e| format!("创建 resourcepacks 目录失败: {}", e))?;
+
+    if as_zip {
+        let target = resourcepacks_dir.join(format!("{}.zip", pack_name));
+        crate::zip_handler::create_zip_filtered(
+            pack_path,
+            &target,
+            &[],
+            |relative| !is_hidden_relative_path(relative),
+            crate::zip_handler::ZipCompression::Default.file_options(),
+            None,
+        )?;
+        return Ok(target);
+    }
+
+    let target = resourcepacks_dir.join(&pack_name);
+    // 材质包本身就在 resourcepacks 中时无需复制
+    if let (Ok(source), Ok(existing)) = (pack_path.canonicalize(), target.canonicalize()) {
+        if source == existing {
+            return Err("材质包已位于该 resourcepacks 目录中".to_string());
+        }
+    }
+    if target.exists() {
+        fs::remove_dir_all(&target)
+            .map_err(|e| format!("删除旧的部署失败: {}", e))?;
+    }
+    copy_pack_dir(pack_path, &target)?;
+    Ok(target)
+}
+
+/// 将当前材质包导出到 Minecraft 的 resourcepacks 目录, 返回部署后的路径
+#[tauri::command]
+pub async fn deploy_to_minecraft(
+    minecraft_dir: Option<String>,
+    as_zip: bool,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    let pack_path = state.pack_path()?;
+    let resourcepacks_dir = resolve_resourcepacks_dir(minecraft_dir.as_deref())
+        .map_err(AppError::InvalidInput)?;
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    rayon::spawn(move || {
+        let _ = tx.send(deploy_pack(&pack_path, &resourcepacks_dir, as_zip));
+    });
+    let deployed = rx
+        .await
+        .map_err(|e| AppError::Other(format!("Channel error: {}", e)))??;
+
+    Ok(deployed.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_pack(root: &Path) -> PathBuf {
+        let pack = root.join("MyPack");
+        fs::create_dir_all(pack.join("assets/minecraft/lang")).unwrap();
+        fs::create_dir_all(pack.join(".history")).unwrap();
+        fs::write(pack.join("pack.mcmeta"), "{}").unwrap();
+        fs::write(pack.join("assets/minecraft/lang/en_us.json"), "{}").unwrap();
+        fs::write(pack.join(".history/history_meta.json"), "{}").unwrap();
+        pack
+    }
+
+    #[test]
+    fn folder_deploy_replaces_previous_copy() {
+        let dir = tempfile::tempdir().unwrap();
+        let pack = create_pack(dir.path());
+        let resourcepacks = dir.path().join(".minecraft/resourcepacks");
+        fs::create_dir_all(resourcepacks.join("MyPack")).unwrap();
+        fs::write(resourcepacks.join("MyPack/stale.txt"), "old").unwrap();
+
+        let deployed = deploy_pack(&pack, &resourcepacks, false).unwrap();
+        assert_eq!(deployed, resourcepacks.join("MyPack"));
+        assert!(deployed.join("pack.mcmeta").is_file());
+        assert!(deployed.join("assets/minecraft/lang/en_us.json").is_file());
+        assert!(!deployed.join("stale.txt").exists());
+        assert!(!deployed.join(".history").exists());
+    }
+
+    #[test]
+    fn zip_deploy_excludes_internal_folders() {
+        let dir = tempfile::tempdir().unwrap();
+        let pack = create_pack(dir.path());
+        let resourcepacks = dir.path().join("resourcepacks");
+
+        let deployed = deploy_pack(&pack, &resourcepacks, true).unwrap();
+        assert_eq!(deployed, resourcepacks.join("MyPack.zip"));
+
+        let archive = zip::ZipArchive::new(fs::File::open(&deployed).unwrap()).unwrap();
+        let mut names: Vec<&str> = archive.file_names().filter(|name| !name.ends_with('/')).collect();
+        names.sort();
+        assert_eq!(names, ["assets/minecraft/lang/en_us.json", "pack.mcmeta"]);
+    }
+
+    #[test]
+    fn resourcepacks_dir_is_resolved_from_minecraft_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let minecraft = dir.path().join(".minecraft");
+        let resourcepacks = minecraft.join("resourcepacks");
+
+        assert!(resolve_resourcepacks_dir(minecraft.to_str()).is_err());
+        fs::create_dir_all(&minecraft).unwrap();
+        assert_eq!(resolve_resourcepacks_dir(minecraft.to_str()).unwrap(), resourcepacks);
+        assert_eq!(resolve_resourcepacks_dir(resourcepacks.to_str()).unwrap(), resourcepacks);
+    }
+}
@@ -0,0 +1,204 @@
+use crate::checksum::{hash_file, HashAlgorithm};
+use crate::error::AppError;
+use crate::pack_parser::{read_pack_format_range, scan_pack_directory};
+use crate::pack_settings::load_pack_settings;
+use crate::version_converter::get_release_versions_in_format_range;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+/// 发布平台
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DistributionTarget {
+    Modrinth,
+    Curseforge,
+}
+
+impl DistributionTarget {
+    fn file_name(self) -> &'static str {
+        match self {
+            DistributionTarget::Modrinth => "modrinth.json",
+            DistributionTarget::Curseforge => "curseforge.json",
+        }
+    }
+}
+
+/// ZIP 的哈希值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DistributionHashes {
+    pub sha1: String,
+    pub sha512: String,
+}
+
+/// 导出结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DistributionResult {
+    pub zip_path: String,
+    pub size: u64,
+    pub hashes: DistributionHashes,
+    pub game_versions: Vec<String>,
+    /// 生成的元数据文件路径
+    pub metadata_files: Vec<String>,
+}
+
+/// 生成元数据所需的信息
+struct DistributionInfo<'a> {
+    name: &'a str,
+    version: Option<&'a str>,
+    changelog: &'a str,
+    game_versions: &'a [String],
+    file_name: &'a str,
+    size: u64,
+    hashes: &'a DistributionHashes,
+}
+
+/// 字段与 Modrinth 创建版本表单一致
+fn modrinth_metadata(info: &DistributionInfo) -> Value {
+    let version_number = info.version.unwrap_or("1.0.0");
+    json!({
+        "name": format!("{} {}", info.name, version_number),
+        "version_number": version_number,
+        "changelog": info.changelog,
+        "game_versions": info.game_versions,
+        "loaders": ["minecraft"],
+        "version_type": "release",
+        "featured": false,
+        "files": [{
+            "filename": info.file_name,
+            "size": info.size,
+            "primary": true,
+            "hashes": {
+                "sha1": info.hashes.sha1,
+                "sha512": info.hashes.sha512,
+            },
+        }],
+    })
+}
+
+/// 字段与 CurseForge 上传文件表单一致
+fn curseforge_metadata(info: &DistributionInfo) -> Value {
+    let display_name = match info.version {
+        Some(version) => format!("{} {}", info.name, version),
+        None => info.name.to_string(),
+    };
+    json!({
+        "displayName": display_name,
+        "changelog": info.changelog,
+        "changelogType": "markdown",
+        "gameVersions": info.game_versions,
+        "releaseType": "release",
+        "fileName": info.file_name,
+        "fileLength": info.size,
+        "hashes": [
+            { "algo": "sha1", "value": info.hashes.sha1 },
+            { "algo": "sha512", "value": info.hashes.sha512 },
+        ],
+    })
+}
+
+/// 文件名中不能出现的字符替换为 _
+fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_control() || r#"<>:"/\|?*"#.contains(c) { '_' } else { c })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+fn build_zip_with_hashes(pack_path: &Path, zip_path: &Path) -> Result<DistributionHashes, String> {
+    crate::zip_handler::create_zip(
+        pack_path,
+        zip_path,
+        &[],
+        &crate::zip_handler::ExcludePatterns::export_defaults(),
+        crate::zip_handler::ZipCompression::Default.file_options(),
+        None,
+    )?;
+    Ok(DistributionHashes {
+        sha1: hash_file(HashAlgorithm::Sha1, zip_path)?,
+        sha512: hash_file(HashAlgorithm::Sha512, zip_path)?,
+    })
+}
+
+/// 导出ZIP并在同目录生成发布平台的元数据
+#[tauri::command]
+pub async fn export_distribution(
+    output_dir: String,
+    targets: Vec<DistributionTarget>,
+    changelog: Option<String>,
+    state: State<'_, crate::commands::AppState>,
+) -> Result<DistributionResult, AppError> {
+    let base_path = state.pack_path()?;
+    let output_dir = PathBuf::from(output_dir);
+    fs::create_dir_all(&output_dir)
+        .map_err(|e| AppError::io("创建输出目录失败", e))?;
+
+    let pack_info = match state.pack_info() {
+        Some(info) => info,
+        None => scan_pack_directory(&base_path)?,
+    };
+    let settings = load_pack_settings(&base_path)?;
+    let version = settings.version.as_deref().map(str::trim).filter(|v| !v.is_empty());
+
+    let mcmeta: Value = fs::read_to_string(base_path.join("pack.mcmeta"))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or(Value::Null);
+    let game_versions = mcmeta
+        .get("pack")
+        .and_then(read_pack_format_range)
+        .map(|(min, max)| get_release_versions_in_format_range(min, max))
+        .unwrap_or_default();
+
+    let file_name = match version {
+        Some(version) => format!("{}-{}.zip", sanitize_file_name(&pack_info.name), sanitize_file_name(version)),
+        None => format!("{}.zip", sanitize_file_name(&pack_info.name)),
+    };
+    let zip_path = output_dir.join(&file_name);
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let (pack_path, zip_output) = (base_path.clone(), zip_path.clone());
+    rayon::spawn(move || {
+        let _ = tx.send(build_zip_with_hashes(&pack_path, &zip_output));
+    });
+    let hashes = rx
+        .await
+        .map_err(|e| AppError::Other(format!("Channel error: {}", e)))??;
+
+    let size = fs::metadata(&zip_path)
+        .map_err(|e| AppError::io("读取ZIP失败", e))?
+        .len();
+
+    let info = DistributionInfo {
+        name: &pack_info.name,
+        version,
+        changelog: changelog.as_deref().unwrap_or(""),
+        game_versions: &game_versions,
+        file_name: &file_name,
+        size,
+        hashes: &hashes,
+    };
+
+    let mut metadata_files = Vec::new();
+    for target in targets {
+        let metadata = match target {
+            DistributionTarget::Modrinth => modrinth_metadata(&info),
+            DistributionTarget::Curseforge => curseforge_metadata(&info),
+        };
+        let path = output_dir.join(target.file_name());
+        fs::write(&path, serde_json::to_string_pretty(&metadata)?)
+            .map_err(|e| AppError::io("写入元数据失败", e))?;
+        metadata_files.push(path.to_string_lossy().to_string());
+    }
+
+    Ok(DistributionResult {
+        zip_path: zip_path.to_string_lossy().to_string(),
+        size,
+        hashes,
+        game_versions,
+        metadata_files,
+    })
+}
@@ -1,10 +1,11 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::{Mutex, RwLock};
+use tokio::sync::{Mutex, Notify, RwLock};
 use tokio_util::sync::CancellationToken;
 use tauri::{AppHandle, Emitter};
+use crate::error::AppError;
 
 /// 下载任务状态
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -31,6 +32,24 @@ pub struct DownloadProgress {
     pub error: Option<String>,
 }
 
+/// 重新执行任务所需的原始参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum DownloadParams {
+    Template {
+        version_id: String,
+        keep_cache: bool,
+        /// 只提取的文件夹(相对 assets/), 为空时提取全部
+        #[serde(default)]
+        folders: Vec<String>,
+        /// 下载的语言文件, 旧任务没有时为 None(zh_cn)
+        #[serde(default)]
+        locale: Option<String>,
+    },
+    Sounds { concurrent_downloads: usize },
+    Version { version_id: String },
+}
+
 /// 下载任务
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DownloadTask {
@@ -42,6 +61,149 @@ pub struct DownloadTask {
     pub created_at: u64,
     pub updated_at: u64,
     pub output_dir: PathBuf,
+    /// 可重试任务记录的参数
+    #[serde(default)]
+    pub params: Option<DownloadParams>,
+    /// 开始下载的时间, 排队等待的时间不计入
+    #[serde(default)]
+    pub started_at: Option<u64>,
+    /// 完成时间
+    #[serde(default)]
+    pub finished_at: Option<u64>,
+    /// 已传输的文件数
+    #[serde(default)]
+    pub total_files: u64,
+    /// 已传输的字节数
+    #[serde(default)]
+    pub total_bytes: u64,
+    /// 完成后的平均速度(字节/秒)
+    #[serde(default)]
+    pub average_speed: Option<f64>,
+}
+
+impl DownloadTask {
+    /// 应用新的进度, 记录开始和完成时间
+    fn apply_progress(&mut self, progress: DownloadProgress, now: u64) {
+        match progress.status {
+            DownloadStatus::Downloading if self.started_at.is_none() => {
+                self.started_at = Some(now);
+            }
+            DownloadStatus::Completed if self.status != DownloadStatus::Completed => {
+                let started_at = self.started_at.unwrap_or(self.created_at);
+                let elapsed = now.saturating_sub(started_at).max(1);
+                self.finished_at = Some(now);
+                self.average_speed = Some(self.total_bytes as f64 / elapsed as f64);
+            }
+            _ => {}
+        }
+
+        self.status = progress.status.clone();
+        self.progress = progress;
+        self.updated_at = now;
+    }
+
+    /// 将失败的任务重置为等待状态, 清空进度和统计
+    fn reset_for_retry(&mut self, now: u64) -> Result<(), AppError> {
+        if self.status != DownloadStatus::Failed {
+            return Err(AppError::InvalidInput("只有失败的任务可以重试".to_string()));
+        }
+        if self.params.is_none() {
+            return Err(AppError::InvalidInput("该任务不支持重试".to_string()));
+        }
+
+        self.status = DownloadStatus::Pending;
+        self.progress = DownloadProgress {
+            task_id: self.id.clone(),
+            status: DownloadStatus::Pending,
+            current: 0,
+            total: 0,
+            current_file: None,
+            speed: 0.0,
+            eta: None,
+            error: None,
+        };
+        self.started_at = None;
+        self.finished_at = None;
+        self.total_files = 0;
+        self.total_bytes = 0;
+        self.average_speed = None;
+        self.updated_at = now;
+        Ok(())
+    }
+}
+
+/// 所有下载任务的汇总信息
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DownloadSummary {
+    pub active: usize,
+    pub completed: usize,
+    pub failed: usize,
+    /// 进行中任务的速度之和
+    pub total_speed: f64,
+    /// 所有进行中任务完成前的预计剩余时间(秒), 取各任务中的最大值
+    pub total_eta: Option<u64>,
+}
+
+impl DownloadSummary {
+    /// 汇总任务列表
+    pub fn from_tasks<'a>(tasks: impl IntoIterator<Item = &'a DownloadTask>) -> Self {
+        let mut summary = Self::default();
+        for task in tasks {
+            match task.status {
+                DownloadStatus::Pending | DownloadStatus::Paused => summary.active += 1,
+                DownloadStatus::Downloading => {
+                    summary.active += 1;
+                    summary.total_speed += task.progress.speed;
+                    if let Some(eta) = task.progress.eta {
+                        summary.total_eta = Some(summary.total_eta.map_or(eta, |current| current.max(eta)));
+                    }
+                }
+                DownloadStatus::Completed => summary.completed += 1,
+                DownloadStatus::Failed => summary.failed += 1,
+                DownloadStatus::Cancelled => {}
+            }
+        }
+        summary
+    }
+}
+
+/// 默认同时进行的下载任务数
+pub const DEFAULT_MAX_CONCURRENT_DOWNLOADS: usize = 2;
+
+/// 并发槽位状态
+struct SlotState {
+    max_concurrent: usize,
+    running: usize,
+    /// 等待槽位的任务, 按创建顺序排队
+    queue: VecDeque<String>,
+}
+
+impl SlotState {
+    /// 有空闲槽位且任务排在队首时占用槽位
+    fn try_take(&mut self, task_id: &str) -> bool {
+        if self.running < self.max_concurrent
+            && self.queue.front().map(|id| id.as_str()) == Some(task_id)
+        {
+            self.queue.pop_front();
+            self.running += 1;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// 下载任务占用的并发槽位, 释放时唤醒排队中的任务
+pub struct DownloadSlot {
+    slots: Arc<parking_lot::Mutex<SlotState>>,
+    notify: Arc<Notify>,
+}
+
+impl Drop for DownloadSlot {
+    fn drop(&mut self) {
+        self.slots.lock().running -= 1;
+        self.notify.notify_waiters();
+    }
 }
 
 /// 下载任务管理器
@@ -49,6 +211,8 @@ pub struct DownloadTask {
 pub struct DownloadManager {
     tasks: Arc<RwLock<HashMap<String, DownloadTask>>>,
     cancel_tokens: Arc<Mutex<HashMap<String, CancellationToken>>>,
+    slots: Arc<parking_lot::Mutex<SlotState>>,
+    slot_notify: Arc<Notify>,
     app_handle: AppHandle,
 }
 
@@ -57,16 +221,64 @@ impl DownloadManager {
         Self {
             tasks: Arc::new(RwLock::new(HashMap::new())),
             cancel_tokens: Arc::new(Mutex::new(HashMap::new())),
+            slots: Arc::new(parking_lot::Mutex::new(SlotState {
+                max_concurrent: DEFAULT_MAX_CONCURRENT_DOWNLOADS,
+                running: 0,
+                queue: VecDeque::new(),
+            })),
+            slot_notify: Arc::new(Notify::new()),
             app_handle,
         }
     }
 
+    /// 设置同时进行的下载任务上限, 调大时立即放行排队中的任务
+    pub fn set_max_concurrent(&self, max_concurrent: usize) {
+        self.slots.lock().max_concurrent = max_concurrent.max(1);
+        self.slot_notify.notify_waiters();
+    }
+
+    /// 等待并占用一个并发槽位, 排队期间任务保持 Pending
+    ///
+    /// 任务在排队时被取消或删除则返回 None
+    pub async fn acquire_slot(&self, task_id: &str) -> Option<DownloadSlot> {
+        self.slots.lock().queue.push_back(task_id.to_string());
+
+        loop {
+            let notified = self.slot_notify.notified();
+
+            let cancelled = !matches!(
+                self.get_task(task_id).await.map(|task| task.status),
+                Some(DownloadStatus::Pending)
+            );
+
+            {
+                let mut slots = self.slots.lock();
+                if cancelled {
+                    slots.queue.retain(|id| id != task_id);
+                    drop(slots);
+                    self.slot_notify.notify_waiters();
+                    return None;
+                }
+
+                if slots.try_take(task_id) {
+                    return Some(DownloadSlot {
+                        slots: Arc::clone(&self.slots),
+                        notify: Arc::clone(&self.slot_notify),
+                    });
+                }
+            }
+
+            notified.await;
+        }
+    }
+
     /// 创建新的下载任务
     pub async fn create_task(
         &self,
         name: String,
         task_type: String,
         output_dir: PathBuf,
+        params: Option<DownloadParams>,
     ) -> String {
         let task_id = uuid::Uuid::new_v4().to_string();
         let now = std::time::SystemTime::now()
@@ -92,6 +304,12 @@ impl DownloadManager {
             created_at: now,
             updated_at: now,
             output_dir,
+            params,
+            started_at: None,
+            finished_at: None,
+            total_files: 0,
+            total_bytes: 0,
+            average_speed: None,
         };
 
         let mut tasks = self.tasks.write().await;
@@ -107,18 +325,26 @@ impl DownloadManager {
     pub async fn update_progress(&self, task_id: &str, progress: DownloadProgress) {
         let mut tasks = self.tasks.write().await;
         if let Some(task) = tasks.get_mut(task_id) {
-            task.progress = progress.clone();
-            task.status = progress.status.clone();
-            task.updated_at = std::time::SystemTime::now()
+            let now = std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap()
                 .as_secs();
+            task.apply_progress(progress.clone(), now);
 
             // 发送进度更新事件
             let _ = self.app_handle.emit("download-progress", &progress);
         }
     }
 
+    /// 累计任务已传输的文件数和字节数
+    pub async fn record_transfer(&self, task_id: &str, files: u64, bytes: u64) {
+        let mut tasks = self.tasks.write().await;
+        if let Some(task) = tasks.get_mut(task_id) {
+            task.total_files += files;
+            task.total_bytes += bytes;
+        }
+    }
+
     /// 获取任务
     pub async fn get_task(&self, task_id: &str) -> Option<DownloadTask> {
         let tasks = self.tasks.read().await;
@@ -131,6 +357,29 @@ impl DownloadManager {
         tasks.values().cloned().collect()
     }
 
+    /// 将失败的任务重置为等待状态以便重新执行
+    pub async fn reset_for_retry(&self, task_id: &str) -> Result<DownloadTask, AppError> {
+        let mut tasks = self.tasks.write().await;
+        let task = tasks
+            .get_mut(task_id)
+            .ok_or_else(|| AppError::NotFound("任务不存在".to_string()))?;
+        task.reset_for_retry(
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+        )?;
+
+        let _ = self.app_handle.emit("download-progress", &task.progress);
+        Ok(task.clone())
+    }
+
+    /// 获取所有任务的汇总信息
+    pub async fn summary(&self) -> DownloadSummary {
+        let tasks = self.tasks.read().await;
+        DownloadSummary::from_tasks(tasks.values())
+    }
+
     /// 取消任务
     pub async fn cancel_task(&self, task_id: &str) -> Result<(), String> {
         // 触发取消令牌
@@ -151,6 +400,7 @@ impl DownloadManager {
 
             // 发送取消事件
             let _ = self.app_handle.emit("download-cancelled", task_id);
+            self.slot_notify.notify_waiters();
             Ok(())
         } else {
             Err("任务不存在".to_string())
@@ -169,6 +419,7 @@ impl DownloadManager {
 
         if tasks.remove(task_id).is_some() {
             let _ = self.app_handle.emit("download-deleted", task_id);
+            self.slot_notify.notify_waiters();
             Ok(())
         } else {
             Err("任务不存在".to_string())
@@ -214,7 +465,7 @@ impl DownloadManager {
 #[tauri::command]
 pub async fn get_all_download_tasks(
     manager: tauri::State<'_, Arc<DownloadManager>>,
-) -> Result<Vec<DownloadTask>, String> {
+) -> Result<Vec<DownloadTask>, AppError> {
     Ok(manager.get_all_tasks().await)
 }
 
@@ -223,17 +474,38 @@ pub async fn get_all_download_tasks(
 pub async fn get_download_task(
     task_id: String,
     manager: tauri::State<'_, Arc<DownloadManager>>,
-) -> Result<Option<DownloadTask>, String> {
+) -> Result<Option<DownloadTask>, AppError> {
     Ok(manager.get_task(&task_id).await)
 }
 
+/// 获取下载任务汇总信息
+#[tauri::command]
+pub async fn get_download_summary(
+    manager: tauri::State<'_, Arc<DownloadManager>>,
+) -> Result<DownloadSummary, AppError> {
+    Ok(manager.summary().await)
+}
+
+/// 设置同时进行的下载任务上限
+#[tauri::command]
+pub async fn set_max_concurrent_downloads(
+    n: usize,
+    manager: tauri::State<'_, Arc<DownloadManager>>,
+) -> Result<(), AppError> {
+    if n == 0 {
+        return Err(AppError::InvalidInput("并发数必须大于0".to_string()));
+    }
+    manager.set_max_concurrent(n);
+    Ok(())
+}
+
 /// 取消下载任务
 #[tauri::command]
 pub async fn cancel_download_task(
     task_id: String,
     manager: tauri::State<'_, Arc<DownloadManager>>,
-) -> Result<(), String> {
-    manager.cancel_task(&task_id).await
+) -> Result<(), AppError> {
+    Ok(manager.cancel_task(&task_id).await?)
 }
 
 /// 删除下载任务
@@ -241,14 +513,183 @@ pub async fn cancel_download_task(
 pub async fn delete_download_task(
     task_id: String,
     manager: tauri::State<'_, Arc<DownloadManager>>,
-) -> Result<(), String> {
-    manager.delete_task(&task_id).await
+) -> Result<(), AppError> {
+    Ok(manager.delete_task(&task_id).await?)
 }
 
 /// 清理已完成的任务
 #[tauri::command]
 pub async fn clear_completed_tasks(
     manager: tauri::State<'_, Arc<DownloadManager>>,
-) -> Result<usize, String> {
+) -> Result<usize, AppError> {
     Ok(manager.clear_completed().await)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: &str, status: DownloadStatus, speed: f64, eta: Option<u64>) -> DownloadTask {
+        DownloadTask {
+            id: id.to_string(),
+            name: id.to_string(),
+            task_type: "template".to_string(),
+            status: status.clone(),
+            progress: DownloadProgress {
+                task_id: id.to_string(),
+                status,
+                current: 0,
+                total: 0,
+                current_file: None,
+                speed,
+                eta,
+                error: None,
+            },
+            created_at: 0,
+            updated_at: 0,
+            output_dir: PathBuf::new(),
+            params: None,
+            started_at: None,
+            finished_at: None,
+            total_files: 0,
+            total_bytes: 0,
+            average_speed: None,
+        }
+    }
+
+    #[test]
+    fn summary_counts_tasks_by_state() {
+        let tasks = [
+            task("a", DownloadStatus::Downloading, 1024.0, Some(30)),
+            task("b", DownloadStatus::Downloading, 512.0, Some(90)),
+            task("c", DownloadStatus::Pending, 0.0, None),
+            task("d", DownloadStatus::Completed, 2048.0, None),
+            task("e", DownloadStatus::Failed, 0.0, None),
+            task("f", DownloadStatus::Cancelled, 0.0, None),
+        ];
+
+        let summary = DownloadSummary::from_tasks(&tasks);
+        assert_eq!(summary.active, 3);
+        assert_eq!(summary.completed, 1);
+        assert_eq!(summary.failed, 1);
+        // 只统计进行中任务的速度, 剩余时间取最慢的任务
+        assert_eq!(summary.total_speed, 1536.0);
+        assert_eq!(summary.total_eta, Some(90));
+    }
+
+    #[test]
+    fn third_task_waits_for_a_free_slot() {
+        let mut slots = SlotState { max_concurrent: 2, running: 0, queue: VecDeque::new() };
+        for id in ["a", "b", "c"] {
+            slots.queue.push_back(id.to_string());
+        }
+
+        assert!(slots.try_take("a"));
+        assert!(slots.try_take("b"));
+        assert!(!slots.try_take("c"));
+        assert_eq!(slots.queue, ["c"]);
+
+        // 有任务结束后排队的任务才能开始
+        slots.running -= 1;
+        assert!(slots.try_take("c"));
+        assert!(slots.queue.is_empty());
+    }
+
+    #[test]
+    fn queued_tasks_start_in_order() {
+        let mut slots = SlotState { max_concurrent: 1, running: 0, queue: VecDeque::new() };
+        slots.queue.push_back("a".to_string());
+        slots.queue.push_back("b".to_string());
+
+        assert!(!slots.try_take("b"));
+        assert!(slots.try_take("a"));
+    }
+
+    #[test]
+    fn failed_task_can_be_retried() {
+        let mut failed = task("a", DownloadStatus::Failed, 0.0, None);
+        failed.params = Some(DownloadParams::Version { version_id: "1.21".to_string() });
+        failed.progress.error = Some("timeout".to_string());
+        failed.started_at = Some(10);
+        failed.total_bytes = 4096;
+
+        failed.reset_for_retry(20).unwrap();
+        assert_eq!(failed.status, DownloadStatus::Pending);
+        assert_eq!(failed.progress.error, None);
+        assert_eq!(failed.started_at, None);
+        assert_eq!(failed.total_bytes, 0);
+
+        let mut progress = failed.progress.clone();
+        progress.status = DownloadStatus::Downloading;
+        failed.apply_progress(progress, 30);
+        assert_eq!(failed.status, DownloadStatus::Downloading);
+        assert_eq!(failed.started_at, Some(30));
+    }
+
+    #[test]
+    fn only_failed_tasks_with_params_are_retryable() {
+        let mut running = task("a", DownloadStatus::Downloading, 0.0, None);
+        running.params = Some(DownloadParams::Version { version_id: "1.21".to_string() });
+        assert!(matches!(running.reset_for_retry(0), Err(AppError::InvalidInput(_))));
+        assert_eq!(running.status, DownloadStatus::Downloading);
+
+        let mut without_params = task("b", DownloadStatus::Failed, 0.0, None);
+        assert!(without_params.reset_for_retry(0).is_err());
+        assert_eq!(without_params.status, DownloadStatus::Failed);
+    }
+
+    #[test]
+    fn completion_records_finish_details() {
+        let mut task = task("a", DownloadStatus::Pending, 0.0, None);
+        let mut progress = task.progress.clone();
+        progress.status = DownloadStatus::Downloading;
+        task.apply_progress(progress.clone(), 100);
+        task.total_files = 1200;
+        task.total_bytes = 1_200_000;
+
+        progress.status = DownloadStatus::Completed;
+        task.apply_progress(progress, 300);
+        assert_eq!(task.finished_at, Some(300));
+        assert_eq!(task.average_speed, Some(6000.0));
+    }
+
+    #[test]
+    fn task_round_trips_through_json() {
+        let mut original = task("a", DownloadStatus::Completed, 0.0, None);
+        original.params = Some(DownloadParams::Sounds { concurrent_downloads: 8 });
+        original.started_at = Some(100);
+        original.finished_at = Some(300);
+        original.total_files = 1200;
+        original.total_bytes = 1_200_000;
+        original.average_speed = Some(6000.0);
+
+        let json = serde_json::to_string(&original).unwrap();
+        let restored: DownloadTask = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.status, DownloadStatus::Completed);
+        assert_eq!(restored.finished_at, Some(300));
+        assert_eq!(restored.total_files, 1200);
+        assert_eq!(restored.total_bytes, 1_200_000);
+        assert_eq!(restored.average_speed, Some(6000.0));
+        assert!(matches!(restored.params, Some(DownloadParams::Sounds { concurrent_downloads: 8 })));
+    }
+
+    #[test]
+    fn tasks_without_finish_details_still_load() {
+        let mut value = serde_json::to_value(task("a", DownloadStatus::Completed, 0.0, None)).unwrap();
+        let object = value.as_object_mut().unwrap();
+        for key in ["params", "started_at", "finished_at", "total_files", "total_bytes", "average_speed"] {
+            object.remove(key);
+        }
+
+        let restored: DownloadTask = serde_json::from_value(value).unwrap();
+        assert_eq!(restored.finished_at, None);
+        assert_eq!(restored.total_bytes, 0);
+    }
+
+    #[test]
+    fn empty_summary_has_no_eta() {
+        let summary = DownloadSummary::from_tasks(&[]);
+        assert_eq!(summary.active, 0);
+        assert_eq!(summary.total_eta, None);
+    }
+}
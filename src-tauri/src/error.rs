@@ -0,0 +1,161 @@
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::fmt;
+
+/// 命令返回给前端的错误, 序列化为 `{ code, message }`
+///
+/// `message` 保持与原先字符串错误相同的内容, `code` 供前端可靠地区分错误类型
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppError {
+    /// 当前没有加载材质包
+    NoPackLoaded,
+    /// 文件或资源不存在
+    NotFound(String),
+    /// 没有访问权限
+    PermissionDenied(String),
+    /// 目标已存在
+    AlreadyExists(String),
+    /// 参数不合法
+    InvalidInput(String),
+    /// 解析 JSON 等内容失败
+    Parse(String),
+    /// 网络请求失败
+    Network(String),
+    /// 不是 ZIP 格式的压缩包(如 rar、7z)
+    UnsupportedArchive(String),
+    /// 带密码的压缩包
+    EncryptedArchive(String),
+    /// 压缩包损坏或不完整
+    CorruptArchive(String),
+    /// 其他 IO 错误
+    Io(String),
+    /// 未分类的错误
+    Other(String),
+}
+
+impl AppError {
+    /// 稳定的错误代码
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::NoPackLoaded => "no_pack_loaded",
+            AppError::NotFound(_) => "not_found",
+            AppError::PermissionDenied(_) => "permission_denied",
+            AppError::AlreadyExists(_) => "already_exists",
+            AppError::InvalidInput(_) => "invalid_input",
+            AppError::Parse(_) => "parse_error",
+            AppError::Network(_) => "network_error",
+            AppError::UnsupportedArchive(_) => "unsupported_archive",
+            AppError::EncryptedArchive(_) => "encrypted_archive",
+            AppError::CorruptArchive(_) => "corrupt_archive",
+            AppError::Io(_) => "io_error",
+            AppError::Other(_) => "other",
+        }
+    }
+
+    /// 错误信息
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::NoPackLoaded => "No pack loaded",
+            AppError::NotFound(msg)
+            | AppError::PermissionDenied(msg)
+            | AppError::AlreadyExists(msg)
+            | AppError::InvalidInput(msg)
+            | AppError::Parse(msg)
+            | AppError::Network(msg)
+            | AppError::UnsupportedArchive(msg)
+            | AppError::EncryptedArchive(msg)
+            | AppError::CorruptArchive(msg)
+            | AppError::Io(msg)
+            | AppError::Other(msg) => msg,
+        }
+    }
+
+    /// 带上下文的IO错误, 按错误类型归类
+    pub fn io(context: &str, e: std::io::Error) -> Self {
+        Self::from_io_kind(e.kind(), format!("{}: {}", context, e))
+    }
+
+    fn from_io_kind(kind: std::io::ErrorKind, message: String) -> Self {
+        match kind {
+            std::io::ErrorKind::NotFound => AppError::NotFound(message),
+            std::io::ErrorKind::PermissionDenied => AppError::PermissionDenied(message),
+            std::io::ErrorKind::AlreadyExists => AppError::AlreadyExists(message),
+            _ => AppError::Io(message),
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", self.message())?;
+        state.end()
+    }
+}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::Other(message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::Other(message.to_string())
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> Self {
+        Self::from_io_kind(e.kind(), e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(e: serde_json::Error) -> Self {
+        AppError::Parse(e.to_string())
+    }
+}
+
+impl From<reqwest::Error> for AppError {
+    fn from(e: reqwest::Error) -> Self {
+        AppError::Network(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn no_pack_loaded_serializes_code_and_message() {
+        let value = serde_json::to_value(AppError::NoPackLoaded).unwrap();
+        assert_eq!(value, json!({ "code": "no_pack_loaded", "message": "No pack loaded" }));
+    }
+
+    #[test]
+    fn not_found_keeps_message() {
+        let value = serde_json::to_value(AppError::NotFound("文件不存在: a.png".to_string())).unwrap();
+        assert_eq!(value, json!({ "code": "not_found", "message": "文件不存在: a.png" }));
+    }
+
+    #[test]
+    fn io_errors_are_classified_by_kind() {
+        let denied = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+        let value = serde_json::to_value(AppError::io("写入失败", denied)).unwrap();
+        assert_eq!(value, json!({ "code": "permission_denied", "message": "写入失败: denied" }));
+
+        let other = std::io::Error::other("disk full");
+        let value = serde_json::to_value(AppError::from(other)).unwrap();
+        assert_eq!(value, json!({ "code": "io_error", "message": "disk full" }));
+    }
+}
@@ -0,0 +1,182 @@
+use crate::commands::{resolve_pack_path, AppState};
+use crate::error::AppError;
+use crate::text_diff::TextDiff;
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+use std::path::Path;
+use tauri::State;
+
+/// 可比较的最大文件大小
+const MAX_COMPARE_BYTES: usize = 2 * 1024 * 1024;
+
+/// 历史记录引用的前缀, 格式为 history:<文件>:<下标>
+const HISTORY_PREFIX: &str = "history:";
+
+/// 比较一侧的图片信息, 无法解码时尺寸为 None
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageSide {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub size: usize,
+}
+
+/// 比较结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum FileComparison {
+    Text {
+        diff: TextDiff,
+        identical: bool,
+    },
+    /// 任一侧为图片时返回, 前端据此切换到图片对比视图
+    Image {
+        left: ImageSide,
+        right: ImageSide,
+        binary_differs: bool,
+    },
+    /// 其他二进制文件(如音频)
+    Binary {
+        left_size: usize,
+        right_size: usize,
+        binary_differs: bool,
+    },
+}
+
+/// 读取到的一侧内容
+struct CompareSource {
+    /// 对应的材质包文件路径, 用于按扩展名判断类型
+    file_path: String,
+    bytes: Vec<u8>,
+}
+
+fn check_size(reference: &str, size: usize) -> Result<(), AppError> {
+    if size > MAX_COMPARE_BYTES {
+        return Err(AppError::InvalidInput(format!(
+            "{} 过大 ({:.1} MB), 最多比较 {} MB 的文件",
+            reference,
+            size as f64 / 1024.0 / 1024.0,
+            MAX_COMPARE_BYTES / 1024 / 1024
+        )));
+    }
+    Ok(())
+}
+
+/// 读取材质包文件或 history:<文件>:<下标> 引用的历史记录
+fn load_source(base_path: &Path, reference: &str) -> Result<CompareSource, AppError> {
+    let Some(rest) = reference.strip_prefix(HISTORY_PREFIX) else {
+        let full_path = resolve_pack_path(base_path, reference)?;
+        let size = std::fs::metadata(&full_path)
+            .map_err(|e| AppError::io("读取文件失败", e))?
+            .len();
+        check_size(reference, usize::try_from(size).unwrap_or(usize::MAX))?;
+        let bytes = std::fs::read(&full_path).map_err(|e| AppError::io("读取文件失败", e))?;
+        return Ok(CompareSource {
+            file_path: reference.to_string(),
+            bytes,
+        });
+    };
+
+    let (file_path, index) = rest
+        .rsplit_once(':')
+        .and_then(|(file, index)| Some((file, index.parse::<usize>().ok()?)))
+        .ok_or_else(|| {
+            AppError::InvalidInput(format!("历史记录引用格式应为 history:<文件>:<下标>: {}", reference))
+        })?;
+    let entry = crate::history_manager::read_history_entries(base_path, file_path)?
+        .into_iter()
+        .nth(index)
+        .ok_or_else(|| AppError::NotFound(format!("未找到历史记录: {}", reference)))?;
+
+    let bytes = if entry.file_type == "image" || entry.file_type == "binary" {
+        use base64::Engine;
+        let data = entry
+            .content
+            .split_once(',')
+            .map(|(_, data)| data)
+            .unwrap_or(&entry.content);
+        base64::engine::general_purpose::STANDARD
+            .decode(data)
+            .map_err(|e| AppError::Parse(format!("解析历史记录失败: {}", e)))?
+    } else {
+        entry.content.into_bytes()
+    };
+    check_size(reference, bytes.len())?;
+    Ok(CompareSource {
+        file_path: file_path.to_string(),
+        bytes,
+    })
+}
+
+fn image_side(source: &CompareSource) -> ImageSide {
+    // TGA 没有文件头标识, 优先按扩展名确定格式
+    let dimensions = match image::ImageFormat::from_path(&source.file_path) {
+        Ok(format) => image::ImageReader::with_format(Cursor::new(&source.bytes), format)
+            .into_dimensions()
+            .ok(),
+        Err(_) => image::ImageReader::new(Cursor::new(&source.bytes))
+            .with_guessed_format()
+            .ok()
+            .and_then(|reader| reader.into_dimensions().ok()),
+    };
+    ImageSide {
+        width: dimensions.map(|(width, _)| width),
+        height: dimensions.map(|(_, height)| height),
+        size: source.bytes.len(),
+    }
+}
+
+/// 比较两份内容: 图片返回尺寸, 文本返回逐行差异, 其他二进制只判断是否相同
+fn compare_sources(left: &CompareSource, right: &CompareSource) -> FileComparison {
+    let binary_differs = left.bytes != right.bytes;
+    if crate::image_handler::is_image_path(Path::new(&left.file_path))
+        || crate::image_handler::is_image_path(Path::new(&right.file_path))
+    {
+        return FileComparison::Image {
+            left: image_side(left),
+            right: image_side(right),
+            binary_differs,
+        };
+    }
+
+    match (
+        crate::text_encoding::decode_text(&left.bytes),
+        crate::text_encoding::decode_text(&right.bytes),
+    ) {
+        (Ok(old), Ok(new)) => FileComparison::Text {
+            diff: crate::text_diff::diff_lines(
+                &old.content,
+                &new.content,
+                crate::text_diff::DEFAULT_CONTEXT_LINES,
+            ),
+            identical: !binary_differs,
+        },
+        _ => FileComparison::Binary {
+            left_size: left.bytes.len(),
+            right_size: right.bytes.len(),
+            binary_differs,
+        },
+    }
+}
+
+/// 比较两个文件, 路径可以是材质包内的相对路径或 history:<文件>:<下标>
+/// (下标同 load_file_history 返回列表)
+#[tauri::command]
+pub async fn compare_files(
+    left_path: String,
+    right_path: String,
+    pack_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<FileComparison, AppError> {
+    let base_path = state.pack_path_for(pack_id.as_deref())?;
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    rayon::spawn(move || {
+        let result = load_source(&base_path, &left_path).and_then(|left| {
+            let right = load_source(&base_path, &right_path)?;
+            Ok(compare_sources(&left, &right))
+        });
+        let _ = tx.send(result);
+    });
+
+    rx.await.map_err(|e| AppError::Other(format!("Channel error: {}", e)))?
+}
@@ -0,0 +1,656 @@
+use base64::{engine::general_purpose, Engine as _};
+use font_kit::canvas::{Canvas, Format, RasterizationOptions};
+use font_kit::hinting::HintingOptions;
+use image::{imageops, ImageFormat, Rgba, RgbaImage};
+use pathfinder_geometry::transform2d::Transform2F;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tauri::State;
+use crate::error::AppError;
+use crate::validation::{sort_issues, Severity, ValidationIssue};
+use walkdir::WalkDir;
+
+/// reference 提供器的最大嵌套层数
+const MAX_REFERENCE_DEPTH: usize = 8;
+
+/// 位图字体提供器
+#[derive(Debug, Clone)]
+pub struct BitmapProvider {
+    pub file: String,
+    pub height: i32,
+    pub ascent: i32,
+    pub chars: Vec<Vec<char>>,
+}
+
+/// TrueType 字体提供器
+#[derive(Debug, Clone)]
+pub struct TtfProvider {
+    pub file: String,
+    pub size: f32,
+    pub shift: (f32, f32),
+    pub skip: HashSet<char>,
+}
+
+/// 字体定义中的提供器
+#[derive(Debug, Clone)]
+pub enum FontProvider {
+    Bitmap(BitmapProvider),
+    Space(HashMap<char, f32>),
+    Ttf(TtfProvider),
+    Reference(String),
+    #[allow(dead_code)]
+    Unsupported(String),
+}
+
+/// 将 "命名空间:路径" 形式的资源ID解析为材质包内的文件路径
+pub fn resolve_resource_path(base_path: &Path, id: &str, folder: &str) -> PathBuf {
+    let (namespace, path) = id.split_once(':').unwrap_or(("minecraft", id));
+    base_path
+        .join("assets")
+        .join(namespace)
+        .join(folder)
+        .join(path)
+}
+
+/// 解析字体JSON中的 providers 数组
+pub fn parse_font_providers(font: &Value) -> Result<Vec<FontProvider>, String> {
+    let providers = font
+        .get("providers")
+        .and_then(|v| v.as_array())
+        .ok_or("字体文件缺少 providers 数组")?;
+
+    providers
+        .iter()
+        .enumerate()
+        .map(|(index, provider)| {
+            let provider_type = provider
+                .get("type")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| format!("第 {} 个提供器缺少 type", index + 1))?;
+            let provider_type = provider_type.strip_prefix("minecraft:").unwrap_or(provider_type);
+
+            let file = || {
+                provider
+                    .get("file")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string())
+                    .ok_or_else(|| format!("第 {} 个提供器缺少 file", index + 1))
+            };
+
+            Ok(match provider_type {
+                "bitmap" => FontProvider::Bitmap(BitmapProvider {
+                    file: file()?,
+                    height: provider.get("height").and_then(|v| v.as_i64()).unwrap_or(8) as i32,
+                    ascent: provider
+                        .get("ascent")
+                        .and_then(|v| v.as_i64())
+                        .ok_or_else(|| format!("第 {} 个提供器缺少 ascent", index + 1))?
+                        as i32,
+                    chars: provider
+                        .get("chars")
+                        .and_then(|v| v.as_array())
+                        .ok_or_else(|| format!("第 {} 个提供器缺少 chars", index + 1))?
+                        .iter()
+                        .map(|row| row.as_str().unwrap_or_default().chars().collect())
+                        .collect(),
+                }),
+                "space" => FontProvider::Space(
+                    provider
+                        .get("advances")
+                        .and_then(|v| v.as_object())
+                        .map(|advances| {
+                            advances
+                                .iter()
+                                .filter_map(|(key, value)| {
+                                    Some((key.chars().next()?, value.as_f64()? as f32))
+                                })
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                ),
+                "ttf" => {
+                    let shift = provider
+                        .get("shift")
+                        .and_then(|v| v.as_array())
+                        .map(|s| {
+                            (
+                                s.first().and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+                                s.get(1).and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+                            )
+                        })
+                        .unwrap_or((0.0, 0.0));
+                    let skip = match provider.get("skip") {
+                        Some(Value::String(s)) => s.chars().collect(),
+                        Some(Value::Array(rows)) => rows
+                            .iter()
+                            .filter_map(|row| row.as_str())
+                            .flat_map(|row| row.chars())
+                            .collect(),
+                        _ => HashSet::new(),
+                    };
+                    FontProvider::Ttf(TtfProvider {
+                        file: file()?,
+                        size: provider.get("size").and_then(|v| v.as_f64()).unwrap_or(11.0) as f32,
+                        shift,
+                        skip,
+                    })
+                }
+                "reference" => FontProvider::Reference(
+                    provider
+                        .get("id")
+                        .and_then(|v| v.as_str())
+                        .ok_or_else(|| format!("第 {} 个提供器缺少 id", index + 1))?
+                        .to_string(),
+                ),
+                other => FontProvider::Unsupported(other.to_string()),
+            })
+        })
+        .collect()
+}
+
+/// 读取字体文件并展开其中的 reference 提供器
+pub fn load_font_providers(base_path: &Path, font_file: &Path) -> Result<Vec<FontProvider>, String> {
+    fn load(base_path: &Path, font_file: &Path, depth: usize, out: &mut Vec<FontProvider>) -> Result<(), String> {
+        if depth > MAX_REFERENCE_DEPTH {
+            return Err("字体 reference 嵌套过深, 可能存在循环引用".to_string());
+        }
+
+        let content = std::fs::read_to_string(font_file)
+            .map_err(|e| format!("读取字体文件失败 {}: {}", font_file.display(), e))?;
+        let font: Value = serde_json::from_str(&content)
+            .map_err(|e| format!("解析字体文件失败 {}: {}", font_file.display(), e))?;
+
+        for provider in parse_font_providers(&font)? {
+            match provider {
+                FontProvider::Reference(id) => {
+                    let referenced =
+                        resolve_resource_path(base_path, &format!("{}.json", id), "font");
+                    // 引用原版字体时材质包中可能不存在, 跳过即可
+                    if referenced.exists() {
+                        load(base_path, &referenced, depth + 1, out)?;
+                    }
+                }
+                other => out.push(other),
+            }
+        }
+
+        Ok(())
+    }
+
+    let mut providers = Vec::new();
+    load(base_path, font_file, 0, &mut providers)?;
+    Ok(providers)
+}
+
+/// 检查引用的文件是否存在; minecraft 命名空间可能引用原版资源, 只给出警告
+fn check_font_reference(
+    issues: &mut Vec<ValidationIssue>,
+    relative: &str,
+    id: &str,
+    path: &Path,
+    kind: &str,
+) {
+    if path.is_file() {
+        return;
+    }
+    let (severity, suffix) = if id.contains(':') && !id.starts_with("minecraft:") {
+        (Severity::Error, "")
+    } else {
+        (Severity::Warning, " (若为原版资源可忽略)")
+    };
+    issues.push(ValidationIssue::new(
+        severity,
+        "font",
+        relative,
+        format!("{} 不存在: {}{}", kind, id, suffix),
+    ));
+}
+
+/// 检查位图提供器的 chars 网格与纹理尺寸是否匹配
+fn check_bitmap_grid(issues: &mut Vec<ValidationIssue>, relative: &str, provider: &BitmapProvider, texture: &Path) {
+    if provider.ascent > provider.height {
+        issues.push(ValidationIssue::new(
+            Severity::Error,
+            "font",
+            relative,
+            format!("{}: ascent {} 大于 height {}", provider.file, provider.ascent, provider.height),
+        ));
+    }
+
+    let rows = provider.chars.len() as u32;
+    let cols = provider.chars.first().map(|row| row.len()).unwrap_or(0) as u32;
+    if rows == 0 || cols == 0 {
+        issues.push(ValidationIssue::new(Severity::Error, "font", relative, format!("{}: chars 为空", provider.file)));
+        return;
+    }
+    if provider.chars.iter().any(|row| row.len() as u32 != cols) {
+        issues.push(ValidationIssue::new(
+            Severity::Error,
+            "font",
+            relative,
+            format!("{}: chars 每行的字符数必须相同", provider.file),
+        ));
+        return;
+    }
+
+    let Ok((width, height)) = image::image_dimensions(texture) else {
+        return;
+    };
+    if width % cols != 0 || height % rows != 0 {
+        issues.push(ValidationIssue::new(
+            Severity::Error,
+            "font",
+            relative,
+            format!(
+                "{}: 纹理尺寸 {}x{} 不能按 chars 网格 {}x{} 均分",
+                provider.file, width, height, cols, rows
+            ),
+        ));
+    }
+}
+
+/// 校验材质包中的字体定义: 引用的纹理/字体文件是否存在, 位图网格是否与纹理匹配
+pub fn validate_fonts_in_pack(base_path: &Path) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+
+    let font_files = std::fs::read_dir(base_path.join("assets"))
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .flat_map(|namespace| WalkDir::new(namespace.path().join("font")).into_iter().filter_map(|e| e.ok()))
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"));
+
+    for entry in font_files {
+        let relative = entry
+            .path()
+            .strip_prefix(base_path)
+            .unwrap_or(entry.path())
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        let providers = std::fs::read_to_string(entry.path())
+            .map_err(|e| format!("读取失败: {}", e))
+            .and_then(|content| serde_json::from_str::<Value>(&content).map_err(|e| format!("JSON 解析失败: {}", e)))
+            .and_then(|font| parse_font_providers(&font));
+        let providers = match providers {
+            Ok(providers) => providers,
+            Err(e) => {
+                issues.push(ValidationIssue::new(Severity::Error, "font", relative, e));
+                continue;
+            }
+        };
+
+        for provider in providers {
+            match provider {
+                FontProvider::Bitmap(bitmap) => {
+                    let texture = resolve_resource_path(base_path, &bitmap.file, "textures");
+                    check_font_reference(&mut issues, &relative, &bitmap.file, &texture, "位图纹理");
+                    check_bitmap_grid(&mut issues, &relative, &bitmap, &texture);
+                }
+                FontProvider::Ttf(ttf) => {
+                    let file = resolve_resource_path(base_path, &ttf.file, "font");
+                    check_font_reference(&mut issues, &relative, &ttf.file, &file, "TrueType 字体");
+                }
+                FontProvider::Reference(id) => {
+                    let file = resolve_resource_path(base_path, &format!("{}.json", id), "font");
+                    check_font_reference(&mut issues, &relative, &id, &file, "引用的字体");
+                }
+                FontProvider::Space(_) | FontProvider::Unsupported(_) => {}
+            }
+        }
+    }
+
+    sort_issues(&mut issues);
+    issues
+}
+
+/// 校验材质包中的字体定义
+#[tauri::command]
+pub async fn validate_fonts(
+    state: State<'_, crate::commands::AppState>,
+) -> Result<Vec<ValidationIssue>, AppError> {
+    let base_path = state.pack_path()?;
+
+    Ok(validate_fonts_in_pack(&base_path))
+}
+
+/// 字体预览结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FontPreview {
+    pub image: String,
+    pub width: u32,
+    pub height: u32,
+    pub missing_chars: Vec<String>,
+}
+
+/// 已排版的字形, 坐标以基线为原点
+struct PlacedGlyph {
+    x: i64,
+    top: i64,
+    image: RgbaImage,
+}
+
+/// 单个字形的排版结果
+struct GlyphLayout {
+    advance: f32,
+    placed: Option<(f32, i64, RgbaImage)>,
+}
+
+/// 从位图提供器中取出字形
+fn layout_bitmap_glyph(provider: &BitmapProvider, texture: &RgbaImage, c: char, scale: f32) -> Option<GlyphLayout> {
+    let row = provider.chars.iter().position(|row| row.contains(&c))?;
+    let col = provider.chars[row].iter().position(|&ch| ch == c)?;
+
+    let rows = provider.chars.len() as u32;
+    let cols = provider.chars.iter().map(|row| row.len()).max().unwrap_or(1).max(1) as u32;
+    let glyph_width = texture.width() / cols;
+    let glyph_height = texture.height() / rows.max(1);
+    if glyph_width == 0 || glyph_height == 0 {
+        return None;
+    }
+
+    let glyph = imageops::crop_imm(
+        texture,
+        col as u32 * glyph_width,
+        row as u32 * glyph_height,
+        glyph_width,
+        glyph_height,
+    )
+    .to_image();
+
+    // 与游戏一致: 字形宽度为最右侧非透明像素所在列 + 1
+    let trimmed_width = (0..glyph_width)
+        .rev()
+        .find(|&x| (0..glyph_height).any(|y| glyph.get_pixel(x, y)[3] > 0))
+        .map(|x| x + 1)
+        .unwrap_or(0);
+
+    let unit_scale = provider.height as f32 / glyph_height as f32;
+    let advance = (trimmed_width as f32 * unit_scale + 1.0) * scale;
+
+    if trimmed_width == 0 {
+        return Some(GlyphLayout { advance, placed: None });
+    }
+
+    let width = ((trimmed_width as f32 * unit_scale * scale).round() as u32).max(1);
+    let height = ((provider.height as f32 * scale).round() as u32).max(1);
+    let trimmed = imageops::crop_imm(&glyph, 0, 0, trimmed_width, glyph_height).to_image();
+    let resized = imageops::resize(&trimmed, width, height, imageops::FilterType::Nearest);
+    let top = (-(provider.ascent as f32) * scale).round() as i64;
+
+    Some(GlyphLayout {
+        advance,
+        placed: Some((0.0, top, resized)),
+    })
+}
+
+/// 使用 font-kit 渲染 TrueType 字形
+fn layout_ttf_glyph(provider: &TtfProvider, font: &font_kit::font::Font, c: char, scale: f32) -> Option<GlyphLayout> {
+    if provider.skip.contains(&c) {
+        return None;
+    }
+
+    let glyph_id = font.glyph_for_char(c)?;
+    let point_size = provider.size * scale;
+    let units_per_em = font.metrics().units_per_em as f32;
+    let advance = font.advance(glyph_id).ok()?.x() * point_size / units_per_em;
+
+    let bounds = font
+        .raster_bounds(
+            glyph_id,
+            point_size,
+            Transform2F::default(),
+            HintingOptions::None,
+            RasterizationOptions::GrayscaleAa,
+        )
+        .ok()?;
+
+    if bounds.width() <= 0 || bounds.height() <= 0 {
+        return Some(GlyphLayout { advance, placed: None });
+    }
+
+    let mut canvas = Canvas::new(bounds.size(), Format::A8);
+    font.rasterize_glyph(
+        &mut canvas,
+        glyph_id,
+        point_size,
+        Transform2F::from_translation(-bounds.origin().to_f32()),
+        HintingOptions::None,
+        RasterizationOptions::GrayscaleAa,
+    )
+    .ok()?;
+
+    let width = bounds.width() as u32;
+    let height = bounds.height() as u32;
+    let image = RgbaImage::from_fn(width, height, |x, y| {
+        let coverage = canvas.pixels[y as usize * canvas.stride + x as usize];
+        Rgba([255, 255, 255, coverage])
+    });
+
+    let x = bounds.origin_x() as f32 + provider.shift.0 * scale;
+    let top = bounds.origin_y() as i64 + (provider.shift.1 * scale).round() as i64;
+
+    Some(GlyphLayout {
+        advance,
+        placed: Some((x, top, image)),
+    })
+}
+
+/// 缺失字形显示的方框
+fn notdef_glyph(scale: f32) -> GlyphLayout {
+    let width = ((5.0 * scale).round() as u32).max(2);
+    let height = ((8.0 * scale).round() as u32).max(2);
+    let border = (scale.round() as u32).max(1);
+
+    let image = RgbaImage::from_fn(width, height, |x, y| {
+        if x < border || y < border || x >= width - border || y >= height - border {
+            Rgba([255, 255, 255, 255])
+        } else {
+            Rgba([0, 0, 0, 0])
+        }
+    });
+
+    GlyphLayout {
+        advance: 6.0 * scale,
+        placed: Some((0.0, (-7.0 * scale).round() as i64, image)),
+    }
+}
+
+/// 按字体定义渲染一行文本
+pub fn render_font_text(
+    base_path: &Path,
+    providers: &[FontProvider],
+    text: &str,
+    scale: u32,
+) -> Result<FontPreview, String> {
+    let scale = scale.clamp(1, 16) as f32;
+
+    // 预先加载位图和 ttf 文件
+    let mut textures: HashMap<String, Option<RgbaImage>> = HashMap::new();
+    let mut fonts: HashMap<String, Option<font_kit::font::Font>> = HashMap::new();
+    for provider in providers {
+        match provider {
+            FontProvider::Bitmap(bitmap) => {
+                textures.entry(bitmap.file.clone()).or_insert_with(|| {
+                    let path = resolve_resource_path(base_path, &bitmap.file, "textures");
+                    image::open(path).ok().map(|img| img.to_rgba8())
+                });
+            }
+            FontProvider::Ttf(ttf) => {
+                fonts.entry(ttf.file.clone()).or_insert_with(|| {
+                    let path = resolve_resource_path(base_path, &ttf.file, "font");
+                    font_kit::font::Font::from_path(path, 0).ok()
+                });
+            }
+            _ => {}
+        }
+    }
+
+    let mut pen_x = 0.0f32;
+    let mut placed: Vec<PlacedGlyph> = Vec::new();
+    let mut missing_chars: Vec<String> = Vec::new();
+
+    for c in text.chars() {
+        let layout = providers.iter().find_map(|provider| match provider {
+            // 空字符表示位图中的空位
+            FontProvider::Bitmap(bitmap) if c != '\0' => textures
+                .get(&bitmap.file)
+                .and_then(|t| t.as_ref())
+                .and_then(|texture| layout_bitmap_glyph(bitmap, texture, c, scale)),
+            FontProvider::Space(advances) => advances.get(&c).map(|advance| GlyphLayout {
+                advance: advance * scale,
+                placed: None,
+            }),
+            FontProvider::Ttf(ttf) => fonts
+                .get(&ttf.file)
+                .and_then(|f| f.as_ref())
+                .and_then(|font| layout_ttf_glyph(ttf, font, c, scale)),
+            _ => None,
+        });
+
+        let layout = match layout {
+            Some(layout) => layout,
+            None => {
+                let missing = c.to_string();
+                if !missing_chars.contains(&missing) {
+                    missing_chars.push(missing);
+                }
+                notdef_glyph(scale)
+            }
+        };
+
+        if let Some((offset_x, top, image)) = layout.placed {
+            placed.push(PlacedGlyph {
+                x: (pen_x + offset_x).round() as i64,
+                top,
+                image,
+            });
+        }
+        pen_x += layout.advance;
+    }
+
+    // 画布至少容纳一行默认高度
+    let min_top = placed
+        .iter()
+        .map(|g| g.top)
+        .min()
+        .unwrap_or(0)
+        .min((-7.0 * scale) as i64);
+    let max_bottom = placed
+        .iter()
+        .map(|g| g.top + g.image.height() as i64)
+        .max()
+        .unwrap_or(0)
+        .max(scale as i64);
+    let max_right = placed
+        .iter()
+        .map(|g| g.x + g.image.width() as i64)
+        .max()
+        .unwrap_or(0)
+        .max(pen_x.ceil() as i64);
+    let min_left = placed.iter().map(|g| g.x).min().unwrap_or(0).min(0);
+
+    let width = (max_right - min_left).max(1) as u32;
+    let height = (max_bottom - min_top).max(1) as u32;
+    let mut canvas = RgbaImage::from_pixel(width, height, Rgba([0, 0, 0, 0]));
+
+    for glyph in &placed {
+        imageops::overlay(&mut canvas, &glyph.image, glyph.x - min_left, glyph.top - min_top);
+    }
+
+    let mut buffer = Vec::new();
+    canvas
+        .write_to(&mut std::io::Cursor::new(&mut buffer), ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode image: {}", e))?;
+
+    Ok(FontPreview {
+        image: general_purpose::STANDARD.encode(&buffer),
+        width,
+        height,
+        missing_chars,
+    })
+}
+
+/// 渲染字体预览
+#[tauri::command]
+pub async fn render_font_preview(
+    font_path: String,
+    sample_text: String,
+    scale: u32,
+    state: State<'_, crate::commands::AppState>,
+) -> Result<FontPreview, AppError> {
+    let base_path = state.pack_path()?;
+
+    let font_file = crate::commands::resolve_pack_path(&base_path, &font_path)?;
+
+    let providers = load_font_providers(&base_path, &font_file)?;
+    Ok(render_font_text(&base_path, &providers, &sample_text, scale)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn write_font(base: &Path, relative: &str, font: Value) {
+        let path = base.join(relative);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, font.to_string()).unwrap();
+    }
+
+    fn write_texture(base: &Path, relative: &str, width: u32, height: u32) {
+        let path = base.join(relative);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        RgbaImage::new(width, height).save(path).unwrap();
+    }
+
+    #[test]
+    fn missing_bitmap_texture_is_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        write_font(dir.path(), "assets/mypack/font/default.json", json!({
+            "providers": [
+                { "type": "bitmap", "file": "mypack:font/missing.png", "ascent": 7, "chars": ["ab"] }
+            ]
+        }));
+
+        let issues = validate_fonts_in_pack(dir.path());
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Error);
+        assert_eq!(issues[0].path, "assets/mypack/font/default.json");
+        assert!(issues[0].message.contains("mypack:font/missing.png"));
+    }
+
+    #[test]
+    fn vanilla_references_are_only_warnings() {
+        let dir = tempfile::tempdir().unwrap();
+        write_font(dir.path(), "assets/minecraft/font/default.json", json!({
+            "providers": [
+                { "type": "bitmap", "file": "minecraft:font/ascii.png", "ascent": 7, "chars": ["ab"] }
+            ]
+        }));
+
+        let issues = validate_fonts_in_pack(dir.path());
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn mismatched_grid_is_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        write_texture(dir.path(), "assets/mypack/textures/font/glyphs.png", 25, 8);
+        write_texture(dir.path(), "assets/mypack/textures/font/icons.png", 16, 16);
+        write_font(dir.path(), "assets/mypack/font/default.json", json!({
+            "providers": [
+                { "type": "bitmap", "file": "mypack:font/glyphs.png", "ascent": 7, "chars": ["ab"] },
+                { "type": "bitmap", "file": "mypack:font/icons.png", "ascent": 7, "chars": ["ab", "cd"] }
+            ]
+        }));
+
+        let issues = validate_fonts_in_pack(dir.path());
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].message.contains("mypack:font/glyphs.png"), "{}", issues[0].message);
+    }
+}
@@ -0,0 +1,397 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// 递归复制目录, 跳过 exclude(规范化路径)及其子项
+pub fn copy_dir_all_excluding(src: &Path, dst: &Path, exclude: Option<&Path>) -> Result<(), String> {
+    fs::create_dir_all(dst)
+        .map_err(|e| format!("无法创建目录: {}", e))?;
+    
+    for entry in fs::read_dir(src)
+        .map_err(|e| format!("无法读取目录: {}", e))? {
+        let entry = entry.map_err(|e| format!("无法读取条目: {}", e))?;
+        let path = entry.path();
+        let file_name = entry.file_name();
+        let dest_path = dst.join(&file_name);
+        
+        if let Some(exclude_path) = exclude {
+            if let Ok(canonical_path) = path.canonicalize() {
+                if canonical_path == exclude_path || canonical_path.starts_with(exclude_path) {
+                    continue;
+                }
+            }
+        }
+        
+        if path.is_dir() {
+            copy_dir_all_excluding(&path, &dest_path, exclude)?;
+        } else {
+            fs::copy(&path, &dest_path)
+                .map_err(|e| format!("无法复制文件 {:?}: {}", path, e))?;
+        }
+    }
+    
+    Ok(())
+}
+
+/// 编辑器自己的数据目录(历史记录、原版资源缓存、回收站), 不属于材质包内容
+pub const EDITOR_DATA_DIRS: [&str; 3] = [".history", ".little100", ".trash"];
+
+/// 是否为编辑器数据目录的名称
+pub fn is_editor_data_dir(name: &str) -> bool {
+    EDITOR_DATA_DIRS.contains(&name)
+}
+
+/// 目录中的文件数和总大小
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DirStats {
+    pub file_count: u64,
+    pub total_size: u64,
+}
+
+/// 递归统计目录中的文件数和总大小, 始终跳过 .little100 和 .trash, exclude_history 时跳过 .history
+pub fn dir_stats(path: &Path, exclude_history: bool) -> Result<DirStats, String> {
+    let mut stats = DirStats::default();
+
+    if !path.exists() {
+        return Ok(stats);
+    }
+
+    if path.is_file() {
+        stats.file_count = 1;
+        stats.total_size = path.metadata()
+            .map_err(|e| format!("获取文件大小失败: {}", e))?
+            .len();
+        return Ok(stats);
+    }
+
+    let entries = fs::read_dir(path)
+        .map_err(|e| format!("读取目录失败: {}", e))?;
+
+    for entry in entries.flatten() {
+        let entry_path = entry.path();
+        let name = entry_path.file_name().and_then(|s| s.to_str());
+
+        if name.is_some_and(|name| is_editor_data_dir(name) && (exclude_history || name != ".history")) {
+            continue;
+        }
+
+        if entry_path.is_file() {
+            stats.file_count += 1;
+            stats.total_size += entry_path.metadata()
+                .map_err(|e| format!("获取文件大小失败: {}", e))?
+                .len();
+        } else if entry_path.is_dir() {
+            let child = dir_stats(&entry_path, exclude_history)?;
+            stats.file_count += child.file_count;
+            stats.total_size += child.total_size;
+        }
+    }
+
+    Ok(stats)
+}
+
+/// 计算目录大小, 规则同 dir_stats
+pub fn calculate_dir_size(path: &Path, exclude_history: bool) -> Result<u64, String> {
+    dir_stats(path, exclude_history).map(|stats| stats.total_size)
+}
+
+/// 递归复制目录, 跳过名称在 skip_names 中的子目录和文件
+pub fn copy_dir_skipping(src: &Path, dst: &Path, skip_names: &[&str]) -> Result<(), String> {
+    fs::create_dir_all(dst)
+        .map_err(|e| format!("无法创建目录: {}", e))?;
+
+    for entry in fs::read_dir(src)
+        .map_err(|e| format!("无法读取目录: {}", e))? {
+        let entry = entry.map_err(|e| format!("无法读取条目: {}", e))?;
+        let file_name = entry.file_name();
+        if skip_names.iter().any(|name| file_name == *name) {
+            continue;
+        }
+
+        let path = entry.path();
+        let dest_path = dst.join(&file_name);
+        if path.is_dir() {
+            copy_dir_skipping(&path, &dest_path, skip_names)?;
+        } else {
+            fs::copy(&path, &dest_path)
+                .map_err(|e| format!("无法复制文件 {:?}: {}", path, e))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 递归复制目录, include 根据相对路径决定是否复制, 返回 false 的目录整体跳过; 目标中已有的同名文件会被覆盖
+pub fn copy_dir_filtered(src: &Path, dst: &Path, include: impl Fn(&Path) -> bool) -> Result<(), String> {
+    let walker = walkdir::WalkDir::new(src)
+        .into_iter()
+        .filter_entry(|e| include(e.path().strip_prefix(src).unwrap_or(e.path())));
+
+    for entry in walker {
+        let entry = entry.map_err(|e| format!("无法读取条目: {}", e))?;
+        let relative = entry.path().strip_prefix(src)
+            .map_err(|e| format!("无法计算相对路径: {}", e))?;
+        let dest_path = dst.join(relative);
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&dest_path)
+                .map_err(|e| format!("无法创建目录: {}", e))?;
+        } else {
+            fs::copy(entry.path(), &dest_path)
+                .map_err(|e| format!("无法复制文件 {:?}: {}", entry.path(), e))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 在同一目录下为 path 选择不冲突的副本名: name_copy.ext, name_copy2.ext, ...
+pub fn next_copy_path(path: &Path) -> Option<std::path::PathBuf> {
+    let parent = path.parent()?;
+    let stem = path.file_stem()?.to_string_lossy().to_string();
+    // 文件夹名中的 . 不视为扩展名
+    let extension = if path.is_dir() {
+        None
+    } else {
+        path.extension().map(|e| e.to_string_lossy().to_string())
+    };
+    let base = if extension.is_some() { stem } else { path.file_name()?.to_string_lossy().to_string() };
+
+    (1..).map(|n| {
+        let suffix = if n == 1 { "_copy".to_string() } else { format!("_copy{}", n) };
+        let name = match &extension {
+            Some(ext) => format!("{}{}.{}", base, suffix, ext),
+            None => format!("{}{}", base, suffix),
+        };
+        parent.join(name)
+    })
+    .find(|candidate| !candidate.exists())
+}
+
+/// 递归复制目录
+pub fn copy_dir_all(src: &Path, dst: &Path) -> Result<(), String> {
+    copy_dir_all_excluding(src, dst, None)
+}
+
+/// 重命名失败是否因为源和目标位于不同的磁盘或分区
+fn is_cross_device(e: &std::io::Error) -> bool {
+    // EXDEV
+    #[cfg(unix)]
+    const CROSS_DEVICE_CODE: i32 = 18;
+    // ERROR_NOT_SAME_DEVICE
+    #[cfg(windows)]
+    const CROSS_DEVICE_CODE: i32 = 17;
+
+    e.kind() == std::io::ErrorKind::CrossesDevices || e.raw_os_error() == Some(CROSS_DEVICE_CODE)
+}
+
+/// 两个路径是否指向同一个文件或目录(大小写不敏感的文件系统上仅大小写不同的路径)
+pub fn is_same_entry(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// 重命名文件或目录, 仅大小写不同时先改为临时名再改回, 以兼容大小写不敏感的文件系统
+pub fn rename_path(from: &Path, to: &Path) -> std::io::Result<()> {
+    if from != to && is_same_entry(from, to) {
+        let mut tmp_name = to.file_name().unwrap_or_default().to_os_string();
+        tmp_name.push(format!(".rename-{}", std::process::id()));
+        let tmp_path = to.with_file_name(tmp_name);
+        fs::rename(from, &tmp_path)?;
+        return fs::rename(&tmp_path, to).inspect_err(|_| {
+            let _ = fs::rename(&tmp_path, from);
+        });
+    }
+    fs::rename(from, to)
+}
+
+/// 自下而上删除 dir 内的空目录(不删除 dir 本身)
+pub fn remove_empty_dirs(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.is_dir() {
+            remove_empty_dirs(&path);
+            // 非空目录会删除失败, 忽略即可
+            let _ = fs::remove_dir(&path);
+        }
+    }
+}
+
+/// 移动文件或目录, 仅在跨磁盘无法直接重命名时退回复制后删除源, 其他错误原样返回
+pub fn move_path(from: &Path, to: &Path) -> std::io::Result<()> {
+    match rename_path(from, to) {
+        Err(e) if is_cross_device(&e) => {
+            if from.is_dir() {
+                copy_dir_all(from, to).map_err(std::io::Error::other)?;
+                fs::remove_dir_all(from)
+            } else {
+                fs::copy(from, to)?;
+                fs::remove_file(from)
+            }
+        }
+        result => result,
+    }
+}
+
+/// 按字面处理 . 和 .., 不访问文件系统
+fn normalize_lexically(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// 规范化路径中已存在的部分(解析符号链接), 不存在的尾部原样拼接
+fn canonicalize_existing(path: &Path) -> PathBuf {
+    let mut existing = path;
+    let mut tail = Vec::new();
+    loop {
+        if let Ok(canonical) = existing.canonicalize() {
+            return tail.iter().rev().fold(canonical, |acc, name| acc.join(name));
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                tail.push(name.to_os_string());
+                existing = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+/// 解析用户传入的材质包路径, 结果(含符号链接解析后)必须位于 base 之内
+///
+/// 相对路径基于 base, 同时接受 / 和 \ 作为分隔符; 绝对路径只在位于材质包内时允许
+pub fn resolve_pack_path(base: &Path, user_path: &str) -> Result<PathBuf, String> {
+    resolve_pack_path_with(base, user_path, false)
+}
+
+/// 同 resolve_pack_path, allow_external 为 true 时允许材质包外的路径(仅供只读操作使用)
+pub fn resolve_pack_path_with(base: &Path, user_path: &str, allow_external: bool) -> Result<PathBuf, String> {
+    let normalized = user_path.replace('\\', "/");
+    let path = Path::new(&normalized);
+    let joined = if path.is_absolute() { path.to_path_buf() } else { base.join(path) };
+    let lexical = normalize_lexically(&joined);
+
+    let canonical_base = base
+        .canonicalize()
+        .map_err(|e| format!("无法解析材质包路径 {}: {}", base.display(), e))?;
+    // 返回基于 base 的路径, 以便调用方继续用 strip_prefix(base) 计算相对路径
+    if let Ok(relative) = canonicalize_existing(&lexical).strip_prefix(&canonical_base) {
+        return Ok(if relative.as_os_str().is_empty() { base.to_path_buf() } else { base.join(relative) });
+    }
+    if allow_external {
+        return Ok(lexical);
+    }
+    Err(format!("路径超出材质包目录: {}", user_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn move_path_moves_directories_with_contents() {
+        let dir = tempfile::tempdir().unwrap();
+        let from = dir.path().join("a");
+        fs::create_dir_all(from.join("nested")).unwrap();
+        fs::write(from.join("nested/x.png"), b"x").unwrap();
+
+        let to = dir.path().join("b");
+        move_path(&from, &to).unwrap();
+
+        assert!(!from.exists());
+        assert_eq!(fs::read(to.join("nested/x.png")).unwrap(), b"x");
+    }
+
+    #[test]
+    fn move_path_does_not_fall_back_on_other_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let from = dir.path().join("a");
+        let to = dir.path().join("b");
+        fs::create_dir_all(&from).unwrap();
+        fs::create_dir_all(&to).unwrap();
+        fs::write(from.join("x.png"), b"x").unwrap();
+        fs::write(to.join("y.png"), b"y").unwrap();
+
+        // 重命名到非空目录会失败, 不能退回为合并复制并删除源
+        assert!(move_path(&from, &to).is_err());
+        assert!(from.join("x.png").exists());
+        assert!(!to.join("x.png").exists());
+    }
+
+    #[test]
+    fn cross_device_errors_are_detected() {
+        #[cfg(unix)]
+        let code = 18;
+        #[cfg(windows)]
+        let code = 17;
+        assert!(is_cross_device(&std::io::Error::from_raw_os_error(code)));
+        assert!(!is_cross_device(&std::io::Error::from(std::io::ErrorKind::PermissionDenied)));
+        assert!(!is_cross_device(&std::io::Error::from(std::io::ErrorKind::AlreadyExists)));
+    }
+
+    #[test]
+    fn resolve_rejects_parent_escapes() {
+        let dir = tempfile::tempdir().unwrap();
+        let pack = dir.path().join("pack");
+        fs::create_dir_all(pack.join("assets")).unwrap();
+
+        assert!(resolve_pack_path(&pack, "../outside.txt").is_err());
+        assert!(resolve_pack_path(&pack, "assets/../../outside.txt").is_err());
+        assert_eq!(resolve_pack_path(&pack, "assets/../pack.mcmeta").unwrap(), pack.join("pack.mcmeta"));
+        assert!(resolve_pack_path_with(&pack, "../outside.txt", true).is_ok());
+    }
+
+    #[test]
+    fn resolve_accepts_mixed_separators() {
+        let dir = tempfile::tempdir().unwrap();
+        let pack = dir.path().join("pack");
+        fs::create_dir_all(pack.join("assets/minecraft")).unwrap();
+
+        assert_eq!(
+            resolve_pack_path(&pack, "assets\\minecraft/textures\\block/stone.png").unwrap(),
+            pack.join("assets/minecraft/textures/block/stone.png")
+        );
+        assert!(resolve_pack_path(&pack, "assets\\..\\..\\outside.txt").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn resolve_rejects_symlinks_leaving_the_pack() {
+        let dir = tempfile::tempdir().unwrap();
+        let pack = dir.path().join("pack");
+        let outside = dir.path().join("outside");
+        fs::create_dir_all(pack.join("assets")).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        fs::write(outside.join("secret.txt"), b"secret").unwrap();
+        std::os::unix::fs::symlink(&outside, pack.join("link")).unwrap();
+        std::os::unix::fs::symlink(pack.join("assets"), pack.join("inner")).unwrap();
+
+        assert!(resolve_pack_path(&pack, "link/secret.txt").is_err());
+        assert!(resolve_pack_path(&pack, "link/new.txt").is_err());
+        assert!(resolve_pack_path(&pack, "inner/new.txt").is_ok());
+    }
+
+    #[test]
+    fn dir_stats_skips_editor_data_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["assets", ".history", ".little100", ".trash"] {
+            fs::create_dir_all(dir.path().join(name)).unwrap();
+            fs::write(dir.path().join(name).join("a.json"), b"{}").unwrap();
+        }
+
+        assert_eq!(dir_stats(dir.path(), true).unwrap().file_count, 1);
+        assert_eq!(dir_stats(dir.path(), false).unwrap().file_count, 2);
+    }
+}
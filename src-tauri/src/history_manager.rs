@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use tauri::command;
+use crate::error::AppError;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct HistoryEntry {
@@ -31,13 +32,21 @@ fn get_history_dir(pack_dir: &Path) -> PathBuf {
     pack_dir.join(".history")
 }
 
-// 获取文件的历史记录目录
+// 获取文件的历史记录目录, 只保留普通路径组件, 避免 .. 或绝对路径指向 .history 之外
 fn get_file_history_dir(pack_dir: &Path, file_path: &str) -> PathBuf {
-    let history_dir = get_history_dir(pack_dir);
-    let file_history_path = history_dir.join(file_path);
-    file_history_path
+    let relative: PathBuf = Path::new(&file_path.replace('\\', "/"))
+        .components()
+        .filter(|c| matches!(c, std::path::Component::Normal(_)))
+        .collect();
+    get_history_dir(pack_dir).join(relative)
 }
 
+/// 每个文件默认保留的历史记录数量
+pub const DEFAULT_MAX_HISTORY: u32 = 30;
+
+/// 图片等二进制文件体积较大, 单独限制保留数量
+pub const DEFAULT_MAX_BINARY_HISTORY: u32 = 10;
+
 // 保存文件历史记录
 #[command]
 pub async fn save_file_history(
@@ -46,34 +55,45 @@ pub async fn save_file_history(
     content: String,
     file_type: String,
     max_count: u32,
-) -> Result<String, String> {
-    let pack_path = Path::new(&pack_dir);
-    let file_history_dir = get_file_history_dir(pack_path, &file_path);
+) -> Result<String, AppError> {
+    save_history_entry(Path::new(&pack_dir), &file_path, content, file_type, max_count)?;
+    Ok("历史记录保存成功".to_string())
+}
+
+/// 写入一条历史记录, 供后端在修改文件前调用
+pub fn save_history_entry(
+    pack_path: &Path,
+    file_path: &str,
+    content: String,
+    file_type: String,
+    max_count: u32,
+) -> Result<(), String> {
+    let file_history_dir = get_file_history_dir(pack_path, file_path);
     
     // 创建历史记录目录
     fs::create_dir_all(&file_history_dir)
         .map_err(|e| format!("创建历史记录目录失败: {}", e))?;
     
     // 获取现有历史记录数量
-    let entries = fs::read_dir(&file_history_dir)
-        .map_err(|e| format!("读取历史记录目录失败: {}", e))?;
-    
-    let mut count = entries.count() as u32;
-    
-    // 如果超过限制删除最旧的记录
+    let files = history_entry_files(&file_history_dir);
+    let mut count = files.len() as u32;
+
+    // 内容与最新一条记录相同时不重复保存
+    if let Some(latest) = files.last().and_then(|path| read_entry_file(path).ok()) {
+        if latest.content == content && latest.file_type == file_type {
+            return Ok(());
+        }
+    }
+
+    // 如果超过限制删除最旧的记录(上限调低后可能需要删除多条)
     if count >= max_count {
-        let mut files: Vec<_> = fs::read_dir(&file_history_dir)
-            .map_err(|e| format!("读取历史记录失败: {}", e))?
-            .filter_map(|e| e.ok())
-            .collect();
-        
-        files.sort_by_key(|f| f.file_name());
-        
-        if let Some(oldest) = files.first() {
-            fs::remove_file(oldest.path())
+        let excess = (count + 1 - max_count.max(1)) as usize;
+        for oldest in files.iter().take(excess) {
+            fs::remove_file(oldest)
                 .map_err(|e| format!("删除旧历史记录失败: {}", e))?;
             count -= 1;
         }
+        renumber_entries(&file_history_dir)?;
     }
     
     // 创建新的历史记录
@@ -84,47 +104,81 @@ pub async fn save_file_history(
         file_type,
     };
     
-    let history_file = file_history_dir.join(format!("{:03}.json", count + 1));
-    let json = serde_json::to_string_pretty(&entry)
-        .map_err(|e| format!("序列化历史记录失败: {}", e))?;
-    
-    fs::write(&history_file, json)
-        .map_err(|e| format!("写入历史记录失败: {}", e))?;
+    let history_file = file_history_dir.join(entry_file_name(count + 1, true));
+    write_entry_file(&history_file, &entry)?;
     
     // 更新元数据
-    update_metadata(pack_path, &file_path, count + 1, &timestamp)?;
+    update_metadata(pack_path, file_path, count + 1, &timestamp)?;
     
-    Ok("历史记录保存成功".to_string())
+    Ok(())
 }
 
-// 加载文件历史记录
-#[command]
-pub async fn load_file_history(
-    pack_dir: String,
-    file_path: String,
-) -> Result<Vec<HistoryEntry>, String> {
-    let pack_path = Path::new(&pack_dir);
-    let file_history_dir = get_file_history_dir(pack_path, &file_path);
+/// 修改文本文件前保存其当前内容, 文件不存在时跳过
+pub fn snapshot_text_file(pack_path: &Path, file_path: &str) -> Result<(), String> {
+    let full_path = pack_path.join(file_path);
+    if !full_path.is_file() {
+        return Ok(());
+    }
+
+    let content = fs::read_to_string(&full_path)
+        .map_err(|e| format!("读取文件失败: {}", e))?;
+    save_history_entry(pack_path, file_path, content, "text".to_string(), DEFAULT_MAX_HISTORY)
+}
+
+/// 修改或删除文件前保存其当前内容
+///
+/// 文本原样保存; PNG 保存为 image 类型的 data URL, 其余二进制文件保存为 binary 类型的 data URL,
+/// 两者都按 DEFAULT_MAX_BINARY_HISTORY 限制数量
+pub fn snapshot_file(pack_path: &Path, file_path: &str) -> Result<(), String> {
+    let full_path = pack_path.join(file_path);
+    if !full_path.is_file() {
+        return Ok(());
+    }
+
+    let bytes = fs::read(&full_path)
+        .map_err(|e| format!("读取文件失败: {}", e))?;
+    let is_png = full_path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("png"));
+
+    let bytes = if is_png {
+        bytes
+    } else {
+        match String::from_utf8(bytes) {
+            Ok(content) => {
+                return save_history_entry(pack_path, file_path, content, "text".to_string(), DEFAULT_MAX_HISTORY);
+            }
+            Err(e) => e.into_bytes(),
+        }
+    };
+
+    use base64::Engine;
+    let (mime, file_type) = if is_png {
+        ("image/png", "image")
+    } else if full_path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("ogg")) {
+        ("audio/ogg", "binary")
+    } else {
+        ("application/octet-stream", "binary")
+    };
+    let data_url = format!(
+        "data:{};base64,{}",
+        mime,
+        base64::engine::general_purpose::STANDARD.encode(&bytes)
+    );
+    save_history_entry(pack_path, file_path, data_url, file_type.to_string(), DEFAULT_MAX_BINARY_HISTORY)
+}
+
+/// 读取文件的全部历史记录, 按时间戳排序
+pub(crate) fn read_history_entries(pack_path: &Path, file_path: &str) -> Result<Vec<HistoryEntry>, String> {
+    let file_history_dir = get_file_history_dir(pack_path, file_path);
     
     if !file_history_dir.exists() {
         return Ok(Vec::new());
     }
     
     let mut entries = Vec::new();
-    let dir_entries = fs::read_dir(&file_history_dir)
-        .map_err(|e| format!("读取历史记录目录失败: {}", e))?;
-    
-    for entry in dir_entries {
-        if let Ok(entry) = entry {
-            let path = entry.path();
-            if path.extension().and_then(|s| s.to_str()) == Some("json") {
-                let content = fs::read_to_string(&path)
-                    .map_err(|e| format!("读取历史记录文件失败: {}", e))?;
-                let history_entry: HistoryEntry = serde_json::from_str(&content)
-                    .map_err(|e| format!("解析历史记录失败: {}", e))?;
-                entries.push(history_entry);
-            }
-        }
+    for path in history_entry_files(&file_history_dir) {
+        entries.push(read_entry_file(&path)?);
     }
     
     // 按时间戳排序
@@ -133,16 +187,208 @@ pub async fn load_file_history(
     Ok(entries)
 }
 
+// 加载文件历史记录
+#[command]
+pub async fn load_file_history(
+    pack_dir: String,
+    file_path: String,
+) -> Result<Vec<HistoryEntry>, AppError> {
+    Ok(read_history_entries(Path::new(&pack_dir), &file_path)?)
+}
+
+/// 将文件恢复为某条历史记录的内容, 返回恢复的记录
+///
+/// 按 timestamp 查找, 未提供时按 index(load_file_history 返回列表中的下标);
+/// 写入前会先把当前内容保存为新的历史记录, 恢复操作本身也可撤销
+pub fn restore_history_entry(
+    pack_path: &Path,
+    file_path: &str,
+    timestamp: Option<&str>,
+    index: Option<usize>,
+) -> Result<HistoryEntry, AppError> {
+    let full_path = crate::commands::resolve_pack_path(pack_path, file_path)?;
+    let entries = read_history_entries(pack_path, file_path)?;
+
+    let entry = match (timestamp, index) {
+        (Some(timestamp), _) => entries.into_iter().find(|entry| entry.timestamp == timestamp),
+        (None, Some(index)) => entries.into_iter().nth(index),
+        (None, None) => {
+            return Err(AppError::InvalidInput("需要提供 timestamp 或 index".to_string()));
+        }
+    }
+    .ok_or_else(|| AppError::NotFound(format!("未找到历史记录: {}", file_path)))?;
+
+    let bytes = if entry.file_type == "image" || entry.file_type == "binary" {
+        use base64::Engine;
+        let data = entry
+            .content
+            .split_once(',')
+            .map(|(_, data)| data)
+            .unwrap_or(&entry.content);
+        base64::engine::general_purpose::STANDARD
+            .decode(data)
+            .map_err(|e| AppError::Parse(format!("解析图片历史记录失败: {}", e)))?
+    } else {
+        entry.content.clone().into_bytes()
+    };
+
+    snapshot_file(pack_path, file_path)?;
+
+    if let Some(parent) = full_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| AppError::io("创建目录失败", e))?;
+    }
+    fs::write(&full_path, bytes)
+        .map_err(|e| AppError::io("写入文件失败", e))?;
+    crate::image_handler::invalidate_image_caches(&full_path);
+
+    Ok(entry)
+}
+
+// 恢复文件到指定的历史记录, 返回恢复后的内容(图片为 data URL)
+#[command]
+pub async fn restore_file_history(
+    pack_dir: String,
+    file_path: String,
+    timestamp: Option<String>,
+    index: Option<usize>,
+    state: tauri::State<'_, crate::commands::AppState>,
+) -> Result<String, AppError> {
+    let pack_path = Path::new(&pack_dir);
+    let entry = restore_history_entry(pack_path, &file_path, timestamp.as_deref(), index)?;
+    state
+        .preloader_for_path(pack_path)
+        .invalidate(&file_path.replace('\\', "/"));
+    Ok(entry.content)
+}
+
+/// 可以比较差异的文本文件类型
+const DIFFABLE_EXTENSIONS: [&str; 4] = ["json", "mcmeta", "txt", "lang"];
+
+// 比较文件的两条历史记录, to_timestamp 为空时与当前文件内容比较
+#[command]
+pub async fn diff_file_history(
+    pack_dir: String,
+    file_path: String,
+    from_timestamp: String,
+    to_timestamp: Option<String>,
+) -> Result<crate::text_diff::TextDiff, AppError> {
+    let pack_path = Path::new(&pack_dir);
+    let extension = Path::new(&file_path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if !DIFFABLE_EXTENSIONS.contains(&extension.as_str()) {
+        return Err(AppError::InvalidInput(format!("只能比较文本文件的历史记录: {}", file_path)));
+    }
+
+    let entries = read_history_entries(pack_path, &file_path)?;
+    let find_entry = |timestamp: &str| {
+        entries
+            .iter()
+            .find(|entry| entry.timestamp == timestamp)
+            .ok_or_else(|| AppError::NotFound(format!("未找到历史记录: {}", timestamp)))
+            .and_then(|entry| {
+                if entry.file_type == "text" {
+                    Ok(entry.content.clone())
+                } else {
+                    Err(AppError::InvalidInput(format!("历史记录不是文本: {}", timestamp)))
+                }
+            })
+    };
+
+    let old = find_entry(&from_timestamp)?;
+    let new = match to_timestamp {
+        Some(timestamp) => find_entry(&timestamp)?,
+        None => {
+            let full_path = crate::commands::resolve_pack_path(pack_path, &file_path)?;
+            fs::read_to_string(&full_path)
+                .map_err(|e| AppError::io("读取文件失败", e))?
+        }
+    };
+
+    Ok(crate::text_diff::diff_lines(&old, &new, crate::text_diff::DEFAULT_CONTEXT_LINES))
+}
+
+/// 文件或目录重命名后迁移其历史记录, 目标已有的历史记录与之合并
+pub fn move_file_history(pack_path: &Path, old_path: &str, new_path: &str) -> Result<(), String> {
+    let old_dir = get_file_history_dir(pack_path, old_path);
+    if !old_dir.exists() {
+        return Ok(());
+    }
+    let new_dir = get_file_history_dir(pack_path, new_path);
+    if new_dir.exists() && !crate::fs_utils::is_same_entry(&old_dir, &new_dir) {
+        merge_history_dir(&old_dir, &new_dir)?;
+        fs::remove_dir_all(&old_dir)
+            .map_err(|e| format!("删除历史记录失败: {}", e))?;
+    } else {
+        if let Some(parent) = new_dir.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("创建历史记录目录失败: {}", e))?;
+        }
+        crate::fs_utils::move_path(&old_dir, &new_dir)
+            .map_err(|e| format!("移动历史记录失败: {}", e))?;
+    }
+
+    // 元数据中的键按前缀整体替换(目录重命名时包含其下所有文件)
+    let meta_file = get_history_dir(pack_path).join("history_meta.json");
+    let Some(mut metadata) = fs::read_to_string(&meta_file)
+        .ok()
+        .and_then(|content| serde_json::from_str::<HistoryMetadata>(&content).ok())
+    else {
+        return Ok(());
+    };
+    let mut files: HashMap<String, FileHistoryInfo> = HashMap::new();
+    let mut merged = Vec::new();
+    for (key, info) in metadata.files {
+        let key = match key
+            .strip_prefix(old_path)
+            .filter(|rest| rest.is_empty() || rest.starts_with('/'))
+        {
+            Some(rest) => format!("{}{}", new_path, rest),
+            None => key,
+        };
+        match files.get_mut(&key) {
+            Some(existing) => {
+                if info.last_modified > existing.last_modified {
+                    existing.last_modified = info.last_modified;
+                }
+                merged.push(key);
+            }
+            None => {
+                files.insert(key, info);
+            }
+        }
+    }
+    // 合并后的记录数和大小按磁盘上的实际内容重新统计
+    for key in merged {
+        let dir = get_file_history_dir(pack_path, &key);
+        if let Some(info) = files.get_mut(&key) {
+            info.history_count = history_entry_files(&dir).len() as u32;
+            info.size = crate::fs_utils::calculate_dir_size(&dir, false)?;
+        }
+    }
+    metadata.files = files;
+    metadata.total_size = metadata.files.values().map(|f| f.size).sum();
+    let json = serde_json::to_string_pretty(&metadata)
+        .map_err(|e| format!("序列化元数据失败: {}", e))?;
+    fs::write(&meta_file, json)
+        .map_err(|e| format!("写入元数据失败: {}", e))?;
+
+    crate::fs_utils::remove_empty_dirs(&get_history_dir(pack_path));
+    Ok(())
+}
+
 // 获取历史记录统计信息
 #[command]
-pub async fn get_history_stats(pack_dir: String) -> Result<HistoryMetadata, String> {
+pub async fn get_history_stats(pack_dir: String) -> Result<HistoryMetadata, AppError> {
     let pack_path = Path::new(&pack_dir);
     let meta_file = get_history_dir(pack_path).join("history_meta.json");
     
     if !meta_file.exists() {
         return Ok(HistoryMetadata {
             version: "1.0".to_string(),
-            max_history_per_file: 30,
+            max_history_per_file: DEFAULT_MAX_HISTORY,
             files: HashMap::new(),
             total_size: 0,
         });
@@ -158,7 +404,7 @@ pub async fn get_history_stats(pack_dir: String) -> Result<HistoryMetadata, Stri
 
 // 清理指定文件的历史记录
 #[command]
-pub async fn clear_file_history(pack_dir: String, file_path: String) -> Result<String, String> {
+pub async fn clear_file_history(pack_dir: String, file_path: String) -> Result<String, AppError> {
     let pack_path = Path::new(&pack_dir);
     let file_history_dir = get_file_history_dir(pack_path, &file_path);
     
@@ -172,7 +418,7 @@ pub async fn clear_file_history(pack_dir: String, file_path: String) -> Result<S
 
 // 清理所有历史记录
 #[command]
-pub async fn clear_all_history(pack_dir: String) -> Result<String, String> {
+pub async fn clear_all_history(pack_dir: String) -> Result<String, AppError> {
     let pack_path = Path::new(&pack_dir);
     let history_dir = get_history_dir(pack_path);
     
@@ -184,11 +430,318 @@ pub async fn clear_all_history(pack_dir: String) -> Result<String, String> {
     Ok("所有历史记录已清理".to_string())
 }
 
-// 获取材质包大小
+// 获取材质包大小(不含历史记录和编辑器数据), 兼容旧接口, 完整信息见 get_pack_size_summary
 #[command]
-pub async fn get_pack_size(pack_dir: String) -> Result<u64, String> {
-    let pack_path = Path::new(&pack_dir);
-    calculate_dir_size(pack_path, true)
+pub async fn get_pack_size(pack_dir: String) -> Result<u64, AppError> {
+    Ok(crate::pack_stats::get_pack_size_summary(pack_dir).await?.pack_bytes)
+}
+
+/// 整理历史记录的结果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VacuumResult {
+    /// 删除的历史目录数(对应文件已不存在)
+    pub removed_dirs: usize,
+    /// 因超出数量上限删除的记录数
+    pub pruned_entries: usize,
+    pub reclaimed_bytes: u64,
+}
+
+/// 记录文件的编号, 不是记录文件(NNN.json 或压缩的 NNN.json.gz)时返回 None
+fn entry_number(path: &Path) -> Option<u32> {
+    let name = path.file_name()?.to_str()?;
+    let stem = name
+        .strip_suffix(".json.gz")
+        .or_else(|| name.strip_suffix(".json"))?;
+    if stem.is_empty() || !stem.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    stem.parse().ok()
+}
+
+fn entry_file_name(number: u32, compressed: bool) -> String {
+    if compressed {
+        format!("{:03}.json.gz", number)
+    } else {
+        format!("{:03}.json", number)
+    }
+}
+
+/// 历史目录中的记录文件, 按编号排序
+fn history_entry_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<(u32, PathBuf)> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|path| path.is_file())
+        .filter_map(|path| Some((entry_number(&path)?, path)))
+        .collect();
+    files.sort();
+    files.into_iter().map(|(_, path)| path).collect()
+}
+
+/// 把 from 中的历史记录(含子目录)合并进 to, 同一文件的记录按时间排序后重新编号
+fn merge_history_dir(from: &Path, to: &Path) -> Result<(), String> {
+    for entry in fs::read_dir(from).into_iter().flatten().filter_map(|e| e.ok()) {
+        if entry.path().is_dir() {
+            merge_history_dir(&entry.path(), &to.join(entry.file_name()))?;
+        }
+    }
+
+    let incoming = history_entry_files(from);
+    if incoming.is_empty() {
+        return Ok(());
+    }
+    fs::create_dir_all(to)
+        .map_err(|e| format!("创建历史记录目录失败: {}", e))?;
+
+    // RFC3339 的 UTC 时间戳按字符串排序即为时间顺序
+    let mut entries: Vec<(String, PathBuf)> = history_entry_files(to)
+        .into_iter()
+        .chain(incoming)
+        .map(|path| (read_entry_file(&path).map(|entry| entry.timestamp).unwrap_or_default(), path))
+        .collect();
+    entries.sort();
+
+    // 先移到不会与现有编号冲突的临时编号, 再整理为连续编号
+    const MERGE_OFFSET: u32 = 100_000;
+    for (i, (_, path)) in entries.iter().enumerate() {
+        let compressed = path.extension().is_some_and(|ext| ext == "gz");
+        fs::rename(path, to.join(entry_file_name(MERGE_OFFSET + i as u32, compressed)))
+            .map_err(|e| format!("合并历史记录失败: {}", e))?;
+    }
+    renumber_entries(to)
+}
+
+/// 删除记录后把其余记录依次前移编号(保留原有格式), 新记录才不会覆盖已有的记录
+fn renumber_entries(dir: &Path) -> Result<(), String> {
+    for (i, file) in history_entry_files(dir).iter().enumerate() {
+        let compressed = file.extension().is_some_and(|ext| ext == "gz");
+        let target = dir.join(entry_file_name(i as u32 + 1, compressed));
+        if *file != target {
+            fs::rename(file, &target)
+                .map_err(|e| format!("整理历史记录失败: {}", e))?;
+        }
+    }
+    Ok(())
+}
+
+/// 读取记录文件, 兼容旧版未压缩的 .json
+fn read_entry_file(path: &Path) -> Result<HistoryEntry, String> {
+    let bytes = fs::read(path)
+        .map_err(|e| format!("读取历史记录文件失败: {}", e))?;
+    let json = if path.extension().is_some_and(|ext| ext == "gz") {
+        use std::io::Read;
+        let mut json = Vec::new();
+        flate2::read::GzDecoder::new(bytes.as_slice())
+            .read_to_end(&mut json)
+            .map_err(|e| format!("解压历史记录失败: {}", e))?;
+        json
+    } else {
+        bytes
+    };
+    serde_json::from_slice(&json)
+        .map_err(|e| format!("解析历史记录失败: {}", e))
+}
+
+/// 以 gzip 压缩写入记录文件
+fn write_entry_file(path: &Path, entry: &HistoryEntry) -> Result<(), String> {
+    use std::io::Write;
+    let json = serde_json::to_vec(entry)
+        .map_err(|e| format!("序列化历史记录失败: {}", e))?;
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder
+        .write_all(&json)
+        .map_err(|e| format!("压缩历史记录失败: {}", e))?;
+    let compressed = encoder
+        .finish()
+        .map_err(|e| format!("压缩历史记录失败: {}", e))?;
+    fs::write(path, compressed)
+        .map_err(|e| format!("写入历史记录失败: {}", e))
+}
+
+/// 删除已不存在文件的历史记录, 裁剪超出数量上限的记录, 并按实际内容重建元数据
+pub fn vacuum_history_store(pack_path: &Path) -> Result<VacuumResult, String> {
+    let history_dir = get_history_dir(pack_path);
+    let mut result = VacuumResult { removed_dirs: 0, pruned_entries: 0, reclaimed_bytes: 0 };
+    if !history_dir.is_dir() {
+        return Ok(result);
+    }
+
+    let meta_file = history_dir.join("history_meta.json");
+    let mut metadata: HistoryMetadata = fs::read_to_string(&meta_file)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or(HistoryMetadata {
+            version: "1.0".to_string(),
+            max_history_per_file: DEFAULT_MAX_HISTORY,
+            files: HashMap::new(),
+            total_size: 0,
+        });
+    let max_count = metadata.max_history_per_file.max(1) as usize;
+
+    // 直接包含记录文件的目录即某个文件的历史目录
+    let file_dirs: Vec<(String, PathBuf)> = walkdir::WalkDir::new(&history_dir)
+        .min_depth(1)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_dir())
+        .filter(|e| !history_entry_files(e.path()).is_empty())
+        .filter_map(|e| {
+            let relative = e.path().strip_prefix(&history_dir).ok()?;
+            Some((relative.to_string_lossy().replace('\\', "/"), e.into_path()))
+        })
+        .collect();
+
+    let mut files = HashMap::new();
+    for (relative, dir) in file_dirs {
+        // 可能已随上层目录一起删除
+        if !dir.exists() {
+            continue;
+        }
+        // 只有材质包中完全不存在该路径时才删除, 同名目录等情况保留
+        if !pack_path.join(&relative).exists() {
+            result.reclaimed_bytes += crate::fs_utils::calculate_dir_size(&dir, false)?;
+            fs::remove_dir_all(&dir)
+                .map_err(|e| format!("删除历史记录失败 {}: {}", relative, e))?;
+            result.removed_dirs += 1;
+            continue;
+        }
+
+        let entries = history_entry_files(&dir);
+        let excess = entries.len().saturating_sub(max_count);
+        for entry in &entries[..excess] {
+            result.reclaimed_bytes += entry.metadata().map(|m| m.len()).unwrap_or(0);
+            fs::remove_file(entry)
+                .map_err(|e| format!("删除旧历史记录失败: {}", e))?;
+            result.pruned_entries += 1;
+        }
+
+        let last_modified = metadata
+            .files
+            .get(&relative)
+            .map(|info| info.last_modified.clone())
+            .unwrap_or_default();
+        files.insert(
+            relative,
+            FileHistoryInfo {
+                history_count: (entries.len() - excess) as u32,
+                last_modified,
+                size: crate::fs_utils::calculate_dir_size(&dir, false)?,
+            },
+        );
+    }
+
+    crate::fs_utils::remove_empty_dirs(&history_dir);
+
+    metadata.files = files;
+    metadata.total_size = metadata.files.values().map(|f| f.size).sum();
+    let json = serde_json::to_string_pretty(&metadata)
+        .map_err(|e| format!("序列化元数据失败: {}", e))?;
+    fs::write(&meta_file, json)
+        .map_err(|e| format!("写入元数据失败: {}", e))?;
+
+    Ok(result)
+}
+
+// 整理历史记录
+#[command]
+pub async fn vacuum_history(state: tauri::State<'_, crate::commands::AppState>) -> Result<VacuumResult, AppError> {
+    let pack_path = state.pack_path()?;
+    Ok(vacuum_history_store(&pack_path)?)
+}
+
+/// 按时间清理历史记录的结果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PruneResult {
+    pub removed_entries: usize,
+    pub freed_bytes: u64,
+}
+
+/// 删除所有早于 older_than_days 天的历史记录, 删空的历史目录一并删除
+///
+/// 时间戳无法解析的记录保留
+pub fn prune_history_store(pack_path: &Path, older_than_days: u32) -> Result<PruneResult, String> {
+    let history_dir = get_history_dir(pack_path);
+    let mut result = PruneResult { removed_entries: 0, freed_bytes: 0 };
+    if !history_dir.is_dir() {
+        return Ok(result);
+    }
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(older_than_days as i64);
+
+    let file_dirs: Vec<(String, PathBuf)> = walkdir::WalkDir::new(&history_dir)
+        .min_depth(1)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_dir())
+        .filter(|e| !history_entry_files(e.path()).is_empty())
+        .filter_map(|e| {
+            let relative = e.path().strip_prefix(&history_dir).ok()?;
+            Some((relative.to_string_lossy().replace('\\', "/"), e.into_path()))
+        })
+        .collect();
+
+    let mut updated: Vec<(String, u32, u64)> = Vec::new();
+    for (relative, dir) in file_dirs {
+        let mut removed_any = false;
+        for file in history_entry_files(&dir) {
+            let expired = read_entry_file(&file)
+                .ok()
+                .and_then(|entry| chrono::DateTime::parse_from_rfc3339(&entry.timestamp).ok())
+                .is_some_and(|timestamp| timestamp < cutoff);
+            if !expired {
+                continue;
+            }
+            result.freed_bytes += file.metadata().map(|m| m.len()).unwrap_or(0);
+            fs::remove_file(&file)
+                .map_err(|e| format!("删除历史记录失败: {}", e))?;
+            result.removed_entries += 1;
+            removed_any = true;
+        }
+        if removed_any {
+            renumber_entries(&dir)?;
+            let count = history_entry_files(&dir).len() as u32;
+            let size = if count == 0 { 0 } else { crate::fs_utils::calculate_dir_size(&dir, false)? };
+            updated.push((relative, count, size));
+        }
+    }
+
+    if result.removed_entries == 0 {
+        return Ok(result);
+    }
+    crate::fs_utils::remove_empty_dirs(&history_dir);
+
+    let meta_file = history_dir.join("history_meta.json");
+    if let Some(mut metadata) = fs::read_to_string(&meta_file)
+        .ok()
+        .and_then(|content| serde_json::from_str::<HistoryMetadata>(&content).ok())
+    {
+        for (relative, count, size) in updated {
+            if count == 0 {
+                metadata.files.remove(&relative);
+            } else if let Some(info) = metadata.files.get_mut(&relative) {
+                info.history_count = count;
+                info.size = size;
+            }
+        }
+        metadata.total_size = metadata.files.values().map(|f| f.size).sum();
+        let json = serde_json::to_string_pretty(&metadata)
+            .map_err(|e| format!("序列化元数据失败: {}", e))?;
+        fs::write(&meta_file, json)
+            .map_err(|e| format!("写入元数据失败: {}", e))?;
+    }
+
+    Ok(result)
+}
+
+// 删除早于指定天数的历史记录
+#[command]
+pub async fn prune_history(
+    older_than_days: u32,
+    state: tauri::State<'_, crate::commands::AppState>,
+) -> Result<PruneResult, AppError> {
+    let pack_path = state.pack_path()?;
+    Ok(prune_history_store(&pack_path, older_than_days)?)
 }
 
 // 更新元数据
@@ -208,14 +761,14 @@ fn update_metadata(
     } else {
         HistoryMetadata {
             version: "1.0".to_string(),
-            max_history_per_file: 30,
+            max_history_per_file: DEFAULT_MAX_HISTORY,
             files: HashMap::new(),
             total_size: 0,
         }
     };
     
     let file_history_dir = get_file_history_dir(pack_path, file_path);
-    let size = calculate_dir_size(&file_history_dir, false)?;
+    let size = crate::fs_utils::calculate_dir_size(&file_history_dir, false)?;
     
     metadata.files.insert(
         file_path.to_string(),
@@ -238,46 +791,234 @@ fn update_metadata(
     Ok(())
 }
 
-// 计算目录大小
-fn calculate_dir_size(path: &Path, exclude_history: bool) -> Result<u64, String> {
-    let mut total_size = 0u64;
-    
-    if !path.exists() {
-        return Ok(0);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saving_same_content_twice_keeps_one_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = "assets/minecraft/sounds.json";
+        for _ in 0..2 {
+            save_history_entry(dir.path(), file, "{}".to_string(), "text".to_string(), DEFAULT_MAX_HISTORY).unwrap();
+        }
+        assert_eq!(read_history_entries(dir.path(), file).unwrap().len(), 1);
+
+        save_history_entry(dir.path(), file, "{\"a\":{}}".to_string(), "text".to_string(), DEFAULT_MAX_HISTORY).unwrap();
+        assert_eq!(read_history_entries(dir.path(), file).unwrap().len(), 2);
     }
-    
-    if path.is_file() {
-        return Ok(path.metadata()
-            .map_err(|e| format!("获取文件大小失败: {}", e))?
-            .len());
+
+    #[test]
+    fn oldest_entries_are_dropped_over_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = "pack.mcmeta";
+        for i in 0..4 {
+            save_history_entry(dir.path(), file, i.to_string(), "text".to_string(), 3).unwrap();
+        }
+        let contents: Vec<String> = read_history_entries(dir.path(), file)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.content)
+            .collect();
+        assert_eq!(contents, ["1", "2", "3"]);
     }
-    
-    let entries = fs::read_dir(path)
-        .map_err(|e| format!("读取目录失败: {}", e))?;
-    
-    for entry in entries {
-        if let Ok(entry) = entry {
-            let entry_path = entry.path();
-            
-            // 如果需要排除.history文件夹
-            if exclude_history && entry_path.file_name().and_then(|s| s.to_str()) == Some(".history") {
-                continue;
-            }
-            
-            // 始终排除.little100缓存文件夹
-            if entry_path.file_name().and_then(|s| s.to_str()) == Some(".little100") {
-                continue;
-            }
-            
-            if entry_path.is_file() {
-                total_size += entry_path.metadata()
-                    .map_err(|e| format!("获取文件大小失败: {}", e))?
-                    .len();
-            } else if entry_path.is_dir() {
-                total_size += calculate_dir_size(&entry_path, exclude_history)?;
+
+    #[test]
+    fn vacuum_removes_history_of_deleted_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let kept = "assets/minecraft/lang/en_us.json";
+        let deleted = "assets/minecraft/lang/zh_cn.json";
+        std::fs::create_dir_all(dir.path().join("assets/minecraft/lang")).unwrap();
+        std::fs::write(dir.path().join(kept), "{}").unwrap();
+        for file in [kept, deleted] {
+            save_history_entry(dir.path(), file, "{}".to_string(), "text".to_string(), DEFAULT_MAX_HISTORY).unwrap();
+        }
+
+        let result = vacuum_history_store(dir.path()).unwrap();
+        assert_eq!(result.removed_dirs, 1);
+        assert!(result.reclaimed_bytes > 0);
+        assert!(read_history_entries(dir.path(), deleted).unwrap().is_empty());
+        assert_eq!(read_history_entries(dir.path(), kept).unwrap().len(), 1);
+
+        let metadata: HistoryMetadata = serde_json::from_str(
+            &fs::read_to_string(get_history_dir(dir.path()).join("history_meta.json")).unwrap(),
+        )
+        .unwrap();
+        assert!(metadata.files.contains_key(kept));
+        assert!(!metadata.files.contains_key(deleted));
+    }
+
+    #[test]
+    fn restore_writes_entry_and_snapshots_current_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = "assets/minecraft/models/block/stone.json";
+        std::fs::create_dir_all(dir.path().join("assets/minecraft/models/block")).unwrap();
+        for content in ["v1", "v2"] {
+            save_history_entry(dir.path(), file, content.to_string(), "text".to_string(), DEFAULT_MAX_HISTORY).unwrap();
+        }
+        std::fs::write(dir.path().join(file), "v3").unwrap();
+
+        let restored = restore_history_entry(dir.path(), file, None, Some(0)).unwrap();
+        assert_eq!(restored.content, "v1");
+        assert_eq!(std::fs::read_to_string(dir.path().join(file)).unwrap(), "v1");
+
+        // 恢复前的内容被追加为最新的记录
+        let contents: Vec<String> = read_history_entries(dir.path(), file)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.content)
+            .collect();
+        assert_eq!(contents, ["v1", "v2", "v3"]);
+    }
+
+    #[test]
+    fn diff_rejects_binary_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = tauri::async_runtime::block_on(diff_file_history(
+            dir.path().to_string_lossy().to_string(),
+            "assets/minecraft/textures/block/stone.png".to_string(),
+            "2024-01-01".to_string(),
+            None,
+        ));
+        assert!(matches!(result, Err(AppError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn entries_are_stored_compressed_and_plain_entries_still_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = "assets/minecraft/lang/en_us.json";
+        let content = "\"block.minecraft.stone\": \"Stone\",\n".repeat(5000);
+        save_history_entry(dir.path(), file, content.clone(), "text".to_string(), DEFAULT_MAX_HISTORY).unwrap();
+
+        let history_dir = get_file_history_dir(dir.path(), file);
+        let stored = history_dir.join(entry_file_name(1, true));
+        assert!(stored.metadata().unwrap().len() * 20 < content.len() as u64);
+
+        // 旧版未压缩的记录
+        let legacy = HistoryEntry {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            content: "legacy".to_string(),
+            file_type: "text".to_string(),
+        };
+        fs::write(history_dir.join(entry_file_name(2, false)), serde_json::to_vec(&legacy).unwrap()).unwrap();
+
+        let contents: Vec<String> = read_history_entries(dir.path(), file)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.content)
+            .collect();
+        assert_eq!(contents, [content, "legacy".to_string()]);
+    }
+
+    #[test]
+    fn prune_removes_only_entries_older_than_cutoff() {
+        let dir = tempfile::tempdir().unwrap();
+        let mixed = "pack.mcmeta";
+        let stale = "assets/minecraft/sounds.json";
+        for content in ["old", "new"] {
+            save_history_entry(dir.path(), mixed, content.to_string(), "text".to_string(), DEFAULT_MAX_HISTORY).unwrap();
+        }
+        save_history_entry(dir.path(), stale, "old".to_string(), "text".to_string(), DEFAULT_MAX_HISTORY).unwrap();
+
+        // 把内容为 old 的记录改为 40 天前
+        let backdated = (chrono::Utc::now() - chrono::Duration::days(40)).to_rfc3339();
+        for file in [mixed, stale] {
+            for path in history_entry_files(&get_file_history_dir(dir.path(), file)) {
+                let mut entry = read_entry_file(&path).unwrap();
+                if entry.content == "old" {
+                    entry.timestamp = backdated.clone();
+                    write_entry_file(&path, &entry).unwrap();
+                }
             }
         }
+
+        let result = prune_history_store(dir.path(), 30).unwrap();
+        assert_eq!(result.removed_entries, 2);
+        assert!(result.freed_bytes > 0);
+
+        let remaining: Vec<String> = read_history_entries(dir.path(), mixed)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.content)
+            .collect();
+        assert_eq!(remaining, ["new"]);
+        assert!(!get_file_history_dir(dir.path(), stale).exists());
+
+        let metadata: HistoryMetadata = serde_json::from_str(
+            &fs::read_to_string(get_history_dir(dir.path()).join("history_meta.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(metadata.files[mixed].history_count, 1);
+        assert!(!metadata.files.contains_key(stale));
     }
-    
-    Ok(total_size)
-}
\ No newline at end of file
+
+    #[test]
+    fn png_round_trips_through_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = "assets/minecraft/textures/block/stone.png";
+        let full_path = dir.path().join(file);
+        std::fs::create_dir_all(full_path.parent().unwrap()).unwrap();
+
+        let encode = |rgba: [u8; 4]| {
+            let mut bytes = Vec::new();
+            image::RgbaImage::from_pixel(2, 2, image::Rgba(rgba))
+                .write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png)
+                .unwrap();
+            bytes
+        };
+        let original = encode([255, 0, 0, 255]);
+        std::fs::write(&full_path, &original).unwrap();
+        snapshot_file(dir.path(), file).unwrap();
+        std::fs::write(&full_path, encode([0, 0, 255, 255])).unwrap();
+
+        let entries = read_history_entries(dir.path(), file).unwrap();
+        assert_eq!(entries[0].file_type, "image");
+        assert!(entries[0].content.starts_with("data:image/png;base64,"));
+
+        restore_history_entry(dir.path(), file, None, Some(0)).unwrap();
+        assert_eq!(std::fs::read(&full_path).unwrap(), original);
+        assert_eq!(read_history_entries(dir.path(), file).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn binary_history_uses_its_own_cap() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = "assets/minecraft/sounds/step.ogg";
+        let full_path = dir.path().join(file);
+        std::fs::create_dir_all(full_path.parent().unwrap()).unwrap();
+        for i in 0..DEFAULT_MAX_BINARY_HISTORY + 2 {
+            std::fs::write(&full_path, [0xff, 0xfe, i as u8]).unwrap();
+            snapshot_file(dir.path(), file).unwrap();
+        }
+
+        let entries = read_history_entries(dir.path(), file).unwrap();
+        assert_eq!(entries.len(), DEFAULT_MAX_BINARY_HISTORY as usize);
+        assert!(entries.iter().all(|entry| entry.file_type == "binary"));
+    }
+
+    #[test]
+    fn moving_onto_file_with_history_merges_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let (from, to) = ("textures/a.json", "textures/b.json");
+        save_history_entry(dir.path(), to, "b1".to_string(), "text".to_string(), DEFAULT_MAX_HISTORY).unwrap();
+        save_history_entry(dir.path(), from, "a1".to_string(), "text".to_string(), DEFAULT_MAX_HISTORY).unwrap();
+        save_history_entry(dir.path(), to, "b2".to_string(), "text".to_string(), DEFAULT_MAX_HISTORY).unwrap();
+
+        move_file_history(dir.path(), from, to).unwrap();
+
+        let contents: Vec<String> = read_history_entries(dir.path(), to)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.content)
+            .collect();
+        assert_eq!(contents, ["b1", "a1", "b2"]);
+        assert!(!get_file_history_dir(dir.path(), from).exists());
+
+        let metadata: HistoryMetadata = serde_json::from_str(
+            &fs::read_to_string(get_history_dir(dir.path()).join("history_meta.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(metadata.files[to].history_count, 3);
+        assert!(!metadata.files.contains_key(from));
+    }
+}
@@ -74,13 +74,49 @@ pub fn resize_image(
     Ok(())
 }
 
+/// 纹理的最大边长
+pub const MAX_TEXTURE_SIZE: u32 = 8192;
+
+/// 可解码、可生成缩略图的图片扩展名(小写)
+pub const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "tga"];
+
+/// 按扩展名判断是否为支持的图片
+pub fn is_image_path(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.as_str()))
+}
+
 /// 验证图片是否为有效的纹理尺寸
+///
+/// 允许 2 的幂次方、16 的倍数, 以及高度为宽度整数倍的动画帧条(如 16x48)
 pub fn validate_texture_size(width: u32, height: u32) -> bool {
     let is_power_of_two = |n: u32| n > 0 && (n & (n - 1)) == 0;
     let is_multiple_of_16 = |n: u32| n > 0 && n % 16 == 0;
+    let is_valid_side = |n: u32| is_power_of_two(n) || is_multiple_of_16(n);
     
     (is_power_of_two(width) && is_power_of_two(height)) ||
-    (is_multiple_of_16(width) && is_multiple_of_16(height))
+    (is_multiple_of_16(width) && is_multiple_of_16(height)) ||
+    (is_valid_side(width) && height.is_multiple_of(width))
+}
+
+/// 检查新建纹理的尺寸, 超出范围时返回错误, 尺寸少见时返回警告
+pub fn check_texture_size(width: u32, height: u32) -> Result<Vec<String>, String> {
+    if width == 0 || height == 0 {
+        return Err("Width and height must be greater than 0".to_string());
+    }
+    if width > MAX_TEXTURE_SIZE || height > MAX_TEXTURE_SIZE {
+        return Err(format!("Maximum size is {}x{}", MAX_TEXTURE_SIZE, MAX_TEXTURE_SIZE));
+    }
+
+    let mut warnings = Vec::new();
+    if !validate_texture_size(width, height) {
+        warnings.push(format!(
+            "{}x{} 不是 2 的幂次方、16 的倍数或动画帧条尺寸, 仅适用于 GUI 切片等特殊纹理",
+            width, height
+        ));
+    }
+    Ok(warnings)
 }
 
 /// 创建缩略图（优化版本，带缓存）
@@ -205,16 +241,8 @@ pub fn create_transparent_png(
     width: u32,
     height: u32,
 ) -> Result<(), String> {
-    // 验证尺寸是否为2的幂次方
-    let is_power_of_two = |n: u32| n > 0 && (n & (n - 1)) == 0;
-    
-    if !is_power_of_two(width) || !is_power_of_two(height) {
-        return Err("Width and height must be powers of 2".to_string());
-    }
-    
-    if width > 8192 || height > 8192 {
-        return Err("Maximum size is 8192x8192".to_string());
-    }
+    // 尺寸是否少见由调用方通过 check_texture_size 决定, 这里只拒绝超出范围的尺寸
+    check_texture_size(width, height)?;
     
     // 创建透明图片
     let img = RgbaImage::from_pixel(width, height, image::Rgba([0, 0, 0, 0]));
@@ -232,6 +260,126 @@ pub fn create_transparent_png(
     Ok(())
 }
 
+/// 网格预览放大后的目标边长
+const GRID_PREVIEW_TARGET_SIZE: u32 = 512;
+
+/// 生成带像素网格叠加的放大预览图(不修改原文件)
+pub fn create_grid_overlay(
+    path: &Path,
+    grid_size: u32,
+) -> Result<String, String> {
+    if grid_size == 0 {
+        return Err("Grid size must be greater than 0".to_string());
+    }
+
+    let img = image::open(path)
+        .map_err(|e| format!("Failed to open image: {}", e))?;
+    let (width, height) = (img.width(), img.height());
+
+    // 按整数倍放大, 保持像素清晰
+    let scale = (GRID_PREVIEW_TARGET_SIZE / width.max(height).max(1)).max(1);
+    let mut canvas = img
+        .resize_exact(width * scale, height * scale, FilterType::Nearest)
+        .to_rgba8();
+
+    let line_color = image::Rgba([0, 0, 0, 96]);
+    let step = grid_size * scale;
+    for (x, y, pixel) in canvas.enumerate_pixels_mut() {
+        if x % step == 0 || y % step == 0 || x == width * scale - 1 || y == height * scale - 1 {
+            image::Pixel::blend(pixel, &line_color);
+        }
+    }
+
+    let mut buffer = Vec::new();
+    canvas.write_to(&mut std::io::Cursor::new(&mut buffer), ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode image: {}", e))?;
+
+    Ok(general_purpose::STANDARD.encode(&buffer))
+}
+
+/// 将不透明像素的颜色逐圈扩展到相邻的全透明像素中(alpha 保持不变), 返回被填色的像素数
+///
+/// 每个透明像素取已填色的 8 邻域像素颜色的平均值, 用于消除 mipmap 后的暗边
+pub fn alpha_bleed_image(img: &mut RgbaImage) -> u64 {
+    let (width, height) = img.dimensions();
+    let index = |x: u32, y: u32| (y * width + x) as usize;
+    let mut filled: Vec<bool> = img.pixels().map(|p| p[3] > 0).collect();
+    let mut filled_count = 0;
+
+    let neighbors = |x: u32, y: u32| {
+        (-1i64..=1)
+            .flat_map(move |dy| (-1i64..=1).map(move |dx| (dx, dy)))
+            .filter(|&(dx, dy)| dx != 0 || dy != 0)
+            .map(move |(dx, dy)| (x as i64 + dx, y as i64 + dy))
+            .filter(move |&(nx, ny)| nx >= 0 && ny >= 0 && nx < width as i64 && ny < height as i64)
+            .map(|(nx, ny)| (nx as u32, ny as u32))
+    };
+
+    // 与已填色像素相邻的透明像素
+    let mut frontier: Vec<(u32, u32)> = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .filter(|&(x, y)| !filled[index(x, y)] && neighbors(x, y).any(|(nx, ny)| filled[index(nx, ny)]))
+        .collect();
+
+    while !frontier.is_empty() {
+        let colors: Vec<[u8; 3]> = frontier
+            .iter()
+            .map(|&(x, y)| {
+                let (mut sum, mut count) = ([0u32; 3], 0u32);
+                for (nx, ny) in neighbors(x, y).filter(|&(nx, ny)| filled[index(nx, ny)]) {
+                    let p = img.get_pixel(nx, ny);
+                    for c in 0..3 {
+                        sum[c] += p[c] as u32;
+                    }
+                    count += 1;
+                }
+                sum.map(|v| (v / count.max(1)) as u8)
+            })
+            .collect();
+
+        for (&(x, y), color) in frontier.iter().zip(&colors) {
+            let pixel = img.get_pixel_mut(x, y);
+            pixel[0] = color[0];
+            pixel[1] = color[1];
+            pixel[2] = color[2];
+            filled[index(x, y)] = true;
+            filled_count += 1;
+        }
+
+        let mut next: Vec<(u32, u32)> = frontier
+            .iter()
+            .flat_map(|&(x, y)| neighbors(x, y))
+            .filter(|&(x, y)| !filled[index(x, y)])
+            .collect();
+        next.sort_unstable();
+        next.dedup();
+        frontier = next;
+    }
+
+    filled_count
+}
+
+/// 对图片执行 alpha bleed 并保存为 PNG, 返回被填色的像素数
+pub fn alpha_bleed_file(input: &Path, output: &Path) -> Result<u64, String> {
+    let mut img = image::open(input)
+        .map_err(|e| format!("Failed to open image: {}", e))?
+        .to_rgba8();
+
+    let filled = alpha_bleed_image(&mut img);
+
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+    let mut buffer = Vec::new();
+    img.write_to(&mut std::io::Cursor::new(&mut buffer), ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+    write_file_atomic(output, &buffer)?;
+    invalidate_image_caches(output);
+
+    Ok(filled)
+}
+
 /// 异步创建缩略图
 pub async fn create_thumbnail_async(
     path: PathBuf,
@@ -271,16 +419,336 @@ pub async fn create_thumbnails_batch(
 }
 
 /// 清除缓存
-#[allow(dead_code)]
 pub fn clear_caches() {
     THUMBNAIL_CACHE.write().clear();
     IMAGE_INFO_CACHE.write().clear();
 }
 
+/// 已缓存的图片信息, 不读取文件
+pub fn cached_image_info(path: &Path) -> Option<ImageInfo> {
+    IMAGE_INFO_CACHE.read().peek(&path.to_string_lossy().to_string()).cloned()
+}
+
+/// 使单个文件的缩略图和图片信息缓存失效
+pub fn invalidate_image_caches(path: &Path) {
+    let path_str = path.to_string_lossy().to_string();
+    IMAGE_INFO_CACHE.write().pop(&path_str);
+
+    // 缩略图缓存键为 "路径_尺寸"
+    let prefix = format!("{}_", path_str);
+    let mut thumbnails = THUMBNAIL_CACHE.write();
+    let stale: Vec<String> = thumbnails
+        .iter()
+        .filter(|(key, _)| key.strip_prefix(&prefix).is_some_and(|size| size.parse::<u32>().is_ok()))
+        .map(|(key, _)| key.clone())
+        .collect();
+    for key in stale {
+        thumbnails.pop(&key);
+    }
+}
+
+/// 校验图片数据(可带 data URI 前缀), 并按目标扩展名返回需要写入的字节
+pub fn prepare_image_bytes(path: &Path, data: &str, convert: bool) -> Result<Vec<u8>, String> {
+    let encoded = match data.split_once(',') {
+        Some((header, body)) if header.starts_with("data:") => {
+            if !header.ends_with(";base64") {
+                return Err("仅支持 base64 编码的 data URI".to_string());
+            }
+            body
+        }
+        _ => data,
+    };
+    let bytes = general_purpose::STANDARD
+        .decode(encoded.trim())
+        .map_err(|e| format!("Failed to decode base64: {}", e))?;
+
+    let actual = image::guess_format(&bytes)
+        .map_err(|e| format!("无法识别图片格式: {}", e))?;
+    let img = image::load_from_memory_with_format(&bytes, actual)
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+
+    let expected = ImageFormat::from_path(path)
+        .map_err(|e| format!("无法根据扩展名确定图片格式: {}", e))?;
+    if actual == expected {
+        return Ok(bytes);
+    }
+    if !convert {
+        return Err(format!(
+            "图片数据格式为 {:?}, 与目标文件格式 {:?} 不一致",
+            actual, expected
+        ));
+    }
+
+    let mut buffer = Vec::new();
+    img.write_to(&mut std::io::Cursor::new(&mut buffer), expected)
+        .map_err(|e| format!("Failed to encode image: {}", e))?;
+    Ok(buffer)
+}
+
+/// 先写入同目录下的临时文件再重命名, 避免写入中断导致文件损坏
+pub fn write_file_atomic(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| format!("无效的路径: {}", path.display()))?;
+    let mut tmp_name = file_name.to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+
+    std::fs::write(&tmp_path, bytes)
+        .map_err(|e| format!("Failed to save image: {}", e))?;
+    std::fs::rename(&tmp_path, path).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp_path);
+        format!("Failed to save image: {}", e)
+    })
+}
+
+/// .mcmeta 中 animation.frames 的一项, 可以是帧序号或 {index, time}
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(untagged)]
+enum AnimationFrameSpec {
+    Index(u32),
+    Timed { index: u32, time: Option<u32> },
+}
+
+/// .mcmeta 中的 animation 对象
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default)]
+struct AnimationSpec {
+    frametime: Option<u32>,
+    frames: Option<Vec<AnimationFrameSpec>>,
+    interpolate: bool,
+    width: Option<u32>,
+    height: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+struct AnimationMcmeta {
+    animation: Option<AnimationSpec>,
+}
+
+/// 动画的一帧, duration 单位为游戏刻(1/20 秒)
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AnimationFrame {
+    pub index: u32,
+    pub duration: u32,
+    /// PNG base64
+    pub image: String,
+}
+
+/// 动画纹理预览
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AnimationPreview {
+    pub frame_width: u32,
+    pub frame_height: u32,
+    pub interpolate: bool,
+    /// 是否读取到了 .mcmeta 动画定义, 否则按正方形帧推断
+    pub has_mcmeta: bool,
+    pub frames: Vec<AnimationFrame>,
+}
+
+/// 按同目录下的 <name>.png.mcmeta 拆分动画纹理的各帧; 没有 mcmeta 时按正方形帧纵向切分
+pub fn animation_preview(path: &Path) -> Result<AnimationPreview, String> {
+    let mut mcmeta_name = path.file_name().unwrap_or_default().to_os_string();
+    mcmeta_name.push(".mcmeta");
+    let mcmeta_path = path.with_file_name(mcmeta_name);
+    let spec = if mcmeta_path.is_file() {
+        let content = std::fs::read_to_string(&mcmeta_path)
+            .map_err(|e| format!("Failed to read mcmeta: {}", e))?;
+        let mcmeta: AnimationMcmeta = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse mcmeta: {}", e))?;
+        mcmeta.animation
+    } else {
+        None
+    };
+    let has_mcmeta = spec.is_some();
+    let spec = spec.unwrap_or_default();
+
+    let img = image::open(path)
+        .map_err(|e| format!("Failed to open image: {}", e))?;
+    let (width, height) = (img.width(), img.height());
+    let side = width.min(height);
+    let frame_width = spec.width.unwrap_or(side);
+    let frame_height = spec.height.unwrap_or(side);
+    if frame_width == 0 || frame_height == 0 || frame_width > width || frame_height > height {
+        return Err(format!(
+            "帧尺寸 {}x{} 与图片尺寸 {}x{} 不符",
+            frame_width, frame_height, width, height
+        ));
+    }
+
+    // 帧按从左到右、从上到下的顺序排列
+    let columns = width / frame_width;
+    let frame_count = columns * (height / frame_height);
+    let frametime = spec.frametime.unwrap_or(1).max(1);
+    let order: Vec<(u32, u32)> = match spec.frames.filter(|frames| !frames.is_empty()) {
+        Some(frames) => frames
+            .into_iter()
+            .map(|frame| match frame {
+                AnimationFrameSpec::Index(index) => (index, frametime),
+                AnimationFrameSpec::Timed { index, time } => (index, time.unwrap_or(frametime).max(1)),
+            })
+            .collect(),
+        None => (0..frame_count).map(|index| (index, frametime)).collect(),
+    };
+
+    let mut frames = Vec::with_capacity(order.len());
+    for (index, duration) in order {
+        if index >= frame_count {
+            return Err(format!("帧序号 {} 超出范围, 共 {} 帧", index, frame_count));
+        }
+        let x = (index % columns) * frame_width;
+        let y = (index / columns) * frame_height;
+        let frame = img.crop_imm(x, y, frame_width, frame_height);
+        let mut buffer = Vec::new();
+        frame.write_to(&mut std::io::Cursor::new(&mut buffer), ImageFormat::Png)
+            .map_err(|e| format!("Failed to encode frame: {}", e))?;
+        frames.push(AnimationFrame {
+            index,
+            duration,
+            image: general_purpose::STANDARD.encode(&buffer),
+        });
+    }
+
+    Ok(AnimationPreview {
+        frame_width,
+        frame_height,
+        interpolate: spec.interpolate,
+        has_mcmeta,
+        frames,
+    })
+}
+
 /// 获取缓存统计信息
 #[allow(dead_code)]
 pub fn get_cache_stats() -> (usize, usize) {
     let thumb_cache = THUMBNAIL_CACHE.read();
     let info_cache = IMAGE_INFO_CACHE.read();
     (thumb_cache.len(), info_cache.len())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_base64_png(data: &str) -> DynamicImage {
+        let bytes = general_purpose::STANDARD.decode(data).unwrap();
+        image::load_from_memory_with_format(&bytes, ImageFormat::Png).unwrap()
+    }
+
+    #[test]
+    fn tga_textures_are_decoded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("stone.tga");
+        RgbaImage::from_pixel(16, 16, image::Rgba([120, 120, 120, 255]))
+            .save_with_format(&path, ImageFormat::Tga)
+            .unwrap();
+
+        assert!(is_image_path(&path));
+        let info = get_image_info(&path).unwrap();
+        assert_eq!((info.width, info.height), (16, 16));
+        assert!(info.is_valid_texture);
+
+        let thumbnail = decode_base64_png(&create_thumbnail(&path, 64).unwrap());
+        assert_eq!((thumbnail.width(), thumbnail.height()), (16, 16));
+        assert_eq!(thumbnail.to_rgba8().get_pixel(3, 3), &image::Rgba([120, 120, 120, 255]));
+    }
+
+    #[test]
+    fn grid_overlay_is_scaled_up() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dirt.png");
+        RgbaImage::from_pixel(16, 8, image::Rgba([255, 255, 255, 255])).save(&path).unwrap();
+
+        let preview = decode_base64_png(&create_grid_overlay(&path, 4).unwrap()).to_rgba8();
+        // 512 / 16 = 32 倍
+        assert_eq!(preview.dimensions(), (16 * 32, 8 * 32));
+        // 网格线被加深, 格子内部保持原色
+        assert!(preview.get_pixel(128, 10)[0] < 255);
+        assert_eq!(preview.get_pixel(140, 10), &image::Rgba([255, 255, 255, 255]));
+        // 原文件不变
+        assert_eq!(image::open(&path).unwrap().width(), 16);
+    }
+
+    #[test]
+    fn grid_size_zero_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dirt.png");
+        RgbaImage::new(16, 16).save(&path).unwrap();
+
+        assert!(create_grid_overlay(&path, 0).is_err());
+    }
+
+    #[test]
+    fn alpha_bleed_colors_transparent_neighbors() {
+        let mut img = RgbaImage::from_pixel(3, 1, image::Rgba([0, 0, 0, 0]));
+        img.put_pixel(0, 0, image::Rgba([200, 100, 50, 255]));
+
+        assert_eq!(alpha_bleed_image(&mut img), 2);
+        // 颜色逐圈扩展, alpha 保持 0
+        assert_eq!(img.get_pixel(1, 0), &image::Rgba([200, 100, 50, 0]));
+        assert_eq!(img.get_pixel(2, 0), &image::Rgba([200, 100, 50, 0]));
+        assert_eq!(img.get_pixel(0, 0), &image::Rgba([200, 100, 50, 255]));
+    }
+
+    #[test]
+    fn alpha_bleed_leaves_fully_transparent_image() {
+        let mut img = RgbaImage::new(2, 2);
+        assert_eq!(alpha_bleed_image(&mut img), 0);
+        assert!(img.pixels().all(|p| p.0 == [0, 0, 0, 0]));
+    }
+
+    /// 16x32 的两帧动画条, 上半红色、下半蓝色
+    fn write_strip(path: &Path) {
+        let mut strip = RgbaImage::from_pixel(16, 32, image::Rgba([255, 0, 0, 255]));
+        for y in 16..32 {
+            for x in 0..16 {
+                strip.put_pixel(x, y, image::Rgba([0, 0, 255, 255]));
+            }
+        }
+        strip.save(path).unwrap();
+    }
+
+    #[test]
+    fn animation_frames_follow_mcmeta() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lava.png");
+        write_strip(&path);
+        std::fs::write(
+            dir.path().join("lava.png.mcmeta"),
+            r#"{ "animation": { "frametime": 4, "interpolate": true, "frames": [1, { "index": 0, "time": 10 }] } }"#,
+        )
+        .unwrap();
+
+        let preview = animation_preview(&path).unwrap();
+        assert!(preview.has_mcmeta && preview.interpolate);
+        assert_eq!((preview.frame_width, preview.frame_height), (16, 16));
+        let frames: Vec<(u32, u32)> = preview.frames.iter().map(|f| (f.index, f.duration)).collect();
+        assert_eq!(frames, [(1, 4), (0, 10)]);
+
+        let first = decode_base64_png(&preview.frames[0].image).to_rgba8();
+        assert_eq!(first.dimensions(), (16, 16));
+        assert_eq!(first.get_pixel(0, 0), &image::Rgba([0, 0, 255, 255]));
+    }
+
+    #[test]
+    fn animation_frames_are_inferred_without_mcmeta() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("water.png");
+        write_strip(&path);
+
+        let preview = animation_preview(&path).unwrap();
+        assert!(!preview.has_mcmeta);
+        let frames: Vec<(u32, u32)> = preview.frames.iter().map(|f| (f.index, f.duration)).collect();
+        assert_eq!(frames, [(0, 1), (1, 1)]);
+    }
+
+    #[test]
+    fn out_of_range_frame_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fire.png");
+        write_strip(&path);
+        std::fs::write(dir.path().join("fire.png.mcmeta"), r#"{ "animation": { "frames": [0, 2] } }"#).unwrap();
+
+        assert!(animation_preview(&path).is_err());
+    }
+}
@@ -0,0 +1,285 @@
+use crate::commands::AppState;
+use crate::error::AppError;
+use crate::image_handler::alpha_bleed_image;
+use image::{imageops, RgbaImage};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::State;
+
+/// 翻转方向
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FlipDirection {
+    Horizontal,
+    Vertical,
+}
+
+/// 缩放采样方式, 像素画默认使用最近邻
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResizeFilter {
+    #[default]
+    Nearest,
+    Triangle,
+    Lanczos3,
+}
+
+impl From<ResizeFilter> for imageops::FilterType {
+    fn from(filter: ResizeFilter) -> Self {
+        match filter {
+            ResizeFilter::Nearest => imageops::FilterType::Nearest,
+            ResizeFilter::Triangle => imageops::FilterType::Triangle,
+            ResizeFilter::Lanczos3 => imageops::FilterType::Lanczos3,
+        }
+    }
+}
+
+/// 流水线中的单个图片操作
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ImageOp {
+    /// 将 RGB 与颜色相乘(与游戏中草、树叶的染色方式相同)
+    Tint { color: [u8; 3] },
+    Resize {
+        width: u32,
+        height: u32,
+        #[serde(default)]
+        filter: ResizeFilter,
+    },
+    Flip { direction: FlipDirection },
+    Crop { x: u32, y: u32, width: u32, height: u32 },
+    /// 将与 from 各通道差值都不超过 tolerance 的像素替换为 to
+    ReplaceColor {
+        from: [u8; 4],
+        to: [u8; 4],
+        #[serde(default)]
+        tolerance: u8,
+    },
+    AlphaBleed,
+}
+
+impl ImageOp {
+    /// 对图片执行操作
+    pub fn apply(&self, img: RgbaImage) -> Result<RgbaImage, String> {
+        match *self {
+            ImageOp::Tint { color } => {
+                let mut img = img;
+                for pixel in img.pixels_mut() {
+                    for c in 0..3 {
+                        pixel[c] = (pixel[c] as u16 * color[c] as u16 / 255) as u8;
+                    }
+                }
+                Ok(img)
+            }
+            ImageOp::Resize { width, height, filter } => {
+                if width == 0 || height == 0 {
+                    return Err("缩放尺寸不能为 0".to_string());
+                }
+                Ok(imageops::resize(&img, width, height, filter.into()))
+            }
+            ImageOp::Flip { direction } => Ok(match direction {
+                FlipDirection::Horizontal => imageops::flip_horizontal(&img),
+                FlipDirection::Vertical => imageops::flip_vertical(&img),
+            }),
+            ImageOp::Crop { x, y, width, height } => {
+                let in_bounds = width > 0
+                    && height > 0
+                    && x.checked_add(width).is_some_and(|right| right <= img.width())
+                    && y.checked_add(height).is_some_and(|bottom| bottom <= img.height());
+                if !in_bounds {
+                    return Err(format!(
+                        "裁剪区域 {}x{}+{}+{} 超出图片尺寸 {}x{}",
+                        width, height, x, y, img.width(), img.height()
+                    ));
+                }
+                Ok(imageops::crop_imm(&img, x, y, width, height).to_image())
+            }
+            ImageOp::ReplaceColor { from, to, tolerance } => {
+                let mut img = img;
+                for pixel in img.pixels_mut() {
+                    if pixel.0.iter().zip(from).all(|(&a, b)| a.abs_diff(b) <= tolerance) {
+                        pixel.0 = to;
+                    }
+                }
+                Ok(img)
+            }
+            ImageOp::AlphaBleed => {
+                let mut img = img;
+                alpha_bleed_image(&mut img);
+                Ok(img)
+            }
+        }
+    }
+}
+
+/// 单个文件的处理结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineResult {
+    pub path: String,
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+/// 依次执行所有操作, 按原文件的格式(PNG/TGA 等)编码后返回
+fn run_pipeline(path: &Path, operations: &[ImageOp]) -> Result<Vec<u8>, String> {
+    let format = image::ImageFormat::from_path(path)
+        .map_err(|e| format!("不支持的图片格式: {}", e))?;
+    let mut img = image::open(path)
+        .map_err(|e| format!("Failed to open image: {}", e))?
+        .to_rgba8();
+
+    for (index, operation) in operations.iter().enumerate() {
+        img = operation
+            .apply(img)
+            .map_err(|e| format!("第 {} 步失败: {}", index + 1, e))?;
+    }
+
+    let mut buffer = Vec::new();
+    img.write_to(&mut std::io::Cursor::new(&mut buffer), format)
+        .map_err(|e| format!("Failed to encode image: {}", e))?;
+    Ok(buffer)
+}
+
+/// 对多张纹理并行执行操作流水线, 写入前逐个写入历史记录
+pub fn apply_pipeline_to_pack(
+    pack_path: &Path,
+    relative_paths: &[String],
+    operations: &[ImageOp],
+) -> Vec<PipelineResult> {
+    let processed: Vec<_> = relative_paths
+        .par_iter()
+        .map(|relative| {
+            let result = crate::fs_utils::resolve_pack_path(pack_path, relative).and_then(|full_path| {
+                run_pipeline(&full_path, operations).map(|bytes| (full_path, bytes))
+            });
+            (relative, result)
+        })
+        .collect();
+
+    // 历史记录共用元数据文件, 需要顺序写入
+    processed
+        .into_iter()
+        .map(|(relative, result)| {
+            let written = result.and_then(|(full_path, bytes)| {
+                crate::history_manager::snapshot_file(pack_path, relative)?;
+                crate::image_handler::write_file_atomic(&full_path, &bytes)
+                    .map_err(|e| format!("写入 {} 失败: {}", relative, e))?;
+                crate::image_handler::invalidate_image_caches(&full_path);
+                Ok(())
+            });
+            PipelineResult {
+                path: relative.clone(),
+                ok: written.is_ok(),
+                error: written.err(),
+            }
+        })
+        .collect()
+}
+
+/// 对选中的纹理依次执行一组图片操作
+#[tauri::command]
+pub async fn apply_pipeline(
+    relative_paths: Vec<String>,
+    operations: Vec<ImageOp>,
+    state: State<'_, AppState>,
+) -> Result<Vec<PipelineResult>, AppError> {
+    if operations.is_empty() {
+        return Err(AppError::InvalidInput("操作列表不能为空".to_string()));
+    }
+    let base_path = state.pack_path()?;
+    let preloader = state.preloader_for_path(&base_path);
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    rayon::spawn(move || {
+        let _ = tx.send(apply_pipeline_to_pack(&base_path, &relative_paths, &operations));
+    });
+    let results = rx.await
+        .map_err(|e| AppError::Other(format!("Channel error: {}", e)))?;
+
+    for result in results.iter().filter(|result| result.ok) {
+        preloader.invalidate(&result.path.replace('\\', "/"));
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    fn write_texture(pack: &Path, relative: &str, img: &RgbaImage) {
+        let path = pack.join(relative);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        img.save(path).unwrap();
+    }
+
+    #[test]
+    fn two_step_pipeline_is_applied_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let relative = "assets/minecraft/textures/block/grass.png";
+        write_texture(dir.path(), relative, &RgbaImage::from_pixel(16, 16, Rgba([200, 200, 200, 255])));
+
+        let operations: Vec<ImageOp> = serde_json::from_str(
+            r#"[{ "type": "tint", "color": [255, 128, 0] }, { "type": "resize", "width": 8, "height": 4 }]"#,
+        )
+        .unwrap();
+        let results = apply_pipeline_to_pack(dir.path(), &[relative.to_string()], &operations);
+        assert!(results[0].ok, "{:?}", results[0].error);
+
+        let img = image::open(dir.path().join(relative)).unwrap().to_rgba8();
+        assert_eq!(img.dimensions(), (8, 4));
+        assert_eq!(img.get_pixel(0, 0), &Rgba([200, 100, 0, 255]));
+        assert_eq!(crate::history_manager::read_history_entries(dir.path(), relative).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn failing_step_leaves_file_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let relative = "assets/minecraft/textures/item/apple.png";
+        write_texture(dir.path(), relative, &RgbaImage::new(16, 16));
+        let operations = [
+            ImageOp::Flip { direction: FlipDirection::Horizontal },
+            ImageOp::Crop { x: 8, y: 8, width: 16, height: 16 },
+        ];
+
+        let results = apply_pipeline_to_pack(
+            dir.path(),
+            &[relative.to_string(), "assets/minecraft/textures/item/missing.png".to_string()],
+            &operations,
+        );
+        assert!(results.iter().all(|result| !result.ok));
+        assert!(results[0].error.as_deref().unwrap().starts_with("第 2 步失败"));
+        assert_eq!(image::open(dir.path().join(relative)).unwrap().width(), 16);
+        assert!(crate::history_manager::read_history_entries(dir.path(), relative).unwrap().is_empty());
+    }
+
+    #[test]
+    fn replace_color_respects_tolerance() {
+        let mut img = RgbaImage::from_pixel(2, 1, Rgba([100, 100, 100, 255]));
+        img.put_pixel(1, 0, Rgba([110, 100, 100, 255]));
+        let op = ImageOp::ReplaceColor { from: [100, 100, 100, 255], to: [0, 0, 0, 0], tolerance: 5 };
+
+        let img = op.apply(img).unwrap();
+        assert_eq!(img.get_pixel(0, 0), &Rgba([0, 0, 0, 0]));
+        assert_eq!(img.get_pixel(1, 0), &Rgba([110, 100, 100, 255]));
+    }
+
+    #[test]
+    fn tga_texture_stays_tga() {
+        let dir = tempfile::tempdir().unwrap();
+        let relative = "assets/minecraft/textures/entity/sign.tga";
+        let mut img = RgbaImage::new(4, 2);
+        img.put_pixel(0, 0, Rgba([255, 0, 0, 255]));
+        write_texture(dir.path(), relative, &img);
+
+        let operations = [ImageOp::Flip { direction: FlipDirection::Horizontal }];
+        let results = apply_pipeline_to_pack(dir.path(), &[relative.to_string()], &operations);
+        assert!(results[0].ok, "{:?}", results[0].error);
+
+        let bytes = std::fs::read(dir.path().join(relative)).unwrap();
+        assert!(!bytes.starts_with(b"\x89PNG"));
+        let flipped = image::load_from_memory_with_format(&bytes, image::ImageFormat::Tga).unwrap().to_rgba8();
+        assert_eq!(flipped.get_pixel(3, 0), &Rgba([255, 0, 0, 255]));
+    }
+}
@@ -0,0 +1,91 @@
+use crate::commands::{resolve_pack_path, AppState};
+use crate::error::AppError;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+use tauri::State;
+
+/// 格式化时允许的最大缩进
+const MAX_INDENT: usize = 8;
+
+/// 递归按键名排序对象
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Object(entries.into_iter().map(|(k, v)| (k, sort_keys(v))).collect())
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(sort_keys).collect()),
+        other => other,
+    }
+}
+
+fn to_pretty_string(value: &Value, indent: usize) -> Result<String, AppError> {
+    let indent = vec![b' '; indent.min(MAX_INDENT)];
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(&indent);
+    let mut output = Vec::new();
+    let mut serializer = serde_json::Serializer::with_formatter(&mut output, formatter);
+    value
+        .serialize(&mut serializer)
+        .map_err(|e| AppError::Other(format!("序列化 JSON 失败: {}", e)))?;
+    String::from_utf8(output).map_err(|e| AppError::Other(e.to_string()))
+}
+
+/// 解析文件并用 render 重新生成内容, 有变化时先保存历史记录再原子写入, 返回新内容
+///
+/// 解析失败时不修改文件; 结果总是以无 BOM 的 UTF-8 写入
+fn rewrite_json(
+    base_path: &Path,
+    file_path: &str,
+    render: impl FnOnce(Value) -> Result<String, AppError>,
+) -> Result<String, AppError> {
+    let full_path = resolve_pack_path(base_path, file_path)?;
+    let bytes = std::fs::read(&full_path).map_err(|e| AppError::io("读取文件失败", e))?;
+    let decoded = crate::text_encoding::decode_text(&bytes)
+        .map_err(|_| AppError::InvalidInput(format!("不是文本文件: {}", file_path)))?;
+    let value: Value = serde_json::from_str(&decoded.content)
+        .map_err(|e| AppError::Parse(format!("JSON 解析失败, 未修改文件: {}", e)))?;
+
+    let content = render(value)?;
+    if content == decoded.content && !decoded.had_bom {
+        return Ok(content);
+    }
+
+    let relative = full_path
+        .strip_prefix(base_path)
+        .map(|p| p.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_else(|_| file_path.to_string());
+    crate::history_manager::snapshot_file(base_path, &relative)?;
+    crate::image_handler::write_file_atomic(&full_path, content.as_bytes())?;
+    Ok(content)
+}
+
+/// 格式化 JSON 文件, 默认保留键的顺序, sort_keys 时按键名排序
+#[tauri::command]
+pub async fn format_json(
+    file_path: String,
+    indent: usize,
+    sort_keys: bool,
+    pack_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    let base_path = state.pack_path_for(pack_id.as_deref())?;
+    rewrite_json(&base_path, &file_path, |value| {
+        let value = if sort_keys { self::sort_keys(value) } else { value };
+        Ok(to_pretty_string(&value, indent)? + "\n")
+    })
+}
+
+/// 压缩 JSON 文件为单行
+#[tauri::command]
+pub async fn minify_json(
+    file_path: String,
+    pack_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    let base_path = state.pack_path_for(pack_id.as_deref())?;
+    rewrite_json(&base_path, &file_path, |value| {
+        serde_json::to_string(&value).map_err(|e| AppError::Other(format!("序列化 JSON 失败: {}", e)))
+    })
+}
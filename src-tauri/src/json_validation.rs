@@ -0,0 +1,259 @@
+use crate::commands::{resolve_read_path_in, AppState};
+use crate::error::AppError;
+use crate::validation::Severity;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::State;
+
+/// 片段中每行最多保留的字符数
+const SNIPPET_MAX_CHARS: usize = 200;
+
+/// JSON 文件中的一个问题, 行列均从 1 开始
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonProblem {
+    pub severity: Severity,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+    /// 问题所在行的内容
+    pub snippet: String,
+}
+
+/// 单个 JSON 文件的校验结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonValidationResult {
+    /// 相对材质包根目录的路径
+    pub path: String,
+    /// 没有错误(可以有警告)
+    pub valid: bool,
+    pub problems: Vec<JsonProblem>,
+}
+
+fn snippet_at(content: &str, line: usize) -> String {
+    content
+        .lines()
+        .nth(line.saturating_sub(1))
+        .unwrap_or_default()
+        .chars()
+        .take(SNIPPET_MAX_CHARS)
+        .collect()
+}
+
+/// 把注释和尾随逗号替换为空格(保留换行, 行列位置不变), 并为每处生成警告
+fn strip_lenient_syntax(content: &str) -> (String, Vec<(usize, usize, &'static str)>) {
+    let chars: Vec<char> = content.chars().collect();
+    let mut output = chars.clone();
+    let mut warnings = Vec::new();
+
+    // (行, 列) 按字符计算
+    let mut positions = Vec::with_capacity(chars.len());
+    let (mut line, mut column) = (1, 1);
+    for &c in &chars {
+        positions.push((line, column));
+        if c == '\n' {
+            line += 1;
+            column = 1;
+        } else {
+            column += 1;
+        }
+    }
+
+    // 第一遍: 注释
+    let mut i = 0;
+    let mut in_string = false;
+    while i < chars.len() {
+        let c = chars[i];
+        if in_string {
+            if c == '\\' {
+                i += 2;
+                continue;
+            }
+            if c == '"' {
+                in_string = false;
+            }
+            i += 1;
+            continue;
+        }
+        match (c, chars.get(i + 1)) {
+            ('"', _) => in_string = true,
+            ('/', Some('/')) => {
+                let (line, column) = positions[i];
+                warnings.push((line, column, "注释(原版不支持)"));
+                while i < chars.len() && chars[i] != '\n' {
+                    output[i] = ' ';
+                    i += 1;
+                }
+                continue;
+            }
+            ('/', Some('*')) => {
+                let (line, column) = positions[i];
+                warnings.push((line, column, "注释(原版不支持)"));
+                let start = i;
+                i += 2;
+                while i + 1 < chars.len() && !(chars[i] == '*' && chars[i + 1] == '/') {
+                    i += 1;
+                }
+                let end = (i + 2).min(chars.len());
+                for c in &mut output[start..end] {
+                    if *c != '\n' {
+                        *c = ' ';
+                    }
+                }
+                i = end;
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    // 第二遍: 后面紧跟 } 或 ] 的逗号
+    let mut in_string = false;
+    let mut escaped = false;
+    for i in 0..output.len() {
+        let c = output[i];
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        if c == '"' {
+            in_string = true;
+        } else if c == ',' {
+            let next = output[i + 1..].iter().find(|c| !c.is_whitespace());
+            if matches!(next, Some('}') | Some(']')) {
+                let (line, column) = positions[i];
+                warnings.push((line, column, "尾随逗号(原版不支持)"));
+                output[i] = ' ';
+            }
+        }
+    }
+
+    (output.into_iter().collect(), warnings)
+}
+
+/// 校验 JSON 文本; lenient 时注释和尾随逗号只作为警告
+pub fn validate_json_content(content: &str, had_bom: bool, lenient: bool) -> Vec<JsonProblem> {
+    let mut problems = Vec::new();
+    if had_bom {
+        problems.push(JsonProblem {
+            severity: Severity::Warning,
+            line: 1,
+            column: 1,
+            message: "文件带有 UTF-8 BOM".to_string(),
+            snippet: snippet_at(content, 1),
+        });
+    }
+
+    let stripped;
+    let text = if lenient {
+        let (text, warnings) = strip_lenient_syntax(content);
+        problems.extend(warnings.into_iter().map(|(line, column, message)| JsonProblem {
+            severity: Severity::Warning,
+            line,
+            column,
+            message: message.to_string(),
+            snippet: snippet_at(content, line),
+        }));
+        stripped = text;
+        stripped.as_str()
+    } else {
+        content
+    };
+
+    if let Err(e) = serde_json::from_str::<serde_json::Value>(text) {
+        let line = e.line().max(1);
+        problems.push(JsonProblem {
+            severity: Severity::Error,
+            line,
+            column: e.column().max(1),
+            message: e.to_string(),
+            snippet: snippet_at(content, line),
+        });
+    }
+
+    problems.sort_by_key(|problem| (problem.line, problem.column));
+    problems
+}
+
+fn validate_json_file(full_path: &Path, relative: String, lenient: bool) -> JsonValidationResult {
+    let problems = match std::fs::read(full_path) {
+        Ok(bytes) => match crate::text_encoding::decode_text(&bytes) {
+            Ok(decoded) => validate_json_content(&decoded.content, decoded.had_bom, lenient),
+            Err(e) => vec![JsonProblem {
+                severity: Severity::Error,
+                line: 1,
+                column: 1,
+                message: e,
+                snippet: String::new(),
+            }],
+        },
+        Err(e) => vec![JsonProblem {
+            severity: Severity::Error,
+            line: 1,
+            column: 1,
+            message: format!("读取失败: {}", e),
+            snippet: String::new(),
+        }],
+    };
+    JsonValidationResult {
+        path: relative,
+        valid: !problems.iter().any(|problem| problem.severity == Severity::Error),
+        problems,
+    }
+}
+
+/// 校验单个 JSON 文件, 返回错误位置和所在行
+#[tauri::command]
+pub async fn validate_json(
+    file_path: String,
+    lenient: Option<bool>,
+    pack_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<JsonValidationResult, AppError> {
+    let full_path = resolve_read_path_in(&state, pack_id.as_deref(), &file_path, None)?;
+    Ok(validate_json_file(&full_path, file_path.replace('\\', "/"), lenient.unwrap_or(false)))
+}
+
+/// 并行校验材质包中所有 .json / .mcmeta, 只返回有问题的文件
+#[tauri::command]
+pub async fn validate_all_json(
+    lenient: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<JsonValidationResult>, AppError> {
+    let base_path = state.pack_path()?;
+    let lenient = lenient.unwrap_or(false);
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    rayon::spawn(move || {
+        let files: Vec<_> = crate::pack_parser::visible_files(&base_path)
+            .into_iter()
+            .filter(|relative| {
+                relative.extension().is_some_and(|ext| {
+                    ext.eq_ignore_ascii_case("json") || ext.eq_ignore_ascii_case("mcmeta")
+                })
+            })
+            .collect();
+        let mut results: Vec<JsonValidationResult> = files
+            .par_iter()
+            .map(|relative| {
+                validate_json_file(
+                    &base_path.join(relative),
+                    relative.to_string_lossy().replace('\\', "/"),
+                    lenient,
+                )
+            })
+            .filter(|result| !result.problems.is_empty())
+            .collect();
+        results.sort_by(|a, b| a.path.cmp(&b.path));
+        let _ = tx.send(results);
+    });
+
+    rx.await.map_err(|e| AppError::Other(format!("Channel error: {}", e)))
+}
@@ -0,0 +1,79 @@
+use std::path::Path;
+
+/// 语言文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LangFormat {
+    /// 1.13+ 的 JSON 格式
+    Json,
+    /// 1.12 及之前的 key=value 格式
+    Legacy,
+}
+
+impl LangFormat {
+    /// 根据扩展名判断格式
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|s| s.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("lang") => LangFormat::Legacy,
+            _ => LangFormat::Json,
+        }
+    }
+}
+
+/// 解析语言文件内容, 按原文件顺序返回键值对
+pub fn parse_lang_content(content: &str, format: LangFormat) -> Result<Vec<(String, String)>, String> {
+    // 去掉可能存在的BOM
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+
+    match format {
+        LangFormat::Json => {
+            let value: serde_json::Value = serde_json::from_str(content)
+                .map_err(|e| format!("解析语言文件失败: {}", e))?;
+            let object = value.as_object().ok_or("语言文件根节点必须是对象")?;
+
+            Ok(object
+                .iter()
+                .map(|(key, value)| {
+                    let text = match value {
+                        serde_json::Value::String(s) => s.clone(),
+                        other => other.to_string(),
+                    };
+                    (key.clone(), text)
+                })
+                .collect())
+        }
+        LangFormat::Legacy => Ok(content
+            .lines()
+            .map(|line| line.trim_end_matches('\r'))
+            .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                line.split_once('=')
+                    .map(|(key, value)| (key.trim().to_string(), value.to_string()))
+            })
+            .collect()),
+    }
+}
+
+/// 读取并解析语言文件
+pub fn parse_lang_file(path: &Path) -> Result<Vec<(String, String)>, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("读取语言文件失败 {}: {}", path.display(), e))?;
+    parse_lang_content(&content, LangFormat::from_path(path))
+}
+
+/// 按指定格式序列化键值对, 保持传入顺序
+pub fn serialize_lang_entries(entries: &[(String, String)], format: LangFormat) -> Result<String, String> {
+    match format {
+        LangFormat::Json => {
+            let object: serde_json::Map<String, serde_json::Value> = entries
+                .iter()
+                .map(|(key, value)| (key.clone(), serde_json::Value::String(value.clone())))
+                .collect();
+            serde_json::to_string_pretty(&object)
+                .map_err(|e| format!("序列化语言文件失败: {}", e))
+        }
+        LangFormat::Legacy => Ok(entries
+            .iter()
+            .map(|(key, value)| format!("{}={}\n", key, value))
+            .collect()),
+    }
+}
@@ -0,0 +1,230 @@
+use crate::lang_parser::{parse_lang_file, serialize_lang_entries, LangFormat};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tauri::State;
+use crate::error::AppError;
+
+/// 翻译完整度检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LangCompleteness {
+    pub locale: String,
+    pub pack_file: String,
+    pub reference_file: String,
+    pub reference_key_count: usize,
+    pub translated_key_count: usize,
+    /// 原版有但材质包缺少的键
+    pub missing_keys: Vec<String>,
+    /// 材质包有但原版不存在的键
+    pub extra_keys: Vec<String>,
+    /// 与原版取值完全相同的键
+    pub identical_keys: Vec<String>,
+}
+
+/// 在命名空间的 lang 目录中查找指定语言的文件(忽略大小写, 支持 .json 和 .lang)
+pub fn find_lang_file(namespace_dir: &Path, locale: &str) -> Option<PathBuf> {
+    let lang_dir = namespace_dir.join("lang");
+    let mut candidates: Vec<PathBuf> = std::fs::read_dir(&lang_dir)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|path| {
+            let stem_matches = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .map(|stem| stem.eq_ignore_ascii_case(locale))
+                .unwrap_or(false);
+            let ext_matches = matches!(
+                path.extension().and_then(|s| s.to_str()),
+                Some("json") | Some("lang")
+            );
+            stem_matches && ext_matches
+        })
+        .collect();
+
+    // 同时存在时优先使用新版 .json
+    candidates.sort_by_key(|path| path.extension().and_then(|s| s.to_str()) != Some("json"));
+    candidates.into_iter().next()
+}
+
+/// 比对语言文件与参考文件
+pub fn compare_lang_entries(
+    pack_entries: &[(String, String)],
+    reference_entries: &[(String, String)],
+) -> (Vec<String>, Vec<String>, Vec<String>) {
+    let pack_map: HashMap<&str, &str> = pack_entries
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect();
+    let reference_keys: HashSet<&str> = reference_entries.iter().map(|(k, _)| k.as_str()).collect();
+
+    let mut missing = Vec::new();
+    let mut identical = Vec::new();
+    for (key, value) in reference_entries {
+        match pack_map.get(key.as_str()) {
+            Some(pack_value) if *pack_value == value => identical.push(key.clone()),
+            Some(_) => {}
+            None => missing.push(key.clone()),
+        }
+    }
+
+    let extra = pack_entries
+        .iter()
+        .filter(|(key, _)| !reference_keys.contains(key.as_str()))
+        .map(|(key, _)| key.clone())
+        .collect();
+
+    (missing, extra, identical)
+}
+
+/// 合并语言文件时的冲突处理策略
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum LangMergeStrategy {
+    /// 使用覆盖文件的值
+    KeepOverlay,
+    /// 保留基础文件的值
+    KeepBase,
+    /// 保留基础文件的值, 并在其后以带后缀的键写入覆盖文件的值
+    MarkConflicts,
+}
+
+/// 标记冲突时覆盖文件取值使用的键后缀
+pub const CONFLICT_KEY_SUFFIX: &str = ".__conflict";
+
+/// 语言文件合并结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LangMergeResult {
+    /// 覆盖文件新增的键
+    pub added: usize,
+    /// 被覆盖文件取值替换的键
+    pub overridden: usize,
+    /// 两边取值不同的键
+    pub conflicting: usize,
+    /// 输出文件的键总数
+    pub total: usize,
+}
+
+/// 将覆盖文件合并到基础文件上, 保持基础文件的键顺序, 新增键追加在末尾
+pub fn merge_lang_entries(
+    base: &[(String, String)],
+    overlay: &[(String, String)],
+    strategy: LangMergeStrategy,
+) -> (Vec<(String, String)>, LangMergeResult) {
+    let overlay_map: HashMap<&str, &str> = overlay
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect();
+    let base_keys: HashSet<&str> = base.iter().map(|(k, _)| k.as_str()).collect();
+
+    let mut result = LangMergeResult::default();
+    let mut merged = Vec::with_capacity(base.len() + overlay.len());
+
+    for (key, base_value) in base {
+        match overlay_map.get(key.as_str()) {
+            Some(overlay_value) if *overlay_value != base_value => {
+                result.conflicting += 1;
+                match strategy {
+                    LangMergeStrategy::KeepOverlay => {
+                        result.overridden += 1;
+                        merged.push((key.clone(), overlay_value.to_string()));
+                    }
+                    LangMergeStrategy::KeepBase => {
+                        merged.push((key.clone(), base_value.clone()));
+                    }
+                    LangMergeStrategy::MarkConflicts => {
+                        merged.push((key.clone(), base_value.clone()));
+                        merged.push((format!("{}{}", key, CONFLICT_KEY_SUFFIX), overlay_value.to_string()));
+                    }
+                }
+            }
+            _ => merged.push((key.clone(), base_value.clone())),
+        }
+    }
+
+    for (key, value) in overlay {
+        if !base_keys.contains(key.as_str()) {
+            result.added += 1;
+            merged.push((key.clone(), value.clone()));
+        }
+    }
+
+    result.total = merged.len();
+    (merged, result)
+}
+
+/// 合并两个语言文件(.json/.lang 任意组合), 输出格式由输出文件扩展名决定
+#[tauri::command]
+pub async fn merge_lang_files(
+    base_path: String,
+    overlay_path: String,
+    output_path: String,
+    strategy: LangMergeStrategy,
+    state: State<'_, crate::commands::AppState>,
+) -> Result<LangMergeResult, AppError> {
+    let base_entries = parse_lang_file(Path::new(&base_path))?;
+    let overlay_entries = parse_lang_file(Path::new(&overlay_path))?;
+    let (merged, result) = merge_lang_entries(&base_entries, &overlay_entries, strategy);
+
+    let output = PathBuf::from(&output_path);
+    let content = serialize_lang_entries(&merged, LangFormat::from_path(&output))?;
+
+    // 输出文件位于当前材质包内时, 先保存被覆盖的内容, 以便通过历史记录撤销合并
+    if let Ok(pack_path) = state.pack_path() {
+        if let Ok(relative) = output.strip_prefix(&pack_path) {
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            crate::history_manager::snapshot_text_file(&pack_path, &relative)?;
+        }
+    }
+
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("创建输出目录失败: {}", e))?;
+    }
+    std::fs::write(&output, &content)
+        .map_err(|e| format!("写入合并结果失败: {}", e))?;
+
+    Ok(result)
+}
+
+/// 检查材质包语言文件相对原版的翻译完整度
+#[tauri::command]
+pub async fn check_lang_completeness(
+    locale: String,
+    reference_path: Option<String>,
+    state: State<'_, crate::commands::AppState>,
+) -> Result<LangCompleteness, AppError> {
+    let base_path = state.pack_path()?;
+
+    let pack_file = find_lang_file(&base_path.join("assets").join("minecraft"), &locale)
+        .ok_or_else(|| format!("材质包中没有 {} 语言文件", locale))?;
+
+    // 参考文件: 指定的模板语言文件, 否则使用下载模板时保存的 map.json
+    let reference_file = match reference_path {
+        Some(path) => PathBuf::from(path),
+        None => base_path.join(".little100").join("map.json"),
+    };
+    if !reference_file.exists() {
+        return Err(AppError::NotFound("未找到原版参考语言文件, 请先下载模板或指定参考文件".to_string()));
+    }
+
+    let pack_entries = parse_lang_file(&pack_file)?;
+    let reference_entries = parse_lang_file(&reference_file)?;
+    let (missing_keys, extra_keys, identical_keys) =
+        compare_lang_entries(&pack_entries, &reference_entries);
+
+    Ok(LangCompleteness {
+        locale,
+        pack_file: pack_file
+            .strip_prefix(&base_path)
+            .unwrap_or(&pack_file)
+            .to_string_lossy()
+            .replace('\\', "/"),
+        reference_file: reference_file.to_string_lossy().to_string(),
+        reference_key_count: reference_entries.len(),
+        translated_key_count: reference_entries.len() - missing_keys.len(),
+        missing_keys,
+        extra_keys,
+        identical_keys,
+    })
+}
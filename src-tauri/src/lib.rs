@@ -1,4 +1,6 @@
+mod app_dirs;
 mod commands;
+mod error;
 mod image_handler;
 mod pack_parser;
 mod zip_handler;
@@ -8,6 +10,35 @@ mod version_downloader;
 mod preloader;
 mod download_manager;
 mod version_converter;
+mod audio_handler;
+mod lang_parser;
+mod validation;
+mod sound_tools;
+mod font_preview;
+mod model_resolver;
+mod lang_tools;
+mod pack_settings;
+mod pack_readme;
+mod pack_stats;
+mod checksum;
+mod upload;
+mod distribution;
+mod pack_backup;
+mod pack_trash;
+mod pack_optimizer;
+mod vanilla_assets;
+mod pack_hash;
+mod optifine;
+mod texture_checks;
+mod deploy;
+mod image_pipeline;
+mod fs_utils;
+mod pack_watcher;
+mod text_encoding;
+mod text_diff;
+mod json_validation;
+mod json_format;
+mod file_compare;
 
 #[cfg(feature = "web-server")]
 mod web_server;
@@ -22,28 +53,24 @@ use web_server::{WebServerState, start_server, stop_server, get_server_status};
 
 /// 初始化日志
 fn init_logging() {
-    // 获取exe目录
-    if let Ok(exe_path) = std::env::current_exe() {
-        if let Some(exe_dir) = exe_path.parent() {
-            let logs_dir = exe_dir.join("logs");
-            let log_file = logs_dir.join("latest.log");
-            
-            // 创建logs目录
-            let _ = std::fs::create_dir_all(&logs_dir);
-            
-            // 如果latest.log存在，删除它
-            if log_file.exists() {
-                let _ = std::fs::remove_file(&log_file);
-            }
-            
-            // 写入启动日志
-            use std::io::Write;
-            if let Ok(mut file) = std::fs::File::create(&log_file) {
-                let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
-                let _ = writeln!(file, "[{}] [INFO] 应用程序启动", timestamp);
-                let _ = writeln!(file, "[{}] [INFO] 日志系统初始化完成", timestamp);
-            }
-        }
+    let logs_dir = app_dirs::logs_dir();
+    let log_file = logs_dir.join("latest.log");
+
+    // 创建logs目录
+    let _ = std::fs::create_dir_all(&logs_dir);
+
+    // 如果latest.log存在，删除它(连同上次轮转的日志)
+    if log_file.exists() {
+        let _ = std::fs::remove_file(&log_file);
+    }
+    let _ = std::fs::remove_file(logs_dir.join("latest.1.log"));
+
+    // 写入启动日志
+    use std::io::Write;
+    if let Ok(mut file) = std::fs::File::create(&log_file) {
+        let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
+        let _ = writeln!(file, "[{}] [INFO] 应用程序启动", timestamp);
+        let _ = writeln!(file, "[{}] [INFO] 日志系统初始化完成", timestamp);
     }
 }
 
@@ -58,12 +85,18 @@ pub fn run() {
         .plugin(tauri_plugin_process::init())
         .manage(AppState::default())
         .setup(|app| {
+            // 确定数据目录(缓存、日志), 并迁移旧版本放在程序目录下的数据
+            app_dirs::init(app.handle());
+
             // 初始化日志系统
             init_logging();
             
             // 初始化下载管理器
             let download_manager = DownloadManager::new(app.handle().clone());
             app.manage(Arc::new(download_manager));
+
+            // 启动自动备份
+            pack_backup::start_auto_backup(app.handle().clone());
             
             // 初始化窗口
             let window = app.get_webview_window("main").unwrap();
@@ -91,13 +124,23 @@ pub fn run() {
     builder = builder.invoke_handler(tauri::generate_handler![
         import_pack_zip,
         import_pack_folder,
+        open_pack,
+        close_pack,
+        set_active_pack,
+        list_open_packs,
+        copy_between_packs,
         check_pack_mcmeta,
         get_current_pack_info,
         get_current_pack_path,
         get_image_thumbnail,
         get_image_preview,
         get_image_details,
+        get_file_metadata,
+        get_animation_preview,
+        get_texture_with_grid,
         export_pack,
+        export_pack_folder,
+        export_pack_subset,
         cleanup_temp,
         read_file_content,
         read_file_binary,
@@ -105,10 +148,19 @@ pub fn run() {
         create_new_file,
         create_new_folder,
         delete_file,
+        delete_files,
+        get_delete_impact,
         rename_file,
+        move_file,
+        batch_rename,
+        copy_file,
+        duplicate_file,
+        import_files,
         get_pack_mcmeta,
         update_pack_mcmeta,
         create_new_pack,
+        add_overlay,
+        remove_overlay,
         create_item_model,
         create_block_model,
         create_multiple_item_models,
@@ -123,25 +175,43 @@ pub fn run() {
         download_latest_minecraft_version,
         extract_assets_from_jar,
         download_and_extract_template,
+        list_jar_asset_folders,
+        get_template_cache_info,
         clear_template_cache,
+        clear_template_cache_version,
         preload_folder_images,
+        cancel_preload,
         get_preloader_stats,
         clear_preloader_cache,
         preload_folder_aggressive,
         get_debug_info,
         open_logs_folder,
+        open_temp_folder,
+        app_dirs::get_data_directory,
+        app_dirs::set_data_directory,
+        log_message,
+        set_log_max_size,
         load_language_map,
         get_sound_subtitles,
         search_files,
+        start_search,
+        cancel_search,
+        replace_in_files,
+        find_broken_model_references,
         download_minecraft_sounds,
+        retry_download_task,
         download_manager::get_all_download_tasks,
         download_manager::get_download_task,
+        download_manager::get_download_summary,
+        download_manager::set_max_concurrent_downloads,
         download_manager::cancel_download_task,
         download_manager::delete_download_task,
         download_manager::clear_completed_tasks,
         read_pack_mcmeta,
         get_supported_versions,
+        get_pack_version_range,
         convert_pack_version,
+        preview_conversion,
         fetch_url,
         check_file_exists,
         check_temp_audio_files,
@@ -150,10 +220,53 @@ pub fn run() {
         open_in_explorer,
         history_manager::save_file_history,
         history_manager::load_file_history,
+        history_manager::restore_file_history,
+        history_manager::diff_file_history,
         history_manager::get_history_stats,
         history_manager::clear_file_history,
         history_manager::clear_all_history,
+        history_manager::vacuum_history,
+        history_manager::prune_history,
         history_manager::get_pack_size,
+        pack_stats::get_pack_size_breakdown,
+        pack_stats::get_pack_size_summary,
+        pack_stats::list_files_modified_since,
+        checksum::generate_checksum_manifest,
+        upload::upload_pack,
+        distribution::export_distribution,
+        pack_backup::list_backups,
+        pack_backup::restore_backup,
+        pack_trash::list_trash,
+        pack_trash::restore_from_trash,
+        pack_trash::empty_trash,
+        pack_optimizer::remove_vanilla_duplicates,
+        vanilla_assets::get_vanilla_asset,
+        pack_hash::get_pack_hash,
+        optifine::get_properties_entries,
+        optifine::set_properties_entry,
+        optifine::validate_optifine,
+        texture_checks::find_transparency_halos,
+        texture_checks::alpha_bleed,
+        texture_checks::list_invalid_textures,
+        texture_checks::find_orphaned_textures,
+        deploy::deploy_to_minecraft,
+        image_pipeline::apply_pipeline,
+        audio_handler::get_audio_metadata,
+        json_validation::validate_json,
+        json_validation::validate_all_json,
+        json_format::format_json,
+        json_format::minify_json,
+        file_compare::compare_files,
+        sound_tools::validate_sounds,
+        sound_tools::dedupe_sounds_json,
+        font_preview::render_font_preview,
+        font_preview::validate_fonts,
+        model_resolver::resolve_model,
+        lang_tools::check_lang_completeness,
+        lang_tools::merge_lang_files,
+        pack_settings::get_pack_settings,
+        pack_settings::save_pack_settings,
+        pack_readme::generate_pack_readme,
         #[cfg(feature = "web-server")]
         start_server,
         #[cfg(feature = "web-server")]
@@ -0,0 +1,218 @@
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+use tauri::State;
+use crate::error::AppError;
+
+/// 将模型ID(如 "minecraft:block/cube_all" 或 "item/generated")拆分为命名空间和路径
+pub fn split_resource_id(id: &str) -> (&str, &str) {
+    id.split_once(':').unwrap_or(("minecraft", id))
+}
+
+/// 模型ID对应的相对路径
+pub fn model_id_to_relative_path(id: &str) -> PathBuf {
+    let (namespace, path) = split_resource_id(id);
+    Path::new("assets")
+        .join(namespace)
+        .join("models")
+        .join(format!("{}.json", path))
+}
+
+/// 由引擎内置, 没有对应文件的父模型
+pub fn is_builtin_model(id: &str) -> bool {
+    split_resource_id(id).1.starts_with("builtin/")
+}
+
+/// 在材质包和原版模板目录中查找模型文件
+fn find_model_file(id: &str, pack_path: &Path, template_dir: Option<&Path>) -> Option<PathBuf> {
+    let relative = model_id_to_relative_path(id);
+    std::iter::once(pack_path)
+        .chain(template_dir)
+        .map(|root| root.join(&relative))
+        .find(|path| path.is_file())
+}
+
+/// 读取模型JSON
+fn read_model(path: &Path) -> Result<Map<String, Value>, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("读取模型失败 {}: {}", path.display(), e))?;
+    match serde_json::from_str(&content) {
+        Ok(Value::Object(model)) => Ok(model),
+        Ok(_) => Err(format!("模型根节点必须是对象: {}", path.display())),
+        Err(e) => Err(format!("解析模型失败 {}: {}", path.display(), e)),
+    }
+}
+
+/// 按继承规则将子模型合并到父模型之上
+fn merge_model(parent: Map<String, Value>, child: Map<String, Value>) -> Map<String, Value> {
+    let mut merged = parent;
+
+    for (key, value) in child {
+        match (key.as_str(), merged.get_mut(&key), value) {
+            // textures 和 display 按键合并, 子模型优先
+            ("textures" | "display", Some(Value::Object(target)), Value::Object(source)) => {
+                for (k, v) in source {
+                    target.insert(k, v);
+                }
+            }
+            // elements 等其余字段由子模型整体覆盖
+            (_, _, value) => {
+                merged.insert(key, value);
+            }
+        }
+    }
+
+    merged
+}
+
+/// 递归解析模型的父模型链并合并
+pub fn resolve_model_file(
+    model_path: &Path,
+    pack_path: &Path,
+    template_dir: Option<&Path>,
+) -> Result<Value, String> {
+    let mut chain: Vec<Map<String, Value>> = Vec::new();
+    let mut visited: Vec<String> = Vec::new();
+    let mut builtin_parent: Option<String> = None;
+
+    let mut current = read_model(model_path)?;
+    loop {
+        let parent = current
+            .get("parent")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        chain.push(current);
+
+        let parent = match parent {
+            Some(parent) => parent,
+            None => break,
+        };
+
+        if is_builtin_model(&parent) {
+            builtin_parent = Some(parent);
+            break;
+        }
+
+        // 统一带上命名空间后再检测循环
+        let (namespace, path) = split_resource_id(&parent);
+        let qualified = format!("{}:{}", namespace, path);
+        if visited.contains(&qualified) {
+            visited.push(qualified);
+            return Err(format!("检测到循环继承: {}", visited.join(" -> ")));
+        }
+        visited.push(qualified);
+
+        let parent_file = find_model_file(&parent, pack_path, template_dir)
+            .ok_or_else(|| format!("找不到父模型: {}", parent))?;
+        current = read_model(&parent_file)?;
+    }
+
+    // 从最顶层父模型开始逐级合并
+    let mut resolved = Map::new();
+    for model in chain.into_iter().rev() {
+        resolved = merge_model(resolved, model);
+    }
+
+    resolved.remove("parent");
+    if let Some(parent) = builtin_parent {
+        resolved.insert("parent".to_string(), Value::String(parent));
+    }
+
+    Ok(Value::Object(resolved))
+}
+
+/// 解析并展开模型的完整父模型链
+#[tauri::command]
+pub async fn resolve_model(
+    relative_path: String,
+    template_dir: Option<String>,
+    state: State<'_, crate::commands::AppState>,
+) -> Result<Value, AppError> {
+    let base_path = state.pack_path()?;
+
+    let model_path = crate::commands::resolve_pack_path(&base_path, &relative_path)?;
+    if !model_path.is_file() {
+        return Err(AppError::NotFound(format!("模型文件不存在: {}", relative_path)));
+    }
+
+    Ok(resolve_model_file(
+        &model_path,
+        &base_path,
+        template_dir.as_deref().map(Path::new),
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn write_model(root: &Path, id: &str, model: Value) -> PathBuf {
+        let path = root.join(model_id_to_relative_path(id));
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, model.to_string()).unwrap();
+        path
+    }
+
+    #[test]
+    fn parent_chain_is_merged_child_first() {
+        let pack = tempfile::tempdir().unwrap();
+        let template = tempfile::tempdir().unwrap();
+        write_model(template.path(), "block/cube", json!({
+            "parent": "block/block",
+            "elements": [{ "from": [0, 0, 0], "to": [16, 16, 16] }],
+            "textures": { "particle": "#down" }
+        }));
+        write_model(template.path(), "block/block", json!({
+            "display": { "gui": { "scale": [0.625, 0.625, 0.625] } }
+        }));
+        write_model(pack.path(), "block/cube_all", json!({
+            "parent": "minecraft:block/cube",
+            "textures": { "particle": "#all", "down": "#all" }
+        }));
+        let model = write_model(pack.path(), "mypack:block/stone", json!({
+            "parent": "block/cube_all",
+            "textures": { "all": "mypack:block/stone" }
+        }));
+
+        let resolved = resolve_model_file(&model, pack.path(), Some(template.path())).unwrap();
+        assert_eq!(resolved["textures"], json!({
+            "particle": "#all",
+            "down": "#all",
+            "all": "mypack:block/stone"
+        }));
+        assert_eq!(resolved["elements"][0]["to"], json!([16, 16, 16]));
+        assert!(resolved["display"]["gui"].is_object());
+        assert!(resolved.get("parent").is_none());
+    }
+
+    #[test]
+    fn builtin_parent_is_kept() {
+        let pack = tempfile::tempdir().unwrap();
+        let model = write_model(pack.path(), "item/apple", json!({
+            "parent": "builtin/generated",
+            "textures": { "layer0": "item/apple" }
+        }));
+
+        let resolved = resolve_model_file(&model, pack.path(), None).unwrap();
+        assert_eq!(resolved["parent"], "builtin/generated");
+    }
+
+    #[test]
+    fn cyclic_parents_are_rejected() {
+        let pack = tempfile::tempdir().unwrap();
+        let model = write_model(pack.path(), "block/a", json!({ "parent": "block/b" }));
+        write_model(pack.path(), "block/b", json!({ "parent": "minecraft:block/a" }));
+
+        let error = resolve_model_file(&model, pack.path(), None).unwrap_err();
+        assert!(error.contains("循环继承"), "{}", error);
+    }
+
+    #[test]
+    fn missing_parent_is_reported() {
+        let pack = tempfile::tempdir().unwrap();
+        let model = write_model(pack.path(), "block/a", json!({ "parent": "block/missing" }));
+
+        let error = resolve_model_file(&model, pack.path(), None).unwrap_err();
+        assert!(error.contains("block/missing"), "{}", error);
+    }
+}
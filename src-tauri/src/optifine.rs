@@ -0,0 +1,226 @@
+use crate::commands::AppState;
+use crate::error::AppError;
+use crate::validation::{sort_issues, Severity, ValidationIssue};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+use walkdir::WalkDir;
+
+/// 引用纹理路径的键
+const TEXTURE_KEYS: &[&str] = &["tiles", "texture", "connectTiles"];
+
+/// .properties 文件中的一个键值对
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PropertiesEntry {
+    pub key: String,
+    pub value: String,
+    /// 从 1 开始的行号
+    pub line: usize,
+}
+
+/// 拆分一行为 (键, 值), 注释和空行返回 None
+fn split_property_line(line: &str) -> Option<(&str, &str)> {
+    let trimmed = line.trim_start();
+    if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with('!') {
+        return None;
+    }
+
+    let separator = trimmed.find(['=', ':'])?;
+    Some((trimmed[..separator].trim(), trimmed[separator + 1..].trim()))
+}
+
+/// 解析 .properties 内容, 按出现顺序返回
+pub fn parse_properties(content: &str) -> Vec<PropertiesEntry> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            split_property_line(line).map(|(key, value)| PropertiesEntry {
+                key: key.to_string(),
+                value: value.to_string(),
+                line: index + 1,
+            })
+        })
+        .collect()
+}
+
+/// 修改或追加一个键, 其余行(包括注释)保持不变
+pub fn set_property(content: &str, key: &str, value: &str) -> String {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+
+    let existing = lines
+        .iter()
+        .position(|line| split_property_line(line).is_some_and(|(k, _)| k == key));
+
+    match existing {
+        Some(index) => {
+            // 保留原有的缩进、键和分隔符写法
+            let line = &lines[index];
+            let separator = line.find(['=', ':']).unwrap_or(line.len() - 1);
+            let rest = &line[separator + 1..];
+            let spacing = &rest[..rest.len() - rest.trim_start().len()];
+            lines[index] = format!("{}{}{}", &line[..=separator], spacing, value);
+        }
+        None => lines.push(format!("{}={}", key, value)),
+    }
+
+    let mut result = lines.join("\n");
+    if content.is_empty() || content.ends_with('\n') {
+        result.push('\n');
+    }
+    result
+}
+
+/// 将 OptiFine 的纹理引用解析为材质包内的相对路径
+///
+/// 支持同目录文件名、./ 相对路径、~/ (optifine 根目录)、命名空间路径和数字范围
+fn resolve_texture_references(properties_relative: &Path, value: &str) -> Vec<PathBuf> {
+    let dir = properties_relative.parent().unwrap_or(Path::new(""));
+    let with_png = |path: PathBuf| {
+        if path.extension().is_some() { path } else { path.with_extension("png") }
+    };
+
+    let mut paths = Vec::new();
+    for token in value.split_whitespace() {
+        // <skip>、<default> 等特殊值不指向文件
+        if token.starts_with('<') {
+            continue;
+        }
+
+        if let Some((start, end)) = token.split_once('-') {
+            if let (Ok(start), Ok(end)) = (start.parse::<u32>(), end.parse::<u32>()) {
+                paths.extend((start..=end).map(|i| dir.join(format!("{}.png", i))));
+                continue;
+            }
+        }
+
+        let path = if let Some(rest) = token.strip_prefix("./") {
+            dir.join(rest)
+        } else if let Some(rest) = token.strip_prefix("~/") {
+            Path::new("assets/minecraft/optifine").join(rest)
+        } else if let Some((namespace, rest)) = token.split_once(':') {
+            Path::new("assets").join(namespace).join(rest)
+        } else if token.contains('/') {
+            Path::new("assets/minecraft").join(token)
+        } else {
+            dir.join(token)
+        };
+        paths.push(with_png(path));
+    }
+    paths
+}
+
+/// 检查 OptiFine .properties 中引用的纹理是否存在
+pub fn validate_optifine_references(base_path: &Path) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+
+    let properties_files = WalkDir::new(base_path.join("assets"))
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "properties"));
+
+    for entry in properties_files {
+        let relative = entry.path().strip_prefix(base_path).unwrap_or(entry.path()).to_path_buf();
+        let relative_str = relative.to_string_lossy().replace('\\', "/");
+        let content = match fs::read_to_string(entry.path()) {
+            Ok(content) => content,
+            Err(e) => {
+                issues.push(ValidationIssue::new(Severity::Error, "optifine", relative_str, format!("读取失败: {}", e)));
+                continue;
+            }
+        };
+
+        for property in parse_properties(&content) {
+            if !TEXTURE_KEYS.contains(&property.key.as_str()) && !property.key.starts_with("texture.") {
+                continue;
+            }
+            for texture in resolve_texture_references(&relative, &property.value) {
+                if !base_path.join(&texture).is_file() {
+                    issues.push(ValidationIssue::new(
+                        Severity::Error,
+                        "optifine",
+                        relative_str.clone(),
+                        format!(
+                            "第 {} 行 {} 引用的纹理不存在: {}",
+                            property.line,
+                            property.key,
+                            texture.to_string_lossy().replace('\\', "/")
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    sort_issues(&mut issues);
+    issues
+}
+
+fn properties_path(base_path: &Path, path: &str) -> Result<PathBuf, AppError> {
+    if Path::new(path).extension().is_none_or(|ext| ext != "properties") {
+        return Err(AppError::InvalidInput(format!("不是 .properties 文件: {}", path)));
+    }
+    crate::commands::resolve_pack_path(base_path, path)
+}
+
+/// 读取 .properties 文件的键值对
+#[tauri::command]
+pub async fn get_properties_entries(
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<PropertiesEntry>, AppError> {
+    let base_path = state.pack_path()?;
+    let full_path = properties_path(&base_path, &path)?;
+
+    let content = fs::read_to_string(&full_path)
+        .map_err(|e| AppError::io("读取文件失败", e))?;
+    Ok(parse_properties(&content))
+}
+
+/// 设置 .properties 文件中的一个键, 保留注释和顺序
+#[tauri::command]
+pub async fn set_properties_entry(
+    path: String,
+    key: String,
+    value: String,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let key = key.trim();
+    if key.is_empty() || key.contains(['=', ':', '\n']) {
+        return Err(AppError::InvalidInput(format!("无效的键: {}", key)));
+    }
+    if value.contains('\n') {
+        return Err(AppError::InvalidInput("值不能包含换行".to_string()));
+    }
+
+    let base_path = state.pack_path()?;
+    let full_path = properties_path(&base_path, &path)?;
+    let content = match fs::read_to_string(&full_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(AppError::io("读取文件失败", e)),
+    };
+
+    crate::history_manager::snapshot_text_file(&base_path, &path)?;
+    if let Some(parent) = full_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| AppError::io("创建目录失败", e))?;
+    }
+    fs::write(&full_path, set_property(&content, key, &value))
+        .map_err(|e| AppError::io("写入文件失败", e))
+}
+
+/// 检查 OptiFine 配置中引用的纹理
+#[tauri::command]
+pub async fn validate_optifine(state: State<'_, AppState>) -> Result<Vec<ValidationIssue>, AppError> {
+    let base_path = state.pack_path()?;
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    rayon::spawn(move || {
+        let _ = tx.send(validate_optifine_references(&base_path));
+    });
+    rx.await
+        .map_err(|e| AppError::Other(format!("Channel error: {}", e)))
+}
@@ -0,0 +1,288 @@
+use crate::commands::AppState;
+use crate::error::AppError;
+use crate::pack_parser::{is_hidden_relative_path, scan_pack_directory, visible_files};
+use crate::pack_settings::{load_pack_settings, AutoBackupSettings};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// 后台检查是否需要备份的间隔
+const BACKUP_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+const BACKUP_FILE_PREFIX: &str = "backup-";
+
+/// 备份文件信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupInfo {
+    pub file_name: String,
+    pub path: String,
+    pub size: u64,
+    pub created_at: String,
+}
+
+/// 恢复结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestoreResult {
+    /// 被覆盖或新增的文件
+    pub restored: usize,
+    /// 备份中不存在而被删除的文件
+    pub removed: usize,
+}
+
+/// 上一次备份时的状态
+struct BackupMark {
+    pack_path: PathBuf,
+    fingerprint: u64,
+    time: Instant,
+}
+
+/// 备份目录, 未配置时为 .little100/backups
+pub fn backup_dir(pack_path: &Path, settings: &AutoBackupSettings) -> PathBuf {
+    match settings.destination.as_deref().filter(|d| !d.trim().is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => pack_path.join(".little100").join("backups"),
+    }
+}
+
+/// 根据文件路径、大小和修改时间计算指纹, 用于判断材质包是否有变化
+pub fn pack_fingerprint(pack_path: &Path) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for relative in visible_files(pack_path) {
+        relative.hash(&mut hasher);
+        if let Ok(metadata) = fs::metadata(pack_path.join(&relative)) {
+            metadata.len().hash(&mut hasher);
+            metadata
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_nanos())
+                .hash(&mut hasher);
+        }
+    }
+    hasher.finish()
+}
+
+fn backup_info(path: &Path) -> Option<BackupInfo> {
+    let file_name = path.file_name()?.to_string_lossy().to_string();
+    if !file_name.starts_with(BACKUP_FILE_PREFIX) || !file_name.ends_with(".zip") {
+        return None;
+    }
+
+    let metadata = fs::metadata(path).ok()?;
+    let created_at = metadata
+        .modified()
+        .map(|t| chrono::DateTime::<chrono::Local>::from(t).to_rfc3339())
+        .unwrap_or_default();
+
+    Some(BackupInfo {
+        file_name,
+        path: path.to_string_lossy().to_string(),
+        size: metadata.len(),
+        created_at,
+    })
+}
+
+/// 列出备份, 按时间从新到旧排序
+pub fn list_backups_in(dir: &Path) -> Vec<BackupInfo> {
+    let mut backups: Vec<BackupInfo> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter_map(|e| backup_info(&e.path()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    // 文件名中的时间戳可直接按字典序比较
+    backups.sort_by(|a, b| b.file_name.cmp(&a.file_name));
+    backups
+}
+
+/// 打包备份(不含隐藏目录)并删除超出上限的旧备份
+pub fn create_backup(pack_path: &Path, settings: &AutoBackupSettings) -> Result<BackupInfo, String> {
+    let dir = backup_dir(pack_path, settings);
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("创建备份目录失败: {}", e))?;
+
+    let file_name = format!(
+        "{}{}.zip",
+        BACKUP_FILE_PREFIX,
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    );
+    let output = dir.join(&file_name);
+    crate::zip_handler::create_zip_filtered(
+        pack_path,
+        &output,
+        &[],
+        |relative| !is_hidden_relative_path(relative),
+        crate::zip_handler::ZipCompression::Default.file_options(),
+        None,
+    )?;
+
+    let max_backups = settings.max_backups.max(1) as usize;
+    for old in list_backups_in(&dir).into_iter().skip(max_backups) {
+        if let Err(e) = fs::remove_file(&old.path) {
+            eprintln!("[自动备份] 删除旧备份失败 {}: {}", old.file_name, e);
+        }
+    }
+
+    backup_info(&output).ok_or_else(|| "读取备份信息失败".to_string())
+}
+
+/// 在后台定期检查当前材质包, 有变化且到达间隔时自动备份
+///
+/// 首次检查到某个材质包时只记录指纹, 之后的修改才会触发备份
+pub fn start_auto_backup(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(BACKUP_CHECK_INTERVAL);
+        let mut last: Option<BackupMark> = None;
+
+        loop {
+            ticker.tick().await;
+
+            let Ok(pack_path) = app.state::<AppState>().pack_path() else {
+                continue;
+            };
+            let settings = match load_pack_settings(&pack_path) {
+                Ok(settings) if settings.auto_backup.enabled => settings.auto_backup,
+                _ => continue,
+            };
+
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            let fingerprint_path = pack_path.clone();
+            rayon::spawn(move || {
+                let _ = tx.send(pack_fingerprint(&fingerprint_path));
+            });
+            let Ok(fingerprint) = rx.await else {
+                continue;
+            };
+
+            let mark = match &last {
+                Some(mark) if mark.pack_path == pack_path => mark,
+                _ => {
+                    last = Some(BackupMark { pack_path, fingerprint, time: Instant::now() });
+                    continue;
+                }
+            };
+            let interval = Duration::from_secs(u64::from(settings.interval_minutes.max(1)) * 60);
+            if mark.fingerprint == fingerprint || mark.time.elapsed() < interval {
+                continue;
+            }
+
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            let backup_path = pack_path.clone();
+            rayon::spawn(move || {
+                let _ = tx.send(create_backup(&backup_path, &settings));
+            });
+
+            match rx.await {
+                Ok(Ok(info)) => {
+                    println!("[自动备份] 已创建 {}", info.file_name);
+                    last = Some(BackupMark { pack_path, fingerprint, time: Instant::now() });
+                    let _ = app.emit("auto-backup-completed", &info);
+                }
+                Ok(Err(e)) => eprintln!("[自动备份] 备份失败: {}", e),
+                Err(e) => eprintln!("[自动备份] Channel error: {}", e),
+            }
+        }
+    });
+}
+
+/// 用备份内容替换材质包(隐藏目录除外), 被覆盖和删除的文件先写入历史记录
+fn restore_from_zip(pack_path: &Path, zip_path: &Path) -> Result<RestoreResult, String> {
+    let temp_dir = crate::zip_handler::get_temp_extract_dir()
+        .join(format!("restore-{}", uuid::Uuid::new_v4()));
+    crate::zip_handler::extract_zip(zip_path, &temp_dir)?;
+
+    let result = apply_restore(pack_path, &temp_dir);
+    let _ = fs::remove_dir_all(&temp_dir);
+    result
+}
+
+fn apply_restore(pack_path: &Path, backup_root: &Path) -> Result<RestoreResult, String> {
+    let backup_files = visible_files(backup_root);
+    let backup_set: HashSet<&PathBuf> = backup_files.iter().collect();
+    let mut result = RestoreResult { restored: 0, removed: 0 };
+
+    for relative in &backup_files {
+        let source = backup_root.join(relative);
+        let target = pack_path.join(relative);
+        let relative_str = relative.to_string_lossy().replace('\\', "/");
+
+        if target.is_file() {
+            let unchanged = fs::read(&source).ok() == fs::read(&target).ok();
+            if unchanged {
+                continue;
+            }
+            crate::history_manager::snapshot_file(pack_path, &relative_str)?;
+        }
+
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("创建目录失败: {}", e))?;
+        }
+        fs::copy(&source, &target)
+            .map_err(|e| format!("恢复文件失败 {}: {}", relative_str, e))?;
+        result.restored += 1;
+    }
+
+    for relative in visible_files(pack_path) {
+        if backup_set.contains(&relative) {
+            continue;
+        }
+        let relative_str = relative.to_string_lossy().replace('\\', "/");
+        crate::history_manager::snapshot_file(pack_path, &relative_str)?;
+        fs::remove_file(pack_path.join(&relative))
+            .map_err(|e| format!("删除文件失败 {}: {}", relative_str, e))?;
+        result.removed += 1;
+    }
+
+    Ok(result)
+}
+
+/// 列出当前材质包的备份
+#[tauri::command]
+pub async fn list_backups(state: State<'_, AppState>) -> Result<Vec<BackupInfo>, AppError> {
+    let base_path = state.pack_path()?;
+    let settings = load_pack_settings(&base_path)?;
+
+    Ok(list_backups_in(&backup_dir(&base_path, &settings.auto_backup)))
+}
+
+/// 从备份恢复当前材质包
+#[tauri::command]
+pub async fn restore_backup(
+    file_name: String,
+    state: State<'_, AppState>,
+) -> Result<RestoreResult, AppError> {
+    let base_path = state.pack_path()?;
+    if !file_name.starts_with(BACKUP_FILE_PREFIX) || file_name.contains(['/', '\\']) || file_name.contains("..") {
+        return Err(AppError::InvalidInput(format!("无效的备份文件名: {}", file_name)));
+    }
+
+    let settings = load_pack_settings(&base_path)?;
+    let zip_path = backup_dir(&base_path, &settings.auto_backup).join(&file_name);
+    if !zip_path.is_file() {
+        return Err(AppError::NotFound(format!("备份不存在: {}", file_name)));
+    }
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let pack_path = base_path.clone();
+    rayon::spawn(move || {
+        let _ = tx.send(restore_from_zip(&pack_path, &zip_path));
+    });
+    let result = rx
+        .await
+        .map_err(|e| AppError::Other(format!("Channel error: {}", e)))??;
+
+    // 恢复后重新扫描, 保持缓存的材质包信息一致
+    if let Ok(pack_info) = scan_pack_directory(&base_path) {
+        state.set_pack_info(&base_path, pack_info);
+    }
+
+    Ok(result)
+}
@@ -1,36 +1,101 @@
 use std::fs;
-use std::path::Path;
-use serde_json::json;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use crate::pack_parser::{parse_formats_value, read_pack_format_range};
+
+/// overlay 目录及其适用的 pack_format 范围
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverlaySpec {
+    pub directory: String,
+    pub min_format: u32,
+    pub max_format: u32,
+}
+
+/// Windows 下保留的设备名
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL",
+    "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// 检查材质包名称能否作为文件夹名
+pub fn validate_pack_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("材质包名称不能为空".to_string());
+    }
+    if let Some(c) = name.chars().find(|c| c.is_control() || r#"<>:"/\|?*"#.contains(*c)) {
+        return Err(format!("材质包名称不能包含字符: {:?}", c));
+    }
+    if name.ends_with('.') || name.ends_with(' ') {
+        return Err("材质包名称不能以点或空格结尾".to_string());
+    }
+    let stem = name.split('.').next().unwrap_or(name).to_ascii_uppercase();
+    if RESERVED_NAMES.contains(&stem.as_str()) {
+        return Err(format!("材质包名称不能使用系统保留名: {}", name));
+    }
+    Ok(())
+}
+
+/// 目录存在且包含任何文件或子目录
+pub fn is_non_empty_dir(path: &Path) -> bool {
+    fs::read_dir(path)
+        .map(|mut entries| entries.next().is_some())
+        .unwrap_or(false)
+}
 
 /// 创建新的材质包
+///
+/// 目标目录已有 pack.mcmeta / pack.png 时, 仅在 overwrite_existing 为 true 时覆盖
 pub fn create_new_pack(
     output_path: &Path,
     _pack_name: &str,
     pack_format: i32,
     description: &str,
+    overlays: &[OverlaySpec],
+    overwrite_existing: bool,
 ) -> Result<(), String> {
+    for overlay in overlays {
+        validate_overlay(overlay)?;
+    }
+
     // 创建主目录
     fs::create_dir_all(output_path)
         .map_err(|e| format!("Failed to create pack directory: {}", e))?;
 
     // 创建 pack.mcmeta
-    let pack_mcmeta = json!({
+    let mut pack_mcmeta = json!({
         "pack": {
             "pack_format": pack_format,
             "description": description
         }
     });
+    if !overlays.is_empty() {
+        let entries = overlays.iter().map(overlay_entry).collect();
+        set_overlay_entries(&mut pack_mcmeta, entries);
+    }
 
-    let mcmeta_path = output_path.join("pack.mcmeta");
-    fs::write(
-        mcmeta_path,
-        serde_json::to_string_pretty(&pack_mcmeta)
-            .map_err(|e| format!("Failed to serialize pack.mcmeta: {}", e))?,
-    )
-    .map_err(|e| format!("Failed to write pack.mcmeta: {}", e))?;
+    if overwrite_existing || !output_path.join("pack.mcmeta").exists() {
+        write_pack_mcmeta(output_path, &pack_mcmeta)?;
+    }
+
+    create_assets_skeleton(output_path, pack_format)?;
+    for overlay in overlays {
+        create_assets_skeleton(&output_path.join(&overlay.directory), overlay.max_format as i32)?;
+    }
+
+    // 创建 pack.png (可选的图标)
+    if overwrite_existing || !output_path.join("pack.png").exists() {
+        create_default_pack_icon(output_path)?;
+    }
+
+    Ok(())
+}
 
+/// 在 root 下创建 assets/minecraft 标准目录结构
+fn create_assets_skeleton(root: &Path, pack_format: i32) -> Result<(), String> {
     // 创建 assets/minecraft 目录结构
-    let assets_path = output_path.join("assets").join("minecraft");
+    let assets_path = root.join("assets").join("minecraft");
     
     // 创建标准目录
     let directories = vec![
@@ -58,9 +123,132 @@ pub fn create_new_pack(
             .map_err(|e| format!("Failed to create directory {}: {}", dir, e))?;
     }
 
-    // 创建 pack.png (可选的图标)
-    create_default_pack_icon(output_path)?;
+    Ok(())
+}
+
+fn write_pack_mcmeta(pack_path: &Path, mcmeta: &Value) -> Result<(), String> {
+    let mcmeta_path = pack_path.join("pack.mcmeta");
+    fs::write(
+        mcmeta_path,
+        serde_json::to_string_pretty(mcmeta)
+            .map_err(|e| format!("Failed to serialize pack.mcmeta: {}", e))?,
+    )
+    .map_err(|e| format!("Failed to write pack.mcmeta: {}", e))
+}
+
+fn read_pack_mcmeta(pack_path: &Path) -> Result<Value, String> {
+    let content = fs::read_to_string(pack_path.join("pack.mcmeta"))
+        .map_err(|e| format!("Failed to read pack.mcmeta: {}", e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse pack.mcmeta: {}", e))
+}
+
+/// overlay 目录名只能包含小写字母、数字、下划线和连字符
+fn is_valid_overlay_directory(directory: &str) -> bool {
+    !directory.is_empty()
+        && directory
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+}
+
+fn validate_overlay(overlay: &OverlaySpec) -> Result<(), String> {
+    if !is_valid_overlay_directory(&overlay.directory) {
+        return Err(format!("无效的 overlay 目录名: {}", overlay.directory));
+    }
+    if overlay.min_format > overlay.max_format {
+        return Err(format!(
+            "overlay {} 的格式范围无效: {}-{}",
+            overlay.directory, overlay.min_format, overlay.max_format
+        ));
+    }
+    Ok(())
+}
+
+fn overlay_entry(overlay: &OverlaySpec) -> Value {
+    json!({
+        "formats": [overlay.min_format, overlay.max_format],
+        "directory": overlay.directory
+    })
+}
+
+/// overlay 条目的格式范围, 支持 formats 与 min_format/max_format 两种写法
+fn overlay_entry_range(entry: &Value) -> (u32, u32) {
+    entry
+        .get("formats")
+        .and_then(parse_formats_value)
+        .or_else(|| read_pack_format_range(entry))
+        .unwrap_or((0, 0))
+}
+
+fn overlay_entry_directory(entry: &Value) -> &str {
+    entry.get("directory").and_then(|d| d.as_str()).unwrap_or("")
+}
+
+/// 按格式范围排序后写入 overlays.entries
+fn set_overlay_entries(mcmeta: &mut Value, mut entries: Vec<Value>) {
+    entries.sort_by(|a, b| {
+        overlay_entry_range(a)
+            .cmp(&overlay_entry_range(b))
+            .then_with(|| overlay_entry_directory(a).cmp(overlay_entry_directory(b)))
+    });
+
+    if let Some(root) = mcmeta.as_object_mut() {
+        root.insert("overlays".to_string(), json!({ "entries": entries }));
+    }
+}
+
+fn overlay_entries(mcmeta: &Value) -> Vec<Value> {
+    mcmeta
+        .get("overlays")
+        .and_then(|o| o.get("entries"))
+        .and_then(|e| e.as_array())
+        .cloned()
+        .unwrap_or_default()
+}
 
+/// 为已有材质包添加 overlay
+pub fn add_overlay(pack_path: &Path, overlay: &OverlaySpec) -> Result<(), String> {
+    validate_overlay(overlay)?;
+
+    let mut mcmeta = read_pack_mcmeta(pack_path)?;
+    let mut entries = overlay_entries(&mcmeta);
+    if entries.iter().any(|e| overlay_entry_directory(e) == overlay.directory) {
+        return Err(format!("overlay {} 已存在", overlay.directory));
+    }
+
+    entries.push(overlay_entry(overlay));
+    set_overlay_entries(&mut mcmeta, entries);
+
+    create_assets_skeleton(&pack_path.join(&overlay.directory), overlay.max_format as i32)?;
+    write_pack_mcmeta(pack_path, &mcmeta)
+}
+
+/// 移除 overlay 条目, 目录移入材质包回收站
+pub fn remove_overlay(pack_path: &Path, directory: &str) -> Result<(), String> {
+    if !is_valid_overlay_directory(directory) {
+        return Err(format!("无效的 overlay 目录名: {}", directory));
+    }
+
+    let mut mcmeta = read_pack_mcmeta(pack_path)?;
+    let mut entries = overlay_entries(&mcmeta);
+    let count = entries.len();
+    entries.retain(|e| overlay_entry_directory(e) != directory);
+    if entries.len() == count {
+        return Err(format!("overlay {} 不存在", directory));
+    }
+
+    if entries.is_empty() {
+        if let Some(root) = mcmeta.as_object_mut() {
+            root.remove("overlays");
+        }
+    } else {
+        set_overlay_entries(&mut mcmeta, entries);
+    }
+    write_pack_mcmeta(pack_path, &mcmeta)?;
+
+    if pack_path.join(directory).is_dir() {
+        crate::pack_trash::move_to_trash(pack_path, &[directory.into()])?;
+    }
     Ok(())
 }
 
@@ -84,6 +272,35 @@ fn create_default_pack_icon(output_path: &Path) -> Result<(), String> {
     Ok(())
 }
 
+/// 创建物品模型可能写入的文件(相对路径)
+pub fn item_model_paths(item_ids: &[String]) -> Vec<PathBuf> {
+    let assets_path = Path::new("assets").join("minecraft");
+    item_ids
+        .iter()
+        .flat_map(|id| {
+            [
+                assets_path.join("items").join(format!("{}.json", id)),
+                assets_path.join("models").join("item").join(format!("{}.json", id)),
+            ]
+        })
+        .collect()
+}
+
+/// 创建方块模型可能写入的文件(相对路径)
+pub fn block_model_paths(block_ids: &[String]) -> Vec<PathBuf> {
+    let assets_path = Path::new("assets").join("minecraft");
+    block_ids
+        .iter()
+        .flat_map(|id| {
+            [
+                assets_path.join("blockstates").join(format!("{}.json", id)),
+                assets_path.join("models").join("block").join(format!("{}.json", id)),
+                assets_path.join("models").join("item").join(format!("{}.json", id)),
+            ]
+        })
+        .collect()
+}
+
 /// 为指定物品创建默认模型文件
 pub fn create_item_model(
     pack_path: &Path,
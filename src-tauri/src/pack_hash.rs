@@ -0,0 +1,145 @@
+use crate::checksum::{hash_file, HashAlgorithm};
+use crate::commands::AppState;
+use crate::error::AppError;
+use crate::pack_parser::is_hidden_relative_path;
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tauri::State;
+use walkdir::WalkDir;
+
+/// 已计算过的文件哈希, 以文件大小和修改时间判断是否需要重新计算
+#[derive(Clone)]
+struct CachedFileHash {
+    size: u64,
+    modified: Option<SystemTime>,
+    sha1: String,
+}
+
+static FILE_HASH_CACHE: Lazy<RwLock<HashMap<PathBuf, CachedFileHash>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// 材质包哈希
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackHash {
+    /// 所有文件 "路径 + 哈希" 按路径排序后再计算的 sha1
+    pub sha1: String,
+    pub file_count: usize,
+    /// 本次重新计算哈希的文件数
+    pub rehashed: usize,
+}
+
+/// 计算材质包内容的组合哈希(不含隐藏目录), 未变化的文件复用缓存
+pub fn compute_pack_hash(pack_path: &Path) -> Result<PackHash, String> {
+    let files: Vec<(String, PathBuf)> = WalkDir::new(pack_path)
+        .into_iter()
+        .filter_entry(|e| !is_hidden_relative_path(e.path().strip_prefix(pack_path).unwrap_or(e.path())))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| {
+            let relative = e
+                .path()
+                .strip_prefix(pack_path)
+                .unwrap_or(e.path())
+                .to_string_lossy()
+                .replace('\\', "/");
+            (relative, e.into_path())
+        })
+        .collect();
+
+    let mut hashes = files
+        .par_iter()
+        .map(|(relative, path)| {
+            let metadata = path
+                .metadata()
+                .map_err(|e| format!("获取文件信息失败 {}: {}", relative, e))?;
+            let (size, modified) = (metadata.len(), metadata.modified().ok());
+
+            let cached = FILE_HASH_CACHE.read().get(path).cloned();
+            if let Some(cached) = cached.filter(|c| c.size == size && c.modified == modified) {
+                return Ok((relative.as_str(), cached.sha1, false));
+            }
+
+            let sha1 = hash_file(HashAlgorithm::Sha1, path)?;
+            FILE_HASH_CACHE.write().insert(
+                path.clone(),
+                CachedFileHash { size, modified, sha1: sha1.clone() },
+            );
+            Ok((relative.as_str(), sha1, true))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    hashes.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut hasher = Sha1::new();
+    for (relative, sha1, _) in &hashes {
+        hasher.update(relative.as_bytes());
+        hasher.update([0]);
+        hasher.update(sha1.as_bytes());
+        hasher.update([b'\n']);
+    }
+
+    Ok(PackHash {
+        sha1: hex::encode(hasher.finalize()),
+        file_count: hashes.len(),
+        rehashed: hashes.iter().filter(|(_, _, rehashed)| *rehashed).count(),
+    })
+}
+
+/// 获取当前材质包的组合哈希, 小改动后只需重新计算改动的文件
+#[tauri::command]
+pub async fn get_pack_hash(state: State<'_, AppState>) -> Result<PackHash, AppError> {
+    let base_path = state.pack_path()?;
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    rayon::spawn(move || {
+        let _ = tx.send(compute_pack_hash(&base_path));
+    });
+    Ok(rx
+        .await
+        .map_err(|e| AppError::Other(format!("Channel error: {}", e)))??)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn editing_one_file_changes_the_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("pack.mcmeta"), "{}").unwrap();
+        std::fs::create_dir_all(dir.path().join("assets/minecraft/lang")).unwrap();
+        std::fs::write(dir.path().join("assets/minecraft/lang/en_us.json"), "{}").unwrap();
+
+        let first = compute_pack_hash(dir.path()).unwrap();
+        assert_eq!((first.file_count, first.rehashed), (2, 2));
+
+        // 未改动时全部命中缓存
+        let unchanged = compute_pack_hash(dir.path()).unwrap();
+        assert_eq!(unchanged.sha1, first.sha1);
+        assert_eq!(unchanged.rehashed, 0);
+
+        std::fs::write(dir.path().join("assets/minecraft/lang/en_us.json"), "{\"a\":\"b\"}").unwrap();
+        let edited = compute_pack_hash(dir.path()).unwrap();
+        assert_ne!(edited.sha1, first.sha1);
+        assert_eq!(edited.rehashed, 1);
+    }
+
+    #[test]
+    fn hidden_dirs_are_not_hashed() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("pack.mcmeta"), "{}").unwrap();
+        let before = compute_pack_hash(dir.path()).unwrap();
+
+        std::fs::create_dir_all(dir.path().join(".history")).unwrap();
+        std::fs::write(dir.path().join(".history/history_meta.json"), "{}").unwrap();
+        let after = compute_pack_hash(dir.path()).unwrap();
+        assert_eq!(after.sha1, before.sha1);
+        assert_eq!(after.file_count, 1);
+    }
+}
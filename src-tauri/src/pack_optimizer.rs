@@ -0,0 +1,171 @@
+use crate::checksum::{hash_file, hash_reader, HashAlgorithm};
+use crate::commands::AppState;
+use crate::error::AppError;
+use crate::pack_parser::{is_hidden_relative_path, scan_pack_directory};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use tauri::State;
+use walkdir::WalkDir;
+
+/// 原版 jar 中单个文件的大小和 sha1
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct VanillaFileHash {
+    size: u64,
+    sha1: String,
+}
+
+/// 去除原版重复文件的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VanillaDuplicateReport {
+    /// 与原版相同的文件(相对路径)
+    pub files: Vec<String>,
+    pub count: usize,
+    pub bytes_saved: u64,
+    pub dry_run: bool,
+    /// 文件移入的回收站条目, dry_run 时为空
+    pub trash_entry: Option<String>,
+}
+
+fn hash_manifest_path(cache_dir: &Path, version_id: &str) -> PathBuf {
+    cache_dir.join(format!("{}.hashes.json", version_id))
+}
+
+/// 计算 jar 中 assets 下所有文件的哈希
+fn build_hash_manifest(jar_path: &Path) -> Result<HashMap<String, VanillaFileHash>, String> {
+    let file = File::open(jar_path)
+        .map_err(|e| format!("打开jar文件失败: {}", e))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| format!("读取jar文件失败: {}", e))?;
+
+    let mut manifest = HashMap::new();
+    for i in 0..archive.len() {
+        let entry = archive.by_index(i)
+            .map_err(|e| format!("读取jar条目失败: {}", e))?;
+        let name = entry.name().to_string();
+        if entry.is_dir() || !name.starts_with("assets/") {
+            continue;
+        }
+
+        let size = entry.size();
+        let sha1 = hash_reader(HashAlgorithm::Sha1, entry)
+            .map_err(|e| format!("计算 {} 的哈希失败: {}", name, e))?;
+        manifest.insert(name, VanillaFileHash { size, sha1 });
+    }
+
+    Ok(manifest)
+}
+
+/// 读取版本的哈希清单, 不存在时从缓存的 jar 生成一次并保存
+async fn load_hash_manifest(version_id: &str) -> Result<HashMap<String, VanillaFileHash>, String> {
+    let cache_dir = crate::commands::template_cache_dir()?;
+    let manifest_path = hash_manifest_path(&cache_dir, version_id);
+
+    if let Ok(content) = fs::read_to_string(&manifest_path) {
+        if let Ok(manifest) = serde_json::from_str(&content) {
+            return Ok(manifest);
+        }
+    }
+
+    // jar 未缓存时先下载
+    fs::create_dir_all(&cache_dir)
+        .map_err(|e| format!("创建缓存目录失败: {}", e))?;
+    let jar_path = crate::version_downloader::download_version(version_id, &cache_dir).await?;
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    rayon::spawn(move || {
+        let _ = tx.send(build_hash_manifest(Path::new(&jar_path)));
+    });
+    let manifest = rx.await.map_err(|e| format!("Channel error: {}", e))??;
+
+    let json = serde_json::to_string(&manifest)
+        .map_err(|e| format!("序列化哈希清单失败: {}", e))?;
+    if let Err(e) = fs::write(&manifest_path, json) {
+        eprintln!("[优化材质包] 保存哈希清单失败: {}", e);
+    }
+
+    Ok(manifest)
+}
+
+/// 找出材质包 assets 下与原版内容完全相同的文件
+fn find_vanilla_duplicates(
+    pack_path: &Path,
+    manifest: &HashMap<String, VanillaFileHash>,
+) -> Vec<(PathBuf, u64)> {
+    let assets_dir = pack_path.join("assets");
+    let files: Vec<PathBuf> = WalkDir::new(&assets_dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.path().strip_prefix(pack_path).ok().map(Path::to_path_buf))
+        .filter(|relative| !is_hidden_relative_path(relative))
+        .collect();
+
+    let mut duplicates: Vec<(PathBuf, u64)> = files
+        .into_par_iter()
+        .filter_map(|relative| {
+            let key = relative.to_string_lossy().replace('\\', "/");
+            let vanilla = manifest.get(&key)?;
+            let full_path = pack_path.join(&relative);
+            let size = fs::metadata(&full_path).ok()?.len();
+            // 大小不同时无需计算哈希
+            if size != vanilla.size {
+                return None;
+            }
+            let sha1 = hash_file(HashAlgorithm::Sha1, &full_path).ok()?;
+            (sha1 == vanilla.sha1).then_some((relative, size))
+        })
+        .collect();
+
+    duplicates.sort_by(|a, b| a.0.cmp(&b.0));
+    duplicates
+}
+
+/// 删除与原版相同的文件以减小材质包体积, 文件移入材质包回收站
+#[tauri::command]
+pub async fn remove_vanilla_duplicates(
+    version_id: String,
+    dry_run: bool,
+    state: State<'_, AppState>,
+) -> Result<VanillaDuplicateReport, AppError> {
+    let base_path = state.pack_path()?;
+    let manifest = load_hash_manifest(&version_id).await?;
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let pack_path = base_path.clone();
+    rayon::spawn(move || {
+        let _ = tx.send(find_vanilla_duplicates(&pack_path, &manifest));
+    });
+    let duplicates = rx
+        .await
+        .map_err(|e| AppError::Other(format!("Channel error: {}", e)))?;
+
+    let files: Vec<String> = duplicates
+        .iter()
+        .map(|(relative, _)| relative.to_string_lossy().replace('\\', "/"))
+        .collect();
+    let bytes_saved = duplicates.iter().map(|(_, size)| size).sum();
+
+    let trash_entry = if dry_run || duplicates.is_empty() {
+        None
+    } else {
+        let relatives: Vec<PathBuf> = duplicates.into_iter().map(|(relative, _)| relative).collect();
+        let entry = crate::pack_trash::move_to_trash(&base_path, &relatives)?;
+        crate::fs_utils::remove_empty_dirs(&base_path.join("assets"));
+
+        if let Ok(pack_info) = scan_pack_directory(&base_path) {
+            state.set_pack_info(&base_path, pack_info);
+        }
+        Some(entry)
+    };
+
+    Ok(VanillaDuplicateReport {
+        count: files.len(),
+        files,
+        bytes_saved,
+        dry_run,
+        trash_entry,
+    })
+}
@@ -24,8 +24,77 @@ pub struct PackMeta {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PackMetaInfo {
+    /// 1.21.9+ 只写 min_format/max_format 时可以省略
+    #[serde(default)]
     pub pack_format: i32,
-    pub description: String,
+    /// 字符串或文本组件(对象/数组), 显示时用 flatten_text_component 展开
+    #[serde(default)]
+    pub description: serde_json::Value,
+    /// 1.20.2+: 整数 / [min, max] / {min_inclusive, max_inclusive}
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supported_formats: Option<serde_json::Value>,
+    /// 1.21.9+: 整数 / [major, minor]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_format: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_format: Option<serde_json::Value>,
+}
+
+impl PackMetaInfo {
+    /// 只有 pack_format 的元数据
+    fn with_format(pack_format: i32, description: String) -> Self {
+        Self {
+            pack_format,
+            description: serde_json::Value::String(description),
+            supported_formats: None,
+            min_format: None,
+            max_format: None,
+        }
+    }
+
+    /// 声明支持的 pack_format 范围, 没有范围字段时为 (pack_format, pack_format)
+    pub fn format_range(&self) -> (i32, i32) {
+        serde_json::to_value(self)
+            .ok()
+            .and_then(|pack| read_pack_format_range(&pack))
+            .map(|(min, max)| (min as i32, max as i32))
+            .unwrap_or((self.pack_format, self.pack_format))
+    }
+
+    /// 判断版本使用的格式: 声明了范围时取上限
+    pub fn effective_format(&self) -> i32 {
+        self.format_range().1
+    }
+
+    /// pack_format, 省略时取范围上限
+    pub fn declared_format(&self) -> i32 {
+        if self.pack_format > 0 {
+            self.pack_format
+        } else {
+            self.effective_format()
+        }
+    }
+}
+
+/// 将文本组件(字符串/数组/对象)展开为纯文本
+pub fn flatten_text_component(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Array(parts) => parts.iter().map(flatten_text_component).collect(),
+        serde_json::Value::Object(obj) => {
+            let mut text = obj
+                .get("text")
+                .or_else(|| obj.get("translate"))
+                .map(flatten_text_component)
+                .unwrap_or_default();
+            if let Some(extra) = obj.get("extra") {
+                text.push_str(&flatten_text_component(extra));
+            }
+            text
+        }
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    }
 }
 
 /// 资源类型
@@ -39,6 +108,8 @@ pub enum ResourceType {
     Language,
     Font,
     Shader,
+    /// OptiFine 的 CTM、CEM、随机实体等配置
+    Optifine,
     Other,
 }
 
@@ -59,6 +130,9 @@ pub struct PackInfo {
     pub name: String,
     pub version: MinecraftVersion,
     pub pack_format: i32,
+    /// 声明支持的 pack_format 范围(含), 只有 pack_format 时两者相同
+    pub min_format: i32,
+    pub max_format: i32,
     pub description: String,
     pub resources: HashMap<ResourceType, Vec<ResourceFile>>,
     pub namespaces: Vec<String>,
@@ -113,6 +187,11 @@ pub fn parse_resource_type(path: &Path, version: &MinecraftVersion) -> ResourceT
         return ResourceType::ItemModel;
     }
     
+    // optifine 目录下也有纹理, 需先于 textures 判断
+    if path_str.contains("/optifine/") || path_str.contains("/mcpatcher/") {
+        return ResourceType::Optifine;
+    }
+    
     if path_str.contains("/textures/") {
         ResourceType::Texture
     } else if path_str.contains("/models/") {
@@ -132,6 +211,66 @@ pub fn parse_resource_type(path: &Path, version: &MinecraftVersion) -> ResourceT
     }
 }
 
+/// 读取pack.mcmeta中声明的pack_format范围
+pub fn read_pack_format_range(pack: &serde_json::Value) -> Option<(u32, u32)> {
+    // supported_formats: 整数 / [min, max] / {min_inclusive, max_inclusive}
+    // min_format/max_format: 整数 / [major, minor]
+    let major = |v: &serde_json::Value| match v {
+        serde_json::Value::Array(parts) => parts.first().and_then(|p| p.as_u64()),
+        other => other.as_u64(),
+    };
+
+    let pack_format = pack.get("pack_format").and_then(|v| v.as_u64());
+
+    let range = if let (Some(min), Some(max)) = (
+        pack.get("min_format").and_then(major),
+        pack.get("max_format").and_then(major),
+    ) {
+        Some((min, max))
+    } else {
+        pack.get("supported_formats")
+            .and_then(parse_formats_value)
+            .map(|(min, max)| (min as u64, max as u64))
+    };
+
+    range
+        .or(pack_format.map(|f| (f, f)))
+        .map(|(min, max)| (min as u32, max as u32))
+}
+
+/// 解析 supported_formats / overlay formats 的取值: 整数 / [min, max] / {min_inclusive, max_inclusive}
+pub fn parse_formats_value(value: &serde_json::Value) -> Option<(u32, u32)> {
+    let range = match value {
+        serde_json::Value::Array(bounds) if bounds.len() == 2 => {
+            bounds[0].as_u64().zip(bounds[1].as_u64())
+        }
+        serde_json::Value::Object(obj) => obj
+            .get("min_inclusive")
+            .and_then(|v| v.as_u64())
+            .zip(obj.get("max_inclusive").and_then(|v| v.as_u64())),
+        other => other.as_u64().map(|f| (f, f)),
+    };
+    range.map(|(min, max)| (min as u32, max as u32))
+}
+
+/// 相对路径中是否包含隐藏目录或文件(以 . 开头, 如 .history、.little100)
+pub fn is_hidden_relative_path(path: &Path) -> bool {
+    path.components()
+        .any(|c| c.as_os_str().to_string_lossy().starts_with('.'))
+}
+
+/// 列出材质包中除隐藏目录外的所有文件(相对路径), 按文件名排序
+pub fn visible_files(pack_path: &Path) -> Vec<PathBuf> {
+    WalkDir::new(pack_path)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| !is_hidden_relative_path(e.path().strip_prefix(pack_path).unwrap_or(e.path())))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.path().strip_prefix(pack_path).ok().map(Path::to_path_buf))
+        .collect()
+}
+
 /// 从路径提取命名空间
 pub fn extract_namespace(path: &Path) -> Option<String> {
     let path_str = path.to_string_lossy();
@@ -147,8 +286,8 @@ pub fn extract_namespace(path: &Path) -> Option<String> {
     None
 }
 
-/// 扫描材质包目录
-pub fn scan_pack_directory(root_path: &Path) -> Result<PackInfo, String> {
+/// 读取 pack.mcmeta, 缺失或格式错误时使用默认值
+fn read_pack_meta(root_path: &Path) -> Result<PackMeta, String> {
     // 读取pack.mcmeta
     let mcmeta_path = root_path.join("pack.mcmeta");
     let pack_meta = if mcmeta_path.exists() {
@@ -161,24 +300,62 @@ pub fn scan_pack_directory(root_path: &Path) -> Result<PackInfo, String> {
             Err(e) => {
                 eprintln!("Warning: Failed to parse pack.mcmeta: {}. Using default values.", e);
                 PackMeta {
-                    pack: PackMetaInfo {
-                        pack_format: 34,
-                        description: format!("️pack.mcmeta格式错误: {}", e),
-                    }
+                    pack: PackMetaInfo::with_format(34, format!("️pack.mcmeta格式错误: {}", e)),
                 }
             }
         }
     } else {
         eprintln!("Warning: pack.mcmeta not found. Using default values.");
         PackMeta {
-            pack: PackMetaInfo {
-                pack_format: 34,
-                description: "️ pack.mcmeta文件不存在".to_string(),
-            }
+            pack: PackMetaInfo::with_format(34, "️ pack.mcmeta文件不存在".to_string()),
         }
     };
+    Ok(pack_meta)
+}
+
+/// 为 assets 下的文件生成资源信息
+fn resource_file(
+    root_path: &Path,
+    path: &Path,
+    version: &MinecraftVersion,
+    size: u64,
+) -> Option<ResourceFile> {
+    let namespace = extract_namespace(path)?;
+
+    // 解析资源类型
+    let resource_type = parse_resource_type(path, version);
+
+    // 获取相对路径
+    let relative_path = path
+        .strip_prefix(root_path)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .to_string();
+
+    // 获取文件名
+    let name = path
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+
+    Some(ResourceFile {
+        path: path.to_path_buf(),
+        relative_path,
+        resource_type,
+        namespace,
+        name,
+        size,
+    })
+}
+
+/// 扫描材质包目录
+pub fn scan_pack_directory(root_path: &Path) -> Result<PackInfo, String> {
+    let pack_meta = read_pack_meta(root_path)?;
 
-    let version = MinecraftVersion::from_pack_format(pack_meta.pack.pack_format);
+    let version = MinecraftVersion::from_pack_format(pack_meta.pack.effective_format());
+    let pack_format = pack_meta.pack.declared_format();
+    let (min_format, max_format) = pack_meta.pack.format_range();
     
     let resources: Arc<Mutex<HashMap<ResourceType, Vec<ResourceFile>>>> = 
         Arc::new(Mutex::new(HashMap::new()));
@@ -195,46 +372,18 @@ pub fn scan_pack_directory(root_path: &Path) -> Result<PackInfo, String> {
         entries.par_iter().for_each(|entry| {
             let path = entry.path();
             
-            if let Some(namespace) = extract_namespace(path) {
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            if let Some(resource) = resource_file(root_path, path, &version, size) {
                 {
                     let mut ns = namespaces.lock();
-                    if !ns.contains(&namespace) {
-                        ns.push(namespace.clone());
+                    if !ns.contains(&resource.namespace) {
+                        ns.push(resource.namespace.clone());
                     }
                 }
 
-                // 解析资源类型
-                let resource_type = parse_resource_type(path, &version);
-                
-                // 获取相对路径
-                let relative_path = path
-                    .strip_prefix(root_path)
-                    .unwrap_or(path)
-                    .to_string_lossy()
-                    .to_string();
-
-                // 获取文件名
-                let name = path
-                    .file_stem()
-                    .unwrap_or_default()
-                    .to_string_lossy()
-                    .to_string();
-
-                // 获取文件大小
-                let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
-
-                let resource = ResourceFile {
-                    path: path.to_path_buf(),
-                    relative_path,
-                    resource_type: resource_type.clone(),
-                    namespace,
-                    name,
-                    size,
-                };
-
                 // 更新资源列表
                 let mut res = resources.lock();
-                res.entry(resource_type)
+                res.entry(resource.resource_type.clone())
                     .or_insert_with(Vec::new)
                     .push(resource);
             }
@@ -258,9 +407,200 @@ pub fn scan_pack_directory(root_path: &Path) -> Result<PackInfo, String> {
             .to_string_lossy()
             .to_string(),
         version,
-        pack_format: pack_meta.pack.pack_format,
-        description: pack_meta.pack.description,
+        pack_format,
+        min_format,
+        max_format,
+        description: flatten_text_component(&pack_meta.pack.description),
         resources: final_resources,
         namespaces: final_namespaces,
     })
-}
\ No newline at end of file
+}
+
+/// 根据变更的路径增量更新资源列表, 只重新遍历变更的文件或子目录
+///
+/// changed 为相对材质包根目录的路径; pack.mcmeta 变更导致版本变化时退回完整扫描
+pub fn update_pack_info(info: &mut PackInfo, root_path: &Path, changed: &[PathBuf]) -> Result<(), String> {
+    if changed.iter().any(|p| p == Path::new("pack.mcmeta")) {
+        let pack_meta = read_pack_meta(root_path)?;
+        let version = MinecraftVersion::from_pack_format(pack_meta.pack.effective_format());
+        if version != info.version {
+            // 版本变化会影响资源类型的判断
+            *info = scan_pack_directory(root_path)?;
+            return Ok(());
+        }
+        let (min_format, max_format) = pack_meta.pack.format_range();
+        info.pack_format = pack_meta.pack.declared_format();
+        info.min_format = min_format;
+        info.max_format = max_format;
+        info.description = flatten_text_component(&pack_meta.pack.description);
+    }
+
+    for changed_path in changed.iter().filter(|p| p.starts_with("assets")) {
+        for list in info.resources.values_mut() {
+            list.retain(|r| !Path::new(&r.relative_path).starts_with(changed_path));
+        }
+
+        let full_path = root_path.join(changed_path);
+        if !full_path.exists() {
+            continue;
+        }
+        for entry in WalkDir::new(&full_path).into_iter().filter_map(|e| e.ok()) {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            if let Some(resource) = resource_file(root_path, entry.path(), &info.version, size) {
+                if !info.namespaces.contains(&resource.namespace) {
+                    info.namespaces.push(resource.namespace.clone());
+                }
+                info.resources
+                    .entry(resource.resource_type.clone())
+                    .or_default()
+                    .push(resource);
+            }
+        }
+    }
+
+    info.resources.retain(|_, list| !list.is_empty());
+    let resources = &info.resources;
+    info.namespaces
+        .retain(|ns| resources.values().flatten().any(|r| &r.namespace == ns));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn scan_with_mcmeta(mcmeta: serde_json::Value) -> PackInfo {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("pack.mcmeta"), mcmeta.to_string()).unwrap();
+        scan_pack_directory(dir.path()).unwrap()
+    }
+
+    #[test]
+    fn string_description_is_kept() {
+        let info = scan_with_mcmeta(json!({ "pack": { "pack_format": 34, "description": "My pack" } }));
+        assert_eq!(info.description, "My pack");
+    }
+
+    #[test]
+    fn object_description_is_flattened() {
+        let info = scan_with_mcmeta(json!({ "pack": {
+            "pack_format": 34,
+            "description": { "text": "My ", "color": "gold", "extra": [{ "text": "pack", "bold": true }] }
+        } }));
+        assert_eq!(info.description, "My pack");
+    }
+
+    #[test]
+    fn array_description_is_flattened() {
+        let info = scan_with_mcmeta(json!({ "pack": {
+            "pack_format": 34,
+            "description": ["", { "text": "My", "color": "red" }, " pack"]
+        } }));
+        assert_eq!(info.description, "My pack");
+    }
+
+    fn write(root: &Path, relative: &str, content: &str) {
+        let path = root.join(relative);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn relative_paths(info: &PackInfo, resource_type: &ResourceType) -> Vec<String> {
+        let mut paths: Vec<String> = info
+            .resources
+            .get(resource_type)
+            .into_iter()
+            .flatten()
+            .map(|r| r.relative_path.replace('\\', "/"))
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn incremental_update_only_walks_changed_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "pack.mcmeta", r#"{ "pack": { "pack_format": 34, "description": "" } }"#);
+        write(dir.path(), "assets/minecraft/models/block/stone.json", "{}");
+        let mut info = scan_pack_directory(dir.path()).unwrap();
+
+        write(dir.path(), "assets/mypack/models/block/ruby.json", "{}");
+        // 未列入变更的文件不会被扫描到, 说明没有重新遍历整个材质包
+        write(dir.path(), "assets/minecraft/models/block/dirt.json", "{}");
+        update_pack_info(&mut info, dir.path(), &[PathBuf::from("assets/mypack/models/block/ruby.json")]).unwrap();
+
+        assert_eq!(
+            relative_paths(&info, &ResourceType::Model),
+            ["assets/minecraft/models/block/stone.json", "assets/mypack/models/block/ruby.json"]
+        );
+        assert!(info.namespaces.contains(&"mypack".to_string()));
+    }
+
+    #[test]
+    fn incremental_update_drops_deleted_subtrees() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "pack.mcmeta", r#"{ "pack": { "pack_format": 34, "description": "" } }"#);
+        write(dir.path(), "assets/minecraft/models/block/stone.json", "{}");
+        write(dir.path(), "assets/mypack/models/block/ruby.json", "{}");
+        let mut info = scan_pack_directory(dir.path()).unwrap();
+
+        std::fs::remove_dir_all(dir.path().join("assets/mypack")).unwrap();
+        update_pack_info(&mut info, dir.path(), &[PathBuf::from("assets/mypack")]).unwrap();
+
+        assert_eq!(relative_paths(&info, &ResourceType::Model), ["assets/minecraft/models/block/stone.json"]);
+        assert!(!info.namespaces.contains(&"mypack".to_string()));
+    }
+
+    #[test]
+    fn mcmeta_change_updates_description() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "pack.mcmeta", r#"{ "pack": { "pack_format": 34, "description": "old" } }"#);
+        let mut info = scan_pack_directory(dir.path()).unwrap();
+
+        write(dir.path(), "pack.mcmeta", r#"{ "pack": { "pack_format": 34, "description": "new" } }"#);
+        update_pack_info(&mut info, dir.path(), &[PathBuf::from("pack.mcmeta")]).unwrap();
+        assert_eq!(info.description, "new");
+    }
+
+    #[test]
+    fn single_pack_format_is_its_own_range() {
+        let info = scan_with_mcmeta(json!({ "pack": { "pack_format": 15, "description": "" } }));
+        assert_eq!((info.pack_format, info.min_format, info.max_format), (15, 15, 15));
+        assert_eq!(info.version, MinecraftVersion::Components);
+    }
+
+    #[test]
+    fn supported_formats_upper_bound_picks_version() {
+        let info = scan_with_mcmeta(json!({ "pack": {
+            "pack_format": 15,
+            "description": "",
+            "supported_formats": { "min_inclusive": 15, "max_inclusive": 46 }
+        } }));
+        assert_eq!((info.pack_format, info.min_format, info.max_format), (15, 15, 46));
+        assert_eq!(info.version, MinecraftVersion::ItemsFolder);
+
+        let info = scan_with_mcmeta(json!({ "pack": {
+            "pack_format": 15,
+            "description": "",
+            "supported_formats": [15, 34]
+        } }));
+        assert_eq!((info.min_format, info.max_format), (15, 34));
+        assert_eq!(info.version, MinecraftVersion::NewModel);
+    }
+
+    #[test]
+    fn min_and_max_format_replace_pack_format() {
+        let info = scan_with_mcmeta(json!({ "pack": {
+            "description": "",
+            "min_format": [69, 0],
+            "max_format": 75
+        } }));
+        assert_eq!((info.pack_format, info.min_format, info.max_format), (75, 69, 75));
+        assert_eq!(info.version, MinecraftVersion::ItemsFolder);
+    }
+}
@@ -0,0 +1,204 @@
+use crate::pack_parser::{flatten_text_component, read_pack_format_range, scan_pack_directory, PackInfo};
+use crate::pack_settings::load_pack_settings;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use tauri::State;
+use crate::error::AppError;
+
+/// 说明文件格式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReadmeFormat {
+    /// credits.txt
+    #[default]
+    Text,
+    /// README.md
+    Markdown,
+}
+
+impl ReadmeFormat {
+    fn file_name(self) -> &'static str {
+        match self {
+            ReadmeFormat::Text => "credits.txt",
+            ReadmeFormat::Markdown => "README.md",
+        }
+    }
+
+    /// 自动生成区域的起止标记
+    fn markers(self) -> (&'static str, &'static str) {
+        match self {
+            ReadmeFormat::Text => (
+                "===== BEGIN GENERATED: Minecraft Resourcespack Editor =====",
+                "===== END GENERATED =====",
+            ),
+            ReadmeFormat::Markdown => (
+                "<!-- BEGIN GENERATED: Minecraft Resourcespack Editor -->",
+                "<!-- END GENERATED -->",
+            ),
+        }
+    }
+}
+
+/// 生成说明文件的选项
+#[derive(Debug, Clone, Deserialize)]
+pub struct PackReadmeOptions {
+    #[serde(default)]
+    pub format: ReadmeFormat,
+    /// 是否列出命名空间及覆盖文件数量
+    #[serde(default)]
+    pub include_namespaces: bool,
+}
+
+/// 生成自动维护部分的内容(不含标记)
+fn build_generated_section(
+    format: ReadmeFormat,
+    pack_info: &PackInfo,
+    mcmeta: &Value,
+    settings: &crate::pack_settings::PackSettings,
+    include_namespaces: bool,
+) -> String {
+    let pack = mcmeta.get("pack").cloned().unwrap_or(Value::Null);
+    let description = pack
+        .get("description")
+        .map(flatten_text_component)
+        .unwrap_or_default();
+    let pack_format = pack.get("pack_format").and_then(|v| v.as_u64());
+
+    let game_versions: Vec<String> = match read_pack_format_range(&pack) {
+        Some((min, max)) => crate::version_converter::get_supported_versions()
+            .into_iter()
+            .filter(|(f, _)| *f >= min && *f <= max)
+            .map(|(_, versions)| versions)
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let mut fields: Vec<(&str, String)> = vec![("Name", pack_info.name.clone())];
+    if !description.is_empty() {
+        fields.push(("Description", description));
+    }
+    if let Some(pack_format) = pack_format {
+        fields.push(("Pack format", pack_format.to_string()));
+    }
+    if !game_versions.is_empty() {
+        fields.push(("Game versions", game_versions.join(", ")));
+    }
+    if let Some(author) = settings.author.as_ref().filter(|s| !s.is_empty()) {
+        fields.push(("Author", author.clone()));
+    }
+    if let Some(license) = settings.license.as_ref().filter(|s| !s.is_empty()) {
+        fields.push(("License", license.clone()));
+    }
+
+    let mut lines = Vec::new();
+    match format {
+        ReadmeFormat::Text => {
+            for (label, value) in &fields {
+                lines.push(format!("{}: {}", label, value));
+            }
+        }
+        ReadmeFormat::Markdown => {
+            lines.push(format!("# {}", pack_info.name));
+            lines.push(String::new());
+            for (label, value) in fields.iter().skip(1) {
+                lines.push(format!("- **{}**: {}", label, value));
+            }
+        }
+    }
+
+    if include_namespaces {
+        // 统计每个命名空间下的文件数量
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for file in pack_info.resources.values().flatten() {
+            *counts.entry(file.namespace.as_str()).or_insert(0) += 1;
+        }
+
+        lines.push(String::new());
+        match format {
+            ReadmeFormat::Text => lines.push("Namespaces:".to_string()),
+            ReadmeFormat::Markdown => {
+                lines.push("## Namespaces".to_string());
+                lines.push(String::new());
+            }
+        }
+        for (namespace, count) in counts {
+            match format {
+                ReadmeFormat::Text => lines.push(format!("  {}: {} files", namespace, count)),
+                ReadmeFormat::Markdown => lines.push(format!("- `{}`: {} files", namespace, count)),
+            }
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// 将自动生成的内容写入已有文件的标记区域, 保留手写内容
+fn splice_generated_section(existing: Option<&str>, generated: &str, format: ReadmeFormat) -> String {
+    let (begin, end) = format.markers();
+    let block = format!("{}\n{}\n{}", begin, generated, end);
+
+    let existing = match existing {
+        Some(content) => content,
+        None => return format!("{}\n", block),
+    };
+
+    if let Some(start) = existing.find(begin) {
+        if let Some(end_offset) = existing[start..].find(end) {
+            let stop = start + end_offset + end.len();
+            return format!("{}{}{}", &existing[..start], block, &existing[stop..]);
+        }
+    }
+
+    // 没有标记时将生成内容放在最前面
+    format!("{}\n\n{}", block, existing)
+}
+
+/// 在材质包根目录生成 credits.txt 或 README.md
+#[tauri::command]
+pub async fn generate_pack_readme(
+    options: PackReadmeOptions,
+    state: State<'_, crate::commands::AppState>,
+) -> Result<String, AppError> {
+    let base_path = state.pack_path()?;
+
+    let pack_info = match state.pack_info() {
+        Some(info) => info,
+        None => scan_pack_directory(&base_path)?,
+    };
+
+    let mcmeta: Value = fs::read_to_string(base_path.join("pack.mcmeta"))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or(Value::Null);
+    let settings = load_pack_settings(&base_path)?;
+
+    let generated = build_generated_section(
+        options.format,
+        &pack_info,
+        &mcmeta,
+        &settings,
+        options.include_namespaces,
+    );
+
+    let file_name = options.format.file_name();
+    let output_path = base_path.join(file_name);
+    let existing = if output_path.exists() {
+        Some(fs::read_to_string(&output_path)
+            .map_err(|e| format!("读取 {} 失败: {}", file_name, e))?)
+    } else {
+        None
+    };
+
+    let content = splice_generated_section(existing.as_deref(), &generated, options.format);
+    if existing.as_deref() == Some(content.as_str()) {
+        return Ok(file_name.to_string());
+    }
+
+    crate::history_manager::snapshot_text_file(&base_path, file_name)?;
+    fs::write(&output_path, content)
+        .map_err(|e| format!("写入 {} 失败: {}", file_name, e))?;
+
+    Ok(file_name.to_string())
+}
@@ -0,0 +1,127 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+use crate::error::AppError;
+
+/// 材质包级别的编辑器设置, 保存在 .little100/settings.json
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PackSettings {
+    pub author: Option<String>,
+    /// 发布用的版本号, 如 1.2.0
+    pub version: Option<String>,
+    pub license: Option<String>,
+    pub auto_backup: AutoBackupSettings,
+    pub delete_guard: DeleteGuardSettings,
+    pub preview_warmup: PreviewWarmupSettings,
+}
+
+/// 自动备份设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoBackupSettings {
+    pub enabled: bool,
+    pub interval_minutes: u32,
+    pub max_backups: u32,
+    /// 备份目录, 为空时使用 .little100/backups
+    pub destination: Option<String>,
+}
+
+impl Default for AutoBackupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_minutes: 10,
+            max_backups: 10,
+            destination: None,
+        }
+    }
+}
+
+/// 永久删除文件夹的保护阈值, 超过任一阈值需要 force 才会删除
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeleteGuardSettings {
+    pub max_files: u64,
+    pub max_bytes: u64,
+}
+
+impl Default for DeleteGuardSettings {
+    fn default() -> Self {
+        Self {
+            max_files: 100,
+            max_bytes: 50 * 1024 * 1024,
+        }
+    }
+}
+
+/// 导入后预先生成常用纹理文件夹的缩略图
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PreviewWarmupSettings {
+    pub enabled: bool,
+    /// 每个文件夹最多预热的文件数
+    pub max_files_per_folder: usize,
+}
+
+impl Default for PreviewWarmupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_files_per_folder: 200,
+        }
+    }
+}
+
+fn settings_path(pack_path: &Path) -> PathBuf {
+    pack_path.join(".little100").join("settings.json")
+}
+
+/// 读取材质包设置, 文件不存在时返回默认值
+pub fn load_pack_settings(pack_path: &Path) -> Result<PackSettings, String> {
+    let path = settings_path(pack_path);
+    if !path.exists() {
+        return Ok(PackSettings::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("读取材质包设置失败: {}", e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("解析材质包设置失败: {}", e))
+}
+
+/// 写入材质包设置
+pub fn save_pack_settings_to(pack_path: &Path, settings: &PackSettings) -> Result<(), String> {
+    let path = settings_path(pack_path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("创建设置目录失败: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("序列化材质包设置失败: {}", e))?;
+    fs::write(&path, json)
+        .map_err(|e| format!("写入材质包设置失败: {}", e))
+}
+
+/// 获取当前材质包设置
+#[tauri::command]
+pub async fn get_pack_settings(
+    state: State<'_, crate::commands::AppState>,
+) -> Result<PackSettings, AppError> {
+    let base_path = state.pack_path()?;
+
+    Ok(load_pack_settings(&base_path)?)
+}
+
+/// 保存当前材质包设置
+#[tauri::command]
+pub async fn save_pack_settings(
+    settings: PackSettings,
+    state: State<'_, crate::commands::AppState>,
+) -> Result<(), AppError> {
+    let base_path = state.pack_path()?;
+
+    Ok(save_pack_settings_to(&base_path, &settings)?)
+}
@@ -0,0 +1,311 @@
+use crate::commands::AppState;
+use crate::error::AppError;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+use tauri::State;
+use walkdir::WalkDir;
+
+/// 目录大小树节点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SizeNode {
+    pub name: String,
+    pub size: u64,
+    pub file_count: u64,
+    pub children: Vec<SizeNode>,
+}
+
+/// 单个扩展名的大小统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtensionSize {
+    pub extension: String,
+    pub size: u64,
+    pub file_count: u64,
+}
+
+/// 材质包大小明细
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackSizeBreakdown {
+    pub total: u64,
+    /// assets 下按命名空间和一级子目录统计
+    pub assets: SizeNode,
+    pub history: u64,
+    /// 编辑器的其他内部数据(.little100、.trash 等隐藏目录)
+    pub editor_data: u64,
+    /// 其余文件(pack.mcmeta、pack.png 等)
+    pub other: u64,
+    /// 按扩展名统计, 不含隐藏目录
+    pub extensions: Vec<ExtensionSize>,
+}
+
+/// 材质包大小概览
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackSize {
+    /// 导出时实际包含的内容
+    pub pack_bytes: u64,
+    pub history_bytes: u64,
+    /// 声音镜像、回收站等编辑器内部数据
+    pub editor_data_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// 并行遍历时的累加器
+#[derive(Default)]
+struct SizeAccumulator {
+    assets: HashMap<(String, String), (u64, u64)>,
+    history: u64,
+    editor_data: u64,
+    other: u64,
+    extensions: HashMap<String, (u64, u64)>,
+}
+
+impl SizeAccumulator {
+    fn add(mut self, relative: &Path, size: u64) -> Self {
+        let components: Vec<String> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+
+        match components.first().map(|s| s.as_str()) {
+            Some(".history") => {
+                self.history += size;
+                return self;
+            }
+            Some(name) if name.starts_with('.') => {
+                self.editor_data += size;
+                return self;
+            }
+            Some("assets") if components.len() > 1 => {
+                // 直接位于命名空间目录下的文件记在空名称下
+                let namespace = if components.len() > 2 { components[1].clone() } else { String::new() };
+                let folder = if components.len() > 3 { components[2].clone() } else { String::new() };
+                let entry = self.assets.entry((namespace, folder)).or_default();
+                entry.0 += size;
+                entry.1 += 1;
+            }
+            _ => self.other += size,
+        }
+
+        let extension = relative
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let entry = self.extensions.entry(extension).or_default();
+        entry.0 += size;
+        entry.1 += 1;
+
+        self
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        for (key, (size, count)) in other.assets {
+            let entry = self.assets.entry(key).or_default();
+            entry.0 += size;
+            entry.1 += count;
+        }
+        for (key, (size, count)) in other.extensions {
+            let entry = self.extensions.entry(key).or_default();
+            entry.0 += size;
+            entry.1 += count;
+        }
+        self.history += other.history;
+        self.editor_data += other.editor_data;
+        self.other += other.other;
+        self
+    }
+}
+
+fn sort_nodes(nodes: &mut [SizeNode]) {
+    nodes.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+}
+
+/// 统计材质包各部分占用的空间
+pub fn calculate_size_breakdown(pack_path: &Path) -> PackSizeBreakdown {
+    let files: Vec<_> = WalkDir::new(pack_path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .collect();
+
+    let acc = files
+        .par_iter()
+        .fold(SizeAccumulator::default, |acc, entry| {
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            let relative = entry.path().strip_prefix(pack_path).unwrap_or(entry.path());
+            acc.add(relative, size)
+        })
+        .reduce(SizeAccumulator::default, SizeAccumulator::merge);
+
+    // 组装 assets/<命名空间>/<子目录> 两级树
+    let mut namespaces: HashMap<String, SizeNode> = HashMap::new();
+    for ((namespace, folder), (size, count)) in acc.assets {
+        let node = namespaces.entry(namespace.clone()).or_insert_with(|| SizeNode {
+            name: namespace,
+            size: 0,
+            file_count: 0,
+            children: Vec::new(),
+        });
+        node.size += size;
+        node.file_count += count;
+        node.children.push(SizeNode {
+            name: folder,
+            size,
+            file_count: count,
+            children: Vec::new(),
+        });
+    }
+
+    let mut namespace_nodes: Vec<SizeNode> = namespaces.into_values().collect();
+    for node in &mut namespace_nodes {
+        sort_nodes(&mut node.children);
+    }
+    sort_nodes(&mut namespace_nodes);
+
+    let assets = SizeNode {
+        name: "assets".to_string(),
+        size: namespace_nodes.iter().map(|n| n.size).sum(),
+        file_count: namespace_nodes.iter().map(|n| n.file_count).sum(),
+        children: namespace_nodes,
+    };
+
+    let mut extensions: Vec<ExtensionSize> = acc
+        .extensions
+        .into_iter()
+        .map(|(extension, (size, file_count))| ExtensionSize { extension, size, file_count })
+        .collect();
+    extensions.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.extension.cmp(&b.extension)));
+
+    PackSizeBreakdown {
+        total: assets.size + acc.history + acc.editor_data + acc.other,
+        assets,
+        history: acc.history,
+        editor_data: acc.editor_data,
+        other: acc.other,
+        extensions,
+    }
+}
+
+/// 统计导出内容、历史记录和编辑器数据各自的大小
+pub fn calculate_pack_size(pack_path: &Path) -> PackSize {
+    let breakdown = calculate_size_breakdown(pack_path);
+    PackSize {
+        pack_bytes: breakdown.assets.size + breakdown.other,
+        history_bytes: breakdown.history,
+        editor_data_bytes: breakdown.editor_data,
+        total_bytes: breakdown.total,
+    }
+}
+
+/// 获取材质包大小概览
+#[tauri::command]
+pub async fn get_pack_size_summary(pack_dir: String) -> Result<PackSize, AppError> {
+    let pack_path = Path::new(&pack_dir);
+    if !pack_path.is_dir() {
+        return Err(AppError::NotFound(format!("目录不存在: {}", pack_dir)));
+    }
+
+    let pack_path = pack_path.to_path_buf();
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    rayon::spawn(move || {
+        let _ = tx.send(calculate_pack_size(&pack_path));
+    });
+
+    rx.await
+        .map_err(|e| AppError::Other(format!("Channel error: {}", e)))
+}
+
+/// 获取材质包大小明细
+#[tauri::command]
+pub async fn get_pack_size_breakdown(pack_dir: String) -> Result<PackSizeBreakdown, AppError> {
+    let pack_path = Path::new(&pack_dir);
+    if !pack_path.is_dir() {
+        return Err(AppError::NotFound(format!("目录不存在: {}", pack_dir)));
+    }
+
+    let pack_path = pack_path.to_path_buf();
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    rayon::spawn(move || {
+        let _ = tx.send(calculate_size_breakdown(&pack_path));
+    });
+
+    rx.await
+        .map_err(|e| AppError::Other(format!("Channel error: {}", e)))
+}
+
+/// 修改过的文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModifiedFile {
+    pub relative_path: String,
+    /// 修改时间(Unix 秒)
+    pub modified: u64,
+}
+
+/// 列出修改时间晚于 since(Unix 秒)的文件, 不含隐藏目录, 按修改时间从新到旧排序
+pub fn files_modified_since(pack_path: &Path, since: u64) -> Vec<ModifiedFile> {
+    let mut files: Vec<ModifiedFile> = crate::pack_parser::visible_files(pack_path)
+        .into_par_iter()
+        .filter_map(|relative| {
+            let modified = std::fs::metadata(pack_path.join(&relative))
+                .and_then(|m| m.modified())
+                .ok()?
+                .duration_since(UNIX_EPOCH)
+                .ok()?
+                .as_secs();
+            (modified > since).then(|| ModifiedFile {
+                relative_path: relative.to_string_lossy().replace('\\', "/"),
+                modified,
+            })
+        })
+        .collect();
+
+    files.sort_by(|a, b| b.modified.cmp(&a.modified).then_with(|| a.relative_path.cmp(&b.relative_path)));
+    files
+}
+
+/// 列出指定时间之后修改过的文件
+#[tauri::command]
+pub async fn list_files_modified_since(
+    unix_secs: u64,
+    state: State<'_, AppState>,
+) -> Result<Vec<ModifiedFile>, AppError> {
+    let base_path = state.pack_path()?;
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    rayon::spawn(move || {
+        let _ = tx.send(files_modified_since(&base_path, unix_secs));
+    });
+    rx.await
+        .map_err(|e| AppError::Other(format!("Channel error: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn only_recently_modified_files_are_listed() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = dir.path().join("pack.mcmeta");
+        std::fs::write(&old, "{}").unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&old)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(3600))
+            .unwrap();
+        std::fs::create_dir_all(dir.path().join(".history")).unwrap();
+        std::fs::write(dir.path().join(".history/history_meta.json"), "{}").unwrap();
+
+        let since = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() - 60;
+        std::fs::create_dir_all(dir.path().join("assets/minecraft/lang")).unwrap();
+        std::fs::write(dir.path().join("assets/minecraft/lang/en_us.json"), "{}").unwrap();
+
+        let files = files_modified_since(dir.path(), since);
+        let paths: Vec<&str> = files.iter().map(|file| file.relative_path.as_str()).collect();
+        assert_eq!(paths, ["assets/minecraft/lang/en_us.json"]);
+        assert!(files[0].modified > since);
+    }
+}
@@ -0,0 +1,313 @@
+use crate::commands::AppState;
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+use walkdir::WalkDir;
+
+/// 删除某个路径会移除的内容
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeleteImpact {
+    pub files: u64,
+    pub folders: u64,
+    pub total_bytes: u64,
+}
+
+/// 统计删除 path 会移除的文件数、文件夹数(含自身)和总大小
+pub fn delete_impact(path: &Path) -> DeleteImpact {
+    let mut impact = DeleteImpact::default();
+    for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
+        if entry.file_type().is_dir() {
+            impact.folders += 1;
+        } else {
+            impact.files += 1;
+            impact.total_bytes += entry.metadata().map(|m| m.len()).unwrap_or(0);
+        }
+    }
+    impact
+}
+
+/// 将文件或文件夹移动到系统回收站
+pub fn move_to_recycle_bin(path: &Path) -> Result<(), String> {
+    trash::delete(path).map_err(|e| format!("移动到系统回收站失败: {}", e))
+}
+
+/// 材质包内回收站目录名
+pub const TRASH_DIR_NAME: &str = ".trash";
+
+/// 回收站条目名的时间格式
+const TRASH_ENTRY_FORMAT: &str = "%Y%m%d-%H%M%S-%3f";
+
+/// 材质包内回收站目录
+pub fn trash_dir(pack_path: &Path) -> PathBuf {
+    pack_path.join(TRASH_DIR_NAME)
+}
+
+/// 将一组文件移动到 .trash/<时间戳>/<相对路径>, 返回本次的条目名
+pub fn move_to_trash(pack_path: &Path, relatives: &[PathBuf]) -> Result<String, String> {
+    let entry_id = chrono::Local::now().format(TRASH_ENTRY_FORMAT).to_string();
+    let entry_dir = trash_dir(pack_path).join(&entry_id);
+
+    for relative in relatives {
+        let source = pack_path.join(relative);
+        let target = entry_dir.join(relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("创建回收站目录失败: {}", e))?;
+        }
+        fs::rename(&source, &target)
+            .map_err(|e| format!("移动到回收站失败 {}: {}", relative.display(), e))?;
+    }
+
+    Ok(entry_id)
+}
+
+/// 回收站中的一次删除
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashEntry {
+    pub id: String,
+    /// 删除时间(RFC3339)
+    pub deleted_at: Option<String>,
+    /// 条目中的文件(相对材质包根目录)
+    pub files: Vec<String>,
+    pub total_bytes: u64,
+}
+
+/// 恢复结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrashRestoreResult {
+    pub restored: Vec<String>,
+    /// 因 overwrite 覆盖的现有文件
+    pub overwritten: Vec<String>,
+}
+
+/// 回收站条目目录, 拒绝包含路径分隔符或 .. 的条目名
+fn trash_entry_dir(pack_path: &Path, entry_id: &str) -> Result<PathBuf, AppError> {
+    if entry_id.is_empty() || entry_id.contains(['/', '\\']) || entry_id.contains("..") {
+        return Err(AppError::InvalidInput(format!("无效的回收站条目: {}", entry_id)));
+    }
+    let dir = trash_dir(pack_path).join(entry_id);
+    if !dir.is_dir() {
+        return Err(AppError::NotFound(format!("回收站条目不存在: {}", entry_id)));
+    }
+    Ok(dir)
+}
+
+/// 条目中的所有文件, 返回相对条目目录(即相对材质包根目录)的路径
+fn trash_entry_files(entry_dir: &Path) -> Vec<PathBuf> {
+    WalkDir::new(entry_dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.path().strip_prefix(entry_dir).ok().map(Path::to_path_buf))
+        .collect()
+}
+
+/// 列出回收站中的条目, 最近删除的在前
+#[tauri::command]
+pub async fn list_trash(state: State<'_, AppState>) -> Result<Vec<TrashEntry>, AppError> {
+    Ok(list_trash_entries(&state.pack_path()?))
+}
+
+/// 读取 pack_path 回收站中的条目, 最近删除的在前
+pub fn list_trash_entries(pack_path: &Path) -> Vec<TrashEntry> {
+    let Ok(dirs) = fs::read_dir(trash_dir(pack_path)) else {
+        return Vec::new();
+    };
+
+    let mut entries: Vec<TrashEntry> = dirs
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+        .map(|e| {
+            let id = e.file_name().to_string_lossy().to_string();
+            let deleted_at = chrono::NaiveDateTime::parse_from_str(&id, TRASH_ENTRY_FORMAT)
+                .ok()
+                .and_then(|time| time.and_local_timezone(chrono::Local).single())
+                .map(|time| time.to_rfc3339());
+            let entry_dir = e.path();
+            let files = trash_entry_files(&entry_dir);
+            let total_bytes = files
+                .iter()
+                .filter_map(|file| fs::metadata(entry_dir.join(file)).ok())
+                .map(|m| m.len())
+                .sum();
+            TrashEntry {
+                id,
+                deleted_at,
+                files: files
+                    .iter()
+                    .map(|file| file.to_string_lossy().replace('\\', "/"))
+                    .collect(),
+                total_bytes,
+            }
+        })
+        .collect();
+
+    entries.sort_by(|a, b| b.id.cmp(&a.id));
+    entries
+}
+
+/// 将回收站条目恢复到原位置
+///
+/// 原位置已有文件时返回 AlreadyExists, overwrite 为 true 时覆盖(覆盖前保存历史记录)
+#[tauri::command]
+pub async fn restore_from_trash(
+    entry_id: String,
+    overwrite: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<TrashRestoreResult, AppError> {
+    let base_path = state.pack_path()?;
+    let result = restore_trash_entry(&base_path, &entry_id, overwrite.unwrap_or(false))?;
+
+    crate::image_handler::clear_caches();
+    state.preloader_for_path(&base_path).clear_cache().await;
+    let files: Vec<PathBuf> = result.restored.iter().map(PathBuf::from).collect();
+    state.refresh_pack_info(&base_path, Some(&files))?;
+
+    Ok(result)
+}
+
+/// 将 pack_path 回收站中的条目移回原位置, 不处理缓存
+pub fn restore_trash_entry(
+    base_path: &Path,
+    entry_id: &str,
+    overwrite: bool,
+) -> Result<TrashRestoreResult, AppError> {
+    let entry_dir = trash_entry_dir(base_path, entry_id)?;
+    let files = trash_entry_files(&entry_dir);
+
+    if let Some(file) = files.iter().find(|file| base_path.join(file).is_dir()) {
+        return Err(AppError::AlreadyExists(format!(
+            "同名文件夹已存在: {}",
+            file.to_string_lossy().replace('\\', "/")
+        )));
+    }
+    let conflicts: Vec<&PathBuf> = files
+        .iter()
+        .filter(|file| base_path.join(file).exists())
+        .collect();
+    if !conflicts.is_empty() && !overwrite {
+        return Err(AppError::AlreadyExists(format!(
+            "以下文件已存在, 需要确认后覆盖: {}",
+            conflicts
+                .iter()
+                .map(|file| file.to_string_lossy().replace('\\', "/"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )));
+    }
+
+    let mut result = TrashRestoreResult::default();
+    for file in &files {
+        let relative = file.to_string_lossy().replace('\\', "/");
+        let target = base_path.join(file);
+        if target.exists() {
+            crate::history_manager::snapshot_file(base_path, &relative)?;
+            result.overwritten.push(relative.clone());
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| AppError::io("创建目录失败", e))?;
+        }
+        crate::fs_utils::move_path(&entry_dir.join(file), &target)
+            .map_err(|e| AppError::io(&format!("恢复 {} 失败", relative), e))?;
+        result.restored.push(relative);
+    }
+
+    // 还原删除时的空文件夹
+    for dir in WalkDir::new(&entry_dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_dir())
+    {
+        if let Ok(relative) = dir.path().strip_prefix(&entry_dir) {
+            let _ = fs::create_dir_all(base_path.join(relative));
+        }
+    }
+    let _ = fs::remove_dir_all(&entry_dir);
+
+    Ok(result)
+}
+
+/// 永久删除回收站中的所有内容, 返回释放的文件数和大小
+#[tauri::command]
+pub async fn empty_trash(state: State<'_, AppState>) -> Result<DeleteImpact, AppError> {
+    let base_path = state.pack_path()?;
+    let dir = trash_dir(&base_path);
+    if !dir.exists() {
+        return Ok(DeleteImpact::default());
+    }
+
+    let mut impact = delete_impact(&dir);
+    // 不计回收站目录本身
+    impact.folders = impact.folders.saturating_sub(1);
+    fs::remove_dir_all(&dir).map_err(|e| AppError::io("清空回收站失败", e))?;
+    Ok(impact)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(pack: &Path, relative: &str, content: &str) {
+        let path = pack.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn trashed_files_are_listed_and_restored() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = "assets/minecraft/lang/en_us.json";
+        write(dir.path(), file, "{}");
+
+        let id = move_to_trash(dir.path(), &[PathBuf::from(file)]).unwrap();
+        assert!(!dir.path().join(file).exists());
+
+        let entries = list_trash_entries(dir.path());
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].id, id);
+        assert!(entries[0].deleted_at.is_some());
+        assert_eq!(entries[0].files, [file]);
+        assert_eq!(entries[0].total_bytes, 2);
+
+        let result = restore_trash_entry(dir.path(), &id, false).unwrap();
+        assert_eq!(result.restored, [file]);
+        assert!(result.overwritten.is_empty());
+        assert_eq!(fs::read_to_string(dir.path().join(file)).unwrap(), "{}");
+        assert!(list_trash_entries(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn restoring_onto_existing_file_requires_overwrite() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = "pack.mcmeta";
+        write(dir.path(), file, "old");
+        let id = move_to_trash(dir.path(), &[PathBuf::from(file)]).unwrap();
+        write(dir.path(), file, "new");
+
+        let err = restore_trash_entry(dir.path(), &id, false).unwrap_err();
+        assert!(matches!(err, AppError::AlreadyExists(_)));
+        // 冲突时不移动任何文件
+        assert_eq!(fs::read_to_string(dir.path().join(file)).unwrap(), "new");
+        assert_eq!(list_trash_entries(dir.path()).len(), 1);
+    }
+
+    #[test]
+    fn overwrite_snapshots_the_replaced_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = "pack.mcmeta";
+        write(dir.path(), file, "old");
+        let id = move_to_trash(dir.path(), &[PathBuf::from(file)]).unwrap();
+        write(dir.path(), file, "new");
+
+        let result = restore_trash_entry(dir.path(), &id, true).unwrap();
+        assert_eq!(result.overwritten, [file]);
+        assert_eq!(fs::read_to_string(dir.path().join(file)).unwrap(), "old");
+
+        let history = crate::history_manager::read_history_entries(dir.path(), file).unwrap();
+        assert_eq!(history.last().map(|entry| entry.content.as_str()), Some("new"));
+    }
+}
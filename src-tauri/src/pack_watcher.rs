@@ -0,0 +1,142 @@
+use crate::preloader::ImagePreloader;
+use notify::event::{EventKind, ModifyKind};
+use notify::RecursiveMode;
+use notify_debouncer_full::{new_debouncer, DebounceEventResult, Debouncer, RecommendedCache};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// 合并短时间内的多次变化后再通知
+const DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// 文件变化类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FsChangeKind {
+    Create,
+    Modify,
+    Delete,
+    Rename,
+}
+
+/// 单个文件的变化
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackFsChange {
+    /// 相对材质包根目录的路径
+    pub path: String,
+    pub kind: FsChangeKind,
+}
+
+/// pack-fs-changed 事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackFsChangedEvent {
+    pub pack_id: String,
+    pub changes: Vec<PackFsChange>,
+}
+
+/// 材质包目录的监听器, 释放时停止监听
+pub struct PackWatcher {
+    _debouncer: Debouncer<notify::RecommendedWatcher, RecommendedCache>,
+}
+
+fn change_kind(kind: &EventKind) -> Option<FsChangeKind> {
+    match kind {
+        EventKind::Create(_) => Some(FsChangeKind::Create),
+        EventKind::Modify(ModifyKind::Name(_)) => Some(FsChangeKind::Rename),
+        EventKind::Modify(_) => Some(FsChangeKind::Modify),
+        EventKind::Remove(_) => Some(FsChangeKind::Delete),
+        _ => None,
+    }
+}
+
+/// 相对材质包的路径, 位于忽略目录中或不在材质包内时返回 None
+fn relative_path(roots: &[PathBuf], path: &Path) -> Option<PathBuf> {
+    let relative = roots.iter().find_map(|root| path.strip_prefix(root).ok())?;
+    let first = relative.components().next()?;
+    // 编辑器自己写入的目录, 不监听以免自身写入触发事件
+    if first.as_os_str().to_str().is_some_and(crate::fs_utils::is_editor_data_dir) {
+        return None;
+    }
+    Some(relative.to_path_buf())
+}
+
+/// 开始监听材质包目录, 变化经防抖后以 pack-fs-changed 事件发送
+///
+/// 修改、删除或重命名的文件会同时从缩略图、图片信息和预加载缓存中移除
+pub fn watch_pack(
+    app: AppHandle,
+    pack_id: String,
+    pack_path: PathBuf,
+    preloader: Arc<ImagePreloader>,
+) -> Result<PackWatcher, String> {
+    // 事件中的路径可能是解析符号链接后的路径(如 macOS 的 /private/var)
+    let mut roots = vec![pack_path.clone()];
+    if let Ok(canonical) = pack_path.canonicalize() {
+        if canonical != pack_path {
+            roots.push(canonical);
+        }
+    }
+
+    let watched_path = pack_path.clone();
+    let mut debouncer = new_debouncer(DEBOUNCE_TIMEOUT, None, move |result: DebounceEventResult| {
+        let events = match result {
+            Ok(events) => events,
+            Err(errors) => {
+                for error in errors {
+                    eprintln!("[文件监听] {}: {}", pack_path.display(), error);
+                }
+                return;
+            }
+        };
+
+        let mut changes: Vec<PackFsChange> = Vec::new();
+        let mut changed_paths: Vec<PathBuf> = Vec::new();
+        for event in &events {
+            let Some(kind) = change_kind(&event.kind) else {
+                continue;
+            };
+            for path in &event.paths {
+                let Some(relative) = relative_path(&roots, path) else {
+                    continue;
+                };
+                let relative_str = relative.to_string_lossy().replace('\\', "/");
+
+                if kind != FsChangeKind::Create {
+                    crate::image_handler::invalidate_image_caches(&pack_path.join(&relative));
+                    preloader.invalidate(&relative.to_string_lossy());
+                }
+
+                // 同一文件多次变化只报告最后一次
+                changes.retain(|change| change.path != relative_str);
+                changes.push(PackFsChange { path: relative_str, kind });
+                if !changed_paths.contains(&relative) {
+                    changed_paths.push(relative);
+                }
+            }
+        }
+        if changes.is_empty() {
+            return;
+        }
+
+        let state = app.state::<crate::commands::AppState>();
+        if let Err(e) = state.refresh_pack_info(&pack_path, Some(&changed_paths)) {
+            eprintln!("[文件监听] 刷新材质包信息失败: {}", e);
+        }
+        let _ = app.emit(
+            "pack-fs-changed",
+            PackFsChangedEvent {
+                pack_id: pack_id.clone(),
+                changes,
+            },
+        );
+    })
+    .map_err(|e| format!("创建文件监听失败: {}", e))?;
+
+    debouncer
+        .watch(&watched_path, RecursiveMode::Recursive)
+        .map_err(|e| format!("监听目录失败 {}: {}", watched_path.display(), e))?;
+
+    Ok(PackWatcher { _debouncer: debouncer })
+}
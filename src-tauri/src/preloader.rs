@@ -2,51 +2,200 @@ use dashmap::DashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::Semaphore;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
+use tokio_util::sync::CancellationToken;
 use lru::LruCache;
 use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use serde::Serialize;
+
+/// 缓存容量上限
+#[derive(Debug, Clone, Copy)]
+enum CacheBudget {
+    /// 按条目数
+    Entries(usize),
+    /// 按缓存字符串的总字节数(近似内存占用)
+    Bytes(usize),
+}
+
+impl CacheBudget {
+    fn exceeded(&self, entries: usize, bytes: usize) -> bool {
+        match *self {
+            CacheBudget::Entries(max) => entries > max,
+            CacheBudget::Bytes(max) => bytes > max,
+        }
+    }
+
+    /// 清理到上限的 80%
+    fn trimmed(&self) -> Self {
+        match *self {
+            CacheBudget::Entries(max) => CacheBudget::Entries(max * 4 / 5),
+            CacheBudget::Bytes(max) => CacheBudget::Bytes(max / 5 * 4),
+        }
+    }
+}
+
+/// 预加载缓存统计
+#[derive(Debug, Clone, Serialize)]
+pub struct PreloaderStats {
+    pub cached_files: usize,
+    pub loading: usize,
+    /// 缓存中 base64 字符串的总字节数
+    pub cached_bytes: usize,
+    /// 按字节限制时的上限
+    pub byte_budget: Option<usize>,
+}
+
+/// 一条缓存的近似字节数
+fn entry_size(key: &str, data: &str) -> usize {
+    key.len() + data.len()
+}
+
+/// 饱和减法, 避免与 clear 并发时下溢
+fn sub_bytes(counter: &AtomicUsize, bytes: usize) {
+    let _ = counter.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |current| {
+        Some(current.saturating_sub(bytes))
+    });
+}
 
 pub struct ImagePreloader {
     cache: Arc<DashMap<String, String>>,
     lru_cache: Arc<RwLock<LruCache<String, String>>>,
     loading: Arc<DashMap<String, ()>>,
-    max_cache_size: usize,
+    budget: CacheBudget,
+    cache_bytes: Arc<AtomicUsize>,
+    lru_bytes: Arc<AtomicUsize>,
     semaphore: Arc<Semaphore>,
+    /// 后台预热只占用一个并发, 用户主动触发的加载优先
+    low_priority: Arc<Semaphore>,
+    /// 清空缓存时递增, 用于停止进行中的预热
+    generation: Arc<AtomicU64>,
+    /// 进行中的文件夹预加载(请求 id, 取消令牌), 新请求会取消旧请求
+    current_request: Arc<Mutex<Option<(String, CancellationToken)>>>,
 }
 
 impl ImagePreloader {
+    /// 按条目数限制缓存
+    #[allow(dead_code)]
     pub fn new(max_cache_size: usize) -> Self {
+        let lru = LruCache::new(NonZeroUsize::new(max_cache_size.max(1)).unwrap());
+        Self::with_budget(CacheBudget::Entries(max_cache_size), lru)
+    }
+
+    /// 按缓存内容的总字节数限制缓存
+    pub fn with_byte_budget(bytes: usize) -> Self {
+        Self::with_budget(CacheBudget::Bytes(bytes), LruCache::unbounded())
+    }
+
+    fn with_budget(budget: CacheBudget, lru: LruCache<String, String>) -> Self {
         let cpu_count = num_cpus::get();
         let concurrent_limit = (cpu_count * 2).max(4);
         
         Self {
             cache: Arc::new(DashMap::new()),
-            lru_cache: Arc::new(RwLock::new(
-                LruCache::new(NonZeroUsize::new(max_cache_size).unwrap())
-            )),
+            lru_cache: Arc::new(RwLock::new(lru)),
             loading: Arc::new(DashMap::new()),
-            max_cache_size,
+            budget,
+            cache_bytes: Arc::new(AtomicUsize::new(0)),
+            lru_bytes: Arc::new(AtomicUsize::new(0)),
             semaphore: Arc::new(Semaphore::new(concurrent_limit)),
+            low_priority: Arc::new(Semaphore::new(1)),
+            generation: Arc::new(AtomicU64::new(0)),
+            current_request: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// 开始一次文件夹预加载, 取消之前未完成的请求
+    pub fn begin_request(&self, request_id: &str) -> CancellationToken {
+        let token = CancellationToken::new();
+        let previous = self
+            .current_request
+            .lock()
+            .replace((request_id.to_string(), token.clone()));
+        if let Some((_, previous)) = previous {
+            previous.cancel();
+        }
+        token
+    }
+
+    /// 请求结束后移除其令牌(已被新请求替换时不处理)
+    pub fn finish_request(&self, request_id: &str) {
+        let mut current = self.current_request.lock();
+        if current.as_ref().is_some_and(|(id, _)| id == request_id) {
+            *current = None;
         }
     }
 
+    /// 取消预加载, request_id 为空时取消当前请求; 返回是否取消了请求
+    pub fn cancel_request(&self, request_id: Option<&str>) -> bool {
+        let mut current = self.current_request.lock();
+        let matches = current
+            .as_ref()
+            .is_some_and(|(id, _)| request_id.is_none_or(|request_id| id == request_id));
+        if !matches {
+            return false;
+        }
+        if let Some((_, token)) = current.take() {
+            token.cancel();
+        }
+        true
+    }
+
     #[allow(dead_code)]
     pub fn get(&self, path: &str) -> Option<String> {
         if let Some(data) = self.cache.get(path) {
             return Some(data.clone());
         }
         
-        let mut lru = self.lru_cache.write();
-        if let Some(data) = lru.get(path) {
-            self.cache.insert(path.to_string(), data.clone());
-            return Some(data.clone());
+        let data = self.lru_cache.write().get(path).cloned()?;
+        self.insert_cache(path.to_string(), data.clone());
+        if self.budget.exceeded(self.cache.len(), self.cache_bytes.load(Ordering::SeqCst)) {
+            self.trim_cache();
+        }
+        Some(data)
+    }
+
+    fn insert_cache(&self, key: String, data: String) {
+        self.cache_bytes.fetch_add(entry_size(&key, &data), Ordering::SeqCst);
+        if let Some(old) = self.cache.insert(key.clone(), data) {
+            sub_bytes(&self.cache_bytes, entry_size(&key, &old));
         }
-        
-        None
     }
 
-    /// 预加载单个图片
-    async fn preload_image(&self, path: PathBuf, base_path: &Path, max_size: u32) -> Result<(), String> {
+    /// 写入两级缓存, 超出上限时清理
+    fn store(&self, key: String, data: String) {
+        self.insert_cache(key.clone(), data.clone());
+
+        {
+            let mut lru = self.lru_cache.write();
+            self.lru_bytes.fetch_add(entry_size(&key, &data), Ordering::SeqCst);
+            // push 返回同键的旧值或按容量淘汰的条目
+            if let Some((old_key, old)) = lru.push(key, data) {
+                sub_bytes(&self.lru_bytes, entry_size(&old_key, &old));
+            }
+            if let CacheBudget::Bytes(max) = self.budget {
+                while self.lru_bytes.load(Ordering::SeqCst) > max {
+                    let Some((old_key, old)) = lru.pop_lru() else {
+                        break;
+                    };
+                    sub_bytes(&self.lru_bytes, entry_size(&old_key, &old));
+                }
+            }
+        }
+
+        if self.budget.exceeded(self.cache.len(), self.cache_bytes.load(Ordering::SeqCst)) {
+            self.trim_cache();
+        }
+    }
+
+    /// 预加载单个图片, 等待并发许可时被取消则返回错误
+    async fn preload_image(
+        &self,
+        path: PathBuf,
+        base_path: &Path,
+        max_size: u32,
+        cancel: Option<&CancellationToken>,
+    ) -> Result<(), String> {
         let relative_path = path
             .strip_prefix(base_path)
             .unwrap_or(&path)
@@ -66,8 +215,18 @@ impl ImagePreloader {
         // 标记为正在加载
         self.loading.insert(relative_path.clone(), ());
 
-        let _permit = self.semaphore.acquire().await
-            .map_err(|e| format!("Semaphore error: {}", e))?;
+        let permit = match cancel {
+            Some(token) => tokio::select! {
+                _ = token.cancelled() => None,
+                permit = self.semaphore.acquire() => Some(permit),
+            },
+            None => Some(self.semaphore.acquire().await),
+        };
+        let Some(permit) = permit else {
+            self.loading.remove(&relative_path);
+            return Err("预加载已取消".to_string());
+        };
+        let _permit = permit.map_err(|e| format!("Semaphore error: {}", e))?;
 
         let path_clone = path.clone();
         let (tx, rx) = tokio::sync::oneshot::channel();
@@ -79,14 +238,7 @@ impl ImagePreloader {
 
         match rx.await {
             Ok(Ok(data)) => {
-                self.cache.insert(relative_path.clone(), data.clone());
-                
-                let mut lru = self.lru_cache.write();
-                lru.put(relative_path.clone(), data);
-                
-                if self.cache.len() > self.max_cache_size {
-                    self.trim_cache();
-                }
+                self.store(relative_path.clone(), data);
             }
             Ok(Err(e)) => {
                 eprintln!("Failed to load image {}: {}", relative_path, e);
@@ -101,26 +253,36 @@ impl ImagePreloader {
         Ok(())
     }
 
-    /// 清理缓存
+    /// 清理缓存到上限的 80%
+    ///
+    /// 先移除已被 LRU 淘汰的条目, 再按最近最少使用的顺序移除
     fn trim_cache(&self) {
-        let target_size = (self.max_cache_size as f32 * 0.8) as usize;
-        
-        if self.cache.len() > target_size {
-            let keys: Vec<String> = self.cache.iter()
-                .take(self.cache.len() - target_size)
-                .map(|entry| entry.key().clone())
-                .collect();
-            for key in keys {
-                self.cache.remove(&key);
+        let target = self.budget.trimmed();
+        let lru = self.lru_cache.read();
+        let mut keys: Vec<String> = self.cache.iter()
+            .filter(|entry| !lru.contains(entry.key()))
+            .map(|entry| entry.key().clone())
+            .collect();
+        keys.extend(lru.iter().rev().map(|(key, _)| key.clone()));
+        drop(lru);
+
+        for key in keys {
+            if !target.exceeded(self.cache.len(), self.cache_bytes.load(Ordering::SeqCst)) {
+                break;
+            }
+            if let Some((key, old)) = self.cache.remove(&key) {
+                sub_bytes(&self.cache_bytes, entry_size(&key, &old));
             }
         }
     }
 
+    /// 预加载文件夹中的图片, 返回取消前处理的文件数
     pub async fn preload_folder(
         &self,
         folder_path: &Path,
         base_path: &Path,
         max_size: u32,
+        cancel: CancellationToken,
     ) -> Result<usize, String> {
         use walkdir::WalkDir;
 
@@ -130,32 +292,27 @@ impl ImagePreloader {
             .into_iter()
             .filter_map(|e| e.ok())
             .filter(|e| e.file_type().is_file())
-            .filter(|e| {
-                if let Some(ext) = e.path().extension() {
-                    let ext_str = ext.to_string_lossy().to_lowercase();
-                    matches!(ext_str.as_str(), "png" | "jpg" | "jpeg" | "gif" | "webp")
-                } else {
-                    false
-                }
-            })
+            .filter(|e| crate::image_handler::is_image_path(e.path()))
             .map(|e| e.path().to_path_buf())
             .collect();
 
-        let count = image_files.len();
-
         let tasks: Vec<_> = image_files
             .into_iter()
             .map(|path| {
                 let self_clone = self.clone();
                 let base_path = base_path.to_path_buf();
+                let cancel = cancel.clone();
                 tokio::spawn(async move {
-                    self_clone.preload_image(path, &base_path, max_size).await
+                    self_clone.preload_image(path, &base_path, max_size, Some(&cancel)).await
                 })
             })
             .collect();
 
+        let mut count = 0;
         for task in tasks {
-            let _ = task.await;
+            if matches!(task.await, Ok(Ok(()))) {
+                count += 1;
+            }
         }
 
         Ok(count)
@@ -165,6 +322,7 @@ impl ImagePreloader {
         &self,
         folder_path: &Path,
         base_path: &Path,
+        cancel: CancellationToken,
     ) -> Result<usize, String> {
         use walkdir::WalkDir;
         use rayon::prelude::*;
@@ -174,14 +332,7 @@ impl ImagePreloader {
             .into_iter()
             .filter_map(|e| e.ok())
             .filter(|e| e.file_type().is_file())
-            .filter(|e| {
-                if let Some(ext) = e.path().extension() {
-                    let ext_str = ext.to_string_lossy().to_lowercase();
-                    matches!(ext_str.as_str(), "png" | "jpg" | "jpeg" | "gif" | "webp")
-                } else {
-                    false
-                }
-            })
+            .filter(|e| crate::image_handler::is_image_path(e.path()))
             .map(|e| e.path().to_path_buf())
             .collect();
 
@@ -190,6 +341,9 @@ impl ImagePreloader {
         let results: Vec<_> = image_files
             .par_iter()
             .map(|path| {
+                if cancel.is_cancelled() {
+                    return Err("预加载已取消".to_string());
+                }
                 let relative_path = path
                     .strip_prefix(base_path)
                     .unwrap_or(path)
@@ -202,9 +356,7 @@ impl ImagePreloader {
 
                 match crate::image_handler::create_thumbnail(path, 512) {
                     Ok(data) => {
-                        self.cache.insert(relative_path.clone(), data.clone());
-                        let mut lru = self.lru_cache.write();
-                        lru.put(relative_path, data);
+                        self.store(relative_path, data);
                         Ok(())
                     }
                     Err(e) => Err(e),
@@ -219,15 +371,67 @@ impl ImagePreloader {
         Ok(success_count)
     }
 
+    /// 以低优先级逐个预加载文件, 每完成一个调用 on_progress(已完成, 总数)
+    ///
+    /// 缓存被清空(如切换材质包)后停止, 返回实际处理的文件数
+    pub async fn preload_files_low_priority(
+        &self,
+        files: Vec<PathBuf>,
+        base_path: &Path,
+        max_size: u32,
+        on_progress: impl Fn(usize, usize),
+    ) -> usize {
+        let generation = self.generation.load(Ordering::SeqCst);
+        let total = files.len();
+
+        for (index, path) in files.into_iter().enumerate() {
+            if self.generation.load(Ordering::SeqCst) != generation {
+                return index;
+            }
+            let Ok(_permit) = self.low_priority.acquire().await else {
+                return index;
+            };
+            let _ = self.preload_image(path, base_path, max_size, None).await;
+            on_progress(index + 1, total);
+        }
+
+        total
+    }
+
     /// 获取缓存统计
-    pub async fn get_stats(&self) -> (usize, usize) {
-        (self.cache.len(), self.loading.len())
+    pub async fn get_stats(&self) -> PreloaderStats {
+        PreloaderStats {
+            cached_files: self.cache.len(),
+            loading: self.loading.len(),
+            cached_bytes: self.cache_bytes.load(Ordering::SeqCst),
+            byte_budget: match self.budget {
+                CacheBudget::Bytes(max) => Some(max),
+                CacheBudget::Entries(_) => None,
+            },
+        }
+    }
+
+    /// 移除单个文件的缓存
+    pub fn invalidate(&self, relative_path: &str) {
+        if let Some((key, old)) = self.cache.remove(relative_path) {
+            sub_bytes(&self.cache_bytes, entry_size(&key, &old));
+        }
+        let mut lru = self.lru_cache.write();
+        if let Some(old) = lru.pop(relative_path) {
+            sub_bytes(&self.lru_bytes, entry_size(relative_path, &old));
+        }
     }
 
     /// 清空缓存
     pub async fn clear_cache(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        self.cancel_request(None);
         self.cache.clear();
-        self.lru_cache.write().clear();
+        self.cache_bytes.store(0, Ordering::SeqCst);
+        let mut lru = self.lru_cache.write();
+        lru.clear();
+        self.lru_bytes.store(0, Ordering::SeqCst);
+        drop(lru);
         self.loading.clear();
     }
 }
@@ -238,8 +442,13 @@ impl Clone for ImagePreloader {
             cache: Arc::clone(&self.cache),
             lru_cache: Arc::clone(&self.lru_cache),
             loading: Arc::clone(&self.loading),
-            max_cache_size: self.max_cache_size,
+            budget: self.budget,
+            cache_bytes: Arc::clone(&self.cache_bytes),
+            lru_bytes: Arc::clone(&self.lru_bytes),
             semaphore: Arc::clone(&self.semaphore),
+            low_priority: Arc::clone(&self.low_priority),
+            generation: Arc::clone(&self.generation),
+            current_request: Arc::clone(&self.current_request),
         }
     }
 }
@@ -250,4 +459,85 @@ mod num_cpus {
             .map(|n| n.get())
             .unwrap_or(4)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn byte_budget_evicts_to_stay_under_limit() {
+        let preloader = ImagePreloader::with_byte_budget(1000);
+        for i in 0..30 {
+            preloader.store(format!("textures/block/{:02}.png", i), "x".repeat(80));
+        }
+
+        assert!(preloader.cache_bytes.load(Ordering::SeqCst) <= 1000);
+        assert!(preloader.lru_bytes.load(Ordering::SeqCst) <= 1000);
+        assert!(preloader.cache.len() < 30);
+        // 最近写入的条目保留
+        assert!(preloader.get("textures/block/29.png").is_some());
+        assert!(preloader.get("textures/block/00.png").is_none());
+
+        let stats = tauri::async_runtime::block_on(preloader.get_stats());
+        assert_eq!(stats.byte_budget, Some(1000));
+        assert_eq!(stats.cached_bytes, preloader.cache_bytes.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn entry_budget_is_kept_for_new() {
+        let preloader = ImagePreloader::new(10);
+        for i in 0..25 {
+            preloader.store(format!("{}.png", i), "data".to_string());
+        }
+        assert!(preloader.cache.len() <= 10);
+        assert_eq!(tauri::async_runtime::block_on(preloader.get_stats()).byte_budget, None);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn cancelled_preload_stops_before_caching_everything() {
+        let dir = tempfile::tempdir().unwrap();
+        let folder = dir.path().join("assets/minecraft/textures/block");
+        std::fs::create_dir_all(&folder).unwrap();
+        for i in 0..20 {
+            image::RgbaImage::new(4, 4).save(folder.join(format!("{}.png", i))).unwrap();
+        }
+
+        let preloader = ImagePreloader::with_byte_budget(1 << 20);
+        // 占住所有并发许可, 让预加载停在等待许可处
+        let held = preloader
+            .semaphore
+            .acquire_many(preloader.semaphore.available_permits() as u32)
+            .await
+            .unwrap();
+
+        let token = preloader.begin_request("first");
+        let task = tokio::spawn({
+            let preloader = preloader.clone();
+            let base = dir.path().to_path_buf();
+            async move { preloader.preload_folder(&folder, &base, 16, token).await }
+        });
+
+        assert!(preloader.cancel_request(Some("first")));
+        let processed = task.await.unwrap().unwrap();
+        drop(held);
+
+        assert!(processed < 20);
+        assert!(preloader.cache.len() < 20);
+        assert!(preloader.loading.is_empty());
+    }
+
+    #[test]
+    fn new_request_cancels_previous_one() {
+        let preloader = ImagePreloader::with_byte_budget(1 << 20);
+        let first = preloader.begin_request("first");
+        let second = preloader.begin_request("second");
+        assert!(first.is_cancelled());
+        assert!(!second.is_cancelled());
+
+        assert!(!preloader.cancel_request(Some("first")));
+        preloader.finish_request("first");
+        assert!(preloader.cancel_request(None));
+        assert!(second.is_cancelled());
+    }
+}
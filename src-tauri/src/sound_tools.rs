@@ -0,0 +1,438 @@
+use crate::commands::AppState;
+use crate::validation::{sort_issues, Severity, ValidationIssue};
+use serde::de::{Deserialize, Deserializer, MapAccess, Visitor};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use tauri::State;
+use walkdir::WalkDir;
+use crate::error::AppError;
+
+/// 超过该时长(秒)的音频应设置 stream: true
+const STREAM_THRESHOLD_SECS: f64 = 10.0;
+
+/// sounds.json 中的单条音频引用
+struct SoundReference {
+    name: String,
+    is_event: bool,
+    stream: bool,
+}
+
+/// 解析事件中的 sounds 数组
+fn parse_sound_references(event: &Value) -> Vec<SoundReference> {
+    let sounds = match event.get("sounds").and_then(|v| v.as_array()) {
+        Some(sounds) => sounds,
+        None => return Vec::new(),
+    };
+
+    sounds
+        .iter()
+        .filter_map(|sound| match sound {
+            Value::String(name) => Some(SoundReference {
+                name: name.clone(),
+                is_event: false,
+                stream: false,
+            }),
+            Value::Object(obj) => Some(SoundReference {
+                name: obj.get("name")?.as_str()?.to_string(),
+                is_event: obj.get("type").and_then(|v| v.as_str()) == Some("event"),
+                stream: obj.get("stream").and_then(|v| v.as_bool()).unwrap_or(false),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// 将音频名解析为 "命名空间:路径" 形式
+fn qualify_sound_name(name: &str, default_namespace: &str) -> (String, String) {
+    match name.split_once(':') {
+        Some((namespace, path)) => (namespace.to_string(), path.to_string()),
+        None => (default_namespace.to_string(), name.to_string()),
+    }
+}
+
+/// 读取并解析 JSON 文件
+fn read_json(path: &Path) -> Result<Value, String> {
+    let content =
+        std::fs::read_to_string(path).map_err(|e| format!("读取 {} 失败: {}", path.display(), e))?;
+    serde_json::from_str(content.strip_prefix('\u{feff}').unwrap_or(&content))
+        .map_err(|e| format!("解析 {} 失败: {}", path.display(), e))
+}
+
+/// 列出 assets 下的所有命名空间目录
+fn list_namespaces(base_path: &Path) -> Vec<(String, PathBuf)> {
+    let mut namespaces: Vec<(String, PathBuf)> = std::fs::read_dir(base_path.join("assets"))
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().is_dir())
+                .map(|e| (e.file_name().to_string_lossy().to_string(), e.path()))
+                .collect()
+        })
+        .unwrap_or_default();
+    namespaces.sort();
+    namespaces
+}
+
+/// 收集材质包中所有语言文件的键
+fn collect_pack_lang_keys(namespaces: &[(String, PathBuf)]) -> HashSet<String> {
+    let mut keys = HashSet::new();
+
+    for (_, namespace_dir) in namespaces {
+        let lang_dir = namespace_dir.join("lang");
+        let entries = match std::fs::read_dir(&lang_dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+
+        for entry in entries.filter_map(|e| e.ok()) {
+            if let Ok(pairs) = crate::lang_parser::parse_lang_file(&entry.path()) {
+                keys.extend(pairs.into_iter().map(|(key, _)| key));
+            }
+        }
+    }
+
+    keys
+}
+
+/// 收集原版 sounds.json 中的事件名和引用的音频
+fn collect_vanilla_sounds(base_path: &Path) -> Option<(HashSet<String>, HashSet<String>)> {
+    let vanilla = read_json(&base_path.join(".little100").join("sounds.json")).ok()?;
+    let events = vanilla.as_object()?;
+
+    let event_names = events.keys().map(|k| format!("minecraft:{}", k)).collect();
+    let sound_names = events
+        .values()
+        .flat_map(parse_sound_references)
+        .filter(|sound| !sound.is_event)
+        .map(|sound| {
+            let (namespace, path) = qualify_sound_name(&sound.name, "minecraft");
+            format!("{}:{}", namespace, path)
+        })
+        .collect();
+
+    Some((event_names, sound_names))
+}
+
+/// 校验材质包中的 sounds.json 与音频文件是否一致
+pub fn validate_sounds_in_pack(base_path: &Path) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let namespaces = list_namespaces(base_path);
+
+    // 原版参考数据
+    let (vanilla_events, vanilla_sounds) = collect_vanilla_sounds(base_path).unwrap_or_default();
+    let vanilla_mirror = base_path.join(".little100").join("sounds");
+    let vanilla_lang = crate::commands::load_language_map_sync(base_path);
+    let pack_lang_keys = collect_pack_lang_keys(&namespaces);
+
+    // 材质包内的全部音频文件
+    let mut pack_sounds: Vec<(String, String)> = Vec::new();
+    for (namespace, namespace_dir) in &namespaces {
+        let sounds_dir = namespace_dir.join("sounds");
+        for entry in WalkDir::new(&sounds_dir)
+            .follow_links(false)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+        {
+            let path = entry.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("ogg") {
+                continue;
+            }
+            if let Ok(relative) = path.with_extension("").strip_prefix(&sounds_dir) {
+                let relative = relative.to_string_lossy().replace('\\', "/");
+                pack_sounds.push((namespace.clone(), relative));
+            }
+        }
+    }
+
+    // 先收集所有事件名, 以便检查 type: event 的引用
+    let mut sound_files: Vec<(String, String, Value)> = Vec::new();
+    let mut pack_events: HashSet<String> = HashSet::new();
+    for (namespace, namespace_dir) in &namespaces {
+        let sounds_json = namespace_dir.join("sounds.json");
+        if !sounds_json.exists() {
+            continue;
+        }
+
+        let relative = format!("assets/{}/sounds.json", namespace);
+        match read_json(&sounds_json) {
+            Ok(Value::Object(events)) => {
+                pack_events.extend(events.keys().map(|k| format!("{}:{}", namespace, k)));
+                sound_files.push((namespace.clone(), relative, Value::Object(events)));
+            }
+            Ok(_) => issues.push(ValidationIssue::new(
+                Severity::Error,
+                "sounds",
+                relative,
+                "sounds.json 根节点必须是对象",
+            )),
+            Err(e) => issues.push(ValidationIssue::new(Severity::Error, "sounds", relative, e)),
+        }
+    }
+
+    if vanilla_lang.is_empty() {
+        issues.push(ValidationIssue::new(
+            Severity::Info,
+            "sounds",
+            "",
+            "未找到原版语言文件, 字幕键仅与材质包内的语言文件比对",
+        ));
+    }
+
+    let mut referenced: HashSet<String> = HashSet::new();
+
+    for (namespace, relative, events) in &sound_files {
+        let events = match events.as_object() {
+            Some(events) => events,
+            None => continue,
+        };
+
+        for (event_name, event) in events {
+            for sound in parse_sound_references(event) {
+                let (sound_namespace, sound_path) = qualify_sound_name(&sound.name, namespace);
+                let qualified = format!("{}:{}", sound_namespace, sound_path);
+
+                if sound.is_event {
+                    if !pack_events.contains(&qualified) && !vanilla_events.contains(&qualified) {
+                        issues.push(ValidationIssue::new(
+                            Severity::Error,
+                            "sounds",
+                            relative.clone(),
+                            format!("事件 {} 引用了不存在的事件 {}", event_name, qualified),
+                        ));
+                    }
+                    continue;
+                }
+
+                referenced.insert(qualified.clone());
+
+                let file = base_path
+                    .join("assets")
+                    .join(&sound_namespace)
+                    .join("sounds")
+                    .join(format!("{}.ogg", sound_path));
+
+                if file.exists() {
+                    if !sound.stream {
+                        if let Ok(meta) = crate::audio_handler::get_ogg_metadata(&file) {
+                            if meta.duration_secs > STREAM_THRESHOLD_SECS {
+                                issues.push(ValidationIssue::new(
+                                    Severity::Warning,
+                                    "sounds",
+                                    relative.clone(),
+                                    format!(
+                                        "事件 {} 的音频 {} 时长 {:.1} 秒, 建议设置 \"stream\": true",
+                                        event_name, qualified, meta.duration_secs
+                                    ),
+                                ));
+                            }
+                        }
+                    }
+                    continue;
+                }
+
+                // 原版音频不在材质包中是正常的
+                let in_vanilla = sound_namespace == "minecraft"
+                    && (vanilla_sounds.contains(&qualified)
+                        || vanilla_mirror.join(format!("{}.ogg", sound_path)).exists());
+                if !in_vanilla {
+                    issues.push(ValidationIssue::new(
+                        Severity::Error,
+                        "sounds",
+                        relative.clone(),
+                        format!(
+                            "事件 {} 引用的音频文件不存在: assets/{}/sounds/{}.ogg",
+                            event_name, sound_namespace, sound_path
+                        ),
+                    ));
+                }
+            }
+
+            if let Some(subtitle) = event.get("subtitle").and_then(|v| v.as_str()) {
+                if !pack_lang_keys.contains(subtitle) && !vanilla_lang.contains_key(subtitle) {
+                    let severity = if vanilla_lang.is_empty() {
+                        Severity::Info
+                    } else {
+                        Severity::Warning
+                    };
+                    issues.push(ValidationIssue::new(
+                        severity,
+                        "sounds",
+                        relative.clone(),
+                        format!("事件 {} 的字幕键 {} 在语言文件中不存在", event_name, subtitle),
+                    ));
+                }
+            }
+        }
+    }
+
+    // 未被任何 sounds.json 引用的音频文件
+    for (namespace, path) in &pack_sounds {
+        let qualified = format!("{}:{}", namespace, path);
+        if !referenced.contains(&qualified) && !vanilla_sounds.contains(&qualified) {
+            issues.push(ValidationIssue::new(
+                Severity::Warning,
+                "sounds",
+                format!("assets/{}/sounds/{}.ogg", namespace, path),
+                "音频文件未被任何 sounds.json 引用",
+            ));
+        }
+    }
+
+    sort_issues(&mut issues);
+    issues
+}
+
+/// 校验 sounds.json
+#[tauri::command]
+pub async fn validate_sounds(state: State<'_, AppState>) -> Result<Vec<ValidationIssue>, AppError> {
+    let base_path = state.pack_path()?;
+
+    Ok(validate_sounds_in_pack(&base_path))
+}
+
+/// 保留重复键的 JSON 对象(serde_json 默认只保留最后一个)
+struct RawEntries(Vec<(String, Value)>);
+
+impl<'de> Deserialize<'de> for RawEntries {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct EntriesVisitor;
+
+        impl<'de> Visitor<'de> for EntriesVisitor {
+            type Value = RawEntries;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a JSON object")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<RawEntries, A::Error> {
+                let mut entries = Vec::new();
+                while let Some((key, value)) = map.next_entry::<String, Value>()? {
+                    entries.push((key, value));
+                }
+                Ok(RawEntries(entries))
+            }
+        }
+
+        deserializer.deserialize_map(EntriesVisitor)
+    }
+}
+
+/// 合并两个同名事件: sounds 列表拼接去重, 其余字段以后出现的为准
+fn merge_sound_event(existing: &mut Value, incoming: Value) {
+    match (existing.as_object_mut(), incoming) {
+        (Some(target), Value::Object(source)) => {
+            for (key, value) in source {
+                match (target.get_mut(&key), value) {
+                    (Some(Value::Array(sounds)), Value::Array(more)) if key == "sounds" => {
+                        for sound in more {
+                            if !sounds.contains(&sound) {
+                                sounds.push(sound);
+                            }
+                        }
+                    }
+                    (_, value) => {
+                        target.insert(key, value);
+                    }
+                }
+            }
+        }
+        (_, incoming) => *existing = incoming,
+    }
+}
+
+/// 合并 sounds.json 文本中的重复事件, 返回合并后的内容和被合并的事件名
+pub fn dedupe_sounds_content(content: &str) -> Result<Option<(String, Vec<String>)>, String> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let RawEntries(entries) =
+        serde_json::from_str(content).map_err(|e| format!("解析 sounds.json 失败: {}", e))?;
+
+    let mut merged: Vec<(String, Value)> = Vec::with_capacity(entries.len());
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    let mut duplicates: Vec<String> = Vec::new();
+
+    for (key, value) in entries {
+        match positions.get(&key) {
+            Some(&index) => {
+                merge_sound_event(&mut merged[index].1, value);
+                if !duplicates.contains(&key) {
+                    duplicates.push(key);
+                }
+            }
+            None => {
+                positions.insert(key.clone(), merged.len());
+                merged.push((key, value));
+            }
+        }
+    }
+
+    if duplicates.is_empty() {
+        return Ok(None);
+    }
+
+    let object: serde_json::Map<String, Value> = merged.into_iter().collect();
+    let json = serde_json::to_string_pretty(&Value::Object(object))
+        .map_err(|e| format!("序列化 sounds.json 失败: {}", e))?;
+
+    Ok(Some((json, duplicates)))
+}
+
+/// 检测并合并 sounds.json 中重复的事件键
+#[tauri::command]
+pub async fn dedupe_sounds_json(state: State<'_, AppState>) -> Result<Vec<String>, AppError> {
+    let base_path = state.pack_path()?;
+
+    let mut merged_events = Vec::new();
+
+    for (namespace, namespace_dir) in list_namespaces(&base_path) {
+        let sounds_json = namespace_dir.join("sounds.json");
+        if !sounds_json.exists() {
+            continue;
+        }
+
+        let content = std::fs::read_to_string(&sounds_json)
+            .map_err(|e| format!("读取 {} 失败: {}", sounds_json.display(), e))?;
+
+        if let Some((json, duplicates)) = dedupe_sounds_content(&content)? {
+            let relative = format!("assets/{}/sounds.json", namespace);
+            crate::history_manager::snapshot_text_file(&base_path, &relative)?;
+
+            std::fs::write(&sounds_json, json)
+                .map_err(|e| format!("写入 {} 失败: {}", sounds_json.display(), e))?;
+
+            merged_events.extend(duplicates.into_iter().map(|key| format!("{}:{}", namespace, key)));
+        }
+    }
+
+    Ok(merged_events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duplicated_events_are_merged() {
+        let content = r#"{
+            "block.note.harp": { "sounds": ["a"] },
+            "ambient.cave": { "sounds": ["cave1"] },
+            "block.note.harp": { "sounds": ["a", "b"], "subtitle": "note" }
+        }"#;
+        let (json, duplicates) = dedupe_sounds_content(content).unwrap().unwrap();
+        assert_eq!(duplicates, ["block.note.harp"]);
+
+        let value: Value = serde_json::from_str(&json).unwrap();
+        let keys: Vec<&String> = value.as_object().unwrap().keys().collect();
+        assert_eq!(keys, ["block.note.harp", "ambient.cave"]);
+        assert_eq!(value["block.note.harp"]["sounds"], serde_json::json!(["a", "b"]));
+        assert_eq!(value["block.note.harp"]["subtitle"], "note");
+    }
+
+    #[test]
+    fn clean_file_is_left_alone() {
+        let content = "\u{feff}{ \"ambient.cave\": { \"sounds\": [\"cave1\"] } }";
+        assert!(dedupe_sounds_content(content).unwrap().is_none());
+    }
+}
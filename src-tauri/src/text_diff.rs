@@ -0,0 +1,268 @@
+use serde::{Deserialize, Serialize};
+
+/// 每个变更块前后保留的上下文行数
+pub const DEFAULT_CONTEXT_LINES: usize = 3;
+
+/// Myers 搜索的最大编辑距离, 超过时把剩余部分视为整体替换, 限制内存占用
+const MAX_EDIT_DISTANCE: isize = 2048;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffLineKind {
+    Context,
+    Added,
+    Removed,
+}
+
+/// 差异中的一行, 行号从 1 开始
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    /// 在旧文本中的行号, 新增行为 None
+    pub old_line: Option<usize>,
+    /// 在新文本中的行号, 删除行为 None
+    pub new_line: Option<usize>,
+    pub content: String,
+}
+
+/// 变更块, 与 unified diff 的 @@ -old_start,old_lines +new_start,new_lines @@ 对应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffHunk {
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextDiff {
+    pub hunks: Vec<DiffHunk>,
+    pub added: usize,
+    pub removed: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// Myers 差异算法, 返回把 a 变为 b 的最短编辑序列
+///
+/// 每轮只记录 k ∈ [-d, d] 的部分, 内存为 O(D²); 编辑距离超过 MAX_EDIT_DISTANCE 时返回 None
+fn myers(a: &[&str], b: &[&str]) -> Option<Vec<Op>> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max = n + m;
+    let offset = max + 1;
+    let mut v = vec![0isize; (2 * max + 3) as usize];
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+    let mut found = false;
+
+    'search: for d in 0..=max.min(MAX_EDIT_DISTANCE) {
+        trace.push(v[(offset - d) as usize..=(offset + d) as usize].to_vec());
+        for k in (-d..=d).step_by(2) {
+            let down = k == -d || (k != d && v[(offset + k - 1) as usize] < v[(offset + k + 1) as usize]);
+            let mut x = if down {
+                v[(offset + k + 1) as usize]
+            } else {
+                v[(offset + k - 1) as usize] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[(offset + k) as usize] = x;
+            if x >= n && y >= m {
+                found = true;
+                break 'search;
+            }
+        }
+    }
+    if !found {
+        return None;
+    }
+
+    // 从终点沿每轮的选择回溯
+    let mut ops = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, round) in trace.iter().enumerate().skip(1).rev() {
+        let d = d as isize;
+        let at = |k: isize| round[(k + d) as usize];
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && at(k - 1) < at(k + 1)) { k + 1 } else { k - 1 };
+        let prev_x = at(prev_k);
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            ops.push(Op::Equal(x as usize, y as usize));
+        }
+        if x == prev_x {
+            ops.push(Op::Insert(prev_y as usize));
+        } else {
+            ops.push(Op::Delete(prev_x as usize));
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+    while x > 0 && y > 0 {
+        x -= 1;
+        y -= 1;
+        ops.push(Op::Equal(x as usize, y as usize));
+    }
+    ops.reverse();
+    Some(ops)
+}
+
+/// 按行比较两段文本, 变更块前后保留 context 行上下文
+pub fn diff_lines(old: &str, new: &str, context: usize) -> TextDiff {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+
+    // 去掉相同的开头和结尾, 只对中间部分运行 Myers
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+
+    let mut ops: Vec<Op> = (0..prefix).map(|i| Op::Equal(i, i)).collect();
+    match myers(&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]) {
+        Some(middle) => ops.extend(middle.into_iter().map(|op| match op {
+            Op::Equal(i, j) => Op::Equal(i + prefix, j + prefix),
+            Op::Delete(i) => Op::Delete(i + prefix),
+            Op::Insert(j) => Op::Insert(j + prefix),
+        })),
+        None => {
+            ops.extend((prefix..a.len() - suffix).map(Op::Delete));
+            ops.extend((prefix..b.len() - suffix).map(Op::Insert));
+        }
+    }
+    ops.extend((0..suffix).map(|i| Op::Equal(a.len() - suffix + i, b.len() - suffix + i)));
+
+    let added = ops.iter().filter(|op| matches!(op, Op::Insert(_))).count();
+    let removed = ops.iter().filter(|op| matches!(op, Op::Delete(_))).count();
+
+    // 把相距不超过 2 * context 的变更合并到同一块
+    let changes: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, op)| !matches!(op, Op::Equal(..)))
+        .map(|(i, _)| i)
+        .collect();
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for &i in &changes {
+        let start = i.saturating_sub(context);
+        let end = (i + context + 1).min(ops.len());
+        match ranges.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+
+    let hunks = ranges
+        .into_iter()
+        .map(|(start, end)| {
+            let lines: Vec<DiffLine> = ops[start..end]
+                .iter()
+                .map(|op| match *op {
+                    Op::Equal(i, j) => DiffLine {
+                        kind: DiffLineKind::Context,
+                        old_line: Some(i + 1),
+                        new_line: Some(j + 1),
+                        content: a[i].to_string(),
+                    },
+                    Op::Delete(i) => DiffLine {
+                        kind: DiffLineKind::Removed,
+                        old_line: Some(i + 1),
+                        new_line: None,
+                        content: a[i].to_string(),
+                    },
+                    Op::Insert(j) => DiffLine {
+                        kind: DiffLineKind::Added,
+                        old_line: None,
+                        new_line: Some(j + 1),
+                        content: b[j].to_string(),
+                    },
+                })
+                .collect();
+
+            // 块的起始行取块内第一行在两侧的位置
+            let old_start = ops[start..]
+                .iter()
+                .find_map(|op| match *op {
+                    Op::Equal(i, _) | Op::Delete(i) => Some(i + 1),
+                    Op::Insert(_) => None,
+                })
+                .unwrap_or(a.len() + 1);
+            let new_start = ops[start..]
+                .iter()
+                .find_map(|op| match *op {
+                    Op::Equal(_, j) | Op::Insert(j) => Some(j + 1),
+                    Op::Delete(_) => None,
+                })
+                .unwrap_or(b.len() + 1);
+
+            DiffHunk {
+                old_start,
+                old_lines: lines.iter().filter(|l| l.kind != DiffLineKind::Added).count(),
+                new_start,
+                new_lines: lines.iter().filter(|l| l.kind != DiffLineKind::Removed).count(),
+                lines,
+            }
+        })
+        .collect();
+
+    TextDiff { hunks, added, removed }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn changed(diff: &TextDiff, kind: DiffLineKind) -> Vec<(Option<usize>, Option<usize>, &str)> {
+        diff.hunks
+            .iter()
+            .flat_map(|hunk| &hunk.lines)
+            .filter(|line| line.kind == kind)
+            .map(|line| (line.old_line, line.new_line, line.content.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn reports_added_and_removed_lines() {
+        let old = "{\n  \"parent\": \"block/cube_all\",\n  \"textures\": {\n    \"all\": \"block/stone\"\n  }\n}";
+        let new = "{\n  \"parent\": \"block/cube_all\",\n  \"textures\": {\n    \"all\": \"block/granite\",\n    \"particle\": \"block/granite\"\n  }\n}";
+        let diff = diff_lines(old, new, DEFAULT_CONTEXT_LINES);
+
+        assert_eq!((diff.added, diff.removed), (2, 1));
+        assert_eq!(changed(&diff, DiffLineKind::Removed), [(Some(4), None, "    \"all\": \"block/stone\"")]);
+        assert_eq!(
+            changed(&diff, DiffLineKind::Added),
+            [
+                (None, Some(4), "    \"all\": \"block/granite\","),
+                (None, Some(5), "    \"particle\": \"block/granite\""),
+            ]
+        );
+
+        assert_eq!(diff.hunks.len(), 1);
+        let hunk = &diff.hunks[0];
+        assert_eq!((hunk.old_start, hunk.old_lines, hunk.new_start, hunk.new_lines), (1, 6, 1, 7));
+    }
+
+    #[test]
+    fn distant_changes_get_separate_hunks() {
+        let old: String = (1..=20).map(|i| format!("line{}\n", i)).collect();
+        let new = old.replace("line2\n", "changed2\n").replace("line19\n", "changed19\n");
+        let diff = diff_lines(&old, &new, 1);
+
+        assert_eq!(diff.hunks.len(), 2);
+        assert_eq!((diff.hunks[1].old_start, diff.hunks[1].new_start), (18, 18));
+        assert!(diff_lines(&old, &old, 1).hunks.is_empty());
+    }
+}
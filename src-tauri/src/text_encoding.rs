@@ -0,0 +1,168 @@
+use serde::{Deserialize, Serialize};
+
+/// 检测二进制文件时检查的字节数
+const BINARY_SNIFF_LEN: usize = 8 * 1024;
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// 文本文件编码
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextEncoding {
+    #[serde(rename = "utf-8")]
+    Utf8,
+    #[serde(rename = "utf-16le")]
+    Utf16Le,
+    #[serde(rename = "utf-16be")]
+    Utf16Be,
+    /// 旧版材质包的 .lang / .properties 常见的编码
+    #[serde(rename = "latin1")]
+    Latin1,
+}
+
+/// 解码后的文本
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecodedText {
+    pub content: String,
+    pub detected_encoding: TextEncoding,
+    pub had_bom: bool,
+}
+
+fn decode_utf16(bytes: &[u8], little_endian: bool) -> String {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| {
+            if little_endian {
+                u16::from_le_bytes([pair[0], pair[1]])
+            } else {
+                u16::from_be_bytes([pair[0], pair[1]])
+            }
+        })
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+/// 按 BOM 检测编码并解码, 没有 BOM 且不是合法 UTF-8 时按 Latin-1 解码
+///
+/// 开头 8KB 内含 NUL 字节(且没有 UTF-16 BOM)时视为二进制文件
+pub fn decode_text(bytes: &[u8]) -> Result<DecodedText, String> {
+    if let Some(rest) = bytes.strip_prefix(UTF8_BOM) {
+        return Ok(DecodedText {
+            content: String::from_utf8_lossy(rest).into_owned(),
+            detected_encoding: TextEncoding::Utf8,
+            had_bom: true,
+        });
+    }
+    if let Some(rest) = bytes.strip_prefix(UTF16LE_BOM) {
+        return Ok(DecodedText {
+            content: decode_utf16(rest, true),
+            detected_encoding: TextEncoding::Utf16Le,
+            had_bom: true,
+        });
+    }
+    if let Some(rest) = bytes.strip_prefix(UTF16BE_BOM) {
+        return Ok(DecodedText {
+            content: decode_utf16(rest, false),
+            detected_encoding: TextEncoding::Utf16Be,
+            had_bom: true,
+        });
+    }
+
+    if bytes[..bytes.len().min(BINARY_SNIFF_LEN)].contains(&0) {
+        return Err("binary file".to_string());
+    }
+
+    let (content, detected_encoding) = match std::str::from_utf8(bytes) {
+        Ok(text) => (text.to_string(), TextEncoding::Utf8),
+        Err(_) => (bytes.iter().map(|&b| b as char).collect(), TextEncoding::Latin1),
+    };
+    Ok(DecodedText {
+        content,
+        detected_encoding,
+        had_bom: false,
+    })
+}
+
+/// 按指定编码编码文本, bom 为 true 时写入 BOM(Latin-1 没有 BOM)
+pub fn encode_text(content: &str, encoding: TextEncoding, bom: bool) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::with_capacity(content.len() + 3);
+    match encoding {
+        TextEncoding::Utf8 => {
+            if bom {
+                bytes.extend_from_slice(UTF8_BOM);
+            }
+            bytes.extend_from_slice(content.as_bytes());
+        }
+        TextEncoding::Utf16Le => {
+            if bom {
+                bytes.extend_from_slice(UTF16LE_BOM);
+            }
+            for unit in content.encode_utf16() {
+                bytes.extend_from_slice(&unit.to_le_bytes());
+            }
+        }
+        TextEncoding::Utf16Be => {
+            if bom {
+                bytes.extend_from_slice(UTF16BE_BOM);
+            }
+            for unit in content.encode_utf16() {
+                bytes.extend_from_slice(&unit.to_be_bytes());
+            }
+        }
+        TextEncoding::Latin1 => {
+            for c in content.chars() {
+                let code = c as u32;
+                if code > 0xFF {
+                    return Err(format!("字符 '{}' 无法以 Latin-1 编码保存", c));
+                }
+                bytes.push(code as u8);
+            }
+        }
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(encoding: TextEncoding, bom: bool) {
+        let text = "§6金色 ä\r\n";
+        let bytes = encode_text(text, encoding, bom).unwrap();
+        let decoded = decode_text(&bytes).unwrap();
+        assert_eq!(decoded.content, text);
+        assert_eq!(decoded.detected_encoding, encoding);
+        assert_eq!(decoded.had_bom, bom);
+        assert_eq!(encode_text(&decoded.content, decoded.detected_encoding, decoded.had_bom).unwrap(), bytes);
+    }
+
+    #[test]
+    fn bom_encodings_round_trip() {
+        round_trip(TextEncoding::Utf8, true);
+        round_trip(TextEncoding::Utf8, false);
+        round_trip(TextEncoding::Utf16Le, true);
+        round_trip(TextEncoding::Utf16Be, true);
+    }
+
+    #[test]
+    fn invalid_utf8_falls_back_to_latin1() {
+        let bytes = b"name=Caf\xe9";
+        let decoded = decode_text(bytes).unwrap();
+        assert_eq!(decoded.content, "name=Café");
+        assert_eq!(decoded.detected_encoding, TextEncoding::Latin1);
+        assert!(!decoded.had_bom);
+        assert_eq!(encode_text(&decoded.content, TextEncoding::Latin1, true).unwrap(), bytes);
+    }
+
+    #[test]
+    fn nul_without_bom_is_binary() {
+        assert_eq!(decode_text(b"\x89PNG\0\0").unwrap_err(), "binary file");
+    }
+
+    #[test]
+    fn latin1_rejects_wide_characters() {
+        assert!(encode_text("ÿ", TextEncoding::Latin1, false).is_ok());
+        assert!(encode_text("金", TextEncoding::Latin1, false).is_err());
+    }
+}
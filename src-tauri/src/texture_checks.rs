@@ -0,0 +1,463 @@
+use crate::commands::AppState;
+use crate::error::AppError;
+use crate::image_handler::{alpha_bleed_file, alpha_bleed_image};
+use crate::pack_parser::is_hidden_relative_path;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::State;
+use walkdir::WalkDir;
+
+/// 含有透明但 RGB 非零像素的纹理
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransparencyHalo {
+    pub path: String,
+    pub pixel_count: u64,
+}
+
+/// 材质包 assets 下所有 PNG 纹理(相对路径)
+pub fn collect_png_textures(pack_path: &Path) -> Vec<PathBuf> {
+    WalkDir::new(pack_path.join("assets"))
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext.eq_ignore_ascii_case("png")))
+        .filter_map(|e| e.path().strip_prefix(pack_path).ok().map(Path::to_path_buf))
+        .filter(|relative| !is_hidden_relative_path(relative))
+        .collect()
+}
+
+/// 统计 alpha 为 0 但 RGB 不为 0 的像素数, 没有透明通道的图片返回 0
+fn count_halo_pixels(path: &Path) -> Result<u64, String> {
+    let img = image::open(path)
+        .map_err(|e| format!("Failed to open image: {}", e))?;
+    if !img.color().has_alpha() {
+        return Ok(0);
+    }
+
+    Ok(img
+        .to_rgba8()
+        .pixels()
+        .filter(|p| p[3] == 0 && (p[0] != 0 || p[1] != 0 || p[2] != 0))
+        .count() as u64)
+}
+
+/// 并行扫描材质包中可能在 mipmap 后产生暗边的纹理
+pub fn scan_transparency_halos(pack_path: &Path) -> Vec<TransparencyHalo> {
+    let mut halos: Vec<TransparencyHalo> = collect_png_textures(pack_path)
+        .par_iter()
+        .filter_map(|relative| {
+            let pixel_count = count_halo_pixels(&pack_path.join(relative)).ok()?;
+            (pixel_count > 0).then(|| TransparencyHalo {
+                path: relative.to_string_lossy().replace('\\', "/"),
+                pixel_count,
+            })
+        })
+        .collect();
+
+    halos.sort_by(|a, b| b.pixel_count.cmp(&a.pixel_count).then_with(|| a.path.cmp(&b.path)));
+    halos
+}
+
+/// 查找含有透明但带颜色像素的纹理
+#[tauri::command]
+pub async fn find_transparency_halos(
+    state: State<'_, AppState>,
+) -> Result<Vec<TransparencyHalo>, AppError> {
+    let base_path = state.pack_path()?;
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    rayon::spawn(move || {
+        let _ = tx.send(scan_transparency_halos(&base_path));
+    });
+    rx.await
+        .map_err(|e| AppError::Other(format!("Channel error: {}", e)))
+}
+
+/// 尺寸不是 2 的幂次方、16 的倍数或动画帧条的纹理
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvalidTexture {
+    pub path: String,
+    pub namespace: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// 并行检查材质包中所有 PNG 纹理的尺寸, 只读取文件头
+pub fn scan_invalid_textures(pack_path: &Path) -> Vec<InvalidTexture> {
+    let mut invalid: Vec<InvalidTexture> = collect_png_textures(pack_path)
+        .par_iter()
+        .filter_map(|relative| {
+            let (width, height) = image::image_dimensions(pack_path.join(relative)).ok()?;
+            if crate::image_handler::validate_texture_size(width, height) {
+                return None;
+            }
+            // 相对路径形如 assets/<namespace>/textures/...
+            let namespace = relative
+                .components()
+                .nth(1)
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .unwrap_or_default();
+            Some(InvalidTexture {
+                path: relative.to_string_lossy().replace('\\', "/"),
+                namespace,
+                width,
+                height,
+            })
+        })
+        .collect();
+
+    invalid.sort_by(|a, b| a.path.cmp(&b.path));
+    invalid
+}
+
+/// 列出尺寸不符合纹理要求的图片
+#[tauri::command]
+pub async fn list_invalid_textures(
+    state: State<'_, AppState>,
+) -> Result<Vec<InvalidTexture>, AppError> {
+    let base_path = state.pack_path()?;
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    rayon::spawn(move || {
+        let _ = tx.send(scan_invalid_textures(&base_path));
+    });
+    rx.await
+        .map_err(|e| AppError::Other(format!("Channel error: {}", e)))
+}
+
+/// 收集 JSON 中所有 "textures" 对象引用的纹理(相对路径), 以 # 开头的变量引用跳过
+fn collect_texture_refs(value: &serde_json::Value, refs: &mut Vec<PathBuf>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, child) in map {
+                if key == "textures" {
+                    if let serde_json::Value::Object(textures) = child {
+                        refs.extend(
+                            textures
+                                .values()
+                                .filter_map(|v| v.as_str())
+                                .filter(|id| !id.starts_with('#'))
+                                .map(texture_id_to_relative_path),
+                        );
+                    }
+                } else {
+                    collect_texture_refs(child, refs);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            items.iter().for_each(|item| collect_texture_refs(item, refs));
+        }
+        _ => {}
+    }
+}
+
+/// 纹理ID(如 "minecraft:block/stone" 或 "item/apple")对应的相对路径
+pub(crate) fn texture_id_to_relative_path(id: &str) -> PathBuf {
+    let (namespace, path) = crate::model_resolver::split_resource_id(id);
+    Path::new("assets")
+        .join(namespace)
+        .join("textures")
+        .join(format!("{}.png", path))
+}
+
+/// 模型可以引用的纹理目录, 其他目录(entity、gui 等)的纹理不经由模型使用
+const MODEL_TEXTURE_DIRS: [&str; 4] = ["block", "item", "blocks", "items"];
+
+/// 并行扫描所有模型和方块状态, 返回 block/item 纹理目录中未被任何模型引用的 PNG
+pub fn scan_orphaned_textures(pack_path: &Path) -> Vec<String> {
+    let json_files: Vec<PathBuf> = WalkDir::new(pack_path.join("assets"))
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")))
+        .map(|e| e.into_path())
+        .filter(|path| {
+            path.strip_prefix(pack_path).is_ok_and(|relative| {
+                matches!(
+                    relative.components().nth(2).and_then(|c| c.as_os_str().to_str()),
+                    Some("models" | "blockstates")
+                )
+            })
+        })
+        .collect();
+
+    let referenced: std::collections::HashSet<String> = json_files
+        .par_iter()
+        .flat_map_iter(|path| {
+            let mut refs = Vec::new();
+            if let Some(value) = std::fs::read_to_string(path)
+                .ok()
+                .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+            {
+                collect_texture_refs(&value, &mut refs);
+            }
+            refs
+        })
+        .map(|relative| relative.to_string_lossy().replace('\\', "/").to_lowercase())
+        .collect();
+
+    let mut orphaned: Vec<String> = collect_png_textures(pack_path)
+        .into_iter()
+        .filter(|relative| {
+            // 相对路径形如 assets/<namespace>/textures/<block|item>/...
+            let mut components = relative.components().skip(2);
+            components.next().is_some_and(|c| c.as_os_str() == "textures")
+                && components
+                    .next()
+                    .and_then(|c| c.as_os_str().to_str())
+                    .is_some_and(|dir| MODEL_TEXTURE_DIRS.contains(&dir))
+        })
+        .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+        .filter(|relative| !referenced.contains(&relative.to_lowercase()))
+        .collect();
+
+    orphaned.sort();
+    orphaned
+}
+
+/// 查找没有被任何模型引用的方块/物品纹理
+#[tauri::command]
+pub async fn find_orphaned_textures(
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, AppError> {
+    let base_path = state.pack_path()?;
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    rayon::spawn(move || {
+        let _ = tx.send(scan_orphaned_textures(&base_path));
+    });
+    rx.await
+        .map_err(|e| AppError::Other(format!("Channel error: {}", e)))
+}
+
+/// alpha bleed 处理结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlphaBleedResult {
+    pub path: String,
+    pub pixels_filled: u64,
+}
+
+/// 对单张纹理执行 alpha bleed, 返回编码后的 PNG; 没有需要填色的像素时返回 None
+fn bleed_texture_bytes(path: &Path) -> Result<Option<(Vec<u8>, u64)>, String> {
+    let img = image::open(path)
+        .map_err(|e| format!("Failed to open image: {}", e))?;
+    if !img.color().has_alpha() {
+        return Ok(None);
+    }
+
+    let mut rgba = img.to_rgba8();
+    let filled = alpha_bleed_image(&mut rgba);
+    if filled == 0 {
+        return Ok(None);
+    }
+
+    let mut buffer = Vec::new();
+    rgba.write_to(&mut std::io::Cursor::new(&mut buffer), image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode image: {}", e))?;
+    Ok(Some((buffer, filled)))
+}
+
+/// 对整个材质包的纹理执行 alpha bleed, 修改前写入历史记录
+fn alpha_bleed_pack(pack_path: &Path) -> Result<Vec<AlphaBleedResult>, String> {
+    let processed: Vec<(PathBuf, Vec<u8>, u64)> = collect_png_textures(pack_path)
+        .into_par_iter()
+        .filter_map(|relative| {
+            let (bytes, filled) = bleed_texture_bytes(&pack_path.join(&relative)).ok()??;
+            Some((relative, bytes, filled))
+        })
+        .collect();
+
+    // 历史记录共用元数据文件, 需要顺序写入
+    let mut results = Vec::with_capacity(processed.len());
+    for (relative, bytes, pixels_filled) in processed {
+        let relative_str = relative.to_string_lossy().replace('\\', "/");
+        crate::history_manager::snapshot_file(pack_path, &relative_str)?;
+        let full_path = pack_path.join(&relative);
+        crate::image_handler::write_file_atomic(&full_path, &bytes)
+            .map_err(|e| format!("写入 {} 失败: {}", relative_str, e))?;
+        crate::image_handler::invalidate_image_caches(&full_path);
+        results.push(AlphaBleedResult { path: relative_str, pixels_filled });
+    }
+
+    results.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(results)
+}
+
+/// 处理单张纹理写入 output, 覆盖原文件或已存在的输出文件前写入历史记录
+fn alpha_bleed_to(pack_path: &Path, input: &Path, output: &Path) -> Result<u64, String> {
+    if let Ok(relative) = output.strip_prefix(pack_path) {
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        crate::history_manager::snapshot_file(pack_path, &relative)?;
+    }
+    alpha_bleed_file(input, output)
+}
+
+/// 将边缘颜色扩展到透明像素中以消除暗边
+///
+/// 指定 path 时处理单张纹理, output_path 为空则覆盖原文件; 不指定 path 时处理整个材质包
+#[tauri::command]
+pub async fn alpha_bleed(
+    path: Option<String>,
+    output_path: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<AlphaBleedResult>, AppError> {
+    let base_path = state.pack_path()?;
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    match path {
+        Some(path) => {
+            let input = crate::commands::resolve_pack_path(&base_path, &path)?;
+            if !input.is_file() {
+                return Err(AppError::NotFound(format!("文件不存在: {}", path)));
+            }
+            let (output, result_path) = match output_path {
+                Some(output) => (crate::commands::resolve_pack_path(&base_path, &output)?, output),
+                None => (input.clone(), path),
+            };
+            let pack_path = base_path.clone();
+            rayon::spawn(move || {
+                let result = alpha_bleed_to(&pack_path, &input, &output).map(|pixels_filled| {
+                    vec![AlphaBleedResult { path: result_path, pixels_filled }]
+                });
+                let _ = tx.send(result);
+            });
+        }
+        None => {
+            if output_path.is_some() {
+                return Err(AppError::InvalidInput("处理整个材质包时不能指定输出路径".to_string()));
+            }
+            let pack_path = base_path.clone();
+            rayon::spawn(move || {
+                let _ = tx.send(alpha_bleed_pack(&pack_path));
+            });
+        }
+    }
+
+    let results = rx
+        .await
+        .map_err(|e| AppError::Other(format!("Channel error: {}", e)))??;
+
+    let preloader = state.preloader_for_path(&base_path);
+    for result in &results {
+        if let Ok(full_path) = crate::commands::resolve_pack_path(&base_path, &result.path) {
+            if let Ok(relative) = full_path.strip_prefix(&base_path) {
+                preloader.invalidate(&relative.to_string_lossy());
+            }
+        }
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgba, RgbaImage};
+
+    fn write_png(pack: &Path, relative: &str, img: &RgbaImage) {
+        let path = pack.join(relative);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        img.save(path).unwrap();
+    }
+
+    #[test]
+    fn colored_transparent_pixels_are_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut halo = RgbaImage::from_pixel(4, 4, Rgba([0, 0, 0, 0]));
+        halo.put_pixel(0, 0, Rgba([255, 0, 0, 0]));
+        halo.put_pixel(1, 0, Rgba([0, 0, 12, 0]));
+        halo.put_pixel(2, 0, Rgba([200, 200, 200, 255]));
+        write_png(dir.path(), "assets/minecraft/textures/block/halo.png", &halo);
+        write_png(
+            dir.path(),
+            "assets/minecraft/textures/block/clean.png",
+            &RgbaImage::from_pixel(4, 4, Rgba([0, 0, 0, 0])),
+        );
+
+        let halos = scan_transparency_halos(dir.path());
+        assert_eq!(halos.len(), 1);
+        assert_eq!(halos[0].path, "assets/minecraft/textures/block/halo.png");
+        assert_eq!(halos[0].pixel_count, 2);
+    }
+
+    #[test]
+    fn alpha_bleed_pack_rewrites_textures_with_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let relative = "assets/minecraft/textures/block/glass.png";
+        let mut img = RgbaImage::from_pixel(2, 1, Rgba([0, 0, 0, 0]));
+        img.put_pixel(0, 0, Rgba([10, 20, 30, 255]));
+        write_png(dir.path(), relative, &img);
+
+        let results = alpha_bleed_pack(dir.path()).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].path, relative);
+        assert_eq!(results[0].pixels_filled, 1);
+
+        let bled = image::open(dir.path().join(relative)).unwrap().to_rgba8();
+        assert_eq!(bled.get_pixel(1, 0), &Rgba([10, 20, 30, 0]));
+        assert_eq!(crate::history_manager::read_history_entries(dir.path(), relative).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn only_badly_sized_textures_are_listed() {
+        let dir = tempfile::tempdir().unwrap();
+        write_png(dir.path(), "assets/minecraft/textures/block/stone.png", &RgbaImage::new(16, 16));
+        write_png(dir.path(), "assets/mypack/textures/item/ruby.png", &RgbaImage::new(13, 17));
+
+        let invalid = scan_invalid_textures(dir.path());
+        assert_eq!(invalid.len(), 1);
+        assert_eq!(invalid[0].path, "assets/mypack/textures/item/ruby.png");
+        assert_eq!(invalid[0].namespace, "mypack");
+        assert_eq!((invalid[0].width, invalid[0].height), (13, 17));
+    }
+
+    #[test]
+    fn unreferenced_textures_are_orphaned() {
+        let dir = tempfile::tempdir().unwrap();
+        let texture = RgbaImage::new(16, 16);
+        write_png(dir.path(), "pack.png", &texture);
+        write_png(dir.path(), "assets/minecraft/textures/block/stone.png", &texture);
+        write_png(dir.path(), "assets/minecraft/textures/block/unused.png", &texture);
+        write_png(dir.path(), "assets/mypack/textures/item/ruby.png", &texture);
+        write_png(dir.path(), "assets/minecraft/textures/entity/pig.png", &texture);
+
+        let models = dir.path().join("assets/minecraft/models");
+        std::fs::create_dir_all(models.join("item")).unwrap();
+        std::fs::create_dir_all(models.join("block")).unwrap();
+        std::fs::write(
+            models.join("block/stone.json"),
+            r##"{ "textures": { "all": "block/stone", "particle": "#all" } }"##,
+        )
+        .unwrap();
+        std::fs::write(
+            models.join("item/ruby.json"),
+            r#"{ "parent": "item/generated", "textures": { "layer0": "mypack:item/ruby" } }"#,
+        )
+        .unwrap();
+
+        // entity 纹理不经由模型使用, 不计入
+        assert_eq!(scan_orphaned_textures(dir.path()), ["assets/minecraft/textures/block/unused.png"]);
+    }
+
+    #[test]
+    fn existing_output_is_snapshotted_before_bleeding() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = "assets/minecraft/textures/block/glass.png";
+        let output = "assets/minecraft/textures/block/glass_bled.png";
+        let mut img = RgbaImage::from_pixel(2, 1, Rgba([0, 0, 0, 0]));
+        img.put_pixel(0, 0, Rgba([10, 20, 30, 255]));
+        write_png(dir.path(), input, &img);
+        write_png(dir.path(), output, &RgbaImage::from_pixel(1, 1, Rgba([1, 2, 3, 255])));
+
+        let filled = alpha_bleed_to(dir.path(), &dir.path().join(input), &dir.path().join(output)).unwrap();
+        assert_eq!(filled, 1);
+
+        let bled = image::open(dir.path().join(output)).unwrap().to_rgba8();
+        assert_eq!(bled.get_pixel(1, 0), &Rgba([10, 20, 30, 0]));
+        let history = crate::history_manager::read_history_entries(dir.path(), output).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].file_type, "image");
+        assert!(crate::history_manager::read_history_entries(dir.path(), input).unwrap().is_empty());
+    }
+}
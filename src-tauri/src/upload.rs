@@ -0,0 +1,290 @@
+use crate::checksum::{hash_file, HashAlgorithm};
+use crate::download_manager::{DownloadManager, DownloadProgress, DownloadStatus};
+use crate::error::AppError;
+use futures_util::stream;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tauri::State;
+use tokio::io::AsyncReadExt;
+use tokio_util::sync::CancellationToken;
+
+/// 遇到 5xx 或连接错误时的最大尝试次数
+const MAX_UPLOAD_ATTEMPTS: u32 = 4;
+/// 上传时每次读取的块大小
+const UPLOAD_CHUNK_SIZE: usize = 256 * 1024;
+
+/// 上传使用的 HTTP 方法
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum UploadMethod {
+    #[default]
+    Put,
+    Post,
+}
+
+/// 上传结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadResult {
+    pub task_id: String,
+    /// 上传后的访问地址, 可直接填入 server.properties 的 resource-pack
+    pub url: String,
+    /// ZIP 的 sha1, 对应 server.properties 的 resource-pack-sha1
+    pub sha1: String,
+    pub size: u64,
+}
+
+/// 去掉预签名参数等查询字符串, 得到公开访问地址
+fn public_url(url: &reqwest::Url) -> String {
+    let mut url = url.clone();
+    url.set_query(None);
+    url.set_fragment(None);
+    url.to_string()
+}
+
+fn build_headers(headers: &HashMap<String, String>) -> Result<reqwest::header::HeaderMap, AppError> {
+    let mut map = reqwest::header::HeaderMap::new();
+    for (name, value) in headers {
+        let name = reqwest::header::HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| AppError::InvalidInput(format!("无效的请求头 {}: {}", name, e)))?;
+        let value = reqwest::header::HeaderValue::from_str(value)
+            .map_err(|e| AppError::InvalidInput(format!("无效的请求头值 {}: {}", name, e)))?;
+        map.insert(name, value);
+    }
+    Ok(map)
+}
+
+/// 以流的方式读取文件, 同时累计已发送的字节数
+fn file_body(file: tokio::fs::File, sent: Arc<AtomicU64>) -> reqwest::Body {
+    let chunks = stream::unfold(file, move |mut file| {
+        let sent = Arc::clone(&sent);
+        async move {
+            let mut buffer = vec![0u8; UPLOAD_CHUNK_SIZE];
+            match file.read(&mut buffer).await {
+                Ok(0) => None,
+                Ok(read) => {
+                    buffer.truncate(read);
+                    sent.fetch_add(read as u64, Ordering::Relaxed);
+                    Some((Ok::<_, std::io::Error>(buffer), file))
+                }
+                Err(e) => Some((Err(e), file)),
+            }
+        }
+    });
+    reqwest::Body::wrap_stream(chunks)
+}
+
+/// 单次上传请求的参数
+struct UploadRequest<'a> {
+    zip_path: &'a Path,
+    size: u64,
+    url: &'a reqwest::Url,
+    method: UploadMethod,
+    headers: reqwest::header::HeaderMap,
+}
+
+/// 执行上传, 对临时性错误按指数退避重试
+async fn upload_with_retry(
+    request: &UploadRequest<'_>,
+    task_id: &str,
+    manager: &DownloadManager,
+    cancel_token: &CancellationToken,
+) -> Result<reqwest::Response, AppError> {
+    let UploadRequest { zip_path, size, url, method, headers } = request;
+    let size = *size;
+    let client = reqwest::Client::new();
+    let method = match method {
+        UploadMethod::Put => reqwest::Method::PUT,
+        UploadMethod::Post => reqwest::Method::POST,
+    };
+
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+
+        let file = tokio::fs::File::open(zip_path)
+            .await
+            .map_err(|e| AppError::io("打开ZIP失败", e))?;
+        let sent = Arc::new(AtomicU64::new(0));
+        let send = client
+            .request(method.clone(), (*url).clone())
+            .headers(headers.clone())
+            .header(reqwest::header::CONTENT_LENGTH, size)
+            .body(file_body(file, Arc::clone(&sent)))
+            .send();
+        tokio::pin!(send);
+
+        let start_time = std::time::Instant::now();
+        let mut ticker = tokio::time::interval(std::time::Duration::from_millis(500));
+        let result = loop {
+            tokio::select! {
+                result = &mut send => break result,
+                _ = cancel_token.cancelled() => {
+                    return Err(AppError::Other("上传已取消".to_string()));
+                }
+                _ = ticker.tick() => {
+                    let bytes = sent.load(Ordering::Relaxed);
+                    let elapsed = start_time.elapsed().as_secs_f64();
+                    let speed = if elapsed > 0.0 { bytes as f64 / elapsed } else { 0.0 };
+                    let eta = if speed > 0.0 {
+                        Some((size.saturating_sub(bytes) as f64 / speed) as u64)
+                    } else {
+                        None
+                    };
+                    let percent = (bytes * 100).checked_div(size).unwrap_or(0) as usize;
+
+                    manager.update_progress(task_id, DownloadProgress {
+                        task_id: task_id.to_string(),
+                        status: DownloadStatus::Downloading,
+                        current: percent.min(99),
+                        total: 100,
+                        current_file: Some(format!("第 {} 次尝试", attempt)),
+                        speed,
+                        eta,
+                        error: None,
+                    }).await;
+                }
+            }
+        };
+
+        let retryable = match &result {
+            Ok(response) => response.status().is_server_error(),
+            Err(e) => e.is_connect() || e.is_timeout(),
+        };
+
+        if !retryable || attempt >= MAX_UPLOAD_ATTEMPTS {
+            let response = result.map_err(AppError::from)?;
+            if !response.status().is_success() {
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                return Err(AppError::Network(format!("上传失败: HTTP {} {}", status, body.trim())));
+            }
+            return Ok(response);
+        }
+
+        let delay = std::time::Duration::from_secs(1 << (attempt - 1));
+        println!("[上传] 第 {} 次尝试失败, {:?} 后重试", attempt, delay);
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = cancel_token.cancelled() => {
+                return Err(AppError::Other("上传已取消".to_string()));
+            }
+        }
+    }
+}
+
+/// 上传材质包ZIP到指定地址
+///
+/// 未指定 zip_path 时先将当前材质包导出为临时ZIP
+#[tauri::command]
+pub async fn upload_pack(
+    zip_path: Option<String>,
+    upload_url: String,
+    method: Option<UploadMethod>,
+    headers: Option<HashMap<String, String>>,
+    state: State<'_, crate::commands::AppState>,
+    manager: State<'_, Arc<DownloadManager>>,
+) -> Result<UploadResult, AppError> {
+    let url = reqwest::Url::parse(&upload_url)
+        .map_err(|e| AppError::InvalidInput(format!("无效的上传地址: {}", e)))?;
+    let headers = build_headers(&headers.unwrap_or_default())?;
+
+    let (zip_path, temporary) = match zip_path {
+        Some(path) => (PathBuf::from(path), false),
+        None => {
+            let pack_path = state.pack_path()?;
+            let temp_dir = crate::zip_handler::get_temp_extract_dir();
+            std::fs::create_dir_all(&temp_dir)
+                .map_err(|e| AppError::io("创建临时目录失败", e))?;
+            let output = temp_dir.join(format!("upload-{}.zip", uuid::Uuid::new_v4()));
+            crate::zip_handler::create_zip(
+                &pack_path,
+                &output,
+                &[],
+                &crate::zip_handler::ExcludePatterns::export_defaults(),
+                crate::zip_handler::ZipCompression::Default.file_options(),
+                None,
+            )?;
+            (output, true)
+        }
+    };
+
+    let result = upload_zip(&zip_path, &url, method.unwrap_or_default(), headers, &manager).await;
+
+    if temporary {
+        let _ = std::fs::remove_file(&zip_path);
+    }
+
+    result
+}
+
+async fn upload_zip(
+    zip_path: &Path,
+    url: &reqwest::Url,
+    method: UploadMethod,
+    headers: reqwest::header::HeaderMap,
+    manager: &Arc<DownloadManager>,
+) -> Result<UploadResult, AppError> {
+    let size = std::fs::metadata(zip_path)
+        .map_err(|e| AppError::io("读取ZIP失败", e))?
+        .len();
+    let sha1 = hash_file(HashAlgorithm::Sha1, zip_path)?;
+
+    let file_name = zip_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let task_id = manager.create_task(
+        format!("上传材质包: {}", file_name),
+        "upload".to_string(),
+        zip_path.parent().map(Path::to_path_buf).unwrap_or_default(),
+        None,
+    ).await;
+
+    let cancel_token = CancellationToken::new();
+    manager.register_cancel_token(task_id.clone(), cancel_token.clone()).await;
+
+    let request = UploadRequest { zip_path, size, url, method, headers };
+    let result = match manager.acquire_slot(&task_id).await {
+        Some(_slot) => upload_with_retry(&request, &task_id, manager, &cancel_token).await,
+        None => Err(AppError::Other("上传已取消".to_string())),
+    };
+    manager.remove_cancel_token(&task_id).await;
+
+    let final_progress = |status, error| DownloadProgress {
+        task_id: task_id.clone(),
+        status,
+        current: 100,
+        total: 100,
+        current_file: None,
+        speed: 0.0,
+        eta: None,
+        error,
+    };
+
+    match result {
+        Ok(response) => {
+            manager.record_transfer(&task_id, 1, size).await;
+            manager.update_progress(&task_id, final_progress(DownloadStatus::Completed, None)).await;
+
+            // 优先使用服务器返回的 Location
+            let url = response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|location| url.join(location).ok())
+                .map(|location| location.to_string())
+                .unwrap_or_else(|| public_url(url));
+
+            Ok(UploadResult { task_id, url, sha1, size })
+        }
+        Err(e) => {
+            if !cancel_token.is_cancelled() {
+                manager.update_progress(&task_id, final_progress(DownloadStatus::Failed, Some(e.to_string()))).await;
+            }
+            Err(e)
+        }
+    }
+}
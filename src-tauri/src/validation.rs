@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+/// 问题严重程度
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+/// 校验发现的单个问题, 各类校验器共用此结构以便汇总到完整报告中
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    /// 校验器分类, 如 "sounds"
+    pub category: String,
+    /// 问题所在文件(相对材质包根目录)
+    pub path: String,
+    pub message: String,
+}
+
+impl ValidationIssue {
+    pub fn new(severity: Severity, category: &str, path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            category: category.to_string(),
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// 按严重程度和路径排序
+pub fn sort_issues(issues: &mut [ValidationIssue]) {
+    issues.sort_by(|a, b| {
+        a.severity
+            .cmp(&b.severity)
+            .then_with(|| a.path.cmp(&b.path))
+            .then_with(|| a.message.cmp(&b.message))
+    });
+}
@@ -0,0 +1,123 @@
+use crate::commands::AppState;
+use crate::error::AppError;
+use crate::image_handler::{get_image_info, ImageInfo};
+use crate::pack_parser::read_pack_format_range;
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use tauri::State;
+
+/// 原版资源文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VanillaAsset {
+    pub version: String,
+    /// jar 中的路径, 如 assets/minecraft/textures/block/stone.png
+    pub asset_path: String,
+    /// 提取后缓存的本地路径
+    pub cached_path: String,
+    pub base64: String,
+    /// 不是图片时为空
+    pub image_info: Option<ImageInfo>,
+}
+
+/// 规范化为 jar 内的 assets/ 路径, 拒绝 .. 等越界路径
+fn normalize_asset_path(relative_asset_path: &str) -> Result<String, String> {
+    let trimmed = relative_asset_path.replace('\\', "/");
+    let trimmed = trimmed.trim_start_matches('/');
+    let asset_path = if trimmed.starts_with("assets/") {
+        trimmed.to_string()
+    } else {
+        format!("assets/{}", trimmed)
+    };
+
+    if Path::new(&asset_path)
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_)))
+    {
+        return Err(format!("无效的资源路径: {}", relative_asset_path));
+    }
+
+    Ok(asset_path)
+}
+
+/// 从jar中提取单个文件
+fn extract_jar_entry(jar_path: &Path, entry_name: &str, output_path: &Path) -> Result<(), String> {
+    let file = File::open(jar_path)
+        .map_err(|e| format!("打开jar文件失败: {}", e))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| format!("读取jar文件失败: {}", e))?;
+    let mut entry = archive
+        .by_name(entry_name)
+        .map_err(|_| format!("原版资源中不存在 {}", entry_name))?;
+
+    let mut buffer = Vec::new();
+    entry.read_to_end(&mut buffer)
+        .map_err(|e| format!("读取 {} 失败: {}", entry_name, e))?;
+
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("创建缓存目录失败: {}", e))?;
+    }
+    fs::write(output_path, buffer)
+        .map_err(|e| format!("写入缓存文件失败: {}", e))
+}
+
+/// 获取原版文件的本地缓存路径, 未缓存时从jar中提取(jar不存在时先下载)
+pub async fn ensure_vanilla_asset(version_id: &str, asset_path: &str) -> Result<PathBuf, String> {
+    let cache_dir = crate::commands::template_cache_dir()?;
+    let cached_path = cache_dir.join("vanilla").join(version_id).join(asset_path);
+    if cached_path.is_file() {
+        return Ok(cached_path);
+    }
+
+    fs::create_dir_all(&cache_dir)
+        .map_err(|e| format!("创建缓存目录失败: {}", e))?;
+    let jar_path = crate::version_downloader::download_version(version_id, &cache_dir).await?;
+    extract_jar_entry(Path::new(&jar_path), asset_path, &cached_path)?;
+
+    Ok(cached_path)
+}
+
+/// 根据当前材质包的 pack_format 推断对应的最新正式版
+fn infer_pack_version(pack_path: &Path) -> Option<String> {
+    let content = fs::read_to_string(pack_path.join("pack.mcmeta")).ok()?;
+    let mcmeta: serde_json::Value = serde_json::from_str(&content).ok()?;
+    let (min, max) = read_pack_format_range(mcmeta.get("pack")?)?;
+    crate::version_converter::get_release_versions_in_format_range(min, max).pop()
+}
+
+/// 获取原版对应的资源文件, 用于和材质包中的文件对比
+#[tauri::command]
+pub async fn get_vanilla_asset(
+    relative_asset_path: String,
+    version_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<VanillaAsset, AppError> {
+    let asset_path = normalize_asset_path(&relative_asset_path)
+        .map_err(AppError::InvalidInput)?;
+
+    let version = match version_id {
+        Some(version) => version,
+        None => {
+            let base_path = state.pack_path()?;
+            infer_pack_version(&base_path).ok_or_else(|| {
+                AppError::InvalidInput("无法根据 pack.mcmeta 推断游戏版本, 请指定版本".to_string())
+            })?
+        }
+    };
+
+    let cached_path = ensure_vanilla_asset(&version, &asset_path).await?;
+    let bytes = fs::read(&cached_path)
+        .map_err(|e| AppError::io("读取原版资源失败", e))?;
+    let image_info = get_image_info(&cached_path).ok();
+
+    Ok(VanillaAsset {
+        version,
+        asset_path,
+        cached_path: cached_path.to_string_lossy().to_string(),
+        base64: general_purpose::STANDARD.encode(bytes),
+        image_info,
+    })
+}
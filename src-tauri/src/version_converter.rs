@@ -35,6 +35,20 @@ pub struct PackInfo {
     pub supported_formats: Option<Value>,
 }
 
+/// 转换时会被改写的文件类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConversionChangeKind {
+    Mcmeta,
+}
+
+/// 转换预览中的单个文件变更
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversionChange {
+    pub path: String,
+    pub change: ConversionChangeKind,
+}
+
 pub fn convert_pack_version(
     input_path: &Path,
     output_path: &Path,
@@ -51,6 +65,72 @@ pub fn convert_pack_version(
     }
 }
 
+/// 预览转换会改写的文件, 不写入任何内容
+pub fn preview_conversion(
+    input_path: &Path,
+    target_version: &str,
+) -> Result<Vec<ConversionChange>, String> {
+    let target_pack_format = get_pack_format_from_version(target_version)?;
+
+    let mut changes = Vec::new();
+    if input_path.is_file() {
+        let file = fs::File::open(input_path)
+            .map_err(|e| format!("无法打开输入ZIP: {}", e))?;
+        let mut archive = ZipArchive::new(file)
+            .map_err(|e| format!("无法读取ZIP文件: {}", e))?;
+
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i)
+                .map_err(|e| format!("无法读取ZIP内容: {}", e))?;
+            let file_name = file.name().to_string();
+
+            if is_pack_mcmeta_entry(&file_name) {
+                let mut contents = String::new();
+                file.read_to_string(&mut contents)
+                    .map_err(|e| format!("无法读取pack.mcmeta: {}", e))?;
+
+                if mcmeta_needs_update(&contents, target_pack_format)? {
+                    changes.push(ConversionChange {
+                        path: file_name,
+                        change: ConversionChangeKind::Mcmeta,
+                    });
+                }
+            }
+        }
+    } else if input_path.is_dir() {
+        let mcmeta_path = input_path.join("pack.mcmeta");
+        if !mcmeta_path.exists() {
+            return Err("未找到pack.mcmeta文件".to_string());
+        }
+
+        let contents = fs::read_to_string(&mcmeta_path)
+            .map_err(|e| format!("无法读取pack.mcmeta: {}", e))?;
+        if mcmeta_needs_update(&contents, target_pack_format)? {
+            changes.push(ConversionChange {
+                path: "pack.mcmeta".to_string(),
+                change: ConversionChangeKind::Mcmeta,
+            });
+        }
+    } else {
+        return Err("输入路径既不是文件也不是文件夹".to_string());
+    }
+
+    Ok(changes)
+}
+
+fn is_pack_mcmeta_entry(file_name: &str) -> bool {
+    file_name == "pack.mcmeta" || file_name.ends_with("/pack.mcmeta")
+}
+
+/// 转换后的pack.mcmeta与原内容在语义上是否不同
+fn mcmeta_needs_update(contents: &str, target_pack_format: u32) -> Result<bool, String> {
+    let original: Value = serde_json::from_str(contents)
+        .map_err(|e| format!("无法解析JSON: {}", e))?;
+    let updated: Value = serde_json::from_str(&update_pack_format_in_json(contents, target_pack_format)?)
+        .map_err(|e| format!("无法解析JSON: {}", e))?;
+    Ok(original != updated)
+}
+
 fn get_pack_format_from_version(version: &str) -> Result<u32, String> {
     let versions = get_supported_versions();
     
@@ -84,7 +164,7 @@ fn convert_zip_pack(
             .map_err(|e| format!("无法读取ZIP内容: {}", e))?;
         let file_name = file.name().to_string();
         
-        if file_name == "pack.mcmeta" || file_name.ends_with("/pack.mcmeta") {
+        if is_pack_mcmeta_entry(&file_name) {
             let mut contents = String::new();
             file.read_to_string(&mut contents)
                 .map_err(|e| format!("无法读取pack.mcmeta: {}", e))?;
@@ -175,7 +255,7 @@ fn convert_folder_pack(
     
     let output_canonical = output_path.canonicalize().ok();
     
-    copy_dir_all_excluding(input_path, output_path, output_canonical.as_deref())?;
+    crate::fs_utils::copy_dir_all_excluding(input_path, output_path, output_canonical.as_deref())?;
     
     // 修改pack.mcmeta
     let mcmeta_path = output_path.join("pack.mcmeta");
@@ -194,37 +274,6 @@ fn convert_folder_pack(
     Ok(format!("成功转换到输出路径: {:?}", output_path))
 }
 
-/// 递归复制目录
-fn copy_dir_all_excluding(src: &Path, dst: &Path, exclude: Option<&Path>) -> Result<(), String> {
-    fs::create_dir_all(dst)
-        .map_err(|e| format!("无法创建目录: {}", e))?;
-    
-    for entry in fs::read_dir(src)
-        .map_err(|e| format!("无法读取目录: {}", e))? {
-        let entry = entry.map_err(|e| format!("无法读取条目: {}", e))?;
-        let path = entry.path();
-        let file_name = entry.file_name();
-        let dest_path = dst.join(&file_name);
-        
-        if let Some(exclude_path) = exclude {
-            if let Ok(canonical_path) = path.canonicalize() {
-                if canonical_path == exclude_path || canonical_path.starts_with(exclude_path) {
-                    continue;
-                }
-            }
-        }
-        
-        if path.is_dir() {
-            copy_dir_all_excluding(&path, &dest_path, exclude)?;
-        } else {
-            fs::copy(&path, &dest_path)
-                .map_err(|e| format!("无法复制文件 {:?}: {}", path, e))?;
-        }
-    }
-    
-    Ok(())
-}
-
 /// 更新pack_format
 fn update_pack_format_in_json(json_str: &str, new_pack_format: u32) -> Result<String, String> {
     let mut value: Value = serde_json::from_str(json_str)
@@ -294,6 +343,124 @@ pub fn get_supported_versions() -> Vec<(u32, String)> {
 
 /// 从文件加载版本映射
 fn load_version_map_from_file() -> Result<Vec<(u32, String)>, String> {
+    for path in &version_map_candidates()? {
+        if path.exists() {
+            match load_version_map(path) {
+                Ok(versions) => {
+                    let canonical_path = path.canonicalize().ok();
+                    eprintln!("✓ 成功从 {:?} 加载版本映射", canonical_path.unwrap_or_else(|| path.clone()));
+                    return Ok(versions);
+                },
+                Err(e) => eprintln!("✗ 从 {:?} 加载失败: {}", path, e),
+            }
+        }
+    }
+    
+    Err("未找到 version_map.json 文件".to_string())
+}
+
+/// 材质包适用的游戏版本范围
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackVersionRange {
+    pub min_format: u32,
+    pub max_format: u32,
+    pub min_version: String,
+    pub max_version: String,
+}
+
+/// 将 pack_format 范围换算为最早和最新的正式版本号
+///
+/// 优先使用 version_map.json 中的完整版本列表, 否则从支持版本列表的 "1.21 – 1.21.1" 标签中取首尾;
+/// 范围内没有已知格式时使用不大于上限的最近一个格式
+pub fn get_version_range_for_formats(min_format: u32, max_format: u32) -> Option<PackVersionRange> {
+    version_range_from(
+        &get_release_versions_in_format_range(min_format, max_format),
+        &get_supported_versions(),
+        min_format,
+        max_format,
+    )
+}
+
+/// 根据范围内的正式版本和支持版本列表换算版本范围, 不读取文件
+fn version_range_from(
+    releases: &[String],
+    supported: &[(u32, String)],
+    min_format: u32,
+    max_format: u32,
+) -> Option<PackVersionRange> {
+    let range = |min_version: String, max_version: String| PackVersionRange {
+        min_format,
+        max_format,
+        min_version,
+        max_version,
+    };
+
+    if let (Some(first), Some(last)) = (releases.first(), releases.last()) {
+        return Some(range(first.clone(), last.clone()));
+    }
+
+    let mut labels: Vec<&String> = supported
+        .iter()
+        .filter(|(format, _)| (min_format..=max_format).contains(format))
+        .map(|(_, label)| label)
+        .collect();
+    if labels.is_empty() {
+        labels.extend(
+            supported
+                .iter()
+                .filter(|(format, _)| *format <= max_format)
+                .max_by_key(|(format, _)| *format)
+                .map(|(_, label)| label),
+        );
+    }
+
+    let split = |label: &str| {
+        let mut parts = label.split('–').map(str::trim);
+        let start = parts.next().unwrap_or_default().to_string();
+        let end = parts.next_back().map(str::to_string).unwrap_or_else(|| start.clone());
+        (start, end)
+    };
+    let (min_version, _) = split(labels.first()?);
+    let (_, max_version) = split(labels.last()?);
+    Some(range(min_version, max_version))
+}
+
+/// 获取 pack_format 范围内的所有正式版本号, 按版本从旧到新排序
+pub fn get_release_versions_in_format_range(min_format: u32, max_format: u32) -> Vec<String> {
+    let version_map = version_map_candidates()
+        .unwrap_or_default()
+        .iter()
+        .filter(|path| path.exists())
+        .find_map(|path| read_version_map(path).ok());
+
+    match version_map {
+        Some(map) => release_versions_in_range(&map, min_format, max_format),
+        None => Vec::new(),
+    }
+}
+
+/// 从版本映射中筛出 pack_format 范围内的正式版本号
+fn release_versions_in_range(version_map: &VersionMap, min_format: u32, max_format: u32) -> Vec<String> {
+    let mut versions: Vec<String> = version_map
+        .resource_pack
+        .iter()
+        .filter_map(|(k, list)| k.parse::<u32>().ok().map(|format| (format, list)))
+        .filter(|(format, _)| *format >= min_format && *format <= max_format)
+        .flat_map(|(_, list)| list.iter().filter(|v| is_release_version(v)).cloned())
+        .collect();
+
+    versions.sort_by_key(|version| {
+        version
+            .split('.')
+            .map(|part| part.parse::<u32>().unwrap_or(0))
+            .collect::<Vec<_>>()
+    });
+    versions.dedup();
+    versions
+}
+
+/// version_map.json 可能存在的位置
+fn version_map_candidates() -> Result<Vec<PathBuf>, String> {
     // 获取可执行文件目录
     let exe_path = std::env::current_exe()
         .map_err(|e| format!("无法获取执行路径: {}", e))?;
@@ -304,8 +471,9 @@ fn load_version_map_from_file() -> Result<Vec<(u32, String)>, String> {
     let current_dir = std::env::current_dir()
         .map_err(|e| format!("无法获取当前目录: {}", e))?;
     
-    // 尝试多个可能的路径
+    // 尝试多个可能的路径, 数据目录中的文件可覆盖内置映射
     let possible_paths = vec![
+        crate::app_dirs::data_dir().join("version_map.json"),
         exe_dir.join("version_map.json"),
         exe_dir.join("resources").join("version_map.json"),
         exe_dir.join("_up_").join("version_map.json"),
@@ -318,30 +486,24 @@ fn load_version_map_from_file() -> Result<Vec<(u32, String)>, String> {
         exe_dir.join("version_map").join("version_map.json"),
     ];
     
-    for path in &possible_paths {
-        if path.exists() {
-            match load_version_map(path) {
-                Ok(versions) => {
-                    let canonical_path = path.canonicalize().ok();
-                    eprintln!("✓ 成功从 {:?} 加载版本映射", canonical_path.unwrap_or_else(|| path.clone()));
-                    return Ok(versions);
-                },
-                Err(e) => eprintln!("✗ 从 {:?} 加载失败: {}", path, e),
-            }
-        }
-    }
-    
-    Err("未找到 version_map.json 文件".to_string())
+    Ok(possible_paths)
 }
 
 /// 从指定路径加载版本映射
 fn load_version_map(path: &Path) -> Result<Vec<(u32, String)>, String> {
+    Ok(supported_versions_from_map(&read_version_map(path)?))
+}
+
+/// 读取 version_map.json
+fn read_version_map(path: &Path) -> Result<VersionMap, String> {
     let content = fs::read_to_string(path)
         .map_err(|e| format!("读取文件失败: {}", e))?;
-    
-    let version_map: VersionMap = serde_json::from_str(&content)
-        .map_err(|e| format!("解析JSON失败: {}", e))?;
-    
+    serde_json::from_str(&content)
+        .map_err(|e| format!("解析JSON失败: {}", e))
+}
+
+/// 把版本映射整理为 (pack_format, 版本范围标签) 列表
+fn supported_versions_from_map(version_map: &VersionMap) -> Vec<(u32, String)> {
     let mut versions: Vec<(u32, String)> = Vec::new();
     
     for (k, versions_list) in version_map.resource_pack.iter() {
@@ -396,11 +558,110 @@ fn load_version_map(path: &Path) -> Result<Vec<(u32, String)>, String> {
     
     versions.sort_by_key(|(pack_format, _)| *pack_format);
     
-    Ok(versions)
+    versions
 }
 
 /// 判断是否为正式版本
 fn is_release_version(version: &str) -> bool {
     version.chars().all(|c| c.is_numeric() || c == '.')
         && version.split('.').all(|part| !part.is_empty() && part.chars().all(|c| c.is_numeric()))
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 取两个 pack_format 不同的版本
+    fn two_versions() -> ((u32, String), (u32, String)) {
+        let versions = get_supported_versions();
+        let first = versions.first().cloned().unwrap();
+        let last = versions.iter().rev().find(|(format, _)| *format != first.0).cloned().unwrap();
+        (first, last)
+    }
+
+    fn mcmeta(pack_format: u32) -> String {
+        format!(r#"{{ "pack": {{ "pack_format": {}, "description": "test" }} }}"#, pack_format)
+    }
+
+    #[test]
+    fn format_change_lists_mcmeta() {
+        let ((old_format, _), (_, target)) = two_versions();
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("pack.mcmeta"), mcmeta(old_format)).unwrap();
+
+        let changes = preview_conversion(dir.path(), &target).unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].path, "pack.mcmeta");
+        assert_eq!(changes[0].change, ConversionChangeKind::Mcmeta);
+        // 预览不修改文件
+        assert_eq!(fs::read_to_string(dir.path().join("pack.mcmeta")).unwrap(), mcmeta(old_format));
+    }
+
+    #[test]
+    fn same_format_lists_nothing() {
+        let (_, (format, target)) = two_versions();
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("pack.mcmeta"), mcmeta(format)).unwrap();
+
+        assert!(preview_conversion(dir.path(), &target).unwrap().is_empty());
+    }
+
+    #[test]
+    fn zip_pack_lists_nested_mcmeta() {
+        let ((old_format, _), (_, target)) = two_versions();
+        let dir = tempfile::tempdir().unwrap();
+        let zip_path = dir.path().join("pack.zip");
+        let mut writer = zip::ZipWriter::new(fs::File::create(&zip_path).unwrap());
+        writer.start_file("MyPack/pack.mcmeta", SimpleFileOptions::default()).unwrap();
+        writer.write_all(mcmeta(old_format).as_bytes()).unwrap();
+        writer.finish().unwrap();
+
+        let changes = preview_conversion(&zip_path, &target).unwrap();
+        let paths: Vec<&str> = changes.iter().map(|change| change.path.as_str()).collect();
+        assert_eq!(paths, ["MyPack/pack.mcmeta"]);
+    }
+
+    /// 仓库自带的 version_map.json, 不依赖用户数据目录
+    fn fixture_map() -> VersionMap {
+        read_version_map(&Path::new(env!("CARGO_MANIFEST_DIR")).join("../public/version_map/version_map.json")).unwrap()
+    }
+
+    fn fixture_range(min_format: u32, max_format: u32) -> Option<PackVersionRange> {
+        let map = fixture_map();
+        version_range_from(
+            &release_versions_in_range(&map, min_format, max_format),
+            &supported_versions_from_map(&map),
+            min_format,
+            max_format,
+        )
+    }
+
+    #[test]
+    fn format_64_maps_to_its_versions() {
+        let range = fixture_range(64, 64).unwrap();
+        assert_eq!(range.min_version, "1.21.7");
+        assert_eq!(range.max_version, "1.21.8");
+    }
+
+    #[test]
+    fn range_form_mcmeta_spans_versions() {
+        let pack: Value = serde_json::from_str(
+            r#"{ "pack_format": 34, "supported_formats": { "min_inclusive": 34, "max_inclusive": 64 } }"#,
+        )
+        .unwrap();
+        let (min_format, max_format) = crate::pack_parser::read_pack_format_range(&pack).unwrap();
+
+        let range = fixture_range(min_format, max_format).unwrap();
+        assert_eq!((range.min_format, range.max_format), (34, 64));
+        assert_eq!(range.min_version, "1.21");
+        assert_eq!(range.max_version, "1.21.8");
+    }
+
+    #[test]
+    fn range_falls_back_to_labels_without_releases() {
+        let supported = vec![(34, "1.21 – 1.21.1".to_string()), (64, "1.21.7 – 1.21.8".to_string())];
+        let range = version_range_from(&[], &supported, 34, 64).unwrap();
+        assert_eq!(range.min_version, "1.21");
+        assert_eq!(range.max_version, "1.21.8");
+    }
+}
@@ -1,11 +1,15 @@
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use crate::error::AppError;
 
 /// 版本清单
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VersionManifest {
     pub latest: LatestVersions,
     pub versions: Vec<VersionInfo>,
+    /// 离线时使用了本地缓存的清单
+    #[serde(default)]
+    pub stale: bool,
 }
 
 /// 最新版本信息
@@ -85,6 +89,124 @@ pub async fn fetch_version_manifest() -> Result<VersionManifest, String> {
     Ok(manifest)
 }
 
+fn cached_manifest_path(temp_dir: &Path) -> PathBuf {
+    temp_dir.join("version_manifest.json")
+}
+
+/// 缓存的jar能正常打开时才视为有效
+fn is_valid_cached_jar(path: &Path) -> bool {
+    std::fs::File::open(path)
+        .ok()
+        .and_then(|file| zip::ZipArchive::new(file).ok())
+        .is_some_and(|archive| !archive.is_empty())
+}
+
+/// jar 旁记录下载时校验过的 sha1 的文件
+fn jar_sha1_path(jar_path: &Path) -> PathBuf {
+    jar_path.with_extension("jar.sha1")
+}
+
+/// 按 sha1 校验缓存的jar, expected_sha1 为空时使用下载时记录的 sha1
+///
+/// 返回 Some(true) 表示校验通过; 不一致时删除缓存以便重新下载并返回 Some(false);
+/// 没有可比对的 sha1(旧版本留下的缓存)时返回 None
+fn verify_cached_jar(jar_path: &Path, expected_sha1: Option<&str>) -> Option<bool> {
+    if !jar_path.is_file() {
+        return Some(false);
+    }
+    let sha1_path = jar_sha1_path(jar_path);
+    let expected = match expected_sha1 {
+        Some(expected) => expected.to_string(),
+        None => std::fs::read_to_string(&sha1_path).ok()?.trim().to_string(),
+    };
+
+    let matches = crate::checksum::hash_file(crate::checksum::HashAlgorithm::Sha1, jar_path)
+        .is_ok_and(|actual| actual.eq_ignore_ascii_case(&expected));
+    if matches {
+        // 旧缓存通过清单校验后补写记录, 之后离线也能校验
+        if expected_sha1.is_some() {
+            let _ = std::fs::write(&sha1_path, &expected);
+        }
+    } else {
+        eprintln!("[版本下载] 缓存的jar校验失败, 将重新下载: {}", jar_path.display());
+        let _ = std::fs::remove_file(jar_path);
+        let _ = std::fs::remove_file(&sha1_path);
+    }
+    Some(matches)
+}
+
+/// 为缓存目录中已有但清单里没有的jar生成版本条目
+fn cached_jar_versions(temp_dir: &Path, known: &[VersionInfo]) -> Vec<VersionInfo> {
+    let Ok(entries) = std::fs::read_dir(temp_dir) else {
+        return Vec::new();
+    };
+
+    let mut versions: Vec<VersionInfo> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "jar"))
+        .filter_map(|path| {
+            let id = path.file_stem()?.to_string_lossy().to_string();
+            if id.ends_with("-server") || known.iter().any(|v| v.id == id) {
+                return None;
+            }
+            let modified: chrono::DateTime<chrono::Utc> = path.metadata().ok()?.modified().ok()?.into();
+            let is_release = id.split('.').all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()));
+            Some(VersionInfo {
+                version_type: if is_release { "release" } else { "snapshot" }.to_string(),
+                url: String::new(),
+                time: modified.to_rfc3339(),
+                release_time: modified.to_rfc3339(),
+                id,
+            })
+        })
+        .collect();
+
+    versions.sort_by(|a, b| b.release_time.cmp(&a.release_time));
+    versions
+}
+
+/// 获取版本清单, 成功时保存到缓存目录; 获取失败时回退到缓存的清单和已下载的jar
+pub async fn fetch_version_manifest_cached(temp_dir: &Path) -> Result<VersionManifest, String> {
+    let manifest_path = cached_manifest_path(temp_dir);
+
+    let error = match fetch_version_manifest().await {
+        Ok(manifest) => {
+            let saved = std::fs::create_dir_all(temp_dir)
+                .and_then(|_| std::fs::write(&manifest_path, serde_json::to_string(&manifest).unwrap_or_default()));
+            if let Err(e) = saved {
+                eprintln!("[版本清单] 保存缓存失败: {}", e);
+            }
+            return Ok(manifest);
+        }
+        Err(e) => e,
+    };
+
+    let cached: Option<VersionManifest> = std::fs::read_to_string(&manifest_path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok());
+    let mut manifest = cached.unwrap_or(VersionManifest {
+        latest: LatestVersions { release: String::new(), snapshot: String::new() },
+        versions: Vec::new(),
+        stale: true,
+    });
+    manifest.stale = true;
+
+    let mut offline_versions = cached_jar_versions(temp_dir, &manifest.versions);
+    if manifest.versions.is_empty() && offline_versions.is_empty() {
+        return Err(error);
+    }
+    if manifest.latest.release.is_empty() {
+        if let Some(release) = offline_versions.iter().find(|v| v.version_type == "release") {
+            manifest.latest.release = release.id.clone();
+        }
+    }
+    offline_versions.append(&mut manifest.versions);
+    manifest.versions = offline_versions;
+
+    Ok(manifest)
+}
+
 /// 获取版本详细信息
 pub async fn fetch_version_details(version_url: &str) -> Result<VersionDetails, String> {
     let response = reqwest::get(version_url)
@@ -100,11 +222,18 @@ pub async fn fetch_version_details(version_url: &str) -> Result<VersionDetails,
 }
 
 /// 下载jar文件
+///
+/// 先写入 .part 文件, 边下载边计算 sha1, 校验通过后再重命名并记录 sha1, 中断、取消或校验失败时删除未完成的文件;
+/// on_progress 会收到 (已下载字节数, 总字节数)
 pub async fn download_jar_with_progress(
     download_url: &str,
     output_path: &Path,
+    expected_sha1: Option<&str>,
+    cancel_token: Option<&tokio_util::sync::CancellationToken>,
+    mut on_progress: impl FnMut(u64, u64),
 ) -> Result<(), String> {
     use futures_util::StreamExt;
+    use sha1::{Digest, Sha1};
     use std::io::Write;
     
     // 确保输出目录存在
@@ -113,36 +242,61 @@ pub async fn download_jar_with_progress(
             .map_err(|e| format!("Failed to create directory: {}", e))?;
     }
     
-    // 下载文件
-    let response = reqwest::get(download_url)
-        .await
-        .map_err(|e| format!("Failed to download jar: {}", e))?;
-    
-    let total_size = response.content_length().unwrap_or(0);
-    
-    // 创建文件
-    let mut file = std::fs::File::create(output_path)
-        .map_err(|e| format!("Failed to create file: {}", e))?;
-    
-    // 流式下载
-    let mut stream = response.bytes_stream();
-    let mut downloaded: u64 = 0;
-    
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk.map_err(|e| format!("Failed to read chunk: {}", e))?;
-        file.write_all(&chunk)
-            .map_err(|e| format!("Failed to write chunk: {}", e))?;
+    let part_path = output_path.with_extension("jar.part");
+    let result: Result<(), String> = async {
+        // 下载文件
+        let response = reqwest::get(download_url)
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| format!("Failed to download jar: {}", e))?;
+        
+        let total_size = response.content_length().unwrap_or(0);
         
-        downloaded += chunk.len() as u64;
+        // 创建文件
+        let mut file = std::fs::File::create(&part_path)
+            .map_err(|e| format!("Failed to create file: {}", e))?;
+        
+        // 流式下载
+        let mut stream = response.bytes_stream();
+        let mut downloaded: u64 = 0;
+        let mut hasher = Sha1::new();
+        
+        while let Some(chunk) = stream.next().await {
+            if cancel_token.is_some_and(|token| token.is_cancelled()) {
+                return Err("下载已取消".to_string());
+            }
+            let chunk = chunk.map_err(|e| format!("Failed to read chunk: {}", e))?;
+            file.write_all(&chunk)
+                .map_err(|e| format!("Failed to write chunk: {}", e))?;
+            hasher.update(&chunk);
+            
+            downloaded += chunk.len() as u64;
+            on_progress(downloaded, total_size);
+        }
+        file.flush()
+            .map_err(|e| format!("Failed to write file: {}", e))?;
+        drop(file);
+        
+        let actual = hex::encode(hasher.finalize());
+        if let Some(expected) = expected_sha1 {
+            if !actual.eq_ignore_ascii_case(expected) {
+                return Err(format!("jar 文件校验失败: 期望 sha1 {}, 实际 {}", expected, actual));
+            }
+        }
         
-        // 进度
-        if total_size > 0 {
-            let progress = (downloaded as f64 / total_size as f64 * 100.0) as u32;
-            println!("Download progress: {}%", progress);
+        std::fs::rename(&part_path, output_path)
+            .map_err(|e| format!("Failed to rename downloaded file: {}", e))?;
+        if expected_sha1.is_some() {
+            let _ = std::fs::write(jar_sha1_path(output_path), &actual);
         }
+        Ok(())
     }
+    .await;
     
-    Ok(())
+    if result.is_err() {
+        let _ = std::fs::remove_file(&part_path);
+    }
+    result
 }
 
 /// 获取最新的release版本并下载
@@ -166,33 +320,49 @@ pub async fn download_latest_release(output_dir: &Path) -> Result<String, String
     // 构建输出路径
     let output_path = output_dir.join(format!("{}.jar", details.id));
     
-    // 检查文件是否已存在(缓存)
-    if output_path.exists() {
+    // 检查文件是否已存在(缓存)且与清单的 sha1 一致
+    if verify_cached_jar(&output_path, Some(&client_download.sha1)) == Some(true) {
         println!("Using cached jar file: {:?}", output_path);
         return Ok(details.id);
     }
     
     // 下载jar文件
-    download_jar_with_progress(&client_download.url, &output_path).await?;
+    download_jar_with_progress(&client_download.url, &output_path, Some(&client_download.sha1), None, |_, _| {}).await?;
     
     Ok(details.id)
 }
-/// 下载指定版本
-pub async fn download_version(
-    version_id: &str,
-    output_dir: &Path,
-) -> Result<String, String> {
-    // 获取版本清单
+/// 从版本清单中查找版本并获取详细信息
+async fn fetch_version_details_by_id(version_id: &str) -> Result<VersionDetails, String> {
     let manifest = fetch_version_manifest().await?;
-    
-    // 找到指定版本
     let version = manifest.versions
         .iter()
         .find(|v| v.id == version_id)
         .ok_or(format!("Version {} not found", version_id))?;
-    
-    // 获取版本详细信息
-    let details = fetch_version_details(&version.url).await?;
+    fetch_version_details(&version.url).await
+}
+
+/// 下载指定版本
+pub async fn download_version(
+    version_id: &str,
+    output_dir: &Path,
+) -> Result<String, String> {
+    // 已有校验通过的缓存时无需联网
+    let cached_path = output_dir.join(format!("{}.jar", version_id));
+    let cached = verify_cached_jar(&cached_path, None);
+    if cached == Some(true) {
+        println!("Using cached jar file: {:?}", cached_path);
+        return Ok(cached_path.to_string_lossy().to_string());
+    }
+
+    let details = match fetch_version_details_by_id(version_id).await {
+        Ok(details) => details,
+        // 离线时仍可使用无法校验的旧缓存
+        Err(_) if cached.is_none() && is_valid_cached_jar(&cached_path) => {
+            println!("Using unverified cached jar file: {:?}", cached_path);
+            return Ok(cached_path.to_string_lossy().to_string());
+        }
+        Err(e) => return Err(e),
+    };
     
     // 获取客户端下载链接
     let client_download = details.downloads.client
@@ -201,68 +371,294 @@ pub async fn download_version(
     // 构建输出路径
     let output_path = output_dir.join(format!("{}.jar", details.id));
     
-    // 检查文件是否已存在(缓存)
-    if output_path.exists() {
+    // 检查文件是否已存在(缓存)且与清单的 sha1 一致
+    if verify_cached_jar(&output_path, Some(&client_download.sha1)) == Some(true) {
         println!("Using cached jar file: {:?}", output_path);
         return Ok(output_path.to_string_lossy().to_string());
     }
     
     // 下载jar文件
-    download_jar_with_progress(&client_download.url, &output_path).await?;
+    download_jar_with_progress(&client_download.url, &output_path, Some(&client_download.sha1), None, |_, _| {}).await?;
     
     Ok(output_path.to_string_lossy().to_string())
 }
 
-/// 从jar文件中提取assets文件夹
-pub fn extract_assets_from_jar(jar_path: &Path, output_dir: &Path) -> Result<(), String> {
+/// 下载指定版本的jar并通过下载任务报告进度, 可通过取消令牌中断
+pub async fn download_version_with_progress(
+    version_id: &str,
+    output_dir: &Path,
+    task_id: &str,
+    cancel_token: &tokio_util::sync::CancellationToken,
+    manager: &crate::download_manager::DownloadManager,
+) -> Result<String, String> {
+    use crate::download_manager::{DownloadProgress, DownloadStatus};
+    
+    let progress = |current: usize, current_file: String, speed: f64, eta: Option<u64>| DownloadProgress {
+        task_id: task_id.to_string(),
+        status: DownloadStatus::Downloading,
+        current,
+        total: 100,
+        current_file: Some(current_file),
+        speed,
+        eta,
+        error: None,
+    };
+    
+    manager.update_progress(task_id, progress(0, "获取版本信息...".to_string(), 0.0, None)).await;
+    let cached_path = output_dir.join(format!("{}.jar", version_id));
+    let cached = verify_cached_jar(&cached_path, None);
+    if cached == Some(true) {
+        return Ok(cached_path.to_string_lossy().to_string());
+    }
+    
+    let details = match fetch_version_details_by_id(version_id).await {
+        Ok(details) => details,
+        Err(_) if cached.is_none() && is_valid_cached_jar(&cached_path) => {
+            return Ok(cached_path.to_string_lossy().to_string());
+        }
+        Err(e) => return Err(e),
+    };
+    let client_download = details.downloads.client
+        .ok_or("Client download not available")?;
+    let output_path = output_dir.join(format!("{}.jar", details.id));
+    if verify_cached_jar(&output_path, Some(&client_download.sha1)) == Some(true) {
+        return Ok(output_path.to_string_lossy().to_string());
+    }
+    
+    if cancel_token.is_cancelled() {
+        return Err("下载已取消".to_string());
+    }
+    
+    // 下载在当前任务中进行, 进度经通道转发给下载管理器, 每 200ms 最多一次
+    let (progress_tx, mut progress_rx) = tokio::sync::mpsc::unbounded_channel();
+    let start_time = std::time::Instant::now();
+    let mut last_sent: Option<std::time::Instant> = None;
+    let download = download_jar_with_progress(
+        &client_download.url,
+        &output_path,
+        Some(&client_download.sha1),
+        Some(cancel_token),
+        move |downloaded, total| {
+            if last_sent.is_some_and(|sent| sent.elapsed().as_millis() < 200) && downloaded < total {
+                return;
+            }
+            last_sent = Some(std::time::Instant::now());
+            let _ = progress_tx.send((downloaded, total));
+        },
+    );
+    let report = async {
+        // 下载结束后发送端被释放, 循环随之结束
+        while let Some((downloaded, total)) = progress_rx.recv().await {
+            let total = if total > 0 { total } else { client_download.size };
+            let speed = downloaded as f64 / start_time.elapsed().as_secs_f64().max(0.001);
+            let eta = (speed > 0.0).then(|| (total.saturating_sub(downloaded) as f64 / speed) as u64);
+            let percent = (downloaded * 100).checked_div(total).unwrap_or(0).min(100) as usize;
+            let message = format!(
+                "下载 {}.jar ({:.1}/{:.1} MB)",
+                details.id,
+                downloaded as f64 / 1_048_576.0,
+                total as f64 / 1_048_576.0
+            );
+            manager.update_progress(task_id, progress(percent, message, speed, eta)).await;
+        }
+    };
+    let (result, _) = tokio::join!(download, report);
+    result?;
+    
+    let jar_size = std::fs::metadata(&output_path).map(|m| m.len()).unwrap_or(0);
+    manager.record_transfer(task_id, 1, jar_size).await;
+    
+    Ok(output_path.to_string_lossy().to_string())
+}
+
+/// 从jar文件中提取assets文件夹, include_prefixes 为 None 时提取全部
+pub fn extract_assets_from_jar(
+    jar_path: &Path,
+    output_dir: &Path,
+    include_prefixes: Option<&[String]>,
+) -> Result<(), String> {
+    extract_assets_from_jar_filtered(jar_path, output_dir, include_prefixes.unwrap_or_default(), |_, _| {})
+}
+
+/// 规范化用户选择的文件夹前缀为 assets/<前缀>/ 形式
+fn normalize_folder_prefixes(folders: &[String]) -> Vec<String> {
+    folders
+        .iter()
+        .map(|folder| {
+            let folder = folder.replace('\\', "/");
+            let folder = folder.trim_matches('/');
+            let folder = if folder == "assets" {
+                ""
+            } else {
+                folder.strip_prefix("assets/").unwrap_or(folder)
+            };
+            let folder = folder.trim_matches('/');
+            if folder.is_empty() {
+                "assets/".to_string()
+            } else {
+                format!("assets/{}/", folder)
+            }
+        })
+        .collect()
+}
+
+/// 从jar文件中提取assets下的文件, folders 为空时提取全部, 否则只提取这些文件夹(相对 assets/)
+///
+/// 文件在 rayon 线程池中并行解压和写入, 每个线程各自打开一份jar;
+/// on_progress 会收到 (已提取数, 需提取总数), 可能在多个线程中调用
+pub fn extract_assets_from_jar_filtered(
+    jar_path: &Path,
+    output_dir: &Path,
+    folders: &[String],
+    on_progress: impl Fn(usize, usize) + Sync,
+) -> Result<(), String> {
+    use rayon::prelude::*;
     use std::fs::File;
-    use std::io::Read;
+    use std::sync::atomic::{AtomicUsize, Ordering};
     use zip::ZipArchive;
-    
-    // 打开jar文件
-    let file = File::open(jar_path)
+
+    let open_archive = || -> Result<ZipArchive<File>, String> {
+        let file = File::open(jar_path)
+            .map_err(|e| format!("Failed to open jar file: {}", e))?;
+        ZipArchive::new(file)
+            .map_err(|e| format!("Failed to read jar archive: {}", e))
+    };
+    let archive = open_archive()?;
+
+    let prefixes = normalize_folder_prefixes(folders);
+    let is_selected = |name: &str| {
+        name.starts_with("assets/")
+            && (prefixes.is_empty() || prefixes.iter().any(|prefix| name.starts_with(prefix.as_str())))
+    };
+
+    // 先收集需要提取的条目, 用于分配任务和进度显示
+    let mut dirs: Vec<String> = Vec::new();
+    let mut files: Vec<usize> = Vec::new();
+    for i in 0..archive.len() {
+        let Some(name) = archive.name_for_index(i) else {
+            continue;
+        };
+        if !is_selected(name) {
+            continue;
+        }
+        if name.ends_with('/') {
+            dirs.push(name.to_string());
+        } else {
+            files.push(i);
+        }
+    }
+    drop(archive);
+
+    let total = files.len();
+    on_progress(0, total);
+
+    // 目录条目先创建, 文件的父目录在写入前再确保存在(create_dir_all 可并发调用)
+    for dir in &dirs {
+        std::fs::create_dir_all(output_dir.join(dir))
+            .map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    let extracted = AtomicUsize::new(0);
+    files.par_iter().try_for_each_init(
+        open_archive,
+        |archive, &index| -> Result<(), String> {
+            let archive = archive.as_mut().map_err(|e| e.clone())?;
+            let mut file = archive.by_index(index)
+                .map_err(|e| format!("Failed to read file from archive: {}", e))?;
+
+            let output_path = output_dir.join(file.name());
+            if let Some(parent) = output_path.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create parent directory: {}", e))?;
+            }
+
+            let mut output_file = File::create(&output_path)
+                .map_err(|e| format!("Failed to create output file: {}", e))?;
+            std::io::copy(&mut file, &mut output_file)
+                .map_err(|e| format!("Failed to write file: {}", e))?;
+
+            let done = extracted.fetch_add(1, Ordering::Relaxed) + 1;
+            if done.is_multiple_of(200) || done == total {
+                on_progress(done, total);
+            }
+            Ok(())
+        },
+    )
+}
+
+/// jar中assets下的文件夹
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JarAssetFolder {
+    pub name: String,
+    /// 相对 assets/ 的路径, 可直接作为提取时选择的文件夹
+    pub path: String,
+    /// 包含子文件夹在内的文件数
+    pub file_count: usize,
+    /// 解压后的总大小
+    pub size: u64,
+    pub children: Vec<JarAssetFolder>,
+}
+
+impl JarAssetFolder {
+    fn new(name: String, path: String) -> Self {
+        Self { name, path, file_count: 0, size: 0, children: Vec::new() }
+    }
+
+    fn add_file(&mut self, folders: &[&str], size: u64) {
+        self.file_count += 1;
+        self.size += size;
+
+        if let Some((first, rest)) = folders.split_first() {
+            let index = match self.children.iter().position(|c| c.name == *first) {
+                Some(index) => index,
+                None => {
+                    let path = if self.path.is_empty() {
+                        first.to_string()
+                    } else {
+                        format!("{}/{}", self.path, first)
+                    };
+                    self.children.push(JarAssetFolder::new(first.to_string(), path));
+                    self.children.len() - 1
+                }
+            };
+            self.children[index].add_file(rest, size);
+        }
+    }
+
+    fn sort(&mut self) {
+        self.children.sort_by(|a, b| a.name.cmp(&b.name));
+        for child in &mut self.children {
+            child.sort();
+        }
+    }
+}
+
+/// 不解压, 列出jar中assets下的文件夹树
+pub fn list_jar_asset_folders(jar_path: &Path) -> Result<JarAssetFolder, String> {
+    let file = std::fs::File::open(jar_path)
         .map_err(|e| format!("Failed to open jar file: {}", e))?;
-    
-    let mut archive = ZipArchive::new(file)
+    let mut archive = zip::ZipArchive::new(file)
         .map_err(|e| format!("Failed to read jar archive: {}", e))?;
-    
-    // 遍历所有文件
+
+    let mut root = JarAssetFolder::new("assets".to_string(), String::new());
     for i in 0..archive.len() {
-        let mut file = archive.by_index(i)
+        let entry = archive.by_index_raw(i)
             .map_err(|e| format!("Failed to read file from archive: {}", e))?;
-        
-        let file_path = file.name().to_string();
-        
-        // 只提取assets目录下的文件
-        if file_path.starts_with("assets/") {
-            let output_path = output_dir.join(&file_path);
-            
-            if file.is_dir() {
-                // 创建目录
-                std::fs::create_dir_all(&output_path)
-                    .map_err(|e| format!("Failed to create directory: {}", e))?;
-            } else {
-                // 确保父目录存在
-                if let Some(parent) = output_path.parent() {
-                    std::fs::create_dir_all(parent)
-                        .map_err(|e| format!("Failed to create parent directory: {}", e))?;
-                }
-                
-                // 写入文件
-                let mut output_file = File::create(&output_path)
-                    .map_err(|e| format!("Failed to create output file: {}", e))?;
-                
-                let mut buffer = Vec::new();
-                file.read_to_end(&mut buffer)
-                    .map_err(|e| format!("Failed to read file content: {}", e))?;
-                
-                std::io::Write::write_all(&mut output_file, &buffer)
-                    .map_err(|e| format!("Failed to write file: {}", e))?;
-            }
+        if entry.is_dir() {
+            continue;
         }
+        let Some(relative) = entry.name().strip_prefix("assets/") else {
+            continue;
+        };
+
+        let mut parts: Vec<&str> = relative.split('/').filter(|p| !p.is_empty()).collect();
+        // 最后一段是文件名
+        parts.pop();
+        root.add_file(&parts, entry.size());
     }
-    
-    Ok(())
+
+    root.sort();
+    Ok(root)
 }
 
 /// 检测语言文件格式
@@ -279,10 +675,34 @@ fn detect_language_file_extension(output_dir: &Path) -> String {
     "json".to_string()
 }
 
+/// 未指定时下载的语言
+pub const DEFAULT_LANGUAGE_LOCALE: &str = "zh_cn";
+
+/// 规范化语言代码(如 en_us、zh_cn), 只允许小写字母、数字和下划线
+pub fn normalize_locale(locale: Option<&str>) -> Result<String, String> {
+    let locale = match locale.map(str::trim) {
+        Some(locale) if !locale.is_empty() => locale.to_lowercase(),
+        _ => return Ok(DEFAULT_LANGUAGE_LOCALE.to_string()),
+    };
+    if !locale.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(format!("Invalid locale: {}", locale));
+    }
+    Ok(locale)
+}
+
+/// 语言文件在资源索引中的键: 新版 .json 与旧版 .lang
+fn language_asset_keys(locale: &str) -> [String; 2] {
+    [
+        format!("minecraft/lang/{}.json", locale),
+        format!("minecraft/lang/{}.lang", locale),
+    ]
+}
+
 /// 下载语言文件
 async fn download_language_file(
     version_url: &str,
     version_id: &str,
+    locale: &str,
     output_dir: &Path,
     task_id: Option<String>,
     manager: Option<crate::download_manager::DownloadManager>,
@@ -326,17 +746,16 @@ async fn download_language_file(
     let lang_extension = detect_language_file_extension(output_dir);
     println!("Detected language file extension: .{}", lang_extension);
     
-    // 查找中文语言文件
-    let lang_key_json = "minecraft/lang/zh_cn.json";
-    let lang_key_lang = "minecraft/lang/zh_cn.lang";
+    // 查找所选语言的语言文件
+    let [lang_key_json, lang_key_lang] = language_asset_keys(locale);
     
-    let (lang_asset, actual_key) = if let Some(asset) = assets.get(lang_key_json) {
+    let (lang_asset, actual_key) = if let Some(asset) = assets.get(&lang_key_json) {
         (asset, lang_key_json)
-    } else if let Some(asset) = assets.get(lang_key_lang) {
+    } else if let Some(asset) = assets.get(&lang_key_lang) {
         (asset, lang_key_lang)
     } else {
-        // 如果当前版本没有中文文件使用最新 release版本
-        println!("Chinese language file not found for version {}, trying latest release", version_id);
+        // 如果当前版本没有该语言文件使用最新 release版本
+        println!("Language file {} not found for version {}, trying latest release", locale, version_id);
         
         // 更新进度信息
         if let (Some(tid), Some(mgr)) = (&task_id, &manager) {
@@ -345,7 +764,7 @@ async fn download_language_file(
                 status: DownloadStatus::Downloading,
                 current: 3,
                 total: 4,
-                current_file: Some(format!("版本 {} 无中文文件，使用最新版本...", version_id)),
+                current_file: Some(format!("版本 {} 无 {} 语言文件，使用最新版本...", version_id, locale)),
                 speed: 0.0,
                 eta: None,
                 error: None,
@@ -360,10 +779,10 @@ async fn download_language_file(
             .ok_or("Latest release version not found")?;
         
         if latest_version.id == version_id {
-            return Err(format!("Chinese language file not found for version {} and latest release", version_id));
+            return Err(format!("Language file {} not found for version {} and latest release", locale, version_id));
         }
         
-        return Box::pin(download_language_file(&latest_version.url, &latest_version.id, output_dir, task_id, manager)).await
+        return Box::pin(download_language_file(&latest_version.url, &latest_version.id, locale, output_dir, task_id, manager)).await
             .map(|(success, _, _)| (success, true, latest_version.id.clone()));
     };
     
@@ -375,7 +794,7 @@ async fn download_language_file(
         hash
     );
     
-    println!("Downloading Chinese language file from: {}", actual_key);
+    println!("Downloading language file from: {}", actual_key);
     
     // 下载语言文件
     let response = reqwest::get(&download_url)
@@ -395,18 +814,21 @@ async fn download_language_file(
     let map_json_path = little100_dir.join("map.json");
     std::fs::write(&map_json_path, &content)
         .map_err(|e| format!("Failed to write map.json: {}", e))?;
+    if let (Some(tid), Some(mgr)) = (&task_id, &manager) {
+        mgr.record_transfer(tid, 1, content.len() as u64).await;
+    }
     
-    // 根据检测到的扩展名保存到 assets/minecraft/lang/zh_cn.？
+    // 根据检测到的扩展名保存到 assets/minecraft/lang/<locale>.？
     let lang_dir = output_dir.join("assets").join("minecraft").join("lang");
     std::fs::create_dir_all(&lang_dir)
         .map_err(|e| format!("Failed to create lang directory: {}", e))?;
     
-    let zh_cn_filename = format!("zh_cn.{}", lang_extension);
-    let zh_cn_path = lang_dir.join(&zh_cn_filename);
-    std::fs::write(&zh_cn_path, &content)
-        .map_err(|e| format!("Failed to write {}: {}", zh_cn_filename, e))?;
+    let lang_filename = format!("{}.{}", locale, lang_extension);
+    let lang_path = lang_dir.join(&lang_filename);
+    std::fs::write(&lang_path, &content)
+        .map_err(|e| format!("Failed to write {}: {}", lang_filename, e))?;
     
-    println!("Successfully downloaded and saved language file as {} for version {}", zh_cn_filename, version_id);
+    println!("Successfully downloaded and saved language file as {} for version {}", lang_filename, version_id);
     Ok((true, false, version_id.to_string()))
 }
 
@@ -429,10 +851,10 @@ pub async fn download_and_extract_version(
     let jar_path = download_version(version_id, temp_dir).await?;
     
     // 提取assets
-    extract_assets_from_jar(Path::new(&jar_path), output_dir)?;
+    extract_assets_from_jar(Path::new(&jar_path), output_dir, None)?;
     
     // 下载语言文件并返回结果
-    let lang_result = download_language_file(&version.url, version_id, output_dir, None, None).await;
+    let lang_result = download_language_file(&version.url, version_id, DEFAULT_LANGUAGE_LOCALE, output_dir, None, None).await;
     
     let result_message = match lang_result {
         Ok((_, used_latest, actual_version)) => {
@@ -457,11 +879,14 @@ pub async fn download_and_extract_version(
 }
 
 /// 下载版本并提取assets
+#[allow(clippy::too_many_arguments)]
 pub async fn download_and_extract_version_with_progress(
     version_id: &str,
     temp_dir: &Path,
     output_dir: &Path,
     keep_cache: bool,
+    folders: &[String],
+    locale: &str,
     task_id: String,
     manager: crate::download_manager::DownloadManager,
 ) -> Result<String, String> {
@@ -478,7 +903,8 @@ pub async fn download_and_extract_version_with_progress(
         eta: None,
         error: None,
     }).await;
-    let manifest = fetch_version_manifest().await.map_err(|e| {
+    // 离线时使用缓存的清单, 已缓存的jar仍可提取(语言文件会跳过)
+    let manifest = fetch_version_manifest_cached(temp_dir).await.map_err(|e| {
         let error_msg = format!("获取版本清单失败: {}", e);
         tokio::spawn({
             let manager = manager.clone();
@@ -517,6 +943,8 @@ pub async fn download_and_extract_version_with_progress(
         eta: None,
         error: None,
     }).await;
+    // 使用缓存的jar时不计入下载量
+    let jar_cached = temp_dir.join(format!("{}.jar", version_id)).exists();
     let jar_path = download_version(version_id, temp_dir).await.map_err(|e| {
         let error_msg = format!("下载jar文件失败: {}", e);
         tokio::spawn({
@@ -539,6 +967,10 @@ pub async fn download_and_extract_version_with_progress(
         });
         error_msg
     })?;
+    if !jar_cached {
+        let jar_size = std::fs::metadata(&jar_path).map(|m| m.len()).unwrap_or(0);
+        manager.record_transfer(&task_id, 1, jar_size).await;
+    }
     
     // 提取assets
     manager.update_progress(&task_id, DownloadProgress {
@@ -551,7 +983,34 @@ pub async fn download_and_extract_version_with_progress(
         eta: None,
         error: None,
     }).await;
-    extract_assets_from_jar(Path::new(&jar_path), output_dir).map_err(|e| {
+    let extract_result = {
+        let (progress_tx, mut progress_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (done_tx, done_rx) = tokio::sync::oneshot::channel();
+        let (jar, output, folders) = (PathBuf::from(&jar_path), output_dir.to_path_buf(), folders.to_vec());
+        rayon::spawn(move || {
+            let result = extract_assets_from_jar_filtered(&jar, &output, &folders, |done, total| {
+                let _ = progress_tx.send((done, total));
+            });
+            let _ = done_tx.send(result);
+        });
+
+        // 提取结束后发送端被释放, 循环随之结束
+        while let Some((done, total)) = progress_rx.recv().await {
+            manager.update_progress(&task_id, DownloadProgress {
+                task_id: task_id.clone(),
+                status: DownloadStatus::Downloading,
+                current: 2,
+                total: 4,
+                current_file: Some(format!("提取资源文件 {}/{}", done, total)),
+                speed: 0.0,
+                eta: None,
+                error: None,
+            }).await;
+        }
+
+        done_rx.await.unwrap_or_else(|e| Err(format!("Channel error: {}", e)))
+    };
+    extract_result.map_err(|e| {
         let error_msg = format!("提取资源失败: {}", e);
         tokio::spawn({
             let manager = manager.clone();
@@ -580,13 +1039,13 @@ pub async fn download_and_extract_version_with_progress(
         status: DownloadStatus::Downloading,
         current: 3,
         total: 4,
-        current_file: Some("下载中文语言文件...".to_string()),
+        current_file: Some(format!("下载 {} 语言文件...", locale)),
         speed: 0.0,
         eta: None,
         error: None,
     }).await;
     
-    let lang_result = download_language_file(&version.url, version_id, output_dir, Some(task_id.clone()), Some(manager.clone())).await;
+    let lang_result = download_language_file(&version.url, version_id, locale, output_dir, Some(task_id.clone()), Some(manager.clone())).await;
     
     let result_message = match lang_result {
         Ok((_, used_latest, actual_version)) => {
@@ -622,6 +1081,80 @@ pub async fn download_and_extract_version_with_progress(
     Ok(result_message)
 }
 
+/// 缓存的jar文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedJar {
+    pub version: String,
+    pub size_bytes: u64,
+}
+
+/// 模板缓存信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateCacheInfo {
+    pub jars: Vec<CachedJar>,
+    pub total_bytes: u64,
+}
+
+/// 列出缓存目录中的jar文件及大小
+pub fn get_template_cache_info(temp_dir: &Path) -> Result<TemplateCacheInfo, String> {
+    let mut jars = Vec::new();
+
+    if temp_dir.exists() {
+        let entries = std::fs::read_dir(temp_dir)
+            .map_err(|e| format!("Failed to read temp directory: {}", e))?;
+
+        for entry in entries {
+            let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
+            let path = entry.path();
+
+            if path.extension().and_then(|s| s.to_str()) == Some("jar") {
+                let size_bytes = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                let version = path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                jars.push(CachedJar { version, size_bytes });
+            }
+        }
+    }
+
+    jars.sort_by(|a, b| a.version.cmp(&b.version));
+    let total_bytes = jars.iter().map(|jar| jar.size_bytes).sum();
+
+    Ok(TemplateCacheInfo { jars, total_bytes })
+}
+
+/// 清理指定版本缓存的jar文件(含服务端jar)及由其生成的缓存, 返回释放的字节数
+pub fn clear_template_cache_version(temp_dir: &Path, version_id: &str) -> Result<u64, AppError> {
+    if version_id.is_empty() || version_id.contains(['/', '\\']) || version_id.contains("..") {
+        return Err(AppError::InvalidInput(format!("无效的版本号: {}", version_id)));
+    }
+
+    let jars: Vec<_> = [format!("{}.jar", version_id), format!("{}-server.jar", version_id)]
+        .iter()
+        .map(|name| temp_dir.join(name))
+        .filter(|path| path.is_file())
+        .collect();
+    if jars.is_empty() {
+        return Err(AppError::NotFound(format!("版本 {} 没有缓存", version_id)));
+    }
+
+    let mut freed = 0;
+    for jar in &jars {
+        let size = std::fs::metadata(jar).map(|m| m.len()).unwrap_or(0);
+        std::fs::remove_file(jar)
+            .map_err(|e| AppError::io(&format!("Failed to remove {}", jar.display()), e))?;
+        freed += size;
+    }
+
+    // jar 的 sha1 记录、由jar生成的哈希清单和提取的原版文件
+    let _ = std::fs::remove_file(temp_dir.join(format!("{}.jar.sha1", version_id)));
+    let _ = std::fs::remove_file(temp_dir.join(format!("{}.hashes.json", version_id)));
+    let _ = std::fs::remove_dir_all(temp_dir.join("vanilla").join(version_id));
+
+    Ok(freed)
+}
+
 /// 清理缓存的jar文件
 pub fn clear_template_cache(temp_dir: &Path) -> Result<(), String> {
     if !temp_dir.exists() {
@@ -635,7 +1168,7 @@ pub fn clear_template_cache(temp_dir: &Path) -> Result<(), String> {
         let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
         let path = entry.path();
         
-        if path.extension().and_then(|s| s.to_str()) == Some("jar") {
+        if matches!(path.extension().and_then(|s| s.to_str()), Some("jar" | "sha1")) {
             std::fs::remove_file(&path).ok();
         }
     }
@@ -877,6 +1410,7 @@ pub async fn download_minecraft_sounds_with_progress(
     let sounds_json_path = little100_dir.join("sounds.json");
     std::fs::write(&sounds_json_path, &sounds_json_content)
         .map_err(|e| format!("保存 sounds.json 失败: {}", e))?;
+    manager.record_transfer(&task_id, 1, sounds_json_content.len() as u64).await;
     
     // 检查取消
     if cancel_token.is_cancelled() {
@@ -975,6 +1509,8 @@ pub async fn download_minecraft_sounds_with_progress(
                     .await
                     .map_err(|e| format!("保存文件失败 {}: {}", relative_path, e))?;
                 
+                manager.record_transfer(&task_id, 1, content.len() as u64).await;
+                
                 // 更新计数器
                 let current = completed.fetch_add(1, std::sync::atomic::Ordering::Relaxed) + 1;
                 total_bytes.fetch_add(asset.size, std::sync::atomic::Ordering::Relaxed);
@@ -1030,4 +1566,186 @@ pub async fn download_minecraft_sounds_with_progress(
     println!("[下载声音资源] 音频文件已保存到: {:?}", sounds_dir);
     
     Ok(format!("成功下载 {} 的声音资源 (共 {} 个文件)", latest_release.id, total))
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    /// 生成只含文件条目的测试 jar
+    fn write_jar(path: &Path, entries: &[(String, Vec<u8>)]) {
+        let mut writer = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+        for (name, data) in entries {
+            writer.start_file(name.as_str(), SimpleFileOptions::default()).unwrap();
+            writer.write_all(data).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    // sha1("abc")
+    const ABC_SHA1: &str = "a9993e364706816aba3e25717850c26c9cd0d89d";
+
+    #[test]
+    fn cached_jar_with_matching_sha1_is_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let jar = dir.path().join("1.21.jar");
+        std::fs::write(&jar, b"abc").unwrap();
+
+        assert_eq!(verify_cached_jar(&jar, Some(&ABC_SHA1.to_uppercase())), Some(true));
+        assert!(jar.is_file());
+        // 通过清单校验后记录 sha1, 之后离线也能校验
+        assert_eq!(std::fs::read_to_string(jar_sha1_path(&jar)).unwrap().to_lowercase(), ABC_SHA1);
+        assert_eq!(verify_cached_jar(&jar, None), Some(true));
+    }
+
+    #[test]
+    fn corrupted_jar_is_removed_for_redownload() {
+        let dir = tempfile::tempdir().unwrap();
+        let jar = dir.path().join("1.21.jar");
+        std::fs::write(&jar, b"abd").unwrap();
+        std::fs::write(jar_sha1_path(&jar), ABC_SHA1).unwrap();
+
+        assert_eq!(verify_cached_jar(&jar, None), Some(false));
+        assert!(!jar.exists());
+        assert!(!jar_sha1_path(&jar).exists());
+    }
+
+    #[test]
+    fn jar_without_record_cannot_be_verified_offline() {
+        let dir = tempfile::tempdir().unwrap();
+        let jar = dir.path().join("1.21.jar");
+        std::fs::write(&jar, b"abc").unwrap();
+
+        assert_eq!(verify_cached_jar(&jar, None), None);
+        assert!(jar.is_file());
+        assert_eq!(verify_cached_jar(&dir.path().join("missing.jar"), None), Some(false));
+    }
+
+    #[test]
+    fn cache_info_lists_jar_sizes() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("1.21.jar"), vec![0u8; 300]).unwrap();
+        std::fs::write(dir.path().join("1.20.1.jar"), vec![0u8; 100]).unwrap();
+        std::fs::write(dir.path().join("1.21.jar.sha1"), ABC_SHA1).unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "not a jar").unwrap();
+
+        let info = get_template_cache_info(dir.path()).unwrap();
+        let jars: Vec<(&str, u64)> = info.jars.iter().map(|jar| (jar.version.as_str(), jar.size_bytes)).collect();
+        assert_eq!(jars, [("1.20.1", 100), ("1.21", 300)]);
+        assert_eq!(info.total_bytes, 400);
+    }
+
+    #[test]
+    fn cache_info_of_missing_dir_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let info = get_template_cache_info(&dir.path().join("missing")).unwrap();
+        assert!(info.jars.is_empty());
+        assert_eq!(info.total_bytes, 0);
+    }
+
+    #[test]
+    fn clearing_one_version_keeps_the_others() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("1.21.jar"), vec![0u8; 300]).unwrap();
+        std::fs::write(dir.path().join("1.21-server.jar"), vec![0u8; 50]).unwrap();
+        std::fs::write(dir.path().join("1.21.jar.sha1"), ABC_SHA1).unwrap();
+        std::fs::write(dir.path().join("1.20.1.jar"), vec![0u8; 100]).unwrap();
+
+        assert_eq!(clear_template_cache_version(dir.path(), "1.21").unwrap(), 350);
+        assert!(!dir.path().join("1.21.jar").exists());
+        assert!(!dir.path().join("1.21-server.jar").exists());
+        assert!(!dir.path().join("1.21.jar.sha1").exists());
+        assert!(dir.path().join("1.20.1.jar").is_file());
+    }
+
+    #[test]
+    fn clearing_uncached_or_invalid_version_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("1.20.1.jar"), b"jar").unwrap();
+
+        assert!(matches!(clear_template_cache_version(dir.path(), "1.21"), Err(AppError::NotFound(_))));
+        assert!(matches!(clear_template_cache_version(dir.path(), "../1.20.1"), Err(AppError::InvalidInput(_))));
+        assert!(dir.path().join("1.20.1.jar").is_file());
+    }
+
+    #[test]
+    fn language_asset_keys_follow_locale() {
+        assert_eq!(
+            language_asset_keys("fr_fr"),
+            ["minecraft/lang/fr_fr.json".to_string(), "minecraft/lang/fr_fr.lang".to_string()]
+        );
+        assert_eq!(language_asset_keys("ru_ru")[0], "minecraft/lang/ru_ru.json");
+    }
+
+    #[test]
+    fn locale_defaults_to_zh_cn_and_is_normalized() {
+        assert_eq!(normalize_locale(None).unwrap(), "zh_cn");
+        assert_eq!(normalize_locale(Some("  ")).unwrap(), "zh_cn");
+        assert_eq!(normalize_locale(Some("FR_FR")).unwrap(), "fr_fr");
+        assert!(normalize_locale(Some("../en_us")).is_err());
+    }
+
+    #[test]
+    fn only_entries_under_include_prefixes_are_extracted() {
+        let dir = tempfile::tempdir().unwrap();
+        let jar = dir.path().join("1.21.jar");
+        let entries: Vec<(String, Vec<u8>)> = [
+            "assets/minecraft/textures/block/stone.png",
+            "assets/minecraft/textures/item/stick.png",
+            "assets/minecraft/models/block/stone.json",
+            "net/minecraft/Main.class",
+        ]
+        .iter()
+        .map(|name| (name.to_string(), name.as_bytes().to_vec()))
+        .collect();
+        write_jar(&jar, &entries);
+
+        let output = dir.path().join("out");
+        let prefixes = vec!["assets/minecraft/textures/block/".to_string()];
+        extract_assets_from_jar(&jar, &output, Some(&prefixes)).unwrap();
+
+        // jar 中没有目录条目, 父目录需要在写入叶子文件时创建
+        let stone = output.join("assets/minecraft/textures/block/stone.png");
+        assert_eq!(std::fs::read(&stone).unwrap(), b"assets/minecraft/textures/block/stone.png");
+        assert!(!output.join("assets/minecraft/textures/item").exists());
+        assert!(!output.join("assets/minecraft/models").exists());
+        assert!(!output.join("net").exists());
+
+        let full = dir.path().join("full");
+        extract_assets_from_jar(&jar, &full, None).unwrap();
+        assert!(full.join("assets/minecraft/textures/item/stick.png").is_file());
+        assert!(full.join("assets/minecraft/models/block/stone.json").is_file());
+        assert!(!full.join("net").exists());
+    }
+
+    #[test]
+    fn parallel_extraction_writes_every_file_and_reports_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let jar = dir.path().join("synthetic.jar");
+        let entries: Vec<(String, Vec<u8>)> = (0..500)
+            .map(|i| {
+                let name = format!("assets/minecraft/textures/dir{}/file{}.png", i % 17, i);
+                let data = format!("content-{}", i).repeat(i % 7 + 1).into_bytes();
+                (name, data)
+            })
+            .collect();
+        write_jar(&jar, &entries);
+
+        let output = dir.path().join("out");
+        let reports = std::sync::Mutex::new(Vec::new());
+        extract_assets_from_jar_filtered(&jar, &output, &[], |done, total| {
+            reports.lock().unwrap().push((done, total));
+        })
+        .unwrap();
+
+        for (name, data) in &entries {
+            assert_eq!(&std::fs::read(output.join(name)).unwrap(), data, "{}", name);
+        }
+        let reports = reports.into_inner().unwrap();
+        assert_eq!(reports.first(), Some(&(0, 500)));
+        assert!(reports.contains(&(500, 500)));
+        assert!(reports.iter().all(|&(done, total)| total == 500 && done <= total));
+    }
+}
@@ -1,31 +1,140 @@
-use axum::Router;
+use axum::{
+    extract::Path as UrlPath,
+    http::{header, StatusCode},
+    response::{IntoResponse, Redirect, Response},
+    routing::get,
+    Json, Router,
+};
+use include_dir::{include_dir, Dir};
+use serde::{Deserialize, Serialize};
 use tower_http::{
     services::ServeDir,
     cors::CorsLayer,
 };
 use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tauri::State;
+use crate::error::AppError;
+
+/// 内置的浏览页面(web-viewer 目录), 在 /app/ 下提供, 通过 /api 路由获取文件列表
+static VIEWER_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/web-viewer");
+
+/// 保留给内置页面和接口的路由
+const RESERVED_ROUTES: [&str; 2] = ["/app", "/api"];
+
+/// 额外挂载的只读目录, path 必须位于材质包内(如 .little100/sounds)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebMount {
+    pub route: String,
+    pub path: String,
+}
+
+/// 服务器状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerStatus {
+    pub running: bool,
+    /// 当前挂载的路由, 包括材质包根目录和内置页面
+    pub mounts: Vec<WebMount>,
+}
 
 #[derive(Default, Clone)]
 pub struct WebServerState {
     pub running: Arc<Mutex<bool>>,
     pub handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
+    pub mounts: Arc<Mutex<Vec<WebMount>>>,
+}
+
+/// 校验挂载路由并解析目录, 返回 (路由, 绝对路径)
+fn resolve_mount(pack_path: &Path, mount: &WebMount) -> Result<(String, PathBuf), String> {
+    let route = format!("/{}", mount.route.trim_matches('/'));
+    let is_valid_route = route.len() > 1
+        && !route.contains("//")
+        && route[1..]
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '/'));
+    let is_reserved = RESERVED_ROUTES
+        .iter()
+        .any(|r| route == *r || route.starts_with(&format!("{}/", r)));
+    if !is_valid_route || is_reserved {
+        return Err(format!("无效或保留的挂载路由: {}", mount.route));
+    }
+
+    // 挂载目录限制在材质包内, 避免绑定 0.0.0.0 时把其他目录暴露到局域网
+    let full_path = crate::fs_utils::resolve_pack_path(pack_path, &mount.path)?;
+    if !full_path.is_dir() {
+        return Err(format!("挂载目录不存在: {}", full_path.display()));
+    }
+    Ok((route, full_path))
+}
+
+/// 内置页面中的文件, 目录返回其中的 index.html
+fn viewer_file(path: &str) -> Option<&'static include_dir::File<'static>> {
+    let path = path.trim_matches('/');
+    if path.is_empty() {
+        return VIEWER_DIR.get_file("index.html");
+    }
+    VIEWER_DIR
+        .get_file(path)
+        .or_else(|| VIEWER_DIR.get_file(format!("{}/index.html", path)))
+}
+
+async fn serve_viewer(path: Option<UrlPath<String>>) -> Response {
+    let path = path.map(|UrlPath(path)| path).unwrap_or_default();
+    match viewer_file(&path) {
+        Some(file) => {
+            let mime = mime_guess::from_path(file.path()).first_or_octet_stream();
+            ([(header::CONTENT_TYPE, mime.to_string())], file.contents()).into_response()
+        }
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
 }
 
 pub async fn start_web_server(
     port: u16,
     pack_path: String,
     bind_all: bool,
+    serve_app: bool,
+    mounts: &[(String, PathBuf)],
 ) -> Result<tokio::task::JoinHandle<()>, String> {
     // 创建服务目录
     let serve_dir = ServeDir::new(pack_path.clone())
         .append_index_html_on_directories(true);
 
-    // 创建路由
-    let app = Router::new()
-        .nest_service("/", serve_dir)
+    // 创建路由, 材质包目录作为兜底
+    let mut app = Router::new();
+    for (route, path) in mounts {
+        app = app.nest_service(route, ServeDir::new(path));
+    }
+    if serve_app {
+        let pack_root = PathBuf::from(&pack_path);
+        let mount_list: Vec<WebMount> = mounts
+            .iter()
+            .map(|(route, path)| WebMount { route: route.clone(), path: path.to_string_lossy().to_string() })
+            .collect();
+        app = app
+            // 页面中的相对路径基于 /app/
+            .route("/app", get(|| async { Redirect::permanent("/app/") }))
+            .route("/app/", get(serve_viewer))
+            .route("/app/*path", get(serve_viewer))
+            .route(
+                "/api/files",
+                get(move || {
+                    let pack_root = pack_root.clone();
+                    async move {
+                        let files: Vec<String> = crate::pack_parser::visible_files(&pack_root)
+                            .iter()
+                            .map(|p| p.to_string_lossy().replace('\\', "/"))
+                            .collect();
+                        Json(files)
+                    }
+                }),
+            )
+            .route("/api/mounts", get(move || async move { Json(mount_list) }));
+    }
+    let app = app
+        .fallback_service(serve_dir)
         .layer(CorsLayer::permissive());
 
     // 确定绑定地址
@@ -51,34 +160,55 @@ pub async fn start_web_server(
     Ok(handle)
 }
 
+/// 启动 Web 服务器, serve_app 时在 /app 提供内置浏览页面, mounts 为额外的只读目录
 #[tauri::command]
 pub async fn start_server(
     port: u16,
     mode: String,
+    serve_app: Option<bool>,
+    mounts: Option<Vec<WebMount>>,
     state: State<'_, WebServerState>,
     app_state: State<'_, crate::commands::AppState>,
-) -> Result<String, String> {
+) -> Result<String, AppError> {
     let mut running = state.running.lock().await;
     
     if *running {
-        return Err("Server is already running".to_string());
+        return Err(AppError::AlreadyExists("Server is already running".to_string()));
     }
 
     // 获取当前材质包路径
-    let pack_path_str = {
-        let pack_path = app_state.current_pack_path.lock().unwrap();
-        match pack_path.as_ref() {
-            Some(path) => path.to_string_lossy().to_string(),
-            None => return Err("No pack loaded".to_string()),
+    let pack_path = app_state.pack_path()?;
+    let pack_path_str = pack_path.to_string_lossy().to_string();
+
+    let resolved = mounts
+        .unwrap_or_default()
+        .iter()
+        .map(|mount| resolve_mount(&pack_path, mount))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(AppError::InvalidInput)?;
+    for (i, (route, _)) in resolved.iter().enumerate() {
+        if resolved[..i].iter().any(|(other, _)| other == route) {
+            return Err(AppError::InvalidInput(format!("挂载路由重复: {}", route)));
         }
-    };
+    }
+    let serve_app = serve_app.unwrap_or(false);
 
     let bind_all = mode == "all";
     
-    match start_web_server(port, pack_path_str, bind_all).await {
+    match start_web_server(port, pack_path_str.clone(), bind_all, serve_app, &resolved).await {
         Ok(handle) => {
             *state.handle.lock().await = Some(handle);
             *running = true;
+
+            let mut active = vec![WebMount { route: "/".to_string(), path: pack_path_str }];
+            if serve_app {
+                active.push(WebMount { route: "/app".to_string(), path: String::new() });
+            }
+            active.extend(resolved.into_iter().map(|(route, path)| WebMount {
+                route,
+                path: path.to_string_lossy().to_string(),
+            }));
+            *state.mounts.lock().await = active;
             
             let addr = if bind_all {
                 format!("0.0.0.0:{}", port)
@@ -88,16 +218,16 @@ pub async fn start_server(
             
             Ok(format!("Server started on {}", addr))
         }
-        Err(e) => Err(e),
+        Err(e) => Err(AppError::Network(e)),
     }
 }
 
 #[tauri::command]
-pub async fn stop_server(state: State<'_, WebServerState>) -> Result<String, String> {
+pub async fn stop_server(state: State<'_, WebServerState>) -> Result<String, AppError> {
     let mut running = state.running.lock().await;
     
     if !*running {
-        return Err("Server is not running".to_string());
+        return Err(AppError::InvalidInput("Server is not running".to_string()));
     }
 
     if let Some(handle) = state.handle.lock().await.take() {
@@ -105,10 +235,62 @@ pub async fn stop_server(state: State<'_, WebServerState>) -> Result<String, Str
     }
     
     *running = false;
+    state.mounts.lock().await.clear();
     Ok("Server stopped".to_string())
 }
 
+/// 服务器是否运行及当前挂载的路由
 #[tauri::command]
-pub async fn get_server_status(state: State<'_, WebServerState>) -> Result<bool, String> {
-    Ok(*state.running.lock().await)
-}
\ No newline at end of file
+pub async fn get_server_status(state: State<'_, WebServerState>) -> Result<ServerStatus, AppError> {
+    Ok(ServerStatus {
+        running: *state.running.lock().await,
+        mounts: state.mounts.lock().await.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mount(route: &str, path: &str) -> WebMount {
+        WebMount { route: route.to_string(), path: path.to_string() }
+    }
+
+    #[test]
+    fn invalid_and_reserved_routes_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        for route in ["", "/", "a//b", "a b", "../x", "/api", "api/files", "/app/", "app/x"] {
+            assert!(resolve_mount(dir.path(), &mount(route, "")).is_err(), "{}", route);
+        }
+        let (route, path) = resolve_mount(dir.path(), &mount("/pack-root/", "")).unwrap();
+        assert_eq!(route, "/pack-root");
+        assert_eq!(path, dir.path());
+    }
+
+    #[test]
+    fn mount_paths_are_confined_to_the_pack() {
+        let dir = tempfile::tempdir().unwrap();
+        let pack = dir.path().join("pack");
+        std::fs::create_dir_all(pack.join(".little100/sounds")).unwrap();
+        std::fs::create_dir_all(dir.path().join("outside")).unwrap();
+
+        let (_, path) = resolve_mount(&pack, &mount("sounds", ".little100/sounds")).unwrap();
+        assert_eq!(path, pack.join(".little100/sounds"));
+        let inside = pack.join(".little100").to_string_lossy().to_string();
+        assert!(resolve_mount(&pack, &mount("data", &inside)).is_ok());
+
+        let outside = dir.path().join("outside").to_string_lossy().to_string();
+        for path in ["/", "..", "../outside", outside.as_str()] {
+            assert!(resolve_mount(&pack, &mount("x", path)).is_err(), "{}", path);
+        }
+        assert!(resolve_mount(&pack, &mount("x", "missing")).is_err());
+    }
+
+    #[test]
+    fn viewer_files_are_embedded() {
+        let index = viewer_file("").unwrap();
+        assert_eq!(index.path(), Path::new("index.html"));
+        assert_eq!(viewer_file("/index.html").unwrap().contents(), index.contents());
+        assert!(viewer_file("missing.js").is_none());
+    }
+}
@@ -1,9 +1,98 @@
 use std::fs::{self, File};
-use std::io::{Read, Write};
+use std::io::{Read, Seek, Write};
 use std::path::{Path, PathBuf};
 use zip::ZipArchive;
+use crate::error::AppError;
+
+/// 根据文件头识别常见的非 ZIP 压缩格式
+fn detect_archive_format(magic: &[u8]) -> Option<&'static str> {
+    if magic.starts_with(b"Rar!\x1a\x07") {
+        Some("RAR")
+    } else if magic.starts_with(b"7z\xbc\xaf\x27\x1c") {
+        Some("7z")
+    } else if magic.starts_with(&[0x1f, 0x8b]) {
+        Some("gzip")
+    } else {
+        None
+    }
+}
+
+/// 顺序读取本地文件头, 统计能完整读出的条目数(用于中央目录缺失的不完整压缩包)
+fn count_readable_entries(zip_path: &Path) -> usize {
+    let Ok(file) = File::open(zip_path) else {
+        return 0;
+    };
+    let mut reader = std::io::BufReader::new(file);
+    let mut count = 0;
+    while let Ok(Some(mut entry)) = zip::read::read_zipfile_from_stream(&mut reader) {
+        if std::io::copy(&mut entry, &mut std::io::sink()).is_err() {
+            break;
+        }
+        count += 1;
+    }
+    count
+}
+
+/// 导入前检查压缩包, 区分非 ZIP 格式、带密码和损坏的压缩包
+pub fn check_archive(zip_path: &Path) -> Result<(), AppError> {
+    let mut file = File::open(zip_path)
+        .map_err(|e| AppError::io("Failed to open zip file", e))?;
+
+    let mut magic = [0u8; 8];
+    let read = file.read(&mut magic)
+        .map_err(|e| AppError::io("Failed to read zip file", e))?;
+    let magic = &magic[..read];
+    if let Some(format) = detect_archive_format(magic) {
+        return Err(AppError::UnsupportedArchive(format!(
+            "该文件是 {} 压缩包而不是 ZIP, 请解压后以文件夹方式导入, 或重新打包为 ZIP",
+            format
+        )));
+    }
+    if !magic.starts_with(b"PK") {
+        return Err(AppError::UnsupportedArchive("该文件不是 ZIP 压缩包".to_string()));
+    }
+    file.rewind()
+        .map_err(|e| AppError::io("Failed to read zip file", e))?;
+
+    let mut archive = match ZipArchive::new(file) {
+        Ok(archive) => archive,
+        Err(e) => {
+            return Err(AppError::CorruptArchive(format!(
+                "压缩包已损坏或下载不完整, 仅能读取 {} 个条目: {}",
+                count_readable_entries(zip_path),
+                e
+            )));
+        }
+    };
+
+    let total = archive.len();
+    for i in 0..total {
+        let entry = archive.by_index_raw(i).map_err(|e| {
+            AppError::CorruptArchive(format!(
+                "压缩包已损坏, 共 {} 个条目, 仅能读取 {} 个: {}",
+                total, i, e
+            ))
+        })?;
+        if entry.encrypted() {
+            return Err(AppError::EncryptedArchive(format!(
+                "不支持带密码的压缩包 (加密的条目: {})",
+                entry.name()
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// 解压时单个条目解压后的大小上限
+pub const MAX_ENTRY_SIZE: u64 = 512 * 1024 * 1024;
+
+/// 解压时所有条目解压后的总大小上限
+pub const MAX_TOTAL_UNCOMPRESSED_SIZE: u64 = 4 * 1024 * 1024 * 1024;
 
 /// 解压ZIP文件到指定目录
+///
+/// 拒绝解压到目标目录之外的条目, 并限制单个条目和总的解压大小以防止 ZIP 炸弹
 pub fn extract_zip(zip_path: &Path, extract_to: &Path) -> Result<(), String> {
     let file = File::open(zip_path)
         .map_err(|e| format!("Failed to open zip file: {}", e))?;
@@ -14,35 +103,58 @@ pub fn extract_zip(zip_path: &Path, extract_to: &Path) -> Result<(), String> {
     // 创建目标目录
     fs::create_dir_all(extract_to)
         .map_err(|e| format!("Failed to create extract directory: {}", e))?;
+    let root = extract_to.canonicalize()
+        .map_err(|e| format!("Failed to resolve extract directory: {}", e))?;
 
+    let mut total_size: u64 = 0;
     for i in 0..archive.len() {
         let mut file = archive.by_index(i)
             .map_err(|e| format!("Failed to read file from archive: {}", e))?;
         
         let outpath = match file.enclosed_name() {
-            Some(path) => extract_to.join(path),
-            None => continue,
+            Some(path) => root.join(path),
+            None => return Err(format!("压缩包条目路径不安全, 已拒绝解压: {}", file.name())),
         };
 
-        if file.name().ends_with('/') {
+        if file.is_dir() {
             // 创建目录
             fs::create_dir_all(&outpath)
                 .map_err(|e| format!("Failed to create directory: {}", e))?;
+            ensure_inside(&root, &outpath, file.name())?;
         } else {
-            // 创建父目录
+            if file.size() > MAX_ENTRY_SIZE {
+                return Err(format!(
+                    "压缩包条目过大 ({} 字节, 上限 {} 字节): {}",
+                    file.size(), MAX_ENTRY_SIZE, file.name()
+                ));
+            }
+
+            // 创建父目录, 并确认没有通过已解压的符号链接等方式逃出目标目录
             if let Some(parent) = outpath.parent() {
                 fs::create_dir_all(parent)
                     .map_err(|e| format!("Failed to create parent directory: {}", e))?;
+                ensure_inside(&root, parent, file.name())?;
             }
             
-            // 写入文件
-            let mut outfile = File::create(&outpath)
-                .map_err(|e| format!("Failed to create file: {}", e))?;
-            
+            // 声明的大小可能被篡改, 读取时按上限截断
             let mut buffer = Vec::new();
-            file.read_to_end(&mut buffer)
+            (&mut file).take(MAX_ENTRY_SIZE + 1).read_to_end(&mut buffer)
                 .map_err(|e| format!("Failed to read file content: {}", e))?;
+            let size = buffer.len() as u64;
+            if size > MAX_ENTRY_SIZE {
+                return Err(format!("压缩包条目过大 (上限 {} 字节): {}", MAX_ENTRY_SIZE, file.name()));
+            }
+            total_size += size;
+            if total_size > MAX_TOTAL_UNCOMPRESSED_SIZE {
+                return Err(format!(
+                    "压缩包解压后超过 {} 字节上限, 已停止解压",
+                    MAX_TOTAL_UNCOMPRESSED_SIZE
+                ));
+            }
             
+            // 写入文件
+            let mut outfile = File::create(&outpath)
+                .map_err(|e| format!("Failed to create file: {}", e))?;
             outfile.write_all(&buffer)
                 .map_err(|e| format!("Failed to write file: {}", e))?;
         }
@@ -51,20 +163,239 @@ pub fn extract_zip(zip_path: &Path, extract_to: &Path) -> Result<(), String> {
     Ok(())
 }
 
-/// 将目录打包为ZIP文件
-pub fn create_zip(source_dir: &Path, output_path: &Path) -> Result<(), String> {
+/// 确认 path 解析后仍位于解压根目录 root(已规范化)之内
+fn ensure_inside(root: &Path, path: &Path, entry_name: &str) -> Result<(), String> {
+    let resolved = path.canonicalize()
+        .map_err(|e| format!("Failed to resolve path for {}: {}", entry_name, e))?;
+    if !resolved.starts_with(root) {
+        return Err(format!("压缩包条目试图写入解压目录之外, 已拒绝: {}", entry_name));
+    }
+    Ok(())
+}
+
+/// 导出时默认排除的内容(glob, 相对材质包根目录): 编辑器数据目录、日志、系统生成的文件,
+/// 以及所有以 . 开头的文件和目录
+pub const DEFAULT_EXPORT_EXCLUDES: &[&str] = &[
+    ".history/**",
+    ".little100/**",
+    ".trash/**",
+    ".git/**",
+    "logs/**",
+    "**/.*",
+    "**/Thumbs.db",
+];
+
+/// 编译后的导出排除规则, "dir/**" 同时匹配目录本身, 以便遍历时整体跳过
+#[derive(Debug, Clone, Default)]
+pub struct ExcludePatterns {
+    patterns: Vec<glob::Pattern>,
+}
+
+impl ExcludePatterns {
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> Result<Self, String> {
+        let mut compiled = Vec::new();
+        for pattern in patterns {
+            let pattern = pattern.as_ref();
+            let compile = |p: &str| {
+                glob::Pattern::new(p).map_err(|e| format!("无效的排除规则 {}: {}", pattern, e))
+            };
+            compiled.push(compile(pattern)?);
+            if let Some(dir) = pattern.strip_suffix("/**") {
+                compiled.push(compile(dir)?);
+            }
+        }
+        Ok(Self { patterns: compiled })
+    }
+
+    /// 默认导出排除规则
+    pub fn export_defaults() -> Self {
+        Self::new(DEFAULT_EXPORT_EXCLUDES).unwrap_or_default()
+    }
+
+    /// 相对路径是否被排除
+    pub fn is_excluded(&self, relative: &Path) -> bool {
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        if relative.is_empty() {
+            return false;
+        }
+        let options = glob::MatchOptions {
+            case_sensitive: true,
+            require_literal_separator: true,
+            require_literal_leading_dot: false,
+        };
+        self.patterns.iter().any(|p| p.matches_with(&relative, options))
+    }
+}
+
+/// 打包进度
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+pub struct ZipProgress {
+    pub files_done: usize,
+    pub total_files: usize,
+    pub bytes_written: u64,
+}
+
+/// 导出压缩级别
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ZipCompression {
+    /// 仅存储, 不压缩
+    Store,
+    Fast,
+    #[default]
+    Default,
+    Best,
+}
+
+/// 写入ZIP条目时使用的选项
+pub type ZipFileOptions = zip::write::FileOptions<'static, ()>;
+
+impl ZipCompression {
+    pub fn file_options(self) -> ZipFileOptions {
+        let options = zip::write::FileOptions::<()>::default().unix_permissions(0o755);
+        match self {
+            ZipCompression::Store => options.compression_method(zip::CompressionMethod::Stored),
+            ZipCompression::Fast => options
+                .compression_method(zip::CompressionMethod::Deflated)
+                .compression_level(Some(1)),
+            ZipCompression::Default => options.compression_method(zip::CompressionMethod::Deflated),
+            ZipCompression::Best => options
+                .compression_method(zip::CompressionMethod::Deflated)
+                .compression_level(Some(9)),
+        }
+    }
+}
+
+/// 压缩方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ZipMethod {
+    Deflate,
+    Stored,
+}
+
+/// 导出选项, 未指定的项使用默认值
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct ExportOptions {
+    /// glob 排除规则, 未指定时使用 DEFAULT_EXPORT_EXCLUDES
+    pub exclude_patterns: Option<Vec<String>>,
+    /// deflate 压缩级别 0-9
+    pub compression_level: Option<i32>,
+    pub method: Option<ZipMethod>,
+}
+
+impl ExportOptions {
+    pub fn exclude_patterns(&self) -> Result<ExcludePatterns, String> {
+        match &self.exclude_patterns {
+            Some(patterns) => ExcludePatterns::new(patterns),
+            None => Ok(ExcludePatterns::export_defaults()),
+        }
+    }
+
+    /// 在 preset 的基础上应用 method 和 compression_level
+    pub fn file_options(&self, preset: ZipCompression) -> Result<ZipFileOptions, String> {
+        if let Some(level) = self.compression_level {
+            if !(0..=9).contains(&level) {
+                return Err(format!("压缩级别应在 0-9 之间: {}", level));
+            }
+        }
+        let options = match (self.method, self.compression_level) {
+            (Some(ZipMethod::Stored), _) => ZipCompression::Store.file_options(),
+            (Some(ZipMethod::Deflate), level) => ZipCompression::Default
+                .file_options()
+                .compression_level(level.map(i64::from)),
+            (None, Some(level)) if preset != ZipCompression::Store => preset
+                .file_options()
+                .compression_level(Some(i64::from(level))),
+            _ => preset.file_options(),
+        };
+        Ok(options)
+    }
+}
+
+/// 本身已压缩的格式, 再次压缩只浪费 CPU, 始终仅存储
+const STORED_EXTENSIONS: &[&str] = &["png", "ogg"];
+
+fn is_precompressed(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| STORED_EXTENSIONS.iter().any(|s| ext.eq_ignore_ascii_case(s)))
+}
+
+/// 每打包多少个文件报告一次进度
+const PROGRESS_FILE_INTERVAL: usize = 50;
+/// 两次进度报告的最长间隔
+const PROGRESS_TIME_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// 将目录打包为ZIP文件, extra_files 为额外写入根目录的文件(如校验清单), excludes 中的路径不打包,
+/// on_progress 每隔若干文件或 250ms 调用一次, 结束时总会再调用一次; 返回 false 时停止打包并删除未完成的ZIP
+pub fn create_zip(
+    source_dir: &Path,
+    output_path: &Path,
+    extra_files: &[(String, Vec<u8>)],
+    excludes: &ExcludePatterns,
+    options: ZipFileOptions,
+    on_progress: Option<&mut dyn FnMut(ZipProgress) -> bool>,
+) -> Result<(), String> {
+    create_zip_filtered(
+        source_dir,
+        output_path,
+        extra_files,
+        |relative| !excludes.is_excluded(relative),
+        options,
+        on_progress,
+    )
+}
+
+fn is_empty_dir(path: &Path) -> bool {
+    fs::read_dir(path).is_ok_and(|mut entries| entries.next().is_none())
+}
+
+/// 同 create_zip, include 根据相对路径决定是否打包, 返回 false 的目录整体跳过
+pub fn create_zip_filtered(
+    source_dir: &Path,
+    output_path: &Path,
+    extra_files: &[(String, Vec<u8>)],
+    include: impl Fn(&Path) -> bool,
+    options: ZipFileOptions,
+    mut on_progress: Option<&mut dyn FnMut(ZipProgress) -> bool>,
+) -> Result<(), String> {
     let file = File::create(output_path)
         .map_err(|e| format!("Failed to create zip file: {}", e))?;
     
     let mut zip = zip::ZipWriter::new(file);
-    let options = zip::write::FileOptions::<()>::default()
-        .compression_method(zip::CompressionMethod::Deflated)
-        .unix_permissions(0o755);
+    let stored_options = ZipCompression::Store.file_options();
+
+    // 先遍历一次, 以便进度能给出总数
+    let entries: Vec<walkdir::DirEntry> = walkdir::WalkDir::new(source_dir)
+        .into_iter()
+        .filter_entry(|e| include(e.path().strip_prefix(source_dir).unwrap_or(e.path())))
+        .filter_map(|e| e.ok())
+        .collect();
 
-    let walkdir = walkdir::WalkDir::new(source_dir);
-    let it = walkdir.into_iter().filter_map(|e| e.ok());
+    let mut progress = ZipProgress {
+        files_done: 0,
+        total_files: entries.iter().filter(|e| e.file_type().is_file()).count() + extra_files.len(),
+        bytes_written: 0,
+    };
+    let mut last_report = std::time::Instant::now();
+    // 返回 false 表示调用方要求停止
+    let mut report = |progress: &ZipProgress, force: bool| -> bool {
+        let Some(callback) = on_progress.as_mut() else {
+            return true;
+        };
+        if force
+            || progress.files_done.is_multiple_of(PROGRESS_FILE_INTERVAL)
+            || last_report.elapsed() >= PROGRESS_TIME_INTERVAL
+        {
+            last_report = std::time::Instant::now();
+            return callback(*progress);
+        }
+        true
+    };
 
-    for entry in it {
+    for entry in entries {
         let path = entry.path();
         let name = path.strip_prefix(source_dir)
             .map_err(|e| format!("Failed to strip prefix: {}", e))?;
@@ -77,7 +408,8 @@ pub fn create_zip(source_dir: &Path, output_path: &Path) -> Result<(), String> {
         let name_str = name.to_string_lossy().replace('\\', "/");
 
         if path.is_file() {
-            zip.start_file(&name_str, options)
+            let file_options = if is_precompressed(path) { stored_options } else { options };
+            zip.start_file(&name_str, file_options)
                 .map_err(|e| format!("Failed to start file in zip: {}", e))?;
             
             let mut f = File::open(path)
@@ -89,18 +421,75 @@ pub fn create_zip(source_dir: &Path, output_path: &Path) -> Result<(), String> {
             
             zip.write_all(&buffer)
                 .map_err(|e| format!("Failed to write to zip: {}", e))?;
-        } else if path.is_dir() {
+
+            progress.files_done += 1;
+            progress.bytes_written += buffer.len() as u64;
+            if !report(&progress, false) {
+                drop(zip);
+                let _ = fs::remove_file(output_path);
+                return Err("打包已取消".to_string());
+            }
+        } else if path.is_dir() && is_empty_dir(path) {
+            // 含有文件的目录由文件路径隐含, 只为原本就为空的目录写入条目,
+            // 避免过滤后只剩空目录
             zip.add_directory(&name_str, options)
                 .map_err(|e| format!("Failed to add directory to zip: {}", e))?;
         }
     }
 
+    for (name, content) in extra_files {
+        zip.start_file(name.as_str(), options)
+            .map_err(|e| format!("Failed to start file in zip: {}", e))?;
+        zip.write_all(content)
+            .map_err(|e| format!("Failed to write to zip: {}", e))?;
+
+        progress.files_done += 1;
+        progress.bytes_written += content.len() as u64;
+    }
+
     zip.finish()
         .map_err(|e| format!("Failed to finish zip: {}", e))?;
+    report(&progress, true);
 
     Ok(())
 }
 
+/// 若所有条目都位于同一个顶层文件夹中且该文件夹内有 pack.mcmeta, 返回该文件夹名;
+/// pack.mcmeta 已在根目录或存在多个顶层条目时返回 None。macOS 附带的 __MACOSX 不计入
+pub fn nested_pack_root(zip_path: &Path) -> Result<Option<String>, String> {
+    let file = File::open(zip_path)
+        .map_err(|e| format!("Failed to open zip file: {}", e))?;
+    let archive = ZipArchive::new(file)
+        .map_err(|e| format!("Failed to read zip archive: {}", e))?;
+
+    let mut root: Option<&str> = None;
+    let mut has_nested_mcmeta = false;
+    for name in archive.file_names() {
+        let name = name.trim_start_matches('/');
+        let Some((top, rest)) = name.split_once('/') else {
+            // 根目录下的文件, 包括 pack.mcmeta 本身
+            if !name.is_empty() {
+                return Ok(None);
+            }
+            continue;
+        };
+        if top == "__MACOSX" {
+            continue;
+        }
+        match root {
+            Some(existing) if existing != top => return Ok(None),
+            _ => root = Some(top),
+        }
+        if rest == "pack.mcmeta" {
+            has_nested_mcmeta = true;
+        }
+    }
+
+    Ok(root
+        .filter(|_| has_nested_mcmeta)
+        .map(str::to_string))
+}
+
 /// 验证是否为有效的材质包ZIP
 pub fn validate_pack_zip(zip_path: &Path) -> Result<bool, String> {
     let file = File::open(zip_path)
@@ -142,4 +531,279 @@ pub fn cleanup_temp_files() -> Result<(), String> {
         eprintln!("Skipping cleanup: temp_dir is not in system temp or doesn't exist");
     }
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zip::write::SimpleFileOptions;
+
+    /// 用给定的条目生成 ZIP 文件
+    fn write_zip(path: &Path, entries: &[(&str, &[u8])], options: SimpleFileOptions) {
+        let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+        for (name, content) in entries {
+            zip.start_file(*name, options).unwrap();
+            zip.write_all(content).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    /// 在目录中写入文件, 自动创建上级目录
+    fn write_file(root: &Path, relative: &str, content: &[u8]) {
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    /// ZIP 中的所有条目名, 已排序
+    fn entry_names(zip_path: &Path) -> Vec<String> {
+        let archive = ZipArchive::new(File::open(zip_path).unwrap()).unwrap();
+        let mut names: Vec<String> = archive.file_names().map(str::to_string).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn extract_rejects_entries_escaping_the_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let zip_path = dir.path().join("evil.zip");
+        write_zip(
+            &zip_path,
+            &[("pack.mcmeta", b"{}"), ("../../evil.txt", b"pwned")],
+            SimpleFileOptions::default(),
+        );
+
+        let target = dir.path().join("a").join("b");
+        let err = extract_zip(&zip_path, &target).unwrap_err();
+        assert!(err.contains("../../evil.txt"), "{}", err);
+        assert!(!dir.path().join("evil.txt").exists());
+    }
+
+    #[test]
+    fn extract_rejects_oversized_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let zip_path = dir.path().join("bomb.zip");
+        {
+            let mut zip = zip::ZipWriter::new(File::create(&zip_path).unwrap());
+            zip.start_file("bomb.bin", SimpleFileOptions::default()).unwrap();
+            let chunk = vec![0u8; 1024 * 1024];
+            for _ in 0..=(MAX_ENTRY_SIZE / chunk.len() as u64) {
+                zip.write_all(&chunk).unwrap();
+            }
+            zip.finish().unwrap();
+        }
+        // 压缩后体积很小, 解压后超过单个条目上限
+        assert!(fs::metadata(&zip_path).unwrap().len() < 4 * 1024 * 1024);
+
+        let target = dir.path().join("out");
+        let err = extract_zip(&zip_path, &target).unwrap_err();
+        assert!(err.contains("bomb.bin"), "{}", err);
+        assert!(!target.join("bomb.bin").exists());
+    }
+
+    #[test]
+    fn check_archive_reports_encrypted_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let zip_path = dir.path().join("locked.zip");
+        let options = SimpleFileOptions::default().with_aes_encryption(zip::AesMode::Aes256, "secret");
+        write_zip(&zip_path, &[("pack.mcmeta", b"{}")], options);
+
+        assert!(matches!(check_archive(&zip_path), Err(AppError::EncryptedArchive(_))));
+    }
+
+    #[test]
+    fn check_archive_reports_non_zip_formats() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let rar = dir.path().join("pack.zip");
+        fs::write(&rar, b"Rar!\x1a\x07\x01\x00rest of the archive").unwrap();
+        match check_archive(&rar) {
+            Err(AppError::UnsupportedArchive(message)) => assert!(message.contains("RAR")),
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let text = dir.path().join("notes.zip");
+        fs::write(&text, b"just some text").unwrap();
+        assert!(matches!(check_archive(&text), Err(AppError::UnsupportedArchive(_))));
+    }
+
+    #[test]
+    fn check_archive_accepts_plain_zip() {
+        let dir = tempfile::tempdir().unwrap();
+        let zip_path = dir.path().join("pack.zip");
+        write_zip(&zip_path, &[("pack.mcmeta", b"{}")], SimpleFileOptions::default());
+
+        assert_eq!(check_archive(&zip_path), Ok(()));
+    }
+
+    #[test]
+    fn export_skips_editor_data_and_dotfiles() {
+        let dir = tempfile::tempdir().unwrap();
+        let pack = dir.path().join("pack");
+        write_file(&pack, "pack.mcmeta", b"{}");
+        write_file(&pack, "assets/minecraft/lang/en_us.json", b"{}");
+        write_file(&pack, ".history/foo.json", b"{}");
+        write_file(&pack, ".little100/cache.json", b"{}");
+        write_file(&pack, "logs/latest.log", b"log");
+        write_file(&pack, "assets/minecraft/.DS_Store", b"");
+        write_file(&pack, "assets/minecraft/textures/Thumbs.db", b"");
+
+        let zip_path = dir.path().join("out.zip");
+        create_zip(&pack, &zip_path, &[], &ExcludePatterns::export_defaults(), ZipCompression::Default.file_options(), None)
+            .unwrap();
+
+        // 过滤后只剩 Thumbs.db 的 textures 目录也不会留下空目录条目
+        assert_eq!(entry_names(&zip_path), ["assets/minecraft/lang/en_us.json", "pack.mcmeta"]);
+    }
+
+    #[test]
+    fn custom_excludes_replace_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let pack = dir.path().join("pack");
+        write_file(&pack, "pack.mcmeta", b"{}");
+        write_file(&pack, "assets/minecraft/lang/en_us.json", b"{}");
+        write_file(&pack, "assets/minecraft/lang/zh_cn.json", b"{}");
+
+        let excludes = ExcludePatterns::new(&["assets/*/lang/zh_*.json"]).unwrap();
+        let zip_path = dir.path().join("out.zip");
+        create_zip(&pack, &zip_path, &[], &excludes, ZipCompression::Default.file_options(), None).unwrap();
+
+        assert_eq!(entry_names(&zip_path), ["assets/minecraft/lang/en_us.json", "pack.mcmeta"]);
+        assert!(ExcludePatterns::new(&["[unclosed"]).is_err());
+    }
+
+    #[test]
+    fn progress_is_reported_per_batch_and_at_the_end() {
+        let dir = tempfile::tempdir().unwrap();
+        let pack = dir.path().join("pack");
+        for i in 0..120 {
+            write_file(&pack, &format!("assets/minecraft/lang/{:03}.json", i), b"{}");
+        }
+
+        let mut reports = Vec::new();
+        let mut on_progress = |progress: ZipProgress| {
+            reports.push(progress);
+            true
+        };
+        let zip_path = dir.path().join("out.zip");
+        create_zip(
+            &pack,
+            &zip_path,
+            &[("checksums.json".to_string(), b"{}".to_vec())],
+            &ExcludePatterns::default(),
+            ZipCompression::Default.file_options(),
+            Some(&mut on_progress),
+        )
+        .unwrap();
+
+        // 每 50 个文件一次, 结束时再一次; 速度较慢时可能因 250ms 间隔多报告几次
+        let done: Vec<usize> = reports.iter().map(|p| p.files_done).collect();
+        assert!(done.contains(&50) && done.contains(&100), "{:?}", done);
+        assert!(reports.len() >= 3 && reports.len() <= 122, "{:?}", done);
+        let last = reports.last().unwrap();
+        assert_eq!((last.files_done, last.total_files), (121, 121));
+        assert_eq!(last.bytes_written, 121 * 2);
+    }
+
+    #[test]
+    fn returning_false_cancels_and_removes_the_zip() {
+        let dir = tempfile::tempdir().unwrap();
+        let pack = dir.path().join("pack");
+        for i in 0..60 {
+            write_file(&pack, &format!("assets/minecraft/lang/{:03}.json", i), b"{}");
+        }
+
+        let mut on_progress = |_: ZipProgress| false;
+        let zip_path = dir.path().join("out.zip");
+        let result = create_zip(
+            &pack,
+            &zip_path,
+            &[],
+            &ExcludePatterns::default(),
+            ZipCompression::Default.file_options(),
+            Some(&mut on_progress),
+        );
+
+        assert!(result.is_err());
+        assert!(!zip_path.exists());
+    }
+
+    #[test]
+    fn png_entries_are_stored_uncompressed() {
+        let dir = tempfile::tempdir().unwrap();
+        let pack = dir.path().join("pack");
+        // 内容高度重复, deflate 会明显变小
+        let png = vec![7u8; 4096];
+        let json = vec![b' '; 4096];
+        write_file(&pack, "assets/minecraft/textures/block/stone.png", &png);
+        write_file(&pack, "assets/minecraft/models/block/stone.json", &json);
+
+        let zip_path = dir.path().join("out.zip");
+        create_zip(&pack, &zip_path, &[], &ExcludePatterns::default(), ZipCompression::Best.file_options(), None)
+            .unwrap();
+
+        let mut archive = ZipArchive::new(File::open(&zip_path).unwrap()).unwrap();
+        let entry = archive.by_name("assets/minecraft/textures/block/stone.png").unwrap();
+        assert_eq!(entry.compression(), zip::CompressionMethod::Stored);
+        assert_eq!(entry.compressed_size(), png.len() as u64);
+        drop(entry);
+        let entry = archive.by_name("assets/minecraft/models/block/stone.json").unwrap();
+        assert!(entry.compressed_size() < json.len() as u64);
+    }
+
+    #[test]
+    fn store_preset_writes_original_sizes() {
+        let dir = tempfile::tempdir().unwrap();
+        let pack = dir.path().join("pack");
+        write_file(&pack, "pack.mcmeta", &[b' '; 1024]);
+
+        let zip_path = dir.path().join("out.zip");
+        create_zip(&pack, &zip_path, &[], &ExcludePatterns::default(), ZipCompression::Store.file_options(), None)
+            .unwrap();
+
+        let mut archive = ZipArchive::new(File::open(&zip_path).unwrap()).unwrap();
+        assert_eq!(archive.by_name("pack.mcmeta").unwrap().compressed_size(), 1024);
+    }
+
+    #[test]
+    fn nested_top_folder_is_detected() {
+        let dir = tempfile::tempdir().unwrap();
+        let zip_path = dir.path().join("nested.zip");
+        write_zip(
+            &zip_path,
+            &[
+                ("MyPack/pack.mcmeta", b"{}"),
+                ("MyPack/assets/minecraft/lang/en_us.json", b"{}"),
+                ("__MACOSX/MyPack/._pack.mcmeta", b""),
+            ],
+            SimpleFileOptions::default(),
+        );
+
+        assert_eq!(nested_pack_root(&zip_path).unwrap().as_deref(), Some("MyPack"));
+    }
+
+    #[test]
+    fn flat_or_ambiguous_archives_keep_their_root() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let flat = dir.path().join("flat.zip");
+        write_zip(
+            &flat,
+            &[("pack.mcmeta", b"{}"), ("assets/minecraft/lang/en_us.json", b"{}")],
+            SimpleFileOptions::default(),
+        );
+        assert_eq!(nested_pack_root(&flat).unwrap(), None);
+
+        let multiple = dir.path().join("multiple.zip");
+        write_zip(
+            &multiple,
+            &[("A/pack.mcmeta", b"{}"), ("B/pack.mcmeta", b"{}")],
+            SimpleFileOptions::default(),
+        );
+        assert_eq!(nested_pack_root(&multiple).unwrap(), None);
+
+        let without_mcmeta = dir.path().join("assets.zip");
+        write_zip(&without_mcmeta, &[("assets/minecraft/lang/en_us.json", b"{}")], SimpleFileOptions::default());
+        assert_eq!(nested_pack_root(&without_mcmeta).unwrap(), None);
+    }
+}
@@ -24,6 +24,24 @@ import grassBlockImg from "./assets/grass-block.png";
 import avatarImg from "./assets/ava.jpg";
 import { open } from '@tauri-apps/plugin-shell';
 import { checkForUpdates } from "./utils/updater";
+import { getErrorMessage, isAppError } from './utils/error';
+import { listen } from '@tauri-apps/api/event';
+
+// 导出任务进度, 由下载管理器的 download-progress 事件推送
+interface ExportProgress {
+  task_id: string;
+  status: string;
+  current: number;
+  total: number;
+  error: string | null;
+}
+
+// 导入压缩包失败时针对错误类型的处理建议
+const ARCHIVE_ERROR_HINTS: Record<string, string> = {
+  unsupported_archive: '请使用解压软件解压后, 通过"导入文件夹"打开。',
+  encrypted_archive: '请先用解压软件输入密码解压, 再通过"导入文件夹"打开。',
+  corrupt_archive: '文件可能未下载完整, 请重新下载后再试。',
+};
 
 type Theme = "light" | "dark" | "system";
 type WebService = "off" | "lan" | "all";
@@ -76,6 +94,8 @@ function App() {
   const [packInfo, setPackInfo] = useState<PackInfo | null>(null);
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
+  const [exportTaskId, setExportTaskId] = useState<string | null>(null);
+  const [exportProgress, setExportProgress] = useState<ExportProgress | null>(null);
   const [selectedResourceType, setSelectedResourceType] = useState<ResourceType | null>(null);
   const [showCreateModal, setShowCreateModal] = useState(false);
   const [showVersionConverter, setShowVersionConverter] = useState(false);
@@ -210,7 +230,8 @@ function App() {
         setPackInfo(info);
       }
     } catch (err) {
-      setError(err instanceof Error ? err.message : String(err));
+      const hint = isAppError(err) ? ARCHIVE_ERROR_HINTS[err.code] : undefined;
+      setError(hint ? `${getErrorMessage(err)}\n${hint}` : getErrorMessage(err));
     } finally {
       setLoading(false);
     }
@@ -244,7 +265,7 @@ function App() {
       }
     } catch (err) {
       console.error('Import folder error:', err);
-      setError(err instanceof Error ? err.message : String(err));
+      setError(getErrorMessage(err));
       setLoading(false);
     }
   };
@@ -261,7 +282,7 @@ function App() {
       setPendingFolderPath(null);
     } catch (err) {
       console.error('Import folder error:', err);
-      setError(err instanceof Error ? err.message : String(err));
+      setError(getErrorMessage(err));
     } finally {
       setLoading(false);
     }
@@ -272,20 +293,51 @@ function App() {
     setPendingFolderPath(null);
   };
 
+  // 监听导出任务进度
+  useEffect(() => {
+    if (!exportTaskId) return;
+    let unlisten: (() => void) | undefined;
+
+    const setupListener = async () => {
+      unlisten = await listen<ExportProgress>('download-progress', (event) => {
+        const progress = event.payload;
+        if (progress.task_id !== exportTaskId) return;
+        setExportProgress(progress);
+
+        if (progress.status === 'completed' || progress.status === 'failed' || progress.status === 'cancelled') {
+          setExportTaskId(null);
+          setExportProgress(null);
+          setLoading(false);
+          if (progress.status === 'completed') {
+            alert("材质包导出成功!");
+          } else if (progress.status === 'failed') {
+            setError(progress.error || '导出失败');
+          }
+        }
+      });
+    };
+    setupListener();
+
+    return () => {
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, [exportTaskId]);
+
   const handleExport = async () => {
     try {
       setLoading(true);
       setError(null);
       const outputPath = await selectFolder();
       if (outputPath && packInfo) {
-        await exportPack(`${outputPath}/${packInfo.name}.zip`);
-        alert("材质包导出成功!");
+        setExportTaskId(await exportPack(`${outputPath}/${packInfo.name}.zip`));
+        return;
       }
     } catch (err) {
-      setError(err instanceof Error ? err.message : String(err));
-    } finally {
-      setLoading(false);
+      setError(getErrorMessage(err));
     }
+    setLoading(false);
   };
 
   const openLink = async (url: string) => {
@@ -313,11 +365,11 @@ function App() {
         if (!serverRunning && packInfo) {
           try {
             const portNum = parseInt(port) || 3000;
-            const msg = await startWebServer(portNum, webService);
+            const msg = await startWebServer(portNum, webService, true);
             setServerMessage(msg);
             setServerRunning(true);
           } catch (err) {
-            setServerMessage(err instanceof Error ? err.message : String(err));
+            setServerMessage(getErrorMessage(err));
             setWebService('off');
           }
         }
@@ -331,7 +383,7 @@ function App() {
     const checkStatus = async () => {
       try {
         const status = await getServerStatus();
-        setServerRunning(status);
+        setServerRunning(status.running);
       } catch (err) {
         console.error('Failed to check server status:', err);
       }
@@ -416,6 +468,13 @@ function App() {
           </div>
         )}
 
+        {exportProgress && exportProgress.total > 0 && (
+          <div className="export-progress">
+            <progress value={exportProgress.current} max={exportProgress.total} />
+            <span>正在导出 {exportProgress.current}/{exportProgress.total}</span>
+          </div>
+        )}
+
         <div className="action-cards">
           <div className="action-card" onClick={handleImportFolder}>
             <div className="action-icon"><FolderIcon /></div>
@@ -694,7 +753,7 @@ function App() {
               const info = await importPackFolder(packPath);
               setPackInfo(info);
             } catch (err) {
-              setError(err instanceof Error ? err.message : String(err));
+              setError(getErrorMessage(err));
             } finally {
               setLoading(false);
             }
@@ -10,6 +10,7 @@ import {
 } from "../utils/tauri-api";
 import "./CreatePackModal.css";
 import { FolderIcon, NewFolderIcon } from "./Icons";
+import { getErrorMessage, isAppError } from '../utils/error';
 
 interface DownloadProgress {
   task_id: string;
@@ -245,7 +246,7 @@ export default function CreatePackModal({
         setOutputPath(folder);
       }
     } catch (err) {
-      setError(err instanceof Error ? err.message : String(err));
+      setError(getErrorMessage(err));
     }
   };
 
@@ -262,7 +263,14 @@ export default function CreatePackModal({
       setError(null);
 
       const minecraftDescription = convertToMinecraftFormat(description);
-      await createNewPack(fullPath, packName, packFormat, minecraftDescription);
+      try {
+        await createNewPack(fullPath, packName, packFormat, minecraftDescription);
+      } catch (err) {
+        // 目标目录不为空时询问是否仍在其中创建(只补充缺少的文件)
+        if (!isAppError(err) || err.code !== 'already_exists') throw err;
+        if (!confirm(`${err.message}\n是否仍在该目录中创建? 已有的文件不会被覆盖。`)) return;
+        await createNewPack(fullPath, packName, packFormat, minecraftDescription, undefined, true);
+      }
 
       // 下载并提取模板
       if (selectedVersion) {
@@ -280,7 +288,7 @@ export default function CreatePackModal({
 
       onSuccess(fullPath);
     } catch (err) {
-      setError(err instanceof Error ? err.message : String(err));
+      setError(getErrorMessage(err));
     } finally {
       setLoading(false);
     }
@@ -294,7 +302,7 @@ export default function CreatePackModal({
       setVersions(manifest.versions);
       setStep(2);
     } catch (err) {
-      setError(err instanceof Error ? err.message : String(err));
+      setError(getErrorMessage(err));
     } finally {
       setLoading(false);
     }
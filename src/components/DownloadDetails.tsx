@@ -2,6 +2,7 @@ import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import './DownloadDetails.css';
+import { getErrorMessage } from '../utils/error';
 
 interface DownloadProgress {
   task_id: string;
@@ -23,6 +24,11 @@ interface DownloadTask {
   created_at: number;
   updated_at: number;
   output_dir: string;
+  started_at: number | null;
+  finished_at: number | null;
+  total_files: number;
+  total_bytes: number;
+  average_speed: number | null;
 }
 
 interface DownloadDetailsProps {
@@ -56,6 +62,11 @@ export default function DownloadDetails({ onClose }: DownloadDetailsProps) {
         
         return newTasks;
       });
+
+      // 完成后重新加载以获取耗时和平均速度
+      if (event.payload.status === 'completed') {
+        loadTasks();
+      }
     });
 
     const unlistenCreated = listen<string>('download-task-created', () => {
@@ -83,7 +94,7 @@ export default function DownloadDetails({ onClose }: DownloadDetailsProps) {
       await invoke('cancel_download_task', { taskId });
     } catch (error) {
       console.error('取消下载失败:', error);
-      alert(`取消下载失败: ${error}`);
+      alert(`取消下载失败: ${getErrorMessage(error)}`);
     }
   };
 
@@ -94,7 +105,7 @@ export default function DownloadDetails({ onClose }: DownloadDetailsProps) {
       await invoke('delete_download_task', { taskId });
     } catch (error) {
       console.error('删除任务失败:', error);
-      alert(`删除任务失败: ${error}`);
+      alert(`删除任务失败: ${getErrorMessage(error)}`);
     }
   };
 
@@ -122,6 +133,12 @@ export default function DownloadDetails({ onClose }: DownloadDetailsProps) {
     return `${mins}:${secs.toString().padStart(2, '0')}`;
   };
 
+  const formatDuration = (seconds: number): string => {
+    const mins = Math.floor(seconds / 60);
+    const secs = Math.floor(seconds % 60);
+    return mins > 0 ? `${mins}分${secs}秒` : `${secs}秒`;
+  };
+
   const formatDate = (timestamp: number): string => {
     return new Date(timestamp * 1000).toLocaleString('zh-CN');
   };
@@ -266,6 +283,13 @@ export default function DownloadDetails({ onClose }: DownloadDetailsProps) {
 
                   <div className="task-meta">
                     <span>创建时间: {formatDate(task.created_at)}</span>
+                    {task.status === 'completed' && task.finished_at && task.average_speed !== null && (
+                      <span>
+                        共 {task.total_files.toLocaleString()} 个文件,
+                        用时 {formatDuration(task.finished_at - (task.started_at ?? task.created_at))},
+                        平均 {formatSpeed(task.average_speed)}
+                      </span>
+                    )}
                   </div>
                 </div>
               ))}
@@ -9,6 +9,7 @@ import {
   checkGPUSupport,
   getGPUInfo,
 } from "../utils/gpu-canvas";
+import { getErrorMessage } from '../utils/error';
 
 interface ImageViewerProps {
   imagePath: string;
@@ -935,7 +936,7 @@ export default function ImageViewer({
               setHasChanges(false);
               alert('保存成功!');
           }
-      } catch (err) { console.error('保存失败:', err); alert(`保存失败: ${err}`); }
+      } catch (err) { console.error('保存失败:', err); alert(`保存失败: ${getErrorMessage(err)}`); }
   };
   
   useEffect(() => {
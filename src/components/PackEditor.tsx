@@ -1,5 +1,6 @@
 import { useState, useRef, useEffect, useCallback, useMemo, memo } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import "./PackEditor.css";
 import type { PackInfo } from "../types/pack";
 import TextEditor from "./TextEditor";
@@ -11,7 +12,7 @@ import TitleBar from "./TitleBar";
 import DownloadIndicator from "./DownloadIndicator";
 import DownloadDetails from "./DownloadDetails";
 import DownloadSettingsDialog from "./DownloadSettingsDialog";
-import { readFileContent, writeFileContent, searchFiles, type SearchResponse } from "../utils/tauri-api";
+import { readFileContent, writeFileContent, startSearch, cancelSearch, getPackSizeSummary, renameFile, deleteFile, getDeleteImpact, type SearchResponse, type SearchResultBatch, type SearchComplete } from "../utils/tauri-api";
 import {
   FolderIcon, FolderOpenIcon, FileIcon, NewFileIcon,
   NewFolderIcon, ImageIcon, RenameIcon, CopyIcon,
@@ -24,6 +25,7 @@ import eraserIcon from "../assets/eraser.svg";
 import moveIcon from "../assets/move.svg";
 import penToolIcon from "../assets/pen-tool.svg";
 import coloizeIcon from "../assets/coloize.svg";
+import { getErrorMessage, isAppError } from '../utils/error';
 
 // 音频播放器组件
 interface AudioPlayerProps {
@@ -115,6 +117,15 @@ interface FileTreeNode {
   children?: FileTreeNode[];
   file_count?: number;
   loaded?: boolean;
+  size?: number | null;
+  modified_ms?: number | null;
+  fingerprint?: string | null;
+}
+
+interface FolderChildren {
+  fingerprint: string;
+  not_modified: boolean;
+  children: FileTreeNode[] | null;
 }
 
 interface PackEditorProps {
@@ -171,6 +182,7 @@ export default function PackEditor({ packInfo, onClose, debugMode = false }: Pac
   const [toolSizeMenuPos, setToolSizeMenuPos] = useState({ x: 0, y: 0 });
   const [packSize, setPackSize] = useState<number>(0);
   const [historySize, setHistorySize] = useState<number>(0);
+  const [editorDataSize, setEditorDataSize] = useState<number>(0);
   const [showClearHistoryDialog, setShowClearHistoryDialog] = useState(false);
   const [historyStats, setHistoryStats] = useState<{ totalSize: number; fileCount: number } | null>(null);
   const [isPreloading, setIsPreloading] = useState(false);
@@ -180,8 +192,10 @@ export default function PackEditor({ packInfo, onClose, debugMode = false }: Pac
   const [showSearchModal, setShowSearchModal] = useState<boolean>(false);
   const [searchResults, setSearchResults] = useState<SearchResponse | null>(null);
   const [isSearching, setIsSearching] = useState<boolean>(false);
+  const activeSearchRef = useRef<string | null>(null);
   const [soundsJsonExists, setSoundsJsonExists] = useState<boolean>(false);
   const [showDownloadDetails, setShowDownloadDetails] = useState<boolean>(false);
+  const [warmupProgress, setWarmupProgress] = useState<{ done: number; total: number } | null>(null);
   const [showDownloadSettings, setShowDownloadSettings] = useState<boolean>(false);
   const fileTreeRef = useRef<HTMLDivElement>(null);
   const contextMenuRef = useRef<HTMLDivElement>(null);
@@ -357,17 +371,16 @@ export default function PackEditor({ packInfo, onClose, debugMode = false }: Pac
       // 获取当前材质包路径
       const packDir = await invoke<string>('get_current_pack_path');
 
-      // 获取材质包大小 同时排除.history文件夹
-      const pSize = await invoke<number>('get_pack_size', { packDir });
-      setPackSize(pSize);
-
-      // 获取历史记录统计
-      const stats = await invoke<any>('get_history_stats', { packDir });
-      setHistorySize(stats.total_size || 0);
+      // 分别统计导出内容、历史记录和编辑器数据
+      const size = await getPackSizeSummary(packDir);
+      setPackSize(size.pack_bytes);
+      setHistorySize(size.history_bytes);
+      setEditorDataSize(size.editor_data_bytes);
     } catch (error) {
       console.error('获取大小统计失败:', error);
       setPackSize(0);
       setHistorySize(0);
+      setEditorDataSize(0);
     }
   };
 
@@ -486,13 +499,21 @@ export default function PackEditor({ packInfo, onClose, debugMode = false }: Pac
     }
   }, [fileTree, languageMap]);
 
+  useEffect(() => {
+    const unlisten = listen<{ done: number; total: number }>('preload-progress', (event) => {
+      setWarmupProgress(event.payload);
+    });
+    return () => { unlisten.then(fn => fn()); };
+  }, []);
+
   useEffect(() => {
     const loadFileTree = async () => {
       console.log('[性能] 开始加载文件树...');
       const startTime = performance.now();
 
       try {
-        const tree = await invoke<FileTreeNode>('get_file_tree');
+        const tree = await invoke<FileTreeNode | null>('get_file_tree');
+        if (!tree) return;
         const endTime = performance.now();
         const duration = (endTime - startTime).toFixed(2);
 
@@ -516,7 +537,7 @@ export default function PackEditor({ packInfo, onClose, debugMode = false }: Pac
         const endTime = performance.now();
         const duration = (endTime - startTime).toFixed(2);
         console.error(`[性能]  加载文件树失败! 耗时: ${duration}ms`, error);
-        alert(`加载文件树失败: ${error}`);
+        alert(`加载文件树失败: ${getErrorMessage(error)}`);
       }
     };
 
@@ -619,7 +640,7 @@ export default function PackEditor({ packInfo, onClose, debugMode = false }: Pac
       return content;
     } catch (error) {
       console.error('加载文件失败:', error);
-      alert(`加载文件失败: ${error}`);
+      alert(`加载文件失败: ${getErrorMessage(error)}`);
       return '';
     } finally {
       setIsLoading(false);
@@ -781,7 +802,7 @@ export default function PackEditor({ packInfo, onClose, debugMode = false }: Pac
       setOpenTabs(newTabs);
     } catch (error) {
       console.error('保存文件失败:', error);
-      alert(`保存文件失败: ${error}`);
+      alert(`保存文件失败: ${getErrorMessage(error)}`);
     }
   };
 
@@ -880,7 +901,7 @@ export default function PackEditor({ packInfo, onClose, debugMode = false }: Pac
       await refreshFileTree();
     } catch (error) {
       console.error('清理历史记录失败:', error);
-      alert(`清理失败: ${error}`);
+      alert(`清理失败: ${getErrorMessage(error)}`);
     }
   };
 
@@ -924,9 +945,10 @@ export default function PackEditor({ packInfo, onClose, debugMode = false }: Pac
 
     try {
       const invokeStart = performance.now();
-      const children = await invoke<FileTreeNode[]>('load_folder_children', {
+      const { children: loaded } = await invoke<FolderChildren>('load_folder_children', {
         folderPath: folderPath
       });
+      const children = loaded ?? [];
       const invokeEnd = performance.now();
       const invokeDuration = (invokeEnd - invokeStart).toFixed(2);
 
@@ -1214,13 +1236,17 @@ const finishRename = async () => {
       const newPath = pathParts.join('/');
 
       try {
-        await invoke('rename_file', {
-          oldPath: renamingPath,
-          newPath: newPath
-        });
+        try {
+          await renameFile(renamingPath, newPath);
+        } catch (err) {
+          if (!isAppError(err) || err.code !== 'already_exists') throw err;
+          if (confirm(`${err.message}\n是否覆盖?`)) {
+            await renameFile(renamingPath, newPath, true);
+          }
+        }
         await refreshFileTree();
       } catch (error) {
-        alert(`重命名失败: ${error}`);
+        alert(`重命名失败: ${getErrorMessage(error)}`);
       }
     }
   }
@@ -1236,13 +1262,54 @@ const cancelRename = () => {
 
 const refreshFileTree = useCallback(async () => {
   try {
-    const tree = await invoke<FileTreeNode>('get_file_tree');
-    setFileTree(tree);
+    const tree = await invoke<FileTreeNode | null>('get_file_tree');
+    if (tree) setFileTree(tree);
   } catch (error) {
     console.error('刷新文件树失败:', error);
   }
 }, []);
 
+// 外部程序修改了材质包文件时刷新文件树, 对应的缩略图缓存已由后端清除
+useEffect(() => {
+  const unlisten = listen<{ pack_id: string; changes: { path: string; kind: string }[] }>('pack-fs-changed', () => {
+    refreshFileTree();
+  });
+  return () => { unlisten.then(fn => fn()); };
+}, [refreshFileTree]);
+
+// 流式搜索: 只接收当前搜索的结果, 旧搜索的残留事件直接忽略
+useEffect(() => {
+  const unlistenBatch = listen<SearchResultBatch>('search-result-batch', (event) => {
+    const batch = event.payload;
+    if (batch.search_id !== activeSearchRef.current) return;
+    setSearchResults(prev => {
+      const filename_matches = [...(prev?.filename_matches ?? []), ...batch.filename_matches];
+      const content_matches = [...(prev?.content_matches ?? []), ...batch.content_matches];
+      return {
+        filename_matches,
+        content_matches,
+        total_count: filename_matches.length + content_matches.length,
+      };
+    });
+  });
+  const unlistenComplete = listen<SearchComplete>('search-complete', (event) => {
+    if (event.payload.search_id !== activeSearchRef.current) return;
+    activeSearchRef.current = null;
+    setIsSearching(false);
+  });
+  return () => {
+    unlistenBatch.then(fn => fn());
+    unlistenComplete.then(fn => fn());
+  };
+}, []);
+
+const cancelActiveSearch = () => {
+  if (activeSearchRef.current) {
+    cancelSearch(activeSearchRef.current).catch(() => {});
+    activeSearchRef.current = null;
+  }
+};
+
 const handleDownloadSounds = async () => {
   // 显示下载设置对话框
   setShowDownloadSettings(true);
@@ -1260,7 +1327,7 @@ const startDownload = async (threads: number) => {
     setShowDownloadDetails(true);
   } catch (error) {
     console.error('创建下载任务失败:', error);
-    alert(`下载失败: ${error}`);
+    alert(`下载失败: ${getErrorMessage(error)}`);
   }
 };
 
@@ -1272,20 +1339,24 @@ const handleMenuAction = async (action: string) => {
       try {
         await invoke('open_in_explorer', { filePath: contextMenu.path });
       } catch (error) {
-        alert(`打开资源管理器失败: ${error}`);
+        alert(`打开资源管理器失败: ${getErrorMessage(error)}`);
       }
       break;
     case 'downloadSounds':
       await handleDownloadSounds();
       break;
     case 'delete':
-      if (confirm(`确定要删除 ${contextMenu.path} 吗？`)) {
-        try {
-          await invoke('delete_file', { filePath: contextMenu.path });
+      try {
+        const impact = await getDeleteImpact(contextMenu.path);
+        const summary = impact.folders > 0
+          ? `\n将删除 ${impact.files} 个文件、${impact.folders} 个文件夹, 共 ${(impact.total_bytes / 1024 / 1024).toFixed(2)} MB`
+          : '';
+        if (confirm(`确定要删除 ${contextMenu.path} 吗？${summary}\n删除的内容会移动到材质包回收站, 可以恢复`)) {
+          await deleteFile(contextMenu.path, false, true);
           await refreshFileTree();
-        } catch (error) {
-          alert(`删除失败: ${error}`);
         }
+      } catch (error) {
+        alert(`删除失败: ${getErrorMessage(error)}`);
       }
       break;
     case 'rename':
@@ -1302,7 +1373,7 @@ const handleMenuAction = async (action: string) => {
           });
           await refreshFileTree();
         } catch (error) {
-          alert(`创建文件失败: ${error}`);
+          alert(`创建文件失败: ${getErrorMessage(error)}`);
         }
       }
       break;
@@ -1314,7 +1385,7 @@ const handleMenuAction = async (action: string) => {
           await invoke('create_new_folder', { folderPath: folderPath });
           await refreshFileTree();
         } catch (error) {
-          alert(`创建文件夹失败: ${error}`);
+          alert(`创建文件夹失败: ${getErrorMessage(error)}`);
         }
       }
       break;
@@ -1345,7 +1416,7 @@ const handleMenuAction = async (action: string) => {
         // 自动打开创建的文件
         openFileInTab(filePath);
       } catch (error) {
-        alert(`创建 sounds.json 失败: ${error}`);
+        alert(`创建 sounds.json 失败: ${getErrorMessage(error)}`);
       }
       break;
     case 'copy':
@@ -1363,17 +1434,27 @@ const handleMenuAction = async (action: string) => {
 const handleCreatePng = async (width: number, height: number, fileName: string) => {
   try {
     const filePath = pngCreatorFolder ? `${pngCreatorFolder}/${fileName}` : fileName;
-    await invoke('create_transparent_png', {
+    const result = await invoke<{ created: boolean; warnings: string[] }>('create_transparent_png', {
       filePath: filePath,
       width: width,
       height: height
     });
+    // 尺寸少见时先确认
+    if (!result.created) {
+      if (!confirm(`${result.warnings.join('\n')}\n\n仍要创建吗?`)) return;
+      await invoke('create_transparent_png', {
+        filePath: filePath,
+        width: width,
+        height: height,
+        force: true
+      });
+    }
     await refreshFileTree();
     setShowPngCreator(false);
 
     openFileInTab(filePath);
   } catch (error) {
-    alert(`创建PNG失败: ${error}`);
+    alert(`创建PNG失败: ${getErrorMessage(error)}`);
   }
 };
 
@@ -1551,6 +1632,7 @@ return (
     <TitleBar
       packSize={packSize}
       historySize={historySize}
+      editorDataSize={editorDataSize}
       showStats={true}
       debugMode={debugMode}
     />
@@ -1707,30 +1789,38 @@ return (
       {showSearchModal && (
         <SearchModal
           onClose={() => {
+            cancelActiveSearch();
             setShowSearchModal(false);
             setSearchResults(null);
             setIsSearching(false);
           }}
           onResultClick={(filePath, lineNumber) => {
             openFileInTab(filePath, false, lineNumber);
+            cancelActiveSearch();
             setShowSearchModal(false);
             setSearchResults(null);
           }}
           onSearch={async (query, caseSensitive, useRegex) => {
+            cancelActiveSearch();
             if (!query.trim()) {
               setSearchResults(null);
+              setIsSearching(false);
               return;
             }
 
+            const searchId = crypto.randomUUID();
+            activeSearchRef.current = searchId;
+            setSearchResults({ filename_matches: [], content_matches: [], total_count: 0 });
             setIsSearching(true);
             try {
-              const results = await searchFiles(query, caseSensitive, useRegex);
-              setSearchResults(results);
+              await startSearch(query, caseSensitive, useRegex, "exact", searchId);
             } catch (error) {
+              if (activeSearchRef.current === searchId) {
+                activeSearchRef.current = null;
+                setIsSearching(false);
+              }
               console.error('搜索失败:', error);
-              alert(`搜索失败: ${error}`);
-            } finally {
-              setIsSearching(false);
+              alert(`搜索失败: ${getErrorMessage(error)}`);
             }
           }}
           searchResults={searchResults}
@@ -2016,6 +2106,13 @@ return (
         </div>
       </div>
 
+      {/* 导入后后台生成缩略图的进度 */}
+      {warmupProgress && warmupProgress.done < warmupProgress.total && (
+        <div className="preview-warmup-indicator">
+          正在准备预览 {warmupProgress.done}/{warmupProgress.total}
+        </div>
+      )}
+
       {/* 下载指示器 */}
       <DownloadIndicator onShowDetails={() => setShowDownloadDetails(true)} />
 
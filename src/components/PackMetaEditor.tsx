@@ -7,6 +7,7 @@ import PackMetaVisualEditor from "./PackMetaVisualEditor";
 import { writeFileContent } from "../utils/tauri-api";
 import { getCompletions, validateJson } from "../utils/json-schema-helper";
 import { getVersionsByPackFormat } from "../utils/version-map";
+import { getErrorMessage } from '../utils/error';
 
 interface PackMetaEditorProps {
   content: string;
@@ -585,7 +586,7 @@ export default function PackMetaEditor({ content, filePath, onChange, onSave }:
         await saveHistoryToBackend();
       } catch (error) {
         console.error('保存文件失败:', error);
-        alert(`保存文件失败: ${error}`);
+        alert(`保存文件失败: ${getErrorMessage(error)}`);
       }
     }
   };
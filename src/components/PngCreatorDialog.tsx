@@ -9,10 +9,12 @@ interface PngCreatorDialogProps {
 
 export default function PngCreatorDialog({ onClose, onConfirm, folderPath }: PngCreatorDialogProps) {
   const [fileName, setFileName] = useState<string>("new_image.png");
-  const [size, setSize] = useState<number>(16);
+  const [width, setWidth] = useState<number>(16);
+  const [height, setHeight] = useState<number>(16);
 
-  // 选项: 1, 2, 4, 8, 16, 32, 64, 128, 256, 512, 1024, 2048, 4096, 8192
+  // 常用尺寸: 1, 2, 4, 8, 16, 32, 64, 128, 256, 512, 1024, 2048, 4096, 8192
   const sizeOptions = [1, 2, 4, 8, 16, 32, 64, 128, 256, 512, 1024, 2048, 4096, 8192];
+  const previewScale = Math.min(1, 200 / Math.max(width, height, 1));
 
   const handleConfirm = () => {
     if (!fileName.trim()) {
@@ -23,7 +25,12 @@ export default function PngCreatorDialog({ onClose, onConfirm, folderPath }: Png
     // 确保文件名以.png结尾
     const finalFileName = fileName.endsWith('.png') ? fileName : `${fileName}.png`;
     
-    onConfirm(size, size, finalFileName);
+    if (!Number.isInteger(width) || !Number.isInteger(height) || width < 1 || height < 1 || width > 8192 || height > 8192) {
+      alert("尺寸需在 1 到 8192 之间");
+      return;
+    }
+
+    onConfirm(width, height, finalFileName);
   };
 
   const handleKeyDown = (e: React.KeyboardEvent) => {
@@ -61,15 +68,33 @@ export default function PngCreatorDialog({ onClose, onConfirm, folderPath }: Png
           </div>
 
           <div className="form-group">
-            <label>尺寸 (像素):</label>
-            <select
-              value={size}
-              onChange={(e) => setSize(Number(e.target.value))}
-            >
+            <label>尺寸 (像素, 宽 × 高):</label>
+            <div className="size-inputs">
+              <input
+                type="number"
+                min={1}
+                max={8192}
+                list="png-size-options"
+                value={width}
+                onChange={(e) => setWidth(Number(e.target.value))}
+                onKeyDown={handleKeyDown}
+              />
+              <span>×</span>
+              <input
+                type="number"
+                min={1}
+                max={8192}
+                list="png-size-options"
+                value={height}
+                onChange={(e) => setHeight(Number(e.target.value))}
+                onKeyDown={handleKeyDown}
+              />
+            </div>
+            <datalist id="png-size-options">
               {sizeOptions.map(option => (
-                <option key={`size-${option}`} value={option}>{option}px | {option}px</option>
+                <option key={`size-${option}`} value={option} />
               ))}
-            </select>
+            </datalist>
           </div>
 
           <div className="preview-section">
@@ -78,19 +103,19 @@ export default function PngCreatorDialog({ onClose, onConfirm, folderPath }: Png
               <div
                 className="preview-canvas"
                 style={{
-                  width: `${Math.min(size, 200)}px`,
-                  height: `${Math.min(size, 200)}px`
+                  width: `${Math.max(1, width * previewScale)}px`,
+                  height: `${Math.max(1, height * previewScale)}px`
                 }}
               >
-                <span className="preview-size">{size} X {size}</span>
+                <span className="preview-size">{width} X {height}</span>
               </div>
             </div>
           </div>
 
           <div className="form-info">
             <p> 保存位置: {folderPath || '根目录'}</p>
-            <p> 分辨率: {size} | {size}</p>
-            <p> 图片将创建为正方形透明PNG格式</p>
+            <p> 分辨率: {width} | {height}</p>
+            <p> 图片将创建为透明PNG格式, 动画帧条可使用高度为宽度整数倍的尺寸</p>
           </div>
         </div>
 
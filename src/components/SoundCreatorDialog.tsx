@@ -2,6 +2,8 @@ import React, { useState, useEffect, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { convertFileSrc } from '@tauri-apps/api/core';
 import './SoundCreatorDialog.css';
+import { getErrorMessage } from '../utils/error';
+import { readFileContent } from '../utils/tauri-api';
 
 interface SoundCreatorDialogProps {
   onClose: () => void;
@@ -136,9 +138,7 @@ export default function SoundCreatorDialog({ onClose, onSave }: SoundCreatorDial
         }
       } catch (fetchError) {
         console.log('尝试从开发环境路径读取翻译文件...');
-        content = await invoke<string>('read_file_content', {
-          filePath: 'sounds/translate/sounds.json'
-        });
+        content = await readFileContent('sounds/translate/sounds.json');
       }
       
       const data: TranslateData = JSON.parse(content);
@@ -312,9 +312,7 @@ export default function SoundCreatorDialog({ onClose, onSave }: SoundCreatorDial
       let currentData: any = {};
       
       try {
-        const currentContent = await invoke<string>('read_file_content', {
-          filePath: 'assets/minecraft/sounds/sounds.json'
-        });
+        const currentContent = await readFileContent('assets/minecraft/sounds/sounds.json');
         currentData = JSON.parse(currentContent);
       } catch (readError) {
         console.log('sounds.json 不存在，将创建新文件');
@@ -368,7 +366,7 @@ export default function SoundCreatorDialog({ onClose, onSave }: SoundCreatorDial
       onClose();
     } catch (error) {
       console.error('保存失败:', error);
-      alert(`保存失败: ${error}`);
+      alert(`保存失败: ${getErrorMessage(error)}`);
     }
   };
 
@@ -499,9 +497,7 @@ export default function SoundCreatorDialog({ onClose, onSave }: SoundCreatorDial
     try {
       let currentData: any = {};
       try {
-        const currentContent = await invoke<string>('read_file_content', {
-          filePath: 'assets/minecraft/sounds/sounds.json'
-        });
+        const currentContent = await readFileContent('assets/minecraft/sounds/sounds.json');
         currentData = JSON.parse(currentContent);
       } catch (readError) {
         console.log('sounds.json 不存在，将创建新文件');
@@ -574,7 +570,7 @@ export default function SoundCreatorDialog({ onClose, onSave }: SoundCreatorDial
       onClose();
     } catch (error) {
       console.error('保存原版音效失败:', error);
-      alert(`保存失败: ${error}`);
+      alert(`保存失败: ${getErrorMessage(error)}`);
     }
   };
 
@@ -8,6 +8,7 @@ import "./MonacoEditor.css";
 import SoundCreatorDialog from "./SoundCreatorDialog";
 import AudioHoverPlayer from "./AudioHoverPlayer";
 import { readFileContent, writeFileContent } from "../utils/tauri-api";
+import { getErrorMessage } from '../utils/error';
 
 let monacoInitPromise: Promise<Monaco> | null = null;
 const initMonaco = () => {
@@ -349,7 +350,7 @@ export default function TextEditor({
         await saveHistoryToBackend();
       } catch (error) {
         console.error('保存文件失败:', error);
-        alert(`保存文件失败: ${error}`);
+        alert(`保存文件失败: ${getErrorMessage(error)}`);
       }
     }
   };
@@ -15,11 +15,12 @@ import XiaoliangDevAvatar from '../assets/avatar/Xiaoliang-dev.png';
 interface TitleBarProps {
   packSize?: number;      // 材质包大小
   historySize?: number;   // 历史记录大小
+  editorDataSize?: number; // 编辑器内部数据大小
   showStats?: boolean;    // 是否显示统计信息
   debugMode?: boolean;    // 是否启用调试模式
 }
 
-const TitleBar = ({ packSize = 0, historySize = 0, showStats = false, debugMode = false }: TitleBarProps) => {
+const TitleBar = ({ packSize = 0, historySize = 0, editorDataSize = 0, showStats = false, debugMode = false }: TitleBarProps) => {
   const [isMaximized, setIsMaximized] = useState(false);
   const [appWindow, setAppWindow] = useState<any>(null);
   const [showCredits, setShowCredits] = useState(false);
@@ -243,6 +244,11 @@ const TitleBar = ({ packSize = 0, historySize = 0, showStats = false, debugMode
               <span className="stat-label">历史记录:</span>
               <span className="stat-value">{formatSize(historySize)}</span>
             </span>
+            <span className="stat-divider">|</span>
+            <span className="stat-item">
+              <span className="stat-label">编辑器数据:</span>
+              <span className="stat-value">{formatSize(editorDataSize)}</span>
+            </span>
           </div>
         </div>
       )}
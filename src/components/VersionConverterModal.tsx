@@ -3,6 +3,7 @@ import { invoke } from '@tauri-apps/api/core';
 import { selectZipFile, selectFolder, selectOutputFolder } from '../utils/tauri-api';
 import { getVersionRange, getVersionsWithType, isReleaseVersion, getVersionsByPackFormat } from '../utils/version-map';
 import './VersionConverterModal.css';
+import { getErrorMessage } from '../utils/error';
 
 interface PackMetadata {
   pack_format?: number;
@@ -77,7 +78,7 @@ const VersionConverterModal = ({ onClose }: VersionConverterModalProps) => {
         await analyzePackMetadata(zipPath, 'zip');
       }
     } catch (err) {
-      setError(err instanceof Error ? err.message : String(err));
+      setError(getErrorMessage(err));
     } finally {
       setLoading(false);
     }
@@ -98,7 +99,7 @@ const VersionConverterModal = ({ onClose }: VersionConverterModalProps) => {
         await analyzePackMetadata(folderPath, 'folder');
       }
     } catch (err) {
-      setError(err instanceof Error ? err.message : String(err));
+      setError(getErrorMessage(err));
     } finally {
       setLoading(false);
     }
@@ -168,7 +169,7 @@ const VersionConverterModal = ({ onClose }: VersionConverterModalProps) => {
         setError(null);
       }
     } catch (err) {
-      setError('选择输出路径失败: ' + (err instanceof Error ? err.message : String(err)));
+      setError('选择输出路径失败: ' + (getErrorMessage(err)));
     }
   };
 
@@ -234,7 +235,7 @@ const VersionConverterModal = ({ onClose }: VersionConverterModalProps) => {
       console.log('[VersionConverter] 转换结果:', result);
       setConversionSuccess(true);
     } catch (err) {
-      setError('转换失败: ' + (err instanceof Error ? err.message : String(err)));
+      setError('转换失败: ' + (getErrorMessage(err)));
     } finally {
       setConverting(false);
     }
@@ -396,7 +397,7 @@ const VersionConverterModal = ({ onClose }: VersionConverterModalProps) => {
       }
 
     } catch (err) {
-      const errorMsg = err instanceof Error ? err.message : String(err);
+      const errorMsg = getErrorMessage(err);
       
       if (errorMsg.includes('pack.mcmeta') || errorMsg.includes('not found') || errorMsg.includes('找不到')) {
         // 找不到pack.mcmeta显示错误
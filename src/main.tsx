@@ -1,6 +1,17 @@
 import React from "react";
 import ReactDOM from "react-dom/client";
 import App from "./App";
+import { logMessage } from "./utils/tauri-api";
+
+// 未捕获的前端错误写入日志文件
+window.addEventListener("error", (event) => {
+  const location = event.filename ? `${event.filename}:${event.lineno}` : undefined;
+  logMessage("error", event.error?.stack || event.message, location).catch(() => {});
+});
+window.addEventListener("unhandledrejection", (event) => {
+  const reason = event.reason instanceof Error ? event.reason.stack || event.reason.message : String(event.reason);
+  logMessage("error", reason, "unhandledrejection").catch(() => {});
+});
 
 ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
   <React.StrictMode>
@@ -17,6 +17,7 @@ export enum ResourceType {
   Language = "Language",
   Font = "Font",
   Shader = "Shader",
+  Optifine = "Optifine",
   Other = "Other",
 }
 
@@ -35,6 +36,9 @@ export interface PackInfo {
   name: string;
   version: MinecraftVersion;
   pack_format: number;
+  // 声明支持的 pack_format 范围(含)
+  min_format: number;
+  max_format: number;
   description: string;
   resources: Record<ResourceType, ResourceFile[]>;
   namespaces: string[];
@@ -68,5 +72,6 @@ export const RESOURCE_TYPE_NAMES: Record<ResourceType, string> = {
   [ResourceType.Language]: "语言文件",
   [ResourceType.Font]: "字体",
   [ResourceType.Shader]: "着色器",
+  [ResourceType.Optifine]: "OptiFine",
   [ResourceType.Other]: "其他",
 };
\ No newline at end of file
@@ -0,0 +1,22 @@
+// 后端命令返回的错误, 结构为 { code, message }
+export interface AppError {
+  code: string;
+  message: string;
+}
+
+// 判断是否为后端命令错误
+export function isAppError(error: unknown): error is AppError {
+  return (
+    typeof error === 'object' &&
+    error !== null &&
+    typeof (error as AppError).code === 'string' &&
+    typeof (error as AppError).message === 'string'
+  );
+}
+
+// 获取可显示的错误信息
+export function getErrorMessage(error: unknown): string {
+  if (error instanceof Error) return error.message;
+  if (isAppError(error)) return error.message;
+  return String(error);
+}
@@ -1,6 +1,6 @@
 import { invoke } from "@tauri-apps/api/core";
 import { open } from "@tauri-apps/plugin-dialog";
-import type { PackInfo, ImageInfo } from "../types/pack";
+import type { PackInfo, ImageInfo, ResourceType } from "../types/pack";
 
 // 导入材质包
 export async function importPackZip(zipPath: string): Promise<PackInfo> {
@@ -17,6 +17,42 @@ export async function importPackFolder(folderPath: string): Promise<PackInfo> {
   return await invoke<PackInfo>("import_pack_folder", { folderPath });
 }
 
+// 已打开的材质包; 文件命令可传 packId 指定材质包, 省略时使用当前(active)材质包
+export interface OpenPackSummary {
+  id: string;
+  name: string;
+  path: string;
+  active: boolean;
+}
+
+// 在当前材质包之外再打开一个材质包(文件夹或ZIP), 返回 packId
+export async function openPack(path: string, activate?: boolean): Promise<string> {
+  return await invoke<string>("open_pack", { path, activate });
+}
+
+export async function closePack(packId: string): Promise<void> {
+  return await invoke<void>("close_pack", { packId });
+}
+
+export async function setActivePack(packId: string): Promise<PackInfo | null> {
+  return await invoke<PackInfo | null>("set_active_pack", { packId });
+}
+
+export async function listOpenPacks(): Promise<OpenPackSummary[]> {
+  return await invoke<OpenPackSummary[]>("list_open_packs");
+}
+
+// 在两个已打开的材质包之间复制文件或文件夹, 返回目标相对路径
+export async function copyBetweenPacks(
+  srcPackId: string,
+  srcPath: string,
+  dstPackId: string,
+  dstPath: string,
+  overwrite?: boolean
+): Promise<string> {
+  return await invoke<string>("copy_between_packs", { srcPackId, srcPath, dstPackId, dstPath, overwrite });
+}
+
 // 获取当前材质包信息
 export async function getCurrentPackInfo(): Promise<PackInfo | null> {
   return await invoke<PackInfo | null>("get_current_pack_info");
@@ -31,13 +67,77 @@ export async function getImageThumbnail(
 }
 
 // 获取图片详细信息
-export async function getImageDetails(imagePath: string): Promise<ImageInfo> {
-  return await invoke<ImageInfo>("get_image_details", { imagePath });
+export async function getImageDetails(imagePath: string, packId?: string): Promise<ImageInfo> {
+  return await invoke<ImageInfo>("get_image_details", { imagePath, packId });
+}
+
+// 文件或目录的元数据, 时间为 RFC3339; image_info 仅在已缓存时返回, file_count 仅目录有
+export interface FileMetadata {
+  size: number;
+  created: string | null;
+  modified: string | null;
+  is_dir: boolean;
+  extension: string | null;
+  image_info: ImageInfo | null;
+  file_count: number | null;
+}
+
+export async function getFileMetadata(path: string): Promise<FileMetadata> {
+  return await invoke<FileMetadata>("get_file_metadata", { path });
+}
+
+// 动画的一帧, duration 单位为游戏刻(1/20 秒), image 为 PNG base64
+export interface AnimationFrame {
+  index: number;
+  duration: number;
+  image: string;
+}
+
+export interface AnimationPreview {
+  frame_width: number;
+  frame_height: number;
+  interpolate: boolean;
+  has_mcmeta: boolean;
+  frames: AnimationFrame[];
+}
+
+// 按 .png.mcmeta 拆分动画纹理的各帧, 没有 mcmeta 时按正方形帧推断
+export async function getAnimationPreview(texturePath: string, packId?: string): Promise<AnimationPreview> {
+  return await invoke<AnimationPreview>("get_animation_preview", { texturePath, packId });
+}
+
+// 导出压缩级别, png/ogg 始终仅存储
+export type ZipCompression = "store" | "fast" | "default" | "best";
+
+// 导出选项, 未指定的项使用默认值; exclude_patterns 为 glob, 默认排除 .history、.little100、.git 等
+export interface ExportOptions {
+  exclude_patterns?: string[];
+  compression_level?: number;
+  method?: "deflate" | "stored";
 }
 
-// 导出材质包
-export async function exportPack(outputPath: string): Promise<void> {
-  return await invoke<void>("export_pack", { outputPath });
+// 导出材质包, 立即返回任务 ID, 进度通过 download-progress 事件推送(current/total 为文件数)
+export async function exportPack(
+  outputPath: string,
+  compression?: ZipCompression,
+  options?: ExportOptions
+): Promise<string> {
+  return await invoke<string>("export_pack", { outputPath, compression, options });
+}
+
+// 只导出选中的路径(如 assets/minecraft/textures/item), pack.mcmeta 和 pack.png 始终包含
+export async function exportPackSubset(outputPath: string, includePaths: string[]): Promise<void> {
+  return await invoke<void>("export_pack_subset", { outputPath, includePaths });
+}
+
+// 导出为文件夹, 目标已存在时需要 overwrite 才会整体替换
+export async function exportPackFolder(outputPath: string, overwrite?: boolean): Promise<void> {
+  return await invoke<void>("export_pack_folder", { outputPath, overwrite });
+}
+
+// 部署到 Minecraft 的 resourcepacks 目录, 返回部署后的路径
+export async function deployToMinecraft(minecraftDir: string | null, asZip: boolean): Promise<string> {
+  return await invoke<string>("deploy_to_minecraft", { minecraftDir, asZip });
 }
 
 // 清理临时文件
@@ -45,9 +145,22 @@ export async function cleanupTemp(): Promise<void> {
   return await invoke<void>("cleanup_temp");
 }
 
+export type TextEncoding = "utf-8" | "utf-16le" | "utf-16be" | "latin1";
+
+export interface TextFileContent {
+  content: string;
+  detected_encoding: TextEncoding;
+  had_bom: boolean;
+}
+
+// 读取文件内容及检测到的编码
+export async function readTextFile(filePath: string): Promise<TextFileContent> {
+  return await invoke<TextFileContent>("read_file_content", { filePath });
+}
+
 // 读取文件内容
 export async function readFileContent(filePath: string): Promise<string> {
-  return await invoke<string>("read_file_content", { filePath });
+  return (await readTextFile(filePath)).content;
 }
 
 // 读取内容
@@ -58,30 +171,154 @@ export async function readFileBinary(filePath: string): Promise<Uint8Array> {
 // 写入文件内容
 export async function writeFileContent(
   filePath: string,
-  content: string
+  content: string,
+  encoding?: TextEncoding,
+  bom?: boolean
 ): Promise<void> {
-  return await invoke<void>("write_file_content", { filePath, content });
+  return await invoke<void>("write_file_content", { filePath, content, encoding, bom });
 }
 
-// 创建新文件
+// 创建新文件, 文件已存在时报错, overwrite 为 true 时覆盖(旧内容保存到历史记录)
 export async function createNewFile(
   filePath: string,
-  content: string
+  content: string,
+  overwrite?: boolean
 ): Promise<void> {
-  return await invoke<void>("create_new_file", { filePath, content });
+  return await invoke<void>("create_new_file", { filePath, content, overwrite });
+}
+
+// 删除文件, 默认移动到材质包内的 .trash, permanent 为 true 时直接删除
+export async function deleteFile(
+  filePath: string,
+  toRecycleBin?: boolean,
+  force?: boolean,
+  permanent?: boolean
+): Promise<void> {
+  return await invoke<void>("delete_file", { filePath, toRecycleBin, force, permanent });
+}
+
+// 批量删除结果, 单个失败不会中止其余条目
+export interface DeleteFilesResult {
+  deleted: string[];
+  failed: { path: string; code: string; message: string }[];
+}
+
+// 批量删除文件或文件夹
+export async function deleteFiles(
+  paths: string[],
+  toRecycleBin?: boolean,
+  force?: boolean,
+  permanent?: boolean
+): Promise<DeleteFilesResult> {
+  return await invoke<DeleteFilesResult>("delete_files", { paths, toRecycleBin, force, permanent });
+}
+
+// 材质包回收站(.trash)中的一次删除
+export interface TrashEntry {
+  id: string;
+  deleted_at: string | null;
+  files: string[];
+  total_bytes: number;
 }
 
-// 删除文件
-export async function deleteFile(filePath: string): Promise<void> {
-  return await invoke<void>("delete_file", { filePath });
+export interface TrashRestoreResult {
+  restored: string[];
+  overwritten: string[];
 }
 
-// 重命名文件
+export async function listTrash(): Promise<TrashEntry[]> {
+  return await invoke<TrashEntry[]>("list_trash");
+}
+
+// 恢复回收站条目, 原位置已有文件时报错, overwrite 为 true 时覆盖
+export async function restoreFromTrash(entryId: string, overwrite?: boolean): Promise<TrashRestoreResult> {
+  return await invoke<TrashRestoreResult>("restore_from_trash", { entryId, overwrite });
+}
+
+// 清空回收站, 返回释放的文件数和大小
+export async function emptyTrash(): Promise<DeleteImpact> {
+  return await invoke<DeleteImpact>("empty_trash");
+}
+
+// 移动文件或文件夹到其他目录, 历史记录随之迁移, 返回新的相对路径
+export async function moveFile(oldPath: string, newPath: string): Promise<string> {
+  return await invoke<string>("move_file", { oldPath, newPath });
+}
+
+// 在原位置创建副本(name_copy.png 等), 返回副本的相对路径
+export async function duplicateFile(filePath: string): Promise<string> {
+  return await invoke<string>("duplicate_file", { filePath });
+}
+
+// 导入外部文件时目标已存在的处理方式
+export type ImportConflict = "overwrite" | "skip" | "rename";
+
+export interface ImportedFile {
+  source: string;
+  path: string;
+  is_valid_texture: boolean | null;
+}
+
+export interface ImportFilesResult {
+  imported: ImportedFile[];
+  skipped: string[];
+  renamed: ImportedFile[];
+}
+
+// 将外部文件或文件夹(绝对路径)复制到材质包内的目标文件夹
+export async function importFiles(
+  sources: string[],
+  destFolder: string,
+  conflict: ImportConflict,
+  packId?: string
+): Promise<ImportFilesResult> {
+  return await invoke<ImportFilesResult>("import_files", { sources, destFolder, conflict, packId });
+}
+
+// 复制文件或文件夹, 返回目标的相对路径
+export async function copyFile(
+  srcPath: string,
+  destPath: string,
+  overwrite = false
+): Promise<string> {
+  return await invoke<string>("copy_file", { srcPath, destPath, overwrite });
+}
+
+export interface DeleteImpact {
+  files: number;
+  folders: number;
+  total_bytes: number;
+}
+
+// 获取删除会移除的文件数量和大小
+export async function getDeleteImpact(filePath: string, packId?: string): Promise<DeleteImpact> {
+  return await invoke<DeleteImpact>("get_delete_impact", { filePath, packId });
+}
+
+// 批量重命名中的一项
+export interface RenameMapping {
+  old_path: string;
+  new_path: string;
+}
+
+// 用正则批量重命名文件夹内的文件, dryRun 时只返回预览, 返回实际执行的重命名
+export async function batchRename(
+  folderPath: string,
+  pattern: string,
+  replacement: string,
+  dryRun: boolean,
+  packId?: string
+): Promise<RenameMapping[]> {
+  return await invoke<RenameMapping[]>("batch_rename", { folderPath, pattern, replacement, dryRun, packId });
+}
+
+// 重命名文件, 返回新的相对路径
 export async function renameFile(
   oldPath: string,
-  newPath: string
-): Promise<void> {
-  return await invoke<void>("rename_file", { oldPath, newPath });
+  newPath: string,
+  overwrite?: boolean
+): Promise<string> {
+  return await invoke<string>("rename_file", { oldPath, newPath, overwrite });
 }
 
 // 获取pack.mcmeta内容
@@ -182,21 +419,44 @@ export async function searchMinecraftItems(
   return await invoke<MinecraftItem[]>("search_minecraft_items", { query });
 }
 
+// overlay 目录及其适用的 pack_format 范围
+export interface OverlaySpec {
+  directory: string;
+  min_format: number;
+  max_format: number;
+}
+
 // 创建新材质包
 export async function createNewPack(
   outputPath: string,
   packName: string,
   packFormat: number,
-  description: string
-): Promise<void> {
-  return await invoke<void>("create_new_pack", {
+  description: string,
+  overlays?: OverlaySpec[],
+  allowExisting?: boolean,
+  overwriteExisting?: boolean
+): Promise<string> {
+  return await invoke<string>("create_new_pack", {
     outputPath,
     packName,
     packFormat,
     description,
+    overlays,
+    allowExisting,
+    overwriteExisting,
   });
 }
 
+// 添加 overlay
+export async function addOverlay(directory: string, formats: [number, number]): Promise<void> {
+  return await invoke<void>("add_overlay", { directory, formats });
+}
+
+// 移除 overlay
+export async function removeOverlay(directory: string): Promise<void> {
+  return await invoke<void>("remove_overlay", { directory });
+}
+
 // 为物品创建模型
 export async function createItemModel(itemId: string): Promise<void> {
   return await invoke<void>("create_item_model", { itemId });
@@ -226,12 +486,25 @@ export async function getSystemFonts(): Promise<string[]> {
   return await invoke<string[]>("get_system_fonts");
 }
 
-// 启动 Web 服务器
+// 额外挂载的只读目录, path 必须位于材质包内(如 .little100/sounds)
+export interface WebMount {
+  route: string;
+  path: string;
+}
+
+export interface ServerStatus {
+  running: boolean;
+  mounts: WebMount[];
+}
+
+// 启动 Web 服务器, serveApp 时在 /app 提供内置浏览页面
 export async function startWebServer(
   port: number,
-  mode: "lan" | "all"
+  mode: "lan" | "all",
+  serveApp?: boolean,
+  mounts?: WebMount[]
 ): Promise<string> {
-  return await invoke<string>("start_server", { port, mode });
+  return await invoke<string>("start_server", { port, mode, serveApp, mounts });
 }
 
 // 停止 Web 服务器
@@ -240,8 +513,8 @@ export async function stopWebServer(): Promise<string> {
 }
 
 // 获取服务器状态
-export async function getServerStatus(): Promise<boolean> {
-  return await invoke<boolean>("get_server_status");
+export async function getServerStatus(): Promise<ServerStatus> {
+  return await invoke<ServerStatus>("get_server_status");
 }
 
 export interface VersionManifest {
@@ -250,6 +523,8 @@ export interface VersionManifest {
     snapshot: string;
   };
   versions: VersionInfo[];
+  // 离线时使用了本地缓存的清单
+  stale?: boolean;
 }
 
 export interface VersionInfo {
@@ -265,7 +540,13 @@ export async function getMinecraftVersions(): Promise<VersionManifest> {
   return await invoke<VersionManifest>("get_minecraft_versions");
 }
 
-// 下载指定的版本jar文件
+// 下载指定的版本jar文件, 返回下载任务ID; 完成后触发 minecraft-version-downloaded 事件
+export interface VersionDownloadedEvent {
+  task_id: string;
+  version_id: string;
+  path: string;
+}
+
 export async function downloadMinecraftVersion(versionId: string): Promise<string> {
   return await invoke<string>("download_minecraft_version", { versionId });
 }
@@ -278,18 +559,20 @@ export async function downloadLatestMinecraftVersion(): Promise<string> {
 // 从jar文件中提取assets到指定目录
 export async function extractAssetsFromJar(
   jarPath: string,
-  outputPath: string
+  outputPath: string,
+  includePrefixes?: string[]
 ): Promise<void> {
-  return await invoke<void>("extract_assets_from_jar", { jarPath, outputPath });
+  return await invoke<void>("extract_assets_from_jar", { jarPath, outputPath, includePrefixes });
 }
 
 // 下载版本并提取assets到材质包
 export async function downloadAndExtractTemplate(
   versionId: string,
   packPath: string,
-  keepCache: boolean = false
+  keepCache: boolean = false,
+  locale?: string
 ): Promise<string> {
-  return await invoke<string>("download_and_extract_template", { versionId, packPath, keepCache });
+  return await invoke<string>("download_and_extract_template", { versionId, packPath, keepCache, locale });
 }
 
 // 清理模板缓存
@@ -297,6 +580,16 @@ export async function clearTemplateCache(): Promise<void> {
   return await invoke<void>("clear_template_cache");
 }
 
+// 预加载文件夹中的图片, 会取消上一次未完成的预加载; 返回取消前处理的文件数
+export async function preloadFolderImages(folderPath: string, requestId?: string): Promise<number> {
+  return await invoke<number>("preload_folder_images", { folderPath, requestId });
+}
+
+// 取消文件夹预加载, 不传 requestId 时取消当前的预加载
+export async function cancelPreload(requestId?: string): Promise<boolean> {
+  return await invoke<boolean>("cancel_preload", { requestId });
+}
+
 export interface SearchResult {
   file_path: string;
   match_type: string;
@@ -305,6 +598,16 @@ export interface SearchResult {
   match_start?: number;
   match_end?: number;
   translation?: string;
+  score?: number;
+}
+
+export type SearchMode = "exact" | "fuzzy";
+
+// 搜索范围, glob 使用相对材质包的正斜杠路径
+export interface SearchScope {
+  includeGlobs?: string[];
+  excludeGlobs?: string[];
+  resourceTypes?: ResourceType[];
 }
 
 export interface SearchResponse {
@@ -316,11 +619,312 @@ export interface SearchResponse {
 export async function searchFiles(
   query: string,
   caseSensitive: boolean,
-  useRegex: boolean
+  useRegex: boolean,
+  mode: SearchMode = "exact",
+  scope: SearchScope = {},
+  packId?: string
 ): Promise<SearchResponse> {
   return await invoke<SearchResponse>("search_files", {
     query,
     caseSensitive,
     useRegex,
+    mode,
+    ...scope,
+    packId,
   });
-}
\ No newline at end of file
+}
+
+export interface SearchResultBatch {
+  search_id: string;
+  filename_matches: SearchResult[];
+  content_matches: SearchResult[];
+}
+
+export interface SearchComplete {
+  search_id: string;
+  filename_count: number;
+  content_count: number;
+  total_count: number;
+  cancelled: boolean;
+}
+
+// 开始流式搜索, 结果通过 search-result-batch / search-complete 事件返回
+export async function startSearch(
+  query: string,
+  caseSensitive: boolean,
+  useRegex: boolean,
+  mode: SearchMode = "exact",
+  searchId?: string,
+  scope: SearchScope = {},
+  batchSize?: number,
+  packId?: string
+): Promise<string> {
+  return await invoke<string>("start_search", {
+    query,
+    caseSensitive,
+    useRegex,
+    mode,
+    ...scope,
+    searchId,
+    batchSize,
+    packId,
+  });
+}
+
+// 取消流式搜索
+export async function cancelSearch(searchId: string): Promise<void> {
+  return await invoke<void>("cancel_search", { searchId });
+}
+
+export interface ReplacePreviewLine {
+  line_number: number;
+  before: string;
+  after: string;
+}
+
+export interface ReplaceFileResult {
+  file_path: string;
+  replacements: number;
+  preview: ReplacePreviewLine[];
+}
+
+export interface ReplaceFailure {
+  file_path: string;
+  code: string;
+  message: string;
+}
+
+export interface ReplaceResponse {
+  files: ReplaceFileResult[];
+  failed: ReplaceFailure[];
+  files_changed: number;
+  total_replacements: number;
+  dry_run: boolean;
+}
+
+// 批量替换, dryRun 时只返回预览
+export async function replaceInFiles(
+  query: string,
+  replacement: string,
+  caseSensitive: boolean,
+  useRegex: boolean,
+  paths?: string[],
+  dryRun: boolean = true,
+  packId?: string
+): Promise<ReplaceResponse> {
+  return await invoke<ReplaceResponse>("replace_in_files", {
+    query,
+    replacement,
+    caseSensitive,
+    useRegex,
+    paths,
+    dryRun,
+    packId,
+  });
+}
+// 图片流水线中的单个操作
+export type ImageOp =
+  | { type: "tint"; color: [number, number, number] }
+  | { type: "resize"; width: number; height: number; filter?: "nearest" | "triangle" | "lanczos3" }
+  | { type: "flip"; direction: "horizontal" | "vertical" }
+  | { type: "crop"; x: number; y: number; width: number; height: number }
+  | { type: "replace_color"; from: [number, number, number, number]; to: [number, number, number, number]; tolerance?: number }
+  | { type: "alpha_bleed" };
+
+export interface PipelineResult {
+  path: string;
+  ok: boolean;
+  error: string | null;
+}
+
+// 对选中的纹理依次执行一组图片操作
+export async function applyPipeline(relativePaths: string[], operations: ImageOp[]): Promise<PipelineResult[]> {
+  return await invoke<PipelineResult[]>("apply_pipeline", { relativePaths, operations });
+}
+
+export interface PackSize {
+  pack_bytes: number;
+  history_bytes: number;
+  editor_data_bytes: number;
+  total_bytes: number;
+}
+
+// 获取材质包大小概览(导出内容、历史记录、编辑器数据)
+export async function getPackSizeSummary(packDir: string): Promise<PackSize> {
+  return await invoke<PackSize>("get_pack_size_summary", { packDir });
+}
+
+export interface PackVersionRange {
+  min_format: number;
+  max_format: number;
+  min_version: string;
+  max_version: string;
+}
+
+// 获取当前材质包适用的游戏版本范围
+export async function getPackVersionRange(): Promise<PackVersionRange> {
+  return await invoke<PackVersionRange>("get_pack_version_range");
+}
+
+// 写入日志文件(标记为前端日志)
+export async function logMessage(
+  level: "error" | "warn" | "info" | "debug",
+  message: string,
+  source?: string
+): Promise<void> {
+  return await invoke<void>("log_message", { level, message, source });
+}
+
+export interface ModifiedFile {
+  relative_path: string;
+  modified: number;
+}
+
+// 列出指定时间(Unix 秒)之后修改过的文件
+export async function listFilesModifiedSince(unixSecs: number): Promise<ModifiedFile[]> {
+  return await invoke<ModifiedFile[]>("list_files_modified_since", { unixSecs });
+}
+
+export interface VacuumResult {
+  removed_dirs: number;
+  pruned_entries: number;
+  reclaimed_bytes: number;
+}
+
+// 整理历史记录: 删除已不存在文件的记录并裁剪超出上限的记录
+export async function vacuumHistory(): Promise<VacuumResult> {
+  return await invoke<VacuumResult>("vacuum_history");
+}
+
+export interface PruneResult {
+  removed_entries: number;
+  freed_bytes: number;
+}
+
+// 删除早于指定天数的历史记录
+export async function pruneHistory(olderThanDays: number): Promise<PruneResult> {
+  return await invoke<PruneResult>("prune_history", { olderThanDays });
+}
+
+export interface DiffLine {
+  kind: "context" | "added" | "removed";
+  old_line: number | null;
+  new_line: number | null;
+  content: string;
+}
+
+export interface DiffHunk {
+  old_start: number;
+  old_lines: number;
+  new_start: number;
+  new_lines: number;
+  lines: DiffLine[];
+}
+
+export interface TextDiff {
+  hunks: DiffHunk[];
+  added: number;
+  removed: number;
+}
+
+// 比较两条历史记录, toTimestamp 省略时与当前文件比较
+export async function diffFileHistory(
+  packDir: string,
+  filePath: string,
+  fromTimestamp: string,
+  toTimestamp?: string
+): Promise<TextDiff> {
+  return await invoke<TextDiff>("diff_file_history", {
+    packDir,
+    filePath,
+    fromTimestamp,
+    toTimestamp,
+  });
+}
+
+// 将文件恢复到某条历史记录(按时间戳或下标), 返回恢复后的内容
+export async function restoreFileHistory(
+  packDir: string,
+  filePath: string,
+  entry: { timestamp?: string; index?: number }
+): Promise<string> {
+  return await invoke<string>("restore_file_history", {
+    packDir,
+    filePath,
+    timestamp: entry.timestamp,
+    index: entry.index,
+  });
+}
+
+// 获取缓存和日志所在的数据目录
+export async function getDataDirectory(): Promise<string> {
+  return await invoke<string>("get_data_directory");
+}
+
+// 设置数据目录, 传入 null 恢复为系统默认目录
+export async function setDataDirectory(path: string | null): Promise<string> {
+  return await invoke<string>("set_data_directory", { path });
+}
+
+export interface JsonProblem {
+  severity: "error" | "warning" | "info";
+  line: number;
+  column: number;
+  message: string;
+  snippet: string;
+}
+
+export interface JsonValidationResult {
+  path: string;
+  valid: boolean;
+  problems: JsonProblem[];
+}
+
+// 校验单个 JSON 文件, lenient 时注释和尾随逗号只作为警告
+export async function validateJson(
+  filePath: string,
+  lenient: boolean = false
+): Promise<JsonValidationResult> {
+  return await invoke<JsonValidationResult>("validate_json", { filePath, lenient });
+}
+
+// 校验材质包中所有 .json / .mcmeta, 只返回有问题的文件
+export async function validateAllJson(lenient: boolean = false): Promise<JsonValidationResult[]> {
+  return await invoke<JsonValidationResult[]>("validate_all_json", { lenient });
+}
+
+// 格式化 JSON 文件并返回新内容, 默认保留键的顺序; 解析失败时不修改文件
+export async function formatJson(
+  filePath: string,
+  indent: number = 2,
+  sortKeys: boolean = false,
+  packId?: string
+): Promise<string> {
+  return await invoke<string>("format_json", { filePath, indent, sortKeys, packId });
+}
+
+// 压缩 JSON 文件为单行并返回新内容
+export async function minifyJson(filePath: string, packId?: string): Promise<string> {
+  return await invoke<string>("minify_json", { filePath, packId });
+}
+
+export interface ImageSide {
+  width?: number;
+  height?: number;
+  size: number;
+}
+
+// 任一侧为图片时 kind 为 "image", 应切换到图片对比视图
+export type FileComparison =
+  | { kind: "text"; diff: TextDiff; identical: boolean }
+  | { kind: "image"; left: ImageSide; right: ImageSide; binary_differs: boolean }
+  | { kind: "binary"; left_size: number; right_size: number; binary_differs: boolean };
+
+// 比较两个文件, 路径可以是材质包内的相对路径或 history:<文件>:<下标>(下标同 load_file_history 返回列表)
+export async function compareFiles(
+  leftPath: string,
+  rightPath: string,
+  packId?: string
+): Promise<FileComparison> {
+  return await invoke<FileComparison>("compare_files", { leftPath, rightPath, packId });
+}